@@ -15,16 +15,17 @@ use swf::avm1::read::Reader;
 use swf::avm1::types::{Action, Function};
 
 use crate::display_object::{DisplayObject, MovieClip};
+use crate::stage::StageQuality;
 use crate::tag_utils::SwfSlice;
 
 #[cfg(test)]
 #[macro_use]
 mod test_utils;
 
-#[macro_use]
 pub mod listeners;
 
 mod activation;
+mod bitmap_data_object;
 pub mod debug;
 mod fscommand;
 pub mod function;
@@ -48,6 +49,7 @@ mod tests;
 
 use crate::avm1::listeners::SystemListener;
 pub use activation::Activation;
+pub use bitmap_data_object::BitmapDataObject;
 pub use globals::SystemPrototypes;
 pub use object::{Object, ObjectPtr, TObject};
 use scope::Scope;
@@ -77,6 +79,9 @@ pub struct Avm1<'gc> {
     /// System builtins that we use internally to construct new objects.
     prototypes: globals::SystemPrototypes<'gc>,
 
+    /// The functions that `AsBroadcaster.initialize` copies onto new broadcasters.
+    broadcaster_functions: globals::as_broadcaster::BroadcasterFunctions<'gc>,
+
     /// System event listeners that will respond to native events (Mouse, Key, etc)
     system_listeners: listeners::SystemListeners<'gc>,
 
@@ -99,6 +104,7 @@ unsafe impl<'gc> gc_arena::Collect for Avm1<'gc> {
     fn trace(&self, cc: gc_arena::CollectionContext) {
         self.globals.trace(cc);
         self.constant_pool.trace(cc);
+        self.broadcaster_functions.trace(cc);
         self.system_listeners.trace(cc);
         self.prototypes.trace(cc);
         self.display_properties.trace(cc);
@@ -115,13 +121,15 @@ type Error = Box<dyn std::error::Error>;
 
 impl<'gc> Avm1<'gc> {
     pub fn new(gc_context: MutationContext<'gc, '_>, player_version: u8) -> Self {
-        let (prototypes, globals, system_listeners) = create_globals(gc_context);
+        let (prototypes, globals, broadcaster_functions, system_listeners) =
+            create_globals(gc_context);
 
         Self {
             player_version,
             constant_pool: GcCell::allocate(gc_context, vec![]),
             globals,
             prototypes,
+            broadcaster_functions,
             system_listeners,
             display_properties: stage_object::DisplayPropertyMap::new(gc_context),
             stack_frames: vec![],
@@ -344,6 +352,19 @@ impl<'gc> Avm1<'gc> {
         !self.stack_frames.is_empty()
     }
 
+    /// Returns the depth of the stack frames and the operand stack, so that they can be
+    /// restored with `unwind_to` if a call fails.
+    pub fn stack_depth(&self) -> (usize, usize) {
+        (self.stack_frames.len(), self.stack.len())
+    }
+
+    /// Drops the stack frames and operands left behind by a call that failed partway,
+    /// returning to a depth given by `stack_depth`.
+    pub fn unwind_to(&mut self, depth: (usize, usize)) {
+        self.stack_frames.truncate(depth.0);
+        self.stack.truncate(depth.1);
+    }
+
     /// Get the currently executing SWF version.
     pub fn current_swf_version(&self) -> u8 {
         self.current_stack_frame()
@@ -370,15 +391,10 @@ impl<'gc> Avm1<'gc> {
             context.gc_context,
             Activation::from_nothing(swf_version, self.globals, context.gc_context, active_clip),
         ));
-        let listeners = self.system_listeners.get(listener);
-        let mut handlers = listeners.prepare_handlers(self, context, method);
+        let broadcaster = self.system_listeners.get(listener);
+        let _ =
+            globals::as_broadcaster::broadcast_internal(self, context, broadcaster, args, method);
         self.stack_frames.pop();
-
-        // Each callback exec pushes its own stack frame.
-        // The functions are now ready to execute with `run_stack_till_empty`.
-        for (listener, handler) in handlers.drain(..) {
-            let _ = handler.call(self, context, listener, None, &args);
-        }
     }
 
     /// Perform some action with the current stack frame's reader.
@@ -1667,6 +1683,10 @@ impl<'gc> Avm1<'gc> {
         &self.prototypes
     }
 
+    pub fn broadcaster_functions(&self) -> globals::as_broadcaster::BroadcasterFunctions<'gc> {
+        self.broadcaster_functions
+    }
+
     fn action_get_variable(
         &mut self,
         context: &mut UpdateContext<'_, 'gc, '_>,
@@ -1850,7 +1870,11 @@ impl<'gc> Avm1<'gc> {
     ) -> Result<(), Error> {
         if let Some(clip) = self.target_clip() {
             if let Some(clip) = clip.as_movie_clip() {
-                if let Some(frame) = clip.frame_label_to_number(label) {
+                // A scene name goes to the first frame of that scene.
+                let frame = clip
+                    .frame_label_to_number(label)
+                    .or_else(|| clip.scene_label_to_number(label));
+                if let Some(frame) = frame {
                     clip.goto_frame(self, context, frame, true);
                 } else {
                     log::warn!("GoToLabel: Frame label '{}' not found", label);
@@ -2604,8 +2628,12 @@ impl<'gc> Avm1<'gc> {
         Err("Unimplemented action: TargetPath".into())
     }
 
-    fn toggle_quality(&mut self, _context: &mut UpdateContext) -> Result<(), Error> {
-        // TODO(Herschel): Noop for now? Could chang anti-aliasing on render backend.
+    fn toggle_quality(&mut self, context: &mut UpdateContext<'_, 'gc, '_>) -> Result<(), Error> {
+        // Toggles between low and high quality; medium and best count as high.
+        *context.stage_quality = match *context.stage_quality {
+            StageQuality::Low => StageQuality::High,
+            _ => StageQuality::Low,
+        };
         Ok(())
     }
 
@@ -1,5 +1,6 @@
 use crate::avm1::fscommand;
 use crate::avm1::function::{Executable, FunctionObject};
+use crate::avm1::globals::as_broadcaster::BroadcasterFunctions;
 use crate::avm1::listeners::SystemListeners;
 use crate::avm1::return_value::ReturnValue;
 use crate::avm1::{Avm1, Error, Object, ScriptObject, TObject, UpdateContext, Value};
@@ -10,10 +11,15 @@ use rand::Rng;
 use std::f64;
 
 mod array;
+pub(crate) mod as_broadcaster;
+mod bitmap_data;
 pub(crate) mod boolean;
 pub(crate) mod button;
 mod color;
+mod context_menu;
+mod context_menu_item;
 pub(crate) mod display_object;
+mod filters;
 mod function;
 mod key;
 mod math;
@@ -22,6 +28,7 @@ pub(crate) mod movie_clip;
 mod movie_clip_loader;
 pub(crate) mod number;
 mod object;
+mod selection;
 mod sound;
 mod stage;
 pub(crate) mod string;
@@ -127,6 +134,10 @@ pub struct SystemPrototypes<'gc> {
     pub string: Object<'gc>,
     pub number: Object<'gc>,
     pub boolean: Object<'gc>,
+    pub bitmap_data: Object<'gc>,
+    pub context_menu: Object<'gc>,
+    pub context_menu_item: Object<'gc>,
+    pub filters: filters::FilterPrototypes<'gc>,
 }
 
 unsafe impl<'gc> gc_arena::Collect for SystemPrototypes<'gc> {
@@ -144,13 +155,22 @@ unsafe impl<'gc> gc_arena::Collect for SystemPrototypes<'gc> {
         self.string.trace(cc);
         self.number.trace(cc);
         self.boolean.trace(cc);
+        self.bitmap_data.trace(cc);
+        self.context_menu.trace(cc);
+        self.context_menu_item.trace(cc);
+        self.filters.trace(cc);
     }
 }
 
 /// Initialize default global scope and builtins for an AVM1 instance.
 pub fn create_globals<'gc>(
     gc_context: MutationContext<'gc, '_>,
-) -> (SystemPrototypes<'gc>, Object<'gc>, SystemListeners<'gc>) {
+) -> (
+    SystemPrototypes<'gc>,
+    Object<'gc>,
+    BroadcasterFunctions<'gc>,
+    SystemListeners<'gc>,
+) {
     let object_proto = ScriptObject::object_cell(gc_context, None);
     let function_proto = function::create_proto(gc_context, object_proto);
 
@@ -174,6 +194,10 @@ pub fn create_globals<'gc>(
     let array_proto: Object<'gc> = array::create_proto(gc_context, object_proto, function_proto);
 
     let color_proto: Object<'gc> = color::create_proto(gc_context, object_proto, function_proto);
+    let context_menu_proto: Object<'gc> =
+        context_menu::create_proto(gc_context, object_proto, function_proto);
+    let context_menu_item_proto: Object<'gc> =
+        context_menu_item::create_proto(gc_context, object_proto, function_proto);
     let xmlnode_proto: Object<'gc> =
         xml::create_xmlnode_proto(gc_context, object_proto, function_proto);
 
@@ -183,6 +207,10 @@ pub fn create_globals<'gc>(
     let number_proto: Object<'gc> = number::create_proto(gc_context, object_proto, function_proto);
     let boolean_proto: Object<'gc> =
         boolean::create_proto(gc_context, object_proto, function_proto);
+    let bitmap_data_proto: Object<'gc> =
+        bitmap_data::create_proto(gc_context, object_proto, function_proto);
+    let (filter_protos, filters_package) =
+        filters::create_filters_package(gc_context, object_proto, function_proto);
 
     //TODO: These need to be constructors and should also set `.prototype` on each one
     let object = object::create_object_object(gc_context, object_proto, function_proto);
@@ -199,6 +227,18 @@ pub fn create_globals<'gc>(
         Some(function_proto),
         Some(color_proto),
     );
+    let context_menu = FunctionObject::function(
+        gc_context,
+        Executable::Native(context_menu::constructor),
+        Some(function_proto),
+        Some(context_menu_proto),
+    );
+    let context_menu_item = FunctionObject::function(
+        gc_context,
+        Executable::Native(context_menu_item::constructor),
+        Some(function_proto),
+        Some(context_menu_item_proto),
+    );
     let function = FunctionObject::function(
         gc_context,
         Executable::Native(function::constructor),
@@ -252,13 +292,37 @@ pub fn create_globals<'gc>(
     let number = number::create_number_object(gc_context, Some(number_proto), Some(function_proto));
     let boolean =
         boolean::create_boolean_object(gc_context, Some(boolean_proto), Some(function_proto));
+    let bitmap_data = bitmap_data::create_bitmap_data_object(
+        gc_context,
+        Some(bitmap_data_proto),
+        Some(function_proto),
+    );
 
-    let listeners = SystemListeners::new(gc_context, Some(array_proto));
+    let (broadcaster_functions, as_broadcaster) =
+        as_broadcaster::create(gc_context, Some(object_proto), function_proto);
 
     let mut globals = ScriptObject::bare_object(gc_context);
     globals.define_value(gc_context, "Array", array.into(), EnumSet::empty());
+    globals.define_value(
+        gc_context,
+        "AsBroadcaster",
+        as_broadcaster.into(),
+        EnumSet::empty(),
+    );
     globals.define_value(gc_context, "Button", button.into(), EnumSet::empty());
     globals.define_value(gc_context, "Color", color.into(), EnumSet::empty());
+    globals.define_value(
+        gc_context,
+        "ContextMenu",
+        context_menu.into(),
+        EnumSet::empty(),
+    );
+    globals.define_value(
+        gc_context,
+        "ContextMenuItem",
+        context_menu_item.into(),
+        EnumSet::empty(),
+    );
     globals.define_value(gc_context, "Object", object.into(), EnumSet::empty());
     globals.define_value(gc_context, "Function", function.into(), EnumSet::empty());
     globals.define_value(gc_context, "MovieClip", movie_clip.into(), EnumSet::empty());
@@ -282,48 +346,65 @@ pub fn create_globals<'gc>(
     globals.define_value(gc_context, "Number", number.into(), EnumSet::empty());
     globals.define_value(gc_context, "Boolean", boolean.into(), EnumSet::empty());
 
-    globals.define_value(
+    let flash = ScriptObject::object(gc_context, Some(object_proto));
+    let display = ScriptObject::object(gc_context, Some(object_proto));
+    display.define_value(
         gc_context,
-        "Math",
-        Value::Object(math::create(
-            gc_context,
-            Some(object_proto),
-            Some(function_proto),
-        )),
+        "BitmapData",
+        bitmap_data.into(),
         EnumSet::empty(),
     );
-    globals.define_value(
+    flash.define_value(gc_context, "display", display.into(), EnumSet::empty());
+    flash.define_value(
         gc_context,
-        "Mouse",
-        Value::Object(mouse::create_mouse_object(
-            gc_context,
-            Some(object_proto),
-            Some(function_proto),
-            &listeners.mouse,
-        )),
+        "filters",
+        filters_package.into(),
         EnumSet::empty(),
     );
+    globals.define_value(gc_context, "flash", flash.into(), EnumSet::empty());
+
     globals.define_value(
         gc_context,
-        "Key",
-        Value::Object(key::create_key_object(
+        "Math",
+        Value::Object(math::create(
             gc_context,
             Some(object_proto),
             Some(function_proto),
         )),
         EnumSet::empty(),
     );
-    globals.define_value(
+    let mouse = mouse::create_mouse_object(
         gc_context,
-        "Stage",
-        Value::Object(stage::create_stage_object(
-            gc_context,
-            Some(object_proto),
-            Some(array_proto),
-            Some(function_proto),
-        )),
-        EnumSet::empty(),
+        Some(object_proto),
+        Some(function_proto),
+        Some(array_proto),
+        broadcaster_functions,
     );
+    let key = key::create_key_object(
+        gc_context,
+        Some(object_proto),
+        Some(function_proto),
+        Some(array_proto),
+        broadcaster_functions,
+    );
+    let stage = stage::create_stage_object(
+        gc_context,
+        Some(object_proto),
+        Some(array_proto),
+        Some(function_proto),
+        broadcaster_functions,
+    );
+    let selection = selection::create_selection_object(
+        gc_context,
+        Some(object_proto),
+        Some(function_proto),
+        Some(array_proto),
+        broadcaster_functions,
+    );
+    globals.define_value(gc_context, "Mouse", mouse.into(), EnumSet::empty());
+    globals.define_value(gc_context, "Key", key.into(), EnumSet::empty());
+    globals.define_value(gc_context, "Stage", stage.into(), EnumSet::empty());
+    globals.define_value(gc_context, "Selection", selection.into(), EnumSet::empty());
     globals.force_set_function(
         "isNaN",
         is_nan,
@@ -381,9 +462,19 @@ pub fn create_globals<'gc>(
             string: string_proto,
             number: number_proto,
             boolean: boolean_proto,
+            bitmap_data: bitmap_data_proto,
+            context_menu: context_menu_proto,
+            context_menu_item: context_menu_item_proto,
+            filters: filter_protos,
         },
         globals.into(),
-        listeners,
+        broadcaster_functions,
+        SystemListeners {
+            mouse,
+            key,
+            stage,
+            selection,
+        },
     )
 }
 
@@ -0,0 +1,252 @@
+//! `AsBroadcaster` impl
+
+use crate::avm1::function::{Executable, FunctionObject};
+use crate::avm1::property::Attribute;
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Error, Object, ScriptObject, TObject, UpdateContext, Value};
+use gc_arena::{Collect, MutationContext};
+
+/// The native functions that `AsBroadcaster.initialize` copies onto a broadcaster.
+#[derive(Clone, Collect, Debug, Copy)]
+#[collect(no_drop)]
+pub struct BroadcasterFunctions<'gc> {
+    pub add_listener: Object<'gc>,
+    pub remove_listener: Object<'gc>,
+    pub broadcast_message: Object<'gc>,
+}
+
+impl<'gc> BroadcasterFunctions<'gc> {
+    /// Turns `broadcaster` into a broadcaster with an empty `_listeners` array.
+    pub fn initialize(
+        self,
+        gc_context: MutationContext<'gc, '_>,
+        broadcaster: Object<'gc>,
+        array_proto: Option<Object<'gc>>,
+    ) {
+        broadcaster.define_value(
+            gc_context,
+            "broadcastMessage",
+            self.broadcast_message.into(),
+            Attribute::DontEnum.into(),
+        );
+        broadcaster.define_value(
+            gc_context,
+            "addListener",
+            self.add_listener.into(),
+            Attribute::DontEnum.into(),
+        );
+        broadcaster.define_value(
+            gc_context,
+            "removeListener",
+            self.remove_listener.into(),
+            Attribute::DontEnum.into(),
+        );
+
+        let listeners = ScriptObject::array(gc_context, array_proto);
+        broadcaster.define_value(
+            gc_context,
+            "_listeners",
+            listeners.into(),
+            Attribute::DontEnum.into(),
+        );
+    }
+}
+
+pub fn initialize<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(Value::Object(broadcaster)) = args.get(0) {
+        avm.broadcaster_functions().initialize(
+            context.gc_context,
+            *broadcaster,
+            Some(avm.prototypes().array),
+        );
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn add_listener<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let new_listener = args.get(0).cloned().unwrap_or(Value::Undefined);
+    let listeners = this
+        .get("_listeners", avm, context)?
+        .resolve(avm, context)?;
+
+    if let Value::Object(listeners) = listeners {
+        let length = listeners.length();
+        for i in 0..length {
+            if listeners.array_element(i) == new_listener {
+                return Ok(true.into());
+            }
+        }
+
+        listeners.set_array_element(length, new_listener, context.gc_context);
+    }
+
+    Ok(true.into())
+}
+
+pub fn remove_listener<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let old_listener = args.get(0).cloned().unwrap_or(Value::Undefined);
+    let listeners = this
+        .get("_listeners", avm, context)?
+        .resolve(avm, context)?;
+
+    if let Value::Object(listeners) = listeners {
+        let length = listeners.length();
+        for index in 0..length {
+            if listeners.array_element(index) == old_listener {
+                let new_length = length - 1;
+
+                for i in index..new_length {
+                    listeners.set_array_element(
+                        i,
+                        listeners.array_element(i + 1),
+                        context.gc_context,
+                    );
+                }
+
+                listeners.delete_array_element(new_length, context.gc_context);
+                listeners.delete(avm, context.gc_context, &new_length.to_string());
+                listeners.set_length(context.gc_context, new_length);
+
+                return Ok(true.into());
+            }
+        }
+    }
+
+    Ok(false.into())
+}
+
+pub fn broadcast_message<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(event_name) = args.get(0) {
+        let event_name = event_name.clone().coerce_to_string(avm, context)?;
+        broadcast_internal(avm, context, this, &args[1..], &event_name)?;
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+/// Calls `method` on every listener of `broadcaster`.
+///
+/// Like Flash, the number of listeners is read once, but each listener is looked up
+/// just before it is called. Listeners added by a handler are not called until the next
+/// broadcast, and removing a listener shifts the following ones down, skipping one.
+/// A listener that fails doesn't stop the broadcast to the remaining listeners.
+pub fn broadcast_internal<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    broadcaster: Object<'gc>,
+    call_args: &[Value<'gc>],
+    method: &str,
+) -> Result<(), Error> {
+    let listeners = broadcaster
+        .get("_listeners", avm, context)?
+        .resolve(avm, context)?;
+
+    if let Value::Object(listeners) = listeners {
+        let length = listeners.length();
+        for i in 0..length {
+            if let Value::Object(listener) = listeners.array_element(i) {
+                let depth = avm.stack_depth();
+                if let Err(e) = call_listener(avm, context, listener, call_args, method) {
+                    log::warn!("Error in {} listener: {}", method, e);
+                    avm.unwind_to(depth);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn call_listener<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    listener: Object<'gc>,
+    call_args: &[Value<'gc>],
+    method: &str,
+) -> Result<(), Error> {
+    let handler = listener.get(method, avm, context)?.resolve(avm, context)?;
+    if let Value::Object(handler) = handler {
+        handler
+            .call(avm, context, listener, None, call_args)?
+            .resolve(avm, context)?;
+    }
+    Ok(())
+}
+
+pub fn create<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Option<Object<'gc>>,
+    fn_proto: Object<'gc>,
+) -> (BroadcasterFunctions<'gc>, Object<'gc>) {
+    let mut object = ScriptObject::object(gc_context, proto);
+
+    let functions = BroadcasterFunctions {
+        add_listener: FunctionObject::function(
+            gc_context,
+            Executable::Native(add_listener),
+            Some(fn_proto),
+            None,
+        ),
+        remove_listener: FunctionObject::function(
+            gc_context,
+            Executable::Native(remove_listener),
+            Some(fn_proto),
+            None,
+        ),
+        broadcast_message: FunctionObject::function(
+            gc_context,
+            Executable::Native(broadcast_message),
+            Some(fn_proto),
+            None,
+        ),
+    };
+
+    object.define_value(
+        gc_context,
+        "addListener",
+        functions.add_listener.into(),
+        Attribute::DontDelete | Attribute::DontEnum,
+    );
+    object.define_value(
+        gc_context,
+        "removeListener",
+        functions.remove_listener.into(),
+        Attribute::DontDelete | Attribute::DontEnum,
+    );
+    object.define_value(
+        gc_context,
+        "broadcastMessage",
+        functions.broadcast_message.into(),
+        Attribute::DontDelete | Attribute::DontEnum,
+    );
+    object.force_set_function(
+        "initialize",
+        initialize,
+        gc_context,
+        Attribute::DontDelete | Attribute::DontEnum,
+        Some(fn_proto),
+    );
+
+    (functions, object.into())
+}
@@ -0,0 +1,795 @@
+//! flash.display.BitmapData object
+
+use crate::avm1::function::{Executable, FunctionObject};
+use crate::avm1::property::Attribute::*;
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{
+    Avm1, BitmapDataObject, Error, Object, ScriptObject, TObject, UpdateContext, Value,
+};
+use crate::bitmap_data::{self, BitmapData, PixelRect, ThresholdOperation};
+use crate::character::Character;
+use crate::display_object::{self, TDisplayObject};
+use crate::prelude::*;
+use crate::transform::Transform;
+use gc_arena::MutationContext;
+
+/// Implements `BitmapData`
+pub fn constructor<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let width = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(avm, context)?;
+    let height = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(avm, context)?;
+    let transparent = args
+        .get(2)
+        .map(|v| v.as_bool(avm.current_swf_version()))
+        .unwrap_or(true);
+    let fill_color = match args.get(3) {
+        Some(v) => v.coerce_to_u32(avm, context)?,
+        None => 0xFFFF_FFFF,
+    };
+
+    let max = bitmap_data::MAX_DIMENSION as i32;
+    if width <= 0 || width > max || height <= 0 || height > max {
+        log::warn!("BitmapData: Invalid dimensions {}x{}", width, height);
+        return Ok(Value::Undefined.into());
+    }
+
+    if let Some(bitmap_data) = this.as_bitmap_data_object() {
+        let mut data = BitmapData::new(width as u32, height as u32, transparent, fill_color);
+        data.register(context.renderer);
+        bitmap_data.set_bitmap_data(context.gc_context, data);
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let bitmap_data = BitmapDataObject::empty_object(gc_context, Some(proto));
+    let mut object = bitmap_data.as_script_object().unwrap();
+
+    object.add_property(
+        gc_context,
+        "width",
+        Executable::Native(width),
+        None,
+        DontDelete | ReadOnly | DontEnum,
+    );
+    object.add_property(
+        gc_context,
+        "height",
+        Executable::Native(height),
+        None,
+        DontDelete | ReadOnly | DontEnum,
+    );
+    object.add_property(
+        gc_context,
+        "transparent",
+        Executable::Native(transparent),
+        None,
+        DontDelete | ReadOnly | DontEnum,
+    );
+    object.add_property(
+        gc_context,
+        "rectangle",
+        Executable::Native(rectangle),
+        None,
+        DontDelete | ReadOnly | DontEnum,
+    );
+
+    object.force_set_function(
+        "getPixel",
+        get_pixel,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "getPixel32",
+        get_pixel32,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "setPixel",
+        set_pixel,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "setPixel32",
+        set_pixel32,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "fillRect",
+        fill_rect,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "copyPixels",
+        copy_pixels,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "draw",
+        draw,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "noise",
+        noise,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "perlinNoise",
+        perlin_noise,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "threshold",
+        threshold,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "floodFill",
+        flood_fill,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "clone",
+        clone,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+    object.force_set_function(
+        "dispose",
+        dispose,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+
+    bitmap_data.into()
+}
+
+/// Creates the `BitmapData` constructor, including its static methods.
+pub fn create_bitmap_data_object<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    bitmap_data_proto: Option<Object<'gc>>,
+    fn_proto: Option<Object<'gc>>,
+) -> Object<'gc> {
+    let bitmap_data = FunctionObject::function(
+        gc_context,
+        Executable::Native(constructor),
+        fn_proto,
+        bitmap_data_proto,
+    );
+    let mut object = bitmap_data.as_script_object().unwrap();
+
+    object.force_set_function(
+        "loadBitmap",
+        load_bitmap,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        fn_proto,
+    );
+
+    bitmap_data
+}
+
+/// Reads an integer argument, defaulting to 0 if it is missing.
+fn arg_i32<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+) -> Result<i32, Error> {
+    args.get(index)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(avm, context)
+}
+
+/// Reads a color argument, defaulting to the given color if it is missing.
+fn arg_color<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+    default: u32,
+) -> Result<u32, Error> {
+    match args.get(index) {
+        Some(Value::Undefined) | None => Ok(default),
+        Some(v) => v.coerce_to_u32(avm, context),
+    }
+}
+
+/// Reads a numeric property from an object.
+fn get_number<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+    name: &str,
+) -> Result<f64, Error> {
+    object
+        .get(name, avm, context)?
+        .resolve(avm, context)?
+        .as_number(avm, context)
+}
+
+/// Converts a `flash.geom.Rectangle`-like object into a `PixelRect`.
+fn rect_arg<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    value: Option<&Value<'gc>>,
+) -> Result<Option<PixelRect>, Error> {
+    if let Some(Value::Object(rect)) = value {
+        let x = get_number(avm, context, *rect, "x")?;
+        let y = get_number(avm, context, *rect, "y")?;
+        let width = get_number(avm, context, *rect, "width")?;
+        let height = get_number(avm, context, *rect, "height")?;
+        Ok(Some(PixelRect::new(
+            x as i32,
+            y as i32,
+            width as i32,
+            height as i32,
+        )))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Converts a `flash.geom.Point`-like object into a pair of pixel coordinates.
+fn point_arg<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    value: Option<&Value<'gc>>,
+) -> Result<Option<(i32, i32)>, Error> {
+    if let Some(Value::Object(point)) = value {
+        let x = get_number(avm, context, *point, "x")?;
+        let y = get_number(avm, context, *point, "y")?;
+        Ok(Some((x as i32, y as i32)))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Converts a `flash.geom.Matrix`-like object into a `Matrix`.
+/// Missing components default to the identity matrix.
+pub(crate) fn matrix_arg<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    value: Option<&Value<'gc>>,
+) -> Result<Matrix, Error> {
+    let mut matrix = Matrix::default();
+    if let Some(Value::Object(object)) = value {
+        let mut component = |name: &str, default: f64| -> Result<f64, Error> {
+            let n = get_number(avm, context, *object, name)?;
+            Ok(if n.is_finite() { n } else { default })
+        };
+        matrix.a = component("a", 1.0)? as f32;
+        matrix.b = component("b", 0.0)? as f32;
+        matrix.c = component("c", 0.0)? as f32;
+        matrix.d = component("d", 1.0)? as f32;
+        matrix.tx = Twips::from_pixels(component("tx", 0.0)?);
+        matrix.ty = Twips::from_pixels(component("ty", 0.0)?);
+    }
+    Ok(matrix)
+}
+
+/// Converts a `flash.geom.ColorTransform`-like object into a `ColorTransform`.
+fn color_transform_arg<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    value: Option<&Value<'gc>>,
+) -> Result<ColorTransform, Error> {
+    let mut color_transform = ColorTransform::default();
+    if let Some(Value::Object(object)) = value {
+        let mut component = |name: &str, default: f64| -> Result<f32, Error> {
+            let n = get_number(avm, context, *object, name)?;
+            Ok(if n.is_finite() {
+                n as f32
+            } else {
+                default as f32
+            })
+        };
+        color_transform.r_mult = component("redMultiplier", 1.0)?;
+        color_transform.g_mult = component("greenMultiplier", 1.0)?;
+        color_transform.b_mult = component("blueMultiplier", 1.0)?;
+        color_transform.a_mult = component("alphaMultiplier", 1.0)?;
+        color_transform.r_add = component("redOffset", 0.0)? / 255.0;
+        color_transform.g_add = component("greenOffset", 0.0)? / 255.0;
+        color_transform.b_add = component("blueOffset", 0.0)? / 255.0;
+        color_transform.a_add = component("alphaOffset", 0.0)? / 255.0;
+    }
+    Ok(color_transform)
+}
+
+/// Runs an operation that reads from `source` and writes to `dest`.
+/// Handles the case where both are the same `BitmapData`.
+fn with_source<'gc, R>(
+    gc_context: MutationContext<'gc, '_>,
+    dest: BitmapDataObject<'gc>,
+    source: BitmapDataObject<'gc>,
+    f: impl FnOnce(&mut BitmapData, &BitmapData) -> R,
+) -> R {
+    if dest.as_ptr() == source.as_ptr() {
+        let source = source.bitmap_data().clone();
+        f(&mut dest.bitmap_data_mut(gc_context), &source)
+    } else {
+        f(&mut dest.bitmap_data_mut(gc_context), &source.bitmap_data())
+    }
+}
+
+/// Returns the `BitmapData` for `this`, or `None` if it is not a usable bitmap.
+fn this_bitmap_data<'gc>(this: Object<'gc>) -> Option<BitmapDataObject<'gc>> {
+    this.as_bitmap_data_object()
+        .filter(|bitmap_data| !bitmap_data.bitmap_data().disposed())
+}
+
+pub fn width<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(bitmap_data) = this_bitmap_data(this) {
+        return Ok(bitmap_data.bitmap_data().width().into());
+    }
+    Ok((-1).into())
+}
+
+pub fn height<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(bitmap_data) = this_bitmap_data(this) {
+        return Ok(bitmap_data.bitmap_data().height().into());
+    }
+    Ok((-1).into())
+}
+
+pub fn transparent<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(bitmap_data) = this_bitmap_data(this) {
+        return Ok(bitmap_data.bitmap_data().transparent().into());
+    }
+    Ok((-1).into())
+}
+
+pub fn rectangle<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(bitmap_data) = this_bitmap_data(this) {
+        let (width, height) = {
+            let data = bitmap_data.bitmap_data();
+            (data.width(), data.height())
+        };
+        let rect = ScriptObject::object(context.gc_context, Some(avm.prototypes.object));
+        rect.set("x", 0.into(), avm, context)?;
+        rect.set("y", 0.into(), avm, context)?;
+        rect.set("width", width.into(), avm, context)?;
+        rect.set("height", height.into(), avm, context)?;
+        return Ok(rect.into());
+    }
+    Ok((-1).into())
+}
+
+pub fn get_pixel<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(bitmap_data) = this_bitmap_data(this) {
+        let x = arg_i32(avm, context, args, 0)?;
+        let y = arg_i32(avm, context, args, 1)?;
+        return Ok(bitmap_data.bitmap_data().get_pixel(x, y).into());
+    }
+    Ok((-1).into())
+}
+
+pub fn get_pixel32<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(bitmap_data) = this_bitmap_data(this) {
+        let x = arg_i32(avm, context, args, 0)?;
+        let y = arg_i32(avm, context, args, 1)?;
+        return Ok(bitmap_data.bitmap_data().get_pixel32(x, y).into());
+    }
+    Ok((-1).into())
+}
+
+pub fn set_pixel<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(bitmap_data) = this_bitmap_data(this) {
+        let x = arg_i32(avm, context, args, 0)?;
+        let y = arg_i32(avm, context, args, 1)?;
+        let color = arg_color(avm, context, args, 2, 0)?;
+        bitmap_data
+            .bitmap_data_mut(context.gc_context)
+            .set_pixel(x, y, color);
+    }
+    Ok(Value::Undefined.into())
+}
+
+pub fn set_pixel32<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(bitmap_data) = this_bitmap_data(this) {
+        let x = arg_i32(avm, context, args, 0)?;
+        let y = arg_i32(avm, context, args, 1)?;
+        let color = arg_color(avm, context, args, 2, 0)?;
+        bitmap_data
+            .bitmap_data_mut(context.gc_context)
+            .set_pixel32(x, y, color);
+    }
+    Ok(Value::Undefined.into())
+}
+
+pub fn fill_rect<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(bitmap_data) = this_bitmap_data(this) {
+        if let Some(rect) = rect_arg(avm, context, args.get(0))? {
+            let color = arg_color(avm, context, args, 1, 0)?;
+            bitmap_data
+                .bitmap_data_mut(context.gc_context)
+                .fill_rect(rect, color);
+        }
+    }
+    Ok(Value::Undefined.into())
+}
+
+pub fn copy_pixels<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(bitmap_data) = this_bitmap_data(this) {
+        let source = args
+            .get(0)
+            .and_then(|v| v.as_object().ok())
+            .and_then(this_bitmap_data);
+        let src_rect = rect_arg(avm, context, args.get(1))?;
+        let dest_point = point_arg(avm, context, args.get(2))?;
+        if let Some(Value::Object(_)) = args.get(3) {
+            log::warn!("BitmapData.copyPixels: alphaBitmap is not yet implemented");
+        }
+        let merge_alpha = args
+            .get(5)
+            .map(|v| v.as_bool(avm.current_swf_version()))
+            .unwrap_or(false);
+
+        if let (Some(source), Some(src_rect), Some(dest_point)) = (source, src_rect, dest_point) {
+            with_source(context.gc_context, bitmap_data, source, |dest, source| {
+                dest.copy_pixels(source, src_rect, dest_point, merge_alpha)
+            });
+        }
+    }
+    Ok(Value::Undefined.into())
+}
+
+pub fn draw<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(bitmap_data) = this_bitmap_data(this) {
+        let source = args.get(0).and_then(|v| v.as_object().ok());
+        let matrix = matrix_arg(avm, context, args.get(1))?;
+        let clip_rect = rect_arg(avm, context, args.get(4))?;
+
+        if let Some(source) = source.and_then(this_bitmap_data) {
+            with_source(context.gc_context, bitmap_data, source, |dest, source| {
+                dest.draw_bitmap(source, matrix, clip_rect)
+            });
+        } else if let Some(display_object) = source.and_then(|o| o.as_display_object()) {
+            // TODO: blendMode and smoothing are ignored.
+            let color_transform = color_transform_arg(avm, context, args.get(2))?;
+            let (handle, width, height) = {
+                let mut data = bitmap_data.bitmap_data_mut(context.gc_context);
+                let handle = data.register(context.renderer);
+                data.update_texture_if_dirty(context.renderer);
+                (handle, data.width(), data.height())
+            };
+            let transform = Transform {
+                matrix,
+                color_transform,
+            };
+            let rendered = display_object::render_to_bitmap(
+                display_object,
+                context.renderer,
+                context.library,
+                &transform,
+                Some(handle),
+                width,
+                height,
+                Color {
+                    r: 0,
+                    g: 0,
+                    b: 0,
+                    a: 0,
+                },
+            );
+            if let Some(pixels) = rendered {
+                bitmap_data
+                    .bitmap_data_mut(context.gc_context)
+                    .copy_from_rendered(pixels, clip_rect);
+            } else {
+                log::warn!(
+                    "BitmapData.draw: Render backend does not support drawing display objects"
+                );
+            }
+        }
+    }
+    Ok(Value::Undefined.into())
+}
+
+pub fn noise<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(bitmap_data) = this_bitmap_data(this) {
+        let seed = arg_i32(avm, context, args, 0)?;
+        let low = arg_color(avm, context, args, 1, 0)?.min(255) as u8;
+        let high = arg_color(avm, context, args, 2, 255)?.min(255) as u8;
+        let channels = arg_color(avm, context, args, 3, 7)? as u8;
+        let gray_scale = args
+            .get(4)
+            .map(|v| v.as_bool(avm.current_swf_version()))
+            .unwrap_or(false);
+        bitmap_data
+            .bitmap_data_mut(context.gc_context)
+            .noise(seed, low, high, channels, gray_scale);
+    }
+    Ok(Value::Undefined.into())
+}
+
+pub fn perlin_noise<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(bitmap_data) = this_bitmap_data(this) {
+        let swf_version = avm.current_swf_version();
+        let base_x = args
+            .get(0)
+            .unwrap_or(&Value::Undefined)
+            .as_number(avm, context)?;
+        let base_y = args
+            .get(1)
+            .unwrap_or(&Value::Undefined)
+            .as_number(avm, context)?;
+        let num_octaves = arg_i32(avm, context, args, 2)?.max(0) as usize;
+        let seed = arg_i32(avm, context, args, 3)?;
+        let stitch = args.get(4).map(|v| v.as_bool(swf_version)).unwrap_or(false);
+        let fractal_noise = args.get(5).map(|v| v.as_bool(swf_version)).unwrap_or(false);
+        let channels = arg_color(avm, context, args, 6, 7)? as u8;
+        let gray_scale = args.get(7).map(|v| v.as_bool(swf_version)).unwrap_or(false);
+
+        let mut offsets = vec![];
+        if let Some(Value::Object(offsets_array)) = args.get(8) {
+            for point in offsets_array.array() {
+                if let Some(point) = point_arg(avm, context, Some(&point))? {
+                    offsets.push((f64::from(point.0), f64::from(point.1)));
+                }
+            }
+        }
+
+        if base_x > 0.0 && base_y > 0.0 {
+            bitmap_data
+                .bitmap_data_mut(context.gc_context)
+                .perlin_noise(
+                    (base_x, base_y),
+                    num_octaves,
+                    seed,
+                    stitch,
+                    fractal_noise,
+                    channels,
+                    gray_scale,
+                    &offsets,
+                );
+        }
+    }
+    Ok(Value::Undefined.into())
+}
+
+pub fn threshold<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(bitmap_data) = this_bitmap_data(this) {
+        let source = args
+            .get(0)
+            .and_then(|v| v.as_object().ok())
+            .and_then(this_bitmap_data);
+        let src_rect = rect_arg(avm, context, args.get(1))?;
+        let dest_point = point_arg(avm, context, args.get(2))?;
+        let operation = args
+            .get(3)
+            .unwrap_or(&Value::Undefined)
+            .clone()
+            .coerce_to_string(avm, context)?;
+        let threshold = arg_color(avm, context, args, 4, 0)?;
+        let color = arg_color(avm, context, args, 5, 0)?;
+        let mask = arg_color(avm, context, args, 6, 0xFFFF_FFFF)?;
+        let copy_source = args
+            .get(7)
+            .map(|v| v.as_bool(avm.current_swf_version()))
+            .unwrap_or(false);
+
+        let operation = if let Some(operation) = ThresholdOperation::from_operator(&operation) {
+            operation
+        } else {
+            return Ok(0.into());
+        };
+
+        if let (Some(source), Some(src_rect), Some(dest_point)) = (source, src_rect, dest_point) {
+            let count = with_source(context.gc_context, bitmap_data, source, |dest, source| {
+                dest.threshold(
+                    source,
+                    src_rect,
+                    dest_point,
+                    operation,
+                    threshold,
+                    color,
+                    mask,
+                    copy_source,
+                )
+            });
+            return Ok(count.into());
+        }
+    }
+    Ok(0.into())
+}
+
+pub fn flood_fill<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(bitmap_data) = this_bitmap_data(this) {
+        let x = arg_i32(avm, context, args, 0)?;
+        let y = arg_i32(avm, context, args, 1)?;
+        let color = arg_color(avm, context, args, 2, 0)?;
+        bitmap_data
+            .bitmap_data_mut(context.gc_context)
+            .flood_fill(x, y, color);
+    }
+    Ok(Value::Undefined.into())
+}
+
+pub fn clone<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(bitmap_data) = this_bitmap_data(this) {
+        let mut data = bitmap_data.bitmap_data().clone_pixels();
+        data.register(context.renderer);
+        let new_bitmap_data =
+            BitmapDataObject::empty_object(context.gc_context, Some(avm.prototypes.bitmap_data));
+        new_bitmap_data.set_bitmap_data(context.gc_context, data);
+        return Ok(Object::from(new_bitmap_data).into());
+    }
+    Ok((-1).into())
+}
+
+pub fn dispose<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(bitmap_data) = this_bitmap_data(this) {
+        bitmap_data
+            .bitmap_data_mut(context.gc_context)
+            .dispose(context.renderer);
+    }
+    Ok(Value::Undefined.into())
+}
+
+/// Implements `BitmapData.loadBitmap`, which creates a `BitmapData` from an exported library bitmap.
+pub fn load_bitmap<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let export_name = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .clone()
+        .coerce_to_string(avm, context)?;
+
+    let movie = avm
+        .target_clip_or_root()
+        .movie()
+        .unwrap_or_else(|| context.swf.clone());
+    let bitmap_handle = context
+        .library
+        .library_for_movie(movie)
+        .and_then(|l| l.get_character_by_export_name(&export_name))
+        .and_then(|c| match c {
+            Character::Bitmap(bitmap) => Some(bitmap.bitmap_handle()),
+            _ => None,
+        });
+
+    if let Some(bitmap) = bitmap_handle.and_then(|h| context.renderer.get_bitmap_pixels(h)) {
+        let mut data = BitmapData::from_bitmap(bitmap);
+        data.register(context.renderer);
+        let bitmap_data =
+            BitmapDataObject::empty_object(context.gc_context, Some(avm.prototypes.bitmap_data));
+        bitmap_data.set_bitmap_data(context.gc_context, data);
+        return Ok(Object::from(bitmap_data).into());
+    }
+
+    log::warn!(
+        "BitmapData.loadBitmap: Unable to load bitmap '{}'",
+        export_name
+    );
+    Ok(Value::Undefined.into())
+}
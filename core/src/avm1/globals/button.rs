@@ -1,8 +1,11 @@
 //! Button/SimpleButton prototype
 
+use crate::avm1::function::Executable;
 use crate::avm1::globals::display_object;
+use crate::avm1::property::Attribute::*;
 use crate::avm1::return_value::ReturnValue;
-use crate::avm1::{Avm1, Error, Object, ScriptObject, UpdateContext, Value};
+use crate::avm1::{Avm1, Error, Object, ScriptObject, TObject, UpdateContext, Value};
+use crate::display_object::TDisplayObject;
 use gc_arena::MutationContext;
 
 pub fn create_proto<'gc>(
@@ -14,6 +17,35 @@ pub fn create_proto<'gc>(
 
     display_object::define_display_object_proto(gc_context, object, fn_proto);
 
+    object.add_property(
+        gc_context,
+        "enabled",
+        Executable::Native(enabled),
+        Some(Executable::Native(set_enabled)),
+        DontDelete | DontEnum,
+    );
+    object.add_property(
+        gc_context,
+        "useHandCursor",
+        Executable::Native(use_hand_cursor),
+        Some(Executable::Native(set_use_hand_cursor)),
+        DontDelete | DontEnum,
+    );
+    object.add_property(
+        gc_context,
+        "trackAsMenu",
+        Executable::Native(track_as_menu),
+        Some(Executable::Native(set_track_as_menu)),
+        DontDelete | DontEnum,
+    );
+    object.add_property(
+        gc_context,
+        "tabIndex",
+        Executable::Native(tab_index),
+        Some(Executable::Native(set_tab_index)),
+        DontDelete | DontEnum,
+    );
+
     object.into()
 }
 
@@ -26,3 +58,132 @@ pub fn constructor<'gc>(
 ) -> Result<ReturnValue<'gc>, Error> {
     Ok(Value::Undefined.into())
 }
+
+pub fn enabled<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(button) = this.as_display_object().and_then(|dobj| dobj.as_button()) {
+        return Ok(button.enabled().into());
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn set_enabled<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let value = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .as_bool(avm.current_swf_version());
+
+    if let Some(button) = this.as_display_object().and_then(|dobj| dobj.as_button()) {
+        button.set_enabled(context.gc_context, value);
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn use_hand_cursor<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(button) = this.as_display_object().and_then(|dobj| dobj.as_button()) {
+        return Ok(button.use_hand_cursor().into());
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn set_use_hand_cursor<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let value = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .as_bool(avm.current_swf_version());
+
+    if let Some(button) = this.as_display_object().and_then(|dobj| dobj.as_button()) {
+        button.set_use_hand_cursor(context.gc_context, value);
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn track_as_menu<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(button) = this.as_display_object().and_then(|dobj| dobj.as_button()) {
+        return Ok(button.is_track_as_menu().into());
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn set_track_as_menu<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let value = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .as_bool(avm.current_swf_version());
+
+    if let Some(button) = this.as_display_object().and_then(|dobj| dobj.as_button()) {
+        button.set_track_as_menu(context.gc_context, value);
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn tab_index<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(button) = this.as_display_object().and_then(|dobj| dobj.as_button()) {
+        if let Some(tab_index) = button.tab_index() {
+            return Ok(tab_index.into());
+        }
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn set_tab_index<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let tab_index = match args.get(0) {
+        None | Some(Value::Undefined) | Some(Value::Null) => None,
+        Some(value) => Some(value.as_number(avm, context)? as i32),
+    };
+
+    if let Some(button) = this.as_display_object().and_then(|dobj| dobj.as_button()) {
+        button.set_tab_index(context.gc_context, tab_index);
+    }
+
+    Ok(Value::Undefined.into())
+}
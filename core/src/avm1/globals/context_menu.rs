@@ -0,0 +1,135 @@
+//! `ContextMenu` impl
+
+use crate::avm1::property::Attribute::*;
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Error, Object, ScriptObject, TObject, UpdateContext, Value};
+use gc_arena::MutationContext;
+
+/// The names of the properties of `builtInItems`.
+pub const BUILT_IN_ITEMS: &[&str] = &[
+    "save",
+    "zoom",
+    "quality",
+    "play",
+    "loop",
+    "rewind",
+    "forward_back",
+    "print",
+];
+
+/// `new ContextMenu(callback)`
+pub fn constructor<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let callback = args.get(0).cloned().unwrap_or(Value::Undefined);
+    this.set("onSelect", callback, avm, context)?;
+
+    let built_in_items = ScriptObject::object(context.gc_context, Some(avm.prototypes.object));
+    for name in BUILT_IN_ITEMS {
+        built_in_items.set(name, true.into(), avm, context)?;
+    }
+    this.set("builtInItems", built_in_items.into(), avm, context)?;
+
+    let custom_items = ScriptObject::array(context.gc_context, Some(avm.prototypes.array));
+    this.set("customItems", custom_items.into(), avm, context)?;
+
+    Ok(Value::Undefined.into())
+}
+
+/// Implements `ContextMenu.prototype.hideBuiltInItems`
+pub fn hide_built_in_items<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Value::Object(built_in_items) = this
+        .get("builtInItems", avm, context)?
+        .resolve(avm, context)?
+    {
+        for name in BUILT_IN_ITEMS {
+            built_in_items.set(name, false.into(), avm, context)?;
+        }
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+/// Implements `ContextMenu.prototype.copy`
+pub fn copy<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let copy: Object<'gc> =
+        ScriptObject::object(context.gc_context, Some(avm.prototypes.context_menu)).into();
+    let callback = this.get("onSelect", avm, context)?.resolve(avm, context)?;
+    constructor(avm, context, copy, &[callback])?.resolve(avm, context)?;
+
+    if let (Value::Object(from), Value::Object(to)) = (
+        this.get("builtInItems", avm, context)?
+            .resolve(avm, context)?,
+        copy.get("builtInItems", avm, context)?
+            .resolve(avm, context)?,
+    ) {
+        for name in BUILT_IN_ITEMS {
+            let value = from.get(name, avm, context)?.resolve(avm, context)?;
+            to.set(name, value, avm, context)?;
+        }
+    }
+
+    if let (Value::Object(from), Value::Object(to)) = (
+        this.get("customItems", avm, context)?
+            .resolve(avm, context)?,
+        copy.get("customItems", avm, context)?
+            .resolve(avm, context)?,
+    ) {
+        for i in 0..from.length() {
+            let item = match from.array_element(i) {
+                Value::Object(item) => {
+                    let copy_fn = item.get("copy", avm, context)?.resolve(avm, context)?;
+                    match copy_fn {
+                        Value::Object(copy_fn) => copy_fn
+                            .call(avm, context, item, None, &[])?
+                            .resolve(avm, context)?,
+                        _ => Value::Object(item),
+                    }
+                }
+                item => item,
+            };
+            to.set_array_element(i, item, context.gc_context);
+        }
+    }
+
+    Ok(copy.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mut object = ScriptObject::object(gc_context, Some(proto));
+
+    object.force_set_function(
+        "copy",
+        copy,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+
+    object.force_set_function(
+        "hideBuiltInItems",
+        hide_built_in_items,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+
+    object.into()
+}
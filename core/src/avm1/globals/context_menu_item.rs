@@ -0,0 +1,75 @@
+//! `ContextMenuItem` impl
+
+use crate::avm1::property::Attribute::*;
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Error, Object, ScriptObject, TObject, UpdateContext, Value};
+use gc_arena::MutationContext;
+
+/// `new ContextMenuItem(caption, callback, separatorBefore, enabled, visible)`
+pub fn constructor<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let caption = match args.get(0) {
+        Some(caption) => caption.clone().coerce_to_string(avm, context)?,
+        None => "".to_string(),
+    };
+    let callback = args.get(1).cloned().unwrap_or(Value::Undefined);
+    let swf_version = avm.current_swf_version();
+    let flag = |index: usize, default: bool| {
+        args.get(index)
+            .map_or(default, |value| value.as_bool(swf_version))
+    };
+    let (separator_before, enabled, visible) = (flag(2, false), flag(3, true), flag(4, true));
+
+    this.set("caption", caption.into(), avm, context)?;
+    this.set("onSelect", callback, avm, context)?;
+    this.set("separatorBefore", separator_before.into(), avm, context)?;
+    this.set("enabled", enabled.into(), avm, context)?;
+    this.set("visible", visible.into(), avm, context)?;
+
+    Ok(Value::Undefined.into())
+}
+
+/// Implements `ContextMenuItem.prototype.copy`
+pub fn copy<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let copy: Object<'gc> =
+        ScriptObject::object(context.gc_context, Some(avm.prototypes.context_menu_item)).into();
+    for name in &[
+        "caption",
+        "onSelect",
+        "separatorBefore",
+        "enabled",
+        "visible",
+    ] {
+        let value = this.get(name, avm, context)?.resolve(avm, context)?;
+        copy.set(name, value, avm, context)?;
+    }
+
+    Ok(copy.into())
+}
+
+pub fn create_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mut object = ScriptObject::object(gc_context, Some(proto));
+
+    object.force_set_function(
+        "copy",
+        copy,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+
+    object.into()
+}
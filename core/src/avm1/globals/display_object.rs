@@ -1,6 +1,7 @@
 //! DisplayObject common methods
 
 use crate::avm1::function::Executable;
+use crate::avm1::globals::filters;
 use crate::avm1::property::Attribute::*;
 use crate::avm1::return_value::ReturnValue;
 use crate::avm1::{Avm1, Error, Object, ScriptObject, TObject, UpdateContext, Value};
@@ -81,6 +82,14 @@ pub fn define_display_object_proto<'gc>(
         None,
         DontDelete | ReadOnly | DontEnum,
     );
+
+    object.add_property(
+        gc_context,
+        "filters",
+        Executable::Native(get_filters),
+        Some(Executable::Native(set_filters)),
+        DontDelete | DontEnum,
+    );
 }
 
 pub fn get_depth<'gc>(
@@ -97,6 +106,54 @@ pub fn get_depth<'gc>(
     }
 }
 
+/// Implements the `filters` getter.
+/// Returns a new array of copies of the filters, so modifying it has no effect until it is
+/// assigned back to `filters`.
+pub fn get_filters<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(display_object) = this.as_display_object() {
+        let array = ScriptObject::array(context.gc_context, Some(avm.prototypes.array));
+        let swf_filters = display_object.filters().to_vec();
+        let mut i = 0;
+        for filter in &swf_filters {
+            if let Some(filter) = filters::filter_to_object(avm, context, filter)? {
+                array.set_array_element(i, filter.into(), context.gc_context);
+                i += 1;
+            }
+        }
+        return Ok(array.into());
+    }
+    Ok(Value::Undefined.into())
+}
+
+/// Implements the `filters` setter.
+/// The filters in the given array are copied onto the display object.
+pub fn set_filters<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(mut display_object) = this.as_display_object() {
+        let mut swf_filters = vec![];
+        if let Some(Value::Object(array)) = args.get(0) {
+            for value in array.array() {
+                if let Value::Object(filter) = value {
+                    if let Some(filter) = filters::object_to_filter(avm, context, filter)? {
+                        swf_filters.push(filter);
+                    }
+                }
+            }
+        }
+        display_object.set_filters(context.gc_context, swf_filters);
+    }
+    Ok(Value::Undefined.into())
+}
+
 pub fn overwrite_root<'gc>(
     _avm: &mut Avm1<'gc>,
     ac: &mut UpdateContext<'_, 'gc, '_>,
@@ -0,0 +1,378 @@
+//! flash.filters package
+
+use crate::avm1::function::{Executable, FunctionObject, NativeFunction};
+use crate::avm1::property::Attribute::*;
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Error, Object, ScriptObject, TObject, UpdateContext, Value};
+use enumset::EnumSet;
+use gc_arena::{Collect, MutationContext};
+
+mod bevel_filter;
+mod blur_filter;
+mod color_matrix_filter;
+mod convolution_filter;
+mod displacement_map_filter;
+mod drop_shadow_filter;
+mod glow_filter;
+
+/// The prototypes of the `flash.filters` classes.
+#[derive(Clone)]
+pub struct FilterPrototypes<'gc> {
+    pub bitmap_filter: Object<'gc>,
+    pub bevel_filter: Object<'gc>,
+    pub blur_filter: Object<'gc>,
+    pub color_matrix_filter: Object<'gc>,
+    pub convolution_filter: Object<'gc>,
+    pub displacement_map_filter: Object<'gc>,
+    pub drop_shadow_filter: Object<'gc>,
+    pub glow_filter: Object<'gc>,
+}
+
+unsafe impl<'gc> Collect for FilterPrototypes<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        self.bitmap_filter.trace(cc);
+        self.bevel_filter.trace(cc);
+        self.blur_filter.trace(cc);
+        self.color_matrix_filter.trace(cc);
+        self.convolution_filter.trace(cc);
+        self.displacement_map_filter.trace(cc);
+        self.drop_shadow_filter.trace(cc);
+        self.glow_filter.trace(cc);
+    }
+}
+
+/// Creates the `flash.filters` package object and the prototypes of its classes.
+pub fn create_filters_package<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> (FilterPrototypes<'gc>, Object<'gc>) {
+    let bitmap_filter = create_bitmap_filter_proto(gc_context, proto, fn_proto);
+    let prototypes = FilterPrototypes {
+        bitmap_filter,
+        bevel_filter: ScriptObject::object(gc_context, Some(bitmap_filter)).into(),
+        blur_filter: ScriptObject::object(gc_context, Some(bitmap_filter)).into(),
+        color_matrix_filter: ScriptObject::object(gc_context, Some(bitmap_filter)).into(),
+        convolution_filter: ScriptObject::object(gc_context, Some(bitmap_filter)).into(),
+        displacement_map_filter: ScriptObject::object(gc_context, Some(bitmap_filter)).into(),
+        drop_shadow_filter: ScriptObject::object(gc_context, Some(bitmap_filter)).into(),
+        glow_filter: ScriptObject::object(gc_context, Some(bitmap_filter)).into(),
+    };
+
+    let package = ScriptObject::object(gc_context, Some(proto));
+    let classes: [(&str, NativeFunction<'gc>, Object<'gc>); 8] = [
+        ("BitmapFilter", bitmap_filter_constructor, bitmap_filter),
+        (
+            "BevelFilter",
+            bevel_filter::constructor,
+            prototypes.bevel_filter,
+        ),
+        (
+            "BlurFilter",
+            blur_filter::constructor,
+            prototypes.blur_filter,
+        ),
+        (
+            "ColorMatrixFilter",
+            color_matrix_filter::constructor,
+            prototypes.color_matrix_filter,
+        ),
+        (
+            "ConvolutionFilter",
+            convolution_filter::constructor,
+            prototypes.convolution_filter,
+        ),
+        (
+            "DisplacementMapFilter",
+            displacement_map_filter::constructor,
+            prototypes.displacement_map_filter,
+        ),
+        (
+            "DropShadowFilter",
+            drop_shadow_filter::constructor,
+            prototypes.drop_shadow_filter,
+        ),
+        (
+            "GlowFilter",
+            glow_filter::constructor,
+            prototypes.glow_filter,
+        ),
+    ];
+    for (name, constructor, class_proto) in classes.iter() {
+        let constructor = FunctionObject::function(
+            gc_context,
+            Executable::Native(*constructor),
+            Some(fn_proto),
+            Some(*class_proto),
+        );
+        package.define_value(gc_context, name, constructor.into(), EnumSet::empty());
+    }
+
+    (prototypes, package.into())
+}
+
+fn create_bitmap_filter_proto<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Object<'gc>,
+    fn_proto: Object<'gc>,
+) -> Object<'gc> {
+    let mut object = ScriptObject::object(gc_context, Some(proto));
+
+    object.force_set_function(
+        "clone",
+        clone,
+        gc_context,
+        DontDelete | ReadOnly | DontEnum,
+        Some(fn_proto),
+    );
+
+    object.into()
+}
+
+/// Implements `BitmapFilter`
+pub fn bitmap_filter_constructor<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    Ok(Value::Undefined.into())
+}
+
+/// Implements `BitmapFilter.clone`.
+/// Array properties are copied, so the clone can be modified independently.
+pub fn clone<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let clone = ScriptObject::object(context.gc_context, this.proto());
+    for key in this.get_keys(avm) {
+        let value = this.get(&key, avm, context)?.resolve(avm, context)?;
+        let value = copy_array(avm, context, value);
+        clone.set(&key, value, avm, context)?;
+    }
+    Ok(clone.into())
+}
+
+/// Converts an AVM1 filter object into a `swf::Filter`.
+///
+/// Returns `None` if the object is not a filter that can be applied to a display object.
+pub fn object_to_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<Option<swf::Filter>, Error> {
+    let prototypes = avm.prototypes.filters.clone();
+    let filter = if has_proto(object, prototypes.bevel_filter) {
+        swf::Filter::BevelFilter(Box::new(bevel_filter::to_filter(avm, context, object)?))
+    } else if has_proto(object, prototypes.blur_filter) {
+        swf::Filter::BlurFilter(Box::new(blur_filter::to_filter(avm, context, object)?))
+    } else if has_proto(object, prototypes.color_matrix_filter) {
+        swf::Filter::ColorMatrixFilter(Box::new(color_matrix_filter::to_filter(
+            avm, context, object,
+        )?))
+    } else if has_proto(object, prototypes.convolution_filter) {
+        swf::Filter::ConvolutionFilter(Box::new(convolution_filter::to_filter(
+            avm, context, object,
+        )?))
+    } else if has_proto(object, prototypes.drop_shadow_filter) {
+        swf::Filter::DropShadowFilter(Box::new(drop_shadow_filter::to_filter(
+            avm, context, object,
+        )?))
+    } else if has_proto(object, prototypes.glow_filter) {
+        swf::Filter::GlowFilter(Box::new(glow_filter::to_filter(avm, context, object)?))
+    } else {
+        if has_proto(object, prototypes.displacement_map_filter) {
+            log::warn!("DisplacementMapFilter is not yet supported on display objects");
+        }
+        return Ok(None);
+    };
+    Ok(Some(filter))
+}
+
+/// Creates a new AVM1 filter object from a `swf::Filter`.
+///
+/// Returns `None` for filters that have no ActionScript class.
+pub fn filter_to_object<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    filter: &swf::Filter,
+) -> Result<Option<Object<'gc>>, Error> {
+    let object = match filter {
+        swf::Filter::BevelFilter(filter) => bevel_filter::from_filter(avm, context, filter)?,
+        swf::Filter::BlurFilter(filter) => blur_filter::from_filter(avm, context, filter)?,
+        swf::Filter::ColorMatrixFilter(filter) => {
+            color_matrix_filter::from_filter(avm, context, filter)?
+        }
+        swf::Filter::ConvolutionFilter(filter) => {
+            convolution_filter::from_filter(avm, context, filter)?
+        }
+        swf::Filter::DropShadowFilter(filter) => {
+            drop_shadow_filter::from_filter(avm, context, filter)?
+        }
+        swf::Filter::GlowFilter(filter) => glow_filter::from_filter(avm, context, filter)?,
+        swf::Filter::GradientBevelFilter(_) | swf::Filter::GradientGlowFilter(_) => {
+            return Ok(None)
+        }
+    };
+    Ok(Some(object))
+}
+
+/// Returns whether `prototype` is in the prototype chain of `object`.
+fn has_proto<'gc>(object: Object<'gc>, prototype: Object<'gc>) -> bool {
+    let mut proto = object.proto();
+    while let Some(p) = proto {
+        if Object::ptr_eq(p, prototype) {
+            return true;
+        }
+        proto = p.proto();
+    }
+    false
+}
+
+/// Assigns the properties of a newly constructed filter from the constructor arguments,
+/// falling back to the given defaults for any missing or undefined arguments.
+fn init_properties<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+    properties: &[(&str, Value<'gc>)],
+) -> Result<(), Error> {
+    for (i, (name, default)) in properties.iter().enumerate() {
+        let value = match args.get(i) {
+            Some(Value::Undefined) | None => default.clone(),
+            Some(value) => copy_array(avm, context, value.clone()),
+        };
+        this.set(name, value, avm, context)?;
+    }
+    Ok(())
+}
+
+/// Returns a copy of the given value if it is an array, so that filters don't share
+/// arrays with ActionScript code.
+fn copy_array<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    value: Value<'gc>,
+) -> Value<'gc> {
+    if let Value::Object(object) = &value {
+        let is_array = object
+            .proto()
+            .map(|proto| Object::ptr_eq(proto, avm.prototypes.array))
+            .unwrap_or(false);
+        if is_array {
+            return new_array(avm, context, object.array()).into();
+        }
+    }
+    value
+}
+
+/// Creates a new AVM1 array containing the given values.
+fn new_array<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    values: Vec<Value<'gc>>,
+) -> Object<'gc> {
+    let array = ScriptObject::array(context.gc_context, Some(avm.prototypes.array));
+    for (i, value) in values.into_iter().enumerate() {
+        array.set_array_element(i, value, context.gc_context);
+    }
+    array.into()
+}
+
+fn get_value<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+    name: &str,
+) -> Result<Value<'gc>, Error> {
+    object.get(name, avm, context)?.resolve(avm, context)
+}
+
+fn get_number<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+    name: &str,
+) -> Result<f64, Error> {
+    let n = get_value(avm, context, object, name)?.as_number(avm, context)?;
+    Ok(if n.is_finite() { n } else { 0.0 })
+}
+
+fn get_bool<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+    name: &str,
+) -> Result<bool, Error> {
+    Ok(get_value(avm, context, object, name)?.as_bool(avm.current_swf_version()))
+}
+
+/// Reads an array of numbers from a property of a filter object.
+fn get_numbers<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+    name: &str,
+) -> Result<Vec<f64>, Error> {
+    let mut numbers = vec![];
+    if let Value::Object(array) = get_value(avm, context, object, name)? {
+        for value in array.array() {
+            numbers.push(value.as_number(avm, context)?);
+        }
+    }
+    Ok(numbers)
+}
+
+/// Reads the `quality` property of a filter object as a number of passes.
+fn get_quality<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<u8, Error> {
+    Ok(get_number(avm, context, object, "quality")?
+        .max(0.0)
+        .min(15.0) as u8)
+}
+
+/// Reads a color from an RGB property and an alpha property of a filter object.
+fn get_color<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+    color_name: &str,
+    alpha_name: &str,
+) -> Result<swf::Color, Error> {
+    let rgb = get_value(avm, context, object, color_name)?.coerce_to_u32(avm, context)?;
+    let alpha = get_number(avm, context, object, alpha_name)?;
+    Ok(swf::Color {
+        r: (rgb >> 16) as u8,
+        g: (rgb >> 8) as u8,
+        b: rgb as u8,
+        a: (alpha.max(0.0).min(1.0) * 255.0).round() as u8,
+    })
+}
+
+/// Splits a color into the RGB and alpha values used by filter objects.
+fn color_values<'gc>(color: &swf::Color) -> (Value<'gc>, Value<'gc>) {
+    let rgb = (u32::from(color.r) << 16) | (u32::from(color.g) << 8) | u32::from(color.b);
+    (rgb.into(), (f64::from(color.a) / 255.0).into())
+}
+
+/// Creates a new filter object with the given prototype and properties.
+fn new_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    proto: Object<'gc>,
+    properties: Vec<(&str, Value<'gc>)>,
+) -> Result<Object<'gc>, Error> {
+    let object = ScriptObject::object(context.gc_context, Some(proto));
+    for (name, value) in properties {
+        object.set(name, value, avm, context)?;
+    }
+    Ok(object.into())
+}
@@ -0,0 +1,100 @@
+//! flash.filters.BevelFilter object
+
+use super::{
+    color_values, get_bool, get_color, get_number, get_quality, get_value, init_properties,
+    new_filter,
+};
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Error, Object, UpdateContext, Value};
+
+/// Implements `BevelFilter`
+pub fn constructor<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    init_properties(
+        avm,
+        context,
+        this,
+        args,
+        &[
+            ("distance", 4.into()),
+            ("angle", 45.into()),
+            ("highlightColor", 0xFFFFFF.into()),
+            ("highlightAlpha", 1.into()),
+            ("shadowColor", 0.into()),
+            ("shadowAlpha", 1.into()),
+            ("blurX", 4.into()),
+            ("blurY", 4.into()),
+            ("strength", 1.into()),
+            ("quality", 1.into()),
+            ("type", "inner".into()),
+            ("knockout", false.into()),
+        ],
+    )?;
+    Ok(Value::Undefined.into())
+}
+
+pub fn to_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<swf::BevelFilter, Error> {
+    let bevel_type = get_value(avm, context, object, "type")?.coerce_to_string(avm, context)?;
+    let (is_inner, is_on_top) = match bevel_type.as_str() {
+        "outer" => (false, false),
+        "full" => (false, true),
+        _ => (true, false),
+    };
+    Ok(swf::BevelFilter {
+        shadow_color: get_color(avm, context, object, "shadowColor", "shadowAlpha")?,
+        highlight_color: get_color(avm, context, object, "highlightColor", "highlightAlpha")?,
+        blur_x: get_number(avm, context, object, "blurX")?,
+        blur_y: get_number(avm, context, object, "blurY")?,
+        angle: get_number(avm, context, object, "angle")?.to_radians(),
+        distance: get_number(avm, context, object, "distance")?,
+        strength: get_number(avm, context, object, "strength")? as f32,
+        is_inner,
+        is_knockout: get_bool(avm, context, object, "knockout")?,
+        is_on_top,
+        num_passes: get_quality(avm, context, object)?,
+    })
+}
+
+pub fn from_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    filter: &swf::BevelFilter,
+) -> Result<Object<'gc>, Error> {
+    let proto = avm.prototypes.filters.bevel_filter;
+    let (highlight_color, highlight_alpha) = color_values(&filter.highlight_color);
+    let (shadow_color, shadow_alpha) = color_values(&filter.shadow_color);
+    let bevel_type = if filter.is_on_top {
+        "full"
+    } else if filter.is_inner {
+        "inner"
+    } else {
+        "outer"
+    };
+    new_filter(
+        avm,
+        context,
+        proto,
+        vec![
+            ("distance", filter.distance.into()),
+            ("angle", filter.angle.to_degrees().into()),
+            ("highlightColor", highlight_color),
+            ("highlightAlpha", highlight_alpha),
+            ("shadowColor", shadow_color),
+            ("shadowAlpha", shadow_alpha),
+            ("blurX", filter.blur_x.into()),
+            ("blurY", filter.blur_y.into()),
+            ("strength", filter.strength.into()),
+            ("quality", filter.num_passes.into()),
+            ("type", bevel_type.into()),
+            ("knockout", filter.is_knockout.into()),
+        ],
+    )
+}
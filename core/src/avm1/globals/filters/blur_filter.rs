@@ -0,0 +1,56 @@
+//! flash.filters.BlurFilter object
+
+use super::{get_number, get_quality, init_properties, new_filter};
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Error, Object, UpdateContext, Value};
+
+/// Implements `BlurFilter`
+pub fn constructor<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    init_properties(
+        avm,
+        context,
+        this,
+        args,
+        &[
+            ("blurX", 4.into()),
+            ("blurY", 4.into()),
+            ("quality", 1.into()),
+        ],
+    )?;
+    Ok(Value::Undefined.into())
+}
+
+pub fn to_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<swf::BlurFilter, Error> {
+    Ok(swf::BlurFilter {
+        blur_x: get_number(avm, context, object, "blurX")?,
+        blur_y: get_number(avm, context, object, "blurY")?,
+        num_passes: get_quality(avm, context, object)?,
+    })
+}
+
+pub fn from_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    filter: &swf::BlurFilter,
+) -> Result<Object<'gc>, Error> {
+    let proto = avm.prototypes.filters.blur_filter;
+    new_filter(
+        avm,
+        context,
+        proto,
+        vec![
+            ("blurX", filter.blur_x.into()),
+            ("blurY", filter.blur_y.into()),
+            ("quality", filter.num_passes.into()),
+        ],
+    )
+}
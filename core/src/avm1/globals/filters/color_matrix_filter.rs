@@ -0,0 +1,59 @@
+//! flash.filters.ColorMatrixFilter object
+
+use super::{get_numbers, init_properties, new_array, new_filter};
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Error, Object, UpdateContext, Value};
+
+/// The identity color matrix, used when no matrix is given.
+const IDENTITY: [f64; 20] = [
+    1.0, 0.0, 0.0, 0.0, 0.0, //
+    0.0, 1.0, 0.0, 0.0, 0.0, //
+    0.0, 0.0, 1.0, 0.0, 0.0, //
+    0.0, 0.0, 0.0, 1.0, 0.0, //
+];
+
+/// Implements `ColorMatrixFilter`
+pub fn constructor<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let identity = matrix_to_array(avm, context, &IDENTITY);
+    init_properties(avm, context, this, args, &[("matrix", identity.into())])?;
+    Ok(Value::Undefined.into())
+}
+
+pub fn to_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<swf::ColorMatrixFilter, Error> {
+    // Missing entries are treated as 0.
+    let mut matrix = [0.0; 20];
+    for (dest, value) in matrix
+        .iter_mut()
+        .zip(get_numbers(avm, context, object, "matrix")?)
+    {
+        *dest = value;
+    }
+    Ok(swf::ColorMatrixFilter { matrix })
+}
+
+pub fn from_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    filter: &swf::ColorMatrixFilter,
+) -> Result<Object<'gc>, Error> {
+    let proto = avm.prototypes.filters.color_matrix_filter;
+    let matrix = matrix_to_array(avm, context, &filter.matrix);
+    new_filter(avm, context, proto, vec![("matrix", matrix.into())])
+}
+
+fn matrix_to_array<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    matrix: &[f64],
+) -> Object<'gc> {
+    new_array(avm, context, matrix.iter().map(|n| (*n).into()).collect())
+}
@@ -0,0 +1,97 @@
+//! flash.filters.ConvolutionFilter object
+
+use super::{
+    color_values, get_bool, get_color, get_number, get_numbers, init_properties, new_array,
+    new_filter,
+};
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Error, Object, UpdateContext, Value};
+
+/// Implements `ConvolutionFilter`
+pub fn constructor<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let matrix = new_array(avm, context, vec![]);
+    init_properties(
+        avm,
+        context,
+        this,
+        args,
+        &[
+            ("matrixX", 0.into()),
+            ("matrixY", 0.into()),
+            ("matrix", matrix.into()),
+            ("divisor", 1.into()),
+            ("bias", 0.into()),
+            ("preserveAlpha", true.into()),
+            ("clamp", true.into()),
+            ("color", 0.into()),
+            ("alpha", 0.into()),
+        ],
+    )?;
+    Ok(Value::Undefined.into())
+}
+
+pub fn to_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<swf::ConvolutionFilter, Error> {
+    let num_matrix_cols = get_number(avm, context, object, "matrixX")?
+        .max(0.0)
+        .min(15.0) as u8;
+    let num_matrix_rows = get_number(avm, context, object, "matrixY")?
+        .max(0.0)
+        .min(15.0) as u8;
+
+    // The matrix is padded or truncated to `matrixX * matrixY` entries.
+    let mut matrix = get_numbers(avm, context, object, "matrix")?;
+    matrix.resize(
+        usize::from(num_matrix_cols) * usize::from(num_matrix_rows),
+        0.0,
+    );
+
+    Ok(swf::ConvolutionFilter {
+        num_matrix_rows,
+        num_matrix_cols,
+        matrix,
+        divisor: get_number(avm, context, object, "divisor")?,
+        bias: get_number(avm, context, object, "bias")?,
+        default_color: get_color(avm, context, object, "color", "alpha")?,
+        is_clamped: get_bool(avm, context, object, "clamp")?,
+        is_preserve_alpha: get_bool(avm, context, object, "preserveAlpha")?,
+    })
+}
+
+pub fn from_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    filter: &swf::ConvolutionFilter,
+) -> Result<Object<'gc>, Error> {
+    let proto = avm.prototypes.filters.convolution_filter;
+    let matrix = new_array(
+        avm,
+        context,
+        filter.matrix.iter().map(|n| (*n).into()).collect(),
+    );
+    let (color, alpha) = color_values(&filter.default_color);
+    new_filter(
+        avm,
+        context,
+        proto,
+        vec![
+            ("matrixX", filter.num_matrix_cols.into()),
+            ("matrixY", filter.num_matrix_rows.into()),
+            ("matrix", matrix.into()),
+            ("divisor", filter.divisor.into()),
+            ("bias", filter.bias.into()),
+            ("preserveAlpha", filter.is_preserve_alpha.into()),
+            ("clamp", filter.is_clamped.into()),
+            ("color", color),
+            ("alpha", alpha),
+        ],
+    )
+}
@@ -0,0 +1,37 @@
+//! flash.filters.DisplacementMapFilter object
+
+use super::init_properties;
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Error, Object, ScriptObject, TObject, UpdateContext, Value};
+
+/// Implements `DisplacementMapFilter`
+///
+/// This filter has no equivalent in SWF tags, so it can't yet be applied to display objects.
+pub fn constructor<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let map_point = ScriptObject::object(context.gc_context, Some(avm.prototypes.object));
+    map_point.set("x", 0.into(), avm, context)?;
+    map_point.set("y", 0.into(), avm, context)?;
+    init_properties(
+        avm,
+        context,
+        this,
+        args,
+        &[
+            ("mapBitmap", Value::Undefined),
+            ("mapPoint", map_point.into()),
+            ("componentX", 0.into()),
+            ("componentY", 0.into()),
+            ("scaleX", 0.into()),
+            ("scaleY", 0.into()),
+            ("mode", "wrap".into()),
+            ("color", 0.into()),
+            ("alpha", 0.into()),
+        ],
+    )?;
+    Ok(Value::Undefined.into())
+}
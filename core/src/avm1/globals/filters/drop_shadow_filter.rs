@@ -0,0 +1,82 @@
+//! flash.filters.DropShadowFilter object
+
+use super::{
+    color_values, get_bool, get_color, get_number, get_quality, init_properties, new_filter,
+};
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Error, Object, UpdateContext, Value};
+
+/// Implements `DropShadowFilter`
+pub fn constructor<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    init_properties(
+        avm,
+        context,
+        this,
+        args,
+        &[
+            ("distance", 4.into()),
+            ("angle", 45.into()),
+            ("color", 0.into()),
+            ("alpha", 1.into()),
+            ("blurX", 4.into()),
+            ("blurY", 4.into()),
+            ("strength", 1.into()),
+            ("quality", 1.into()),
+            ("inner", false.into()),
+            ("knockout", false.into()),
+            ("hideObject", false.into()),
+        ],
+    )?;
+    Ok(Value::Undefined.into())
+}
+
+pub fn to_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<swf::DropShadowFilter, Error> {
+    // TODO: `hideObject` is not stored in `swf::DropShadowFilter`.
+    Ok(swf::DropShadowFilter {
+        color: get_color(avm, context, object, "color", "alpha")?,
+        blur_x: get_number(avm, context, object, "blurX")?,
+        blur_y: get_number(avm, context, object, "blurY")?,
+        angle: get_number(avm, context, object, "angle")?.to_radians(),
+        distance: get_number(avm, context, object, "distance")?,
+        strength: get_number(avm, context, object, "strength")? as f32,
+        is_inner: get_bool(avm, context, object, "inner")?,
+        is_knockout: get_bool(avm, context, object, "knockout")?,
+        num_passes: get_quality(avm, context, object)?,
+    })
+}
+
+pub fn from_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    filter: &swf::DropShadowFilter,
+) -> Result<Object<'gc>, Error> {
+    let proto = avm.prototypes.filters.drop_shadow_filter;
+    let (color, alpha) = color_values(&filter.color);
+    new_filter(
+        avm,
+        context,
+        proto,
+        vec![
+            ("distance", filter.distance.into()),
+            ("angle", filter.angle.to_degrees().into()),
+            ("color", color),
+            ("alpha", alpha),
+            ("blurX", filter.blur_x.into()),
+            ("blurY", filter.blur_y.into()),
+            ("strength", filter.strength.into()),
+            ("quality", filter.num_passes.into()),
+            ("inner", filter.is_inner.into()),
+            ("knockout", filter.is_knockout.into()),
+            ("hideObject", false.into()),
+        ],
+    )
+}
@@ -0,0 +1,73 @@
+//! flash.filters.GlowFilter object
+
+use super::{
+    color_values, get_bool, get_color, get_number, get_quality, init_properties, new_filter,
+};
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Error, Object, UpdateContext, Value};
+
+/// Implements `GlowFilter`
+pub fn constructor<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    init_properties(
+        avm,
+        context,
+        this,
+        args,
+        &[
+            ("color", 0xFF0000.into()),
+            ("alpha", 1.into()),
+            ("blurX", 6.into()),
+            ("blurY", 6.into()),
+            ("strength", 2.into()),
+            ("quality", 1.into()),
+            ("inner", false.into()),
+            ("knockout", false.into()),
+        ],
+    )?;
+    Ok(Value::Undefined.into())
+}
+
+pub fn to_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: Object<'gc>,
+) -> Result<swf::GlowFilter, Error> {
+    Ok(swf::GlowFilter {
+        color: get_color(avm, context, object, "color", "alpha")?,
+        blur_x: get_number(avm, context, object, "blurX")?,
+        blur_y: get_number(avm, context, object, "blurY")?,
+        strength: get_number(avm, context, object, "strength")? as f32,
+        is_inner: get_bool(avm, context, object, "inner")?,
+        is_knockout: get_bool(avm, context, object, "knockout")?,
+        num_passes: get_quality(avm, context, object)?,
+    })
+}
+
+pub fn from_filter<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    filter: &swf::GlowFilter,
+) -> Result<Object<'gc>, Error> {
+    let proto = avm.prototypes.filters.glow_filter;
+    let (color, alpha) = color_values(&filter.color);
+    new_filter(
+        avm,
+        context,
+        proto,
+        vec![
+            ("color", color),
+            ("alpha", alpha),
+            ("blurX", filter.blur_x.into()),
+            ("blurY", filter.blur_y.into()),
+            ("strength", filter.strength.into()),
+            ("quality", filter.num_passes.into()),
+            ("inner", filter.is_inner.into()),
+            ("knockout", filter.is_knockout.into()),
+        ],
+    )
+}
@@ -1,3 +1,4 @@
+use crate::avm1::globals::as_broadcaster::BroadcasterFunctions;
 use crate::avm1::property::Attribute;
 use crate::avm1::return_value::ReturnValue;
 use crate::avm1::{Avm1, Error, Object, ScriptObject, TObject, UpdateContext, Value};
@@ -33,13 +34,48 @@ pub fn get_code<'gc>(
     Ok(code.into())
 }
 
+pub fn get_ascii<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let ascii = context
+        .input
+        .get_last_key_char()
+        .map(u32::from)
+        .unwrap_or(0);
+    Ok(ascii.into())
+}
+
+pub fn is_toggled<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(key) = args
+        .get(0)
+        .and_then(|v| v.as_number(avm, context).ok())
+        .and_then(|k| KeyCode::try_from(k as u8).ok())
+    {
+        Ok(context.input.is_key_toggled(key).into())
+    } else {
+        Ok(false.into())
+    }
+}
+
 pub fn create_key_object<'gc>(
     gc_context: MutationContext<'gc, '_>,
     proto: Option<Object<'gc>>,
     fn_proto: Option<Object<'gc>>,
+    array_proto: Option<Object<'gc>>,
+    broadcaster_functions: BroadcasterFunctions<'gc>,
 ) -> Object<'gc> {
     let mut key = ScriptObject::object(gc_context, proto);
 
+    broadcaster_functions.initialize(gc_context, key.into(), array_proto);
+
     key.define_value(
         gc_context,
         "ALT",
@@ -171,5 +207,21 @@ pub fn create_key_object<'gc>(
         fn_proto,
     );
 
+    key.force_set_function(
+        "getAscii",
+        get_ascii,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        fn_proto,
+    );
+
+    key.force_set_function(
+        "isToggled",
+        is_toggled,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        fn_proto,
+    );
+
     key.into()
 }
@@ -1,7 +1,7 @@
-use crate::avm1::listeners::Listeners;
+use crate::avm1::globals::as_broadcaster::BroadcasterFunctions;
 use crate::avm1::property::Attribute;
 use crate::avm1::return_value::ReturnValue;
-use crate::avm1::{Avm1, Error, Object, ScriptObject, TObject, UpdateContext, Value};
+use crate::avm1::{Avm1, Error, Object, ScriptObject, UpdateContext, Value};
 
 use gc_arena::MutationContext;
 
@@ -39,11 +39,12 @@ pub fn create_mouse_object<'gc>(
     gc_context: MutationContext<'gc, '_>,
     proto: Option<Object<'gc>>,
     fn_proto: Option<Object<'gc>>,
-    listener: &Listeners<'gc>,
+    array_proto: Option<Object<'gc>>,
+    broadcaster_functions: BroadcasterFunctions<'gc>,
 ) -> Object<'gc> {
     let mut mouse = ScriptObject::object(gc_context, proto);
 
-    register_listener!(gc_context, mouse, listener, fn_proto, mouse);
+    broadcaster_functions.initialize(gc_context, mouse.into(), array_proto);
 
     mouse.force_set_function(
         "show",
@@ -1,15 +1,16 @@
 //! MovieClip prototype
 
+use crate::avm1::globals::bitmap_data;
 use crate::avm1::globals::display_object::{self, AVM_DEPTH_BIAS, AVM_MAX_DEPTH};
 use crate::avm1::property::Attribute::*;
 use crate::avm1::return_value::ReturnValue;
 use crate::avm1::{Avm1, Error, Object, ScriptObject, TObject, UpdateContext, Value};
 use crate::backend::navigator::NavigationMethod;
-use crate::display_object::{DisplayObject, EditText, MovieClip, TDisplayObject};
+use crate::display_object::{Bitmap, DisplayObject, EditText, MovieClip, TDisplayObject};
 use crate::prelude::*;
 use crate::tag_utils::SwfSlice;
 use gc_arena::MutationContext;
-use swf::Twips;
+use swf::{FillStyle, LineStyle, Twips};
 
 /// Implements `MovieClip`
 pub fn constructor<'gc>(
@@ -98,10 +99,16 @@ pub fn create_proto<'gc>(
         gc_context,
         object,
         Some(fn_proto),
+        "attachBitmap" => attach_bitmap,
         "attachMovie" => attach_movie,
+        "beginBitmapFill" => begin_bitmap_fill,
+        "beginFill" => begin_fill,
+        "clear" => clear,
         "createEmptyMovieClip" => create_empty_movie_clip,
         "createTextField" => create_text_field,
+        "curveTo" => curve_to,
         "duplicateMovieClip" => duplicate_movie_clip,
+        "endFill" => end_fill,
         "getBounds" => get_bounds,
         "getBytesLoaded" => get_bytes_loaded,
         "getBytesTotal" => get_bytes_total,
@@ -111,12 +118,17 @@ pub fn create_proto<'gc>(
         "gotoAndPlay" => goto_and_play,
         "gotoAndStop" => goto_and_stop,
         "hitTest" => hit_test,
+        "lineStyle" => line_style,
+        "lineTo" => line_to,
         "loadMovie" => load_movie,
         "loadVariables" => load_variables,
         "localToGlobal" => local_to_global,
+        "moveTo" => move_to,
         "nextFrame" => next_frame,
+        "nextScene" => next_scene,
         "play" => play,
         "prevFrame" => prev_frame,
+        "prevScene" => prev_scene,
         "removeMovieClip" => remove_movie_clip,
         "startDrag" => start_drag,
         "stop" => stop,
@@ -129,6 +141,39 @@ pub fn create_proto<'gc>(
     object.into()
 }
 
+fn attach_bitmap<'gc>(
+    mut movie_clip: MovieClip<'gc>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let bitmap_data = args
+        .get(0)
+        .and_then(|v| v.as_object().ok())
+        .and_then(|o| o.as_bitmap_data_object());
+    let depth = args
+        .get(1)
+        .unwrap_or(&Value::Undefined)
+        .coerce_to_i32(avm, context)?
+        .wrapping_add(AVM_DEPTH_BIAS);
+
+    if let Some(bitmap_data) = bitmap_data {
+        if bitmap_data.bitmap_data().disposed() {
+            return Ok(Value::Undefined.into());
+        }
+        if depth < 0 || depth > AVM_MAX_DEPTH {
+            return Ok(Value::Undefined.into());
+        }
+        // TODO: `pixelSnapping` and `smoothing` parameters are ignored.
+        let bitmap = Bitmap::new_with_bitmap_data(context, bitmap_data);
+        movie_clip.add_child_from_avm(context, bitmap.into(), depth);
+    } else {
+        log::warn!("MovieClip.attachBitmap: Expected a BitmapData");
+    }
+
+    Ok(Value::Undefined.into())
+}
+
 fn attach_movie<'gc>(
     mut movie_clip: MovieClip<'gc>,
     avm: &mut Avm1<'gc>,
@@ -178,6 +223,189 @@ fn attach_movie<'gc>(
     }
 }
 
+/// Converts an RGB color and an alpha percentage into a `Color`.
+fn drawing_color(rgb: u32, alpha: f64) -> Color {
+    Color {
+        r: (rgb >> 16) as u8,
+        g: (rgb >> 8) as u8,
+        b: rgb as u8,
+        a: (alpha.max(0.0).min(100.0) * 2.55) as u8,
+    }
+}
+
+/// Reads the argument at `index` as a coordinate in pixels.
+fn drawing_coordinate<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+    index: usize,
+) -> Result<Twips, Error> {
+    let value = args.get(index).unwrap_or(&Value::Undefined);
+    Ok(Twips::from_pixels(value.as_number(avm, context)?))
+}
+
+fn begin_bitmap_fill<'gc>(
+    movie_clip: MovieClip<'gc>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let bitmap_data = args
+        .get(0)
+        .and_then(|v| v.as_object().ok())
+        .and_then(|o| o.as_bitmap_data_object())
+        .filter(|bitmap_data| !bitmap_data.bitmap_data().disposed());
+
+    if let Some(bitmap_data) = bitmap_data {
+        let matrix = bitmap_data::matrix_arg(avm, context, args.get(1))?;
+        let is_repeating = args
+            .get(2)
+            .map(|v| v.as_bool(avm.current_swf_version()))
+            .unwrap_or(true);
+        let is_smoothed = args
+            .get(3)
+            .map(|v| v.as_bool(avm.current_swf_version()))
+            .unwrap_or(false);
+        let handle = bitmap_data
+            .bitmap_data_mut(context.gc_context)
+            .register(context.renderer);
+        // SWF bitmap fill matrices map bitmap pixels to twips.
+        let matrix = swf::Matrix {
+            scale_x: matrix.a * 20.0,
+            rotate_skew_0: matrix.b * 20.0,
+            rotate_skew_1: matrix.c * 20.0,
+            scale_y: matrix.d * 20.0,
+            translate_x: matrix.tx,
+            translate_y: matrix.ty,
+        };
+        movie_clip.drawing(context.gc_context).begin_bitmap_fill(
+            bitmap_data,
+            handle,
+            matrix,
+            is_repeating,
+            is_smoothed,
+        );
+    } else {
+        log::warn!("MovieClip.beginBitmapFill: Expected a BitmapData");
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+fn begin_fill<'gc>(
+    movie_clip: MovieClip<'gc>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    match args.get(0) {
+        Some(Value::Undefined) | None => movie_clip.drawing(context.gc_context).end_fill(),
+        Some(rgb) => {
+            let rgb = rgb.coerce_to_u32(avm, context)?;
+            let alpha = match args.get(1) {
+                Some(alpha) => alpha.as_number(avm, context)?,
+                None => 100.0,
+            };
+            movie_clip
+                .drawing(context.gc_context)
+                .begin_fill(FillStyle::Color(drawing_color(rgb, alpha)));
+        }
+    }
+    Ok(Value::Undefined.into())
+}
+
+fn clear<'gc>(
+    movie_clip: MovieClip<'gc>,
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    movie_clip.drawing(context.gc_context).clear();
+    Ok(Value::Undefined.into())
+}
+
+fn curve_to<'gc>(
+    movie_clip: MovieClip<'gc>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let control_x = drawing_coordinate(avm, context, args, 0)?;
+    let control_y = drawing_coordinate(avm, context, args, 1)?;
+    let anchor_x = drawing_coordinate(avm, context, args, 2)?;
+    let anchor_y = drawing_coordinate(avm, context, args, 3)?;
+    movie_clip
+        .drawing(context.gc_context)
+        .curve_to(control_x, control_y, anchor_x, anchor_y);
+    Ok(Value::Undefined.into())
+}
+
+fn end_fill<'gc>(
+    movie_clip: MovieClip<'gc>,
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    movie_clip.drawing(context.gc_context).end_fill();
+    Ok(Value::Undefined.into())
+}
+
+fn line_style<'gc>(
+    movie_clip: MovieClip<'gc>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    match args.get(0) {
+        Some(Value::Undefined) | None => movie_clip
+            .drawing(context.gc_context)
+            .set_line_style(None),
+        Some(thickness) => {
+            let thickness = thickness.as_number(avm, context)?.max(0.0).min(255.0);
+            let rgb = match args.get(1) {
+                Some(rgb) => rgb.coerce_to_u32(avm, context)?,
+                None => 0,
+            };
+            let alpha = match args.get(2) {
+                Some(alpha) => alpha.as_number(avm, context)?,
+                None => 100.0,
+            };
+            let style = LineStyle::new_v1(
+                Twips::from_pixels(thickness),
+                drawing_color(rgb, alpha),
+            );
+            movie_clip
+                .drawing(context.gc_context)
+                .set_line_style(Some(style));
+        }
+    }
+    Ok(Value::Undefined.into())
+}
+
+fn line_to<'gc>(
+    movie_clip: MovieClip<'gc>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let x = drawing_coordinate(avm, context, args, 0)?;
+    let y = drawing_coordinate(avm, context, args, 1)?;
+    movie_clip.drawing(context.gc_context).line_to(x, y);
+    Ok(Value::Undefined.into())
+}
+
+fn move_to<'gc>(
+    movie_clip: MovieClip<'gc>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let x = drawing_coordinate(avm, context, args, 0)?;
+    let y = drawing_coordinate(avm, context, args, 1)?;
+    movie_clip.drawing(context.gc_context).move_to(x, y);
+    Ok(Value::Undefined.into())
+}
+
 fn create_empty_movie_clip<'gc>(
     mut movie_clip: MovieClip<'gc>,
     avm: &mut Avm1<'gc>,
@@ -402,6 +630,17 @@ pub fn goto_frame<'gc>(
     stop: bool,
     scene_offset: u16,
 ) -> Result<ReturnValue<'gc>, Error> {
+    let mut args = args;
+    let mut frame_offset = scene_offset;
+    if let (Some(Value::String(scene_label)), Some(_)) = (args.get(0), args.get(1)) {
+        // `gotoAndPlay(scene, frame)`: frame numbers are relative to the named scene.
+        // If this clip has no such scene, the first argument is the frame as usual.
+        if let Some(start) = movie_clip.scene_label_to_number(scene_label) {
+            frame_offset = frame_offset.wrapping_add(start - 1);
+            args = &args[1..];
+        }
+    }
+
     match args.get(0).cloned().unwrap_or(Value::Undefined) {
         // Goto only runs if n is an integer
         Value::Number(n) if n.fract() == 0.0 => {
@@ -411,18 +650,21 @@ pub fn goto_frame<'gc>(
             // Wraps around as an i32.
             // TODO: -1 +1 here to match Flash's behavior.
             // We probably want to change our frame representation to 0-based.
-            // Scene offset is only used by GotoFrame2 global opcode.
+            // Frame offset is the GotoFrame2 scene bias, or the start of the named scene.
             let mut frame = crate::avm1::value::f64_to_wrapping_i32(n);
             frame = frame.wrapping_sub(1);
-            frame = frame.wrapping_add(i32::from(scene_offset));
+            frame = frame.wrapping_add(i32::from(frame_offset));
             if frame >= 0 {
                 movie_clip.goto_frame(avm, context, frame.saturating_add(1) as u16, stop);
             }
         }
         val => {
-            // Coerce to string and search for a frame label.
+            // Coerce to string and search for a frame label, then a scene.
             let frame_label = val.clone().coerce_to_string(avm, context)?;
-            if let Some(mut frame) = movie_clip.frame_label_to_number(&frame_label) {
+            let frame = movie_clip
+                .frame_label_to_number(&frame_label)
+                .or_else(|| movie_clip.scene_label_to_number(&frame_label));
+            if let Some(mut frame) = frame {
                 frame = frame.wrapping_add(scene_offset);
                 movie_clip.goto_frame(avm, context, frame, stop);
             }
@@ -441,6 +683,16 @@ fn next_frame<'gc>(
     Ok(Value::Undefined.into())
 }
 
+fn next_scene<'gc>(
+    movie_clip: MovieClip<'gc>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    movie_clip.next_scene(avm, context);
+    Ok(Value::Undefined.into())
+}
+
 fn play<'gc>(
     movie_clip: MovieClip<'gc>,
     _avm: &mut Avm1<'gc>,
@@ -461,6 +713,16 @@ fn prev_frame<'gc>(
     Ok(Value::Undefined.into())
 }
 
+fn prev_scene<'gc>(
+    movie_clip: MovieClip<'gc>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    movie_clip.prev_scene(avm, context);
+    Ok(Value::Undefined.into())
+}
+
 fn remove_movie_clip<'gc>(
     movie_clip: MovieClip<'gc>,
     _avm: &mut Avm1<'gc>,
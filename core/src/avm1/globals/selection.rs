@@ -0,0 +1,76 @@
+//! Selection object
+//!
+//! TODO: Text selection ranges (`getBeginIndex`, `setSelection`, etc.) are not implemented.
+use crate::avm1::globals::as_broadcaster::BroadcasterFunctions;
+use crate::avm1::property::Attribute;
+use crate::avm1::return_value::ReturnValue;
+use crate::avm1::{Avm1, Error, Object, ScriptObject, UpdateContext, Value};
+use crate::focus_tracker;
+use crate::prelude::*;
+
+use gc_arena::MutationContext;
+
+/// Implements `Selection.getFocus`
+pub fn get_focus<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    Ok(context
+        .focus_tracker
+        .get()
+        .map_or(Value::Null, |focus| focus.path().into())
+        .into())
+}
+
+/// Implements `Selection.setFocus`
+pub fn set_focus<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    _this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let focus = match args.get(0) {
+        None | Some(Value::Undefined) | Some(Value::Null) => None,
+        Some(target) => {
+            let start = avm.target_clip_or_root();
+            match avm.resolve_target_display_object(context, start, target.clone())? {
+                Some(focus) if focus_tracker::is_focusable(focus, avm, context) => Some(focus),
+                _ => return Ok(false.into()),
+            }
+        }
+    };
+    context.focus_tracker.set(focus, context);
+    Ok(true.into())
+}
+
+pub fn create_selection_object<'gc>(
+    gc_context: MutationContext<'gc, '_>,
+    proto: Option<Object<'gc>>,
+    fn_proto: Option<Object<'gc>>,
+    array_proto: Option<Object<'gc>>,
+    broadcaster_functions: BroadcasterFunctions<'gc>,
+) -> Object<'gc> {
+    let mut selection = ScriptObject::object(gc_context, proto);
+
+    broadcaster_functions.initialize(gc_context, selection.into(), array_proto);
+
+    selection.force_set_function(
+        "getFocus",
+        get_focus,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        fn_proto,
+    );
+
+    selection.force_set_function(
+        "setFocus",
+        set_focus,
+        gc_context,
+        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
+        fn_proto,
+    );
+
+    selection.into()
+}
@@ -2,27 +2,24 @@
 //!
 //! TODO: This is a very rough stub with not much implementation.
 use crate::avm1::function::Executable;
+use crate::avm1::globals::as_broadcaster::BroadcasterFunctions;
 use crate::avm1::property::Attribute;
 use crate::avm1::return_value::ReturnValue;
 use crate::avm1::{Avm1, Error, Object, ScriptObject, TObject, UpdateContext, Value};
+use crate::stage::{StageAlign, StageScaleMode};
 
 use gc_arena::MutationContext;
 
 pub fn create_stage_object<'gc>(
     gc_context: MutationContext<'gc, '_>,
     proto: Option<Object<'gc>>,
-    _array_proto: Option<Object<'gc>>,
-    fn_proto: Option<Object<'gc>>,
+    array_proto: Option<Object<'gc>>,
+    _fn_proto: Option<Object<'gc>>,
+    broadcaster_functions: BroadcasterFunctions<'gc>,
 ) -> Object<'gc> {
-    let mut stage = ScriptObject::object(gc_context, proto);
+    let stage = ScriptObject::object(gc_context, proto);
 
-    stage.force_set_function(
-        "addListener",
-        add_listener,
-        gc_context,
-        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
-        fn_proto,
-    );
+    broadcaster_functions.initialize(gc_context, stage.into(), array_proto);
 
     stage.add_property(
         gc_context,
@@ -40,14 +37,6 @@ pub fn create_stage_object<'gc>(
         Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
     );
 
-    stage.force_set_function(
-        "removeListener",
-        remove_listener,
-        gc_context,
-        Attribute::DontEnum | Attribute::DontDelete | Attribute::ReadOnly,
-        fn_proto,
-    );
-
     stage.add_property(
         gc_context,
         "scaleMode",
@@ -75,33 +64,27 @@ pub fn create_stage_object<'gc>(
     stage.into()
 }
 
-fn add_listener<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
-) -> Result<ReturnValue<'gc>, Error> {
-    log::warn!("Stage.addListener: unimplemented");
-    Ok(Value::Undefined.into())
-}
-
 fn align<'gc>(
     _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error> {
-    log::warn!("Stage.align: unimplemented");
-    Ok("".into())
+    Ok(StageAlign::format(*context.stage_align).into())
 }
 
 fn set_align<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error> {
-    log::warn!("Stage.align: unimplemented");
+    let align = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(avm, context)?;
+    *context.stage_align = StageAlign::parse(&align);
     Ok(Value::Undefined.into())
 }
 
@@ -114,53 +97,53 @@ fn height<'gc>(
     Ok(context.stage_size.1.to_pixels().into())
 }
 
-fn remove_listener<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
-    _this: Object<'gc>,
-    _args: &[Value<'gc>],
-) -> Result<ReturnValue<'gc>, Error> {
-    log::warn!("Stage.removeListener: unimplemented");
-    Ok("".into())
-}
-
 fn scale_mode<'gc>(
     _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error> {
-    log::warn!("Stage.scaleMode: unimplemented");
-    Ok("noScale".into())
+    Ok(context.stage_scale_mode.name().into())
 }
 
 fn set_scale_mode<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error> {
-    log::warn!("Stage.scaleMode: unimplemented");
+    let scale_mode = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .coerce_to_string(avm, context)?;
+    // Unknown values are ignored.
+    if let Some(scale_mode) = StageScaleMode::from_name(&scale_mode) {
+        *context.stage_scale_mode = scale_mode;
+    }
     Ok(Value::Undefined.into())
 }
 
 fn show_menu<'gc>(
     _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
     _args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error> {
-    log::warn!("Stage.showMenu: unimplemented");
-    Ok(true.into())
+    Ok((*context.show_menu).into())
 }
 
 fn set_show_menu<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: Object<'gc>,
-    _args: &[Value<'gc>],
+    args: &[Value<'gc>],
 ) -> Result<ReturnValue<'gc>, Error> {
-    log::warn!("Stage.showMenu: unimplemented");
+    let show_menu = args
+        .get(0)
+        .unwrap_or(&Value::Undefined)
+        .as_bool(avm.current_swf_version());
+    *context.show_menu = show_menu;
     Ok(Value::Undefined.into())
 }
 
@@ -186,6 +186,60 @@ pub fn set_word_wrap<'gc>(
     Ok(Value::Undefined.into())
 }
 
+pub fn scroll<'gc>(
+    _avm: &mut Avm1<'gc>,
+    _context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(etext) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        return Ok(Value::Number(etext.scroll() as f64).into());
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn set_scroll<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    let scroll = args
+        .get(0)
+        .cloned()
+        .unwrap_or(Value::Undefined)
+        .as_number(avm, context)?;
+
+    if let Some(etext) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        etext.set_scroll(scroll, context);
+    }
+
+    Ok(Value::Undefined.into())
+}
+
+pub fn maxscroll<'gc>(
+    _avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    this: Object<'gc>,
+    _args: &[Value<'gc>],
+) -> Result<ReturnValue<'gc>, Error> {
+    if let Some(etext) = this
+        .as_display_object()
+        .and_then(|dobj| dobj.as_edit_text())
+    {
+        return Ok(Value::Number(etext.maxscroll(context) as f64).into());
+    }
+
+    Ok(Value::Undefined.into())
+}
+
 pub fn create_proto<'gc>(
     gc_context: MutationContext<'gc, '_>,
     proto: Object<'gc>,
@@ -209,7 +263,7 @@ pub fn create_proto<'gc>(
 
             if let Value::Object(tf) = tf {
                 let tf_parsed = TextFormat::from_avm1_object(tf, avm, context)?;
-                text_field.set_new_text_format(tf_parsed, context.gc_context);
+                text_field.set_new_text_format(tf_parsed, context);
             }
 
             Ok(Value::Undefined.into())
@@ -255,4 +309,18 @@ pub fn attach_virtual_properties<'gc>(gc_context: MutationContext<'gc, '_>, obje
         Some(Executable::Native(set_word_wrap)),
         ReadOnly.into(),
     );
+    object.add_property(
+        gc_context,
+        "scroll",
+        Executable::Native(scroll),
+        Some(Executable::Native(set_scroll)),
+        ReadOnly.into(),
+    );
+    object.add_property(
+        gc_context,
+        "maxscroll",
+        Executable::Native(maxscroll),
+        None,
+        ReadOnly.into(),
+    );
 }
@@ -1,5 +1,6 @@
 //! Object trait to expose objects to AVM
 
+use crate::avm1::bitmap_data_object::BitmapDataObject;
 use crate::avm1::function::{Executable, FunctionObject};
 use crate::avm1::property::Attribute;
 use crate::avm1::return_value::ReturnValue;
@@ -31,6 +32,7 @@ use std::fmt::Debug;
         XMLIDMapObject(XMLIDMapObject<'gc>),
         ValueObject(ValueObject<'gc>),
         FunctionObject(FunctionObject<'gc>),
+        BitmapDataObject(BitmapDataObject<'gc>),
     }
 )]
 pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy {
@@ -378,6 +380,11 @@ pub trait TObject<'gc>: 'gc + Collect + Debug + Into<Object<'gc>> + Clone + Copy
         None
     }
 
+    /// Get the underlying `BitmapDataObject`, if it exists.
+    fn as_bitmap_data_object(&self) -> Option<BitmapDataObject<'gc>> {
+        None
+    }
+
     fn as_ptr(&self) -> *const ObjectPtr;
 
     /// Check if this object is in the prototype chain of the specified test object.
@@ -471,3 +478,21 @@ pub fn search_prototype<'gc>(
 
     Ok((Value::Undefined.into(), None))
 }
+
+/// Reads a property of `object`, or returns `undefined` if `object` isn't an object or the
+/// property can't be read.
+///
+/// For native code outside of an ActionScript call, such as focus handling and context
+/// menus, which has nowhere to report errors.
+pub fn get_property_or_undefined<'gc>(
+    object: Value<'gc>,
+    name: &str,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Value<'gc> {
+    object
+        .as_object()
+        .and_then(|object| object.get(name, avm, context))
+        .and_then(|value| value.resolve(avm, context))
+        .unwrap_or(Value::Undefined)
+}
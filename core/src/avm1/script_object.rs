@@ -235,13 +235,7 @@ impl<'gc> ScriptObject<'gc> {
                 }
 
                 if let Some(this_proto) = proto {
-                    rval = Some(this_proto.call_setter(
-                        name,
-                        value.clone(),
-                        avm,
-                        context,
-                        (*self).into(),
-                    )?);
+                    rval = Some(this_proto.call_setter(name, value.clone(), avm, context, this)?);
                 }
             }
 
@@ -704,13 +698,16 @@ mod tests {
     use crate::avm1::activation::Activation;
     use crate::avm1::property::Attribute::*;
     use crate::backend::audio::NullAudioBackend;
+    use crate::backend::font::NullFontBackend;
     use crate::backend::input::NullInputBackend;
     use crate::backend::navigator::NullNavigatorBackend;
     use crate::backend::render::NullRenderer;
     use crate::display_object::MovieClip;
+    use crate::focus_tracker::FocusTracker;
     use crate::library::Library;
     use crate::loader::LoadManager;
     use crate::prelude::*;
+    use crate::stage::{StageQuality, StageScaleMode};
     use crate::tag_utils::{SwfMovie, SwfSlice};
     use gc_arena::rootless_arena;
     use rand::{rngs::SmallRng, SeedableRng};
@@ -740,6 +737,7 @@ mod tests {
                 action_queue: &mut crate::context::ActionQueue::new(),
                 audio: &mut NullAudioBackend::new(),
                 input: &mut NullInputBackend::new(),
+                fonts: &mut NullFontBackend::new(),
                 background_color: &mut Color {
                     r: 0,
                     g: 0,
@@ -751,9 +749,15 @@ mod tests {
                 renderer: &mut NullRenderer::new(),
                 system_prototypes: avm.prototypes().clone(),
                 mouse_hovered_object: None,
+                focus_tracker: FocusTracker::new(gc_context),
                 mouse_position: &(Twips::new(0), Twips::new(0)),
                 drag_object: &mut None,
                 stage_size: (Twips::from_pixels(550.0), Twips::from_pixels(400.0)),
+                stage_scale_mode: &mut StageScaleMode::default(),
+                stage_align: &mut EnumSet::empty(),
+                stage_quality: &mut StageQuality::default(),
+                show_menu: &mut true,
+                context_menu_callbacks: &mut Vec::new(),
                 player: None,
                 load_manager: &mut LoadManager::new(),
             };
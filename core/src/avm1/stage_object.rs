@@ -8,6 +8,7 @@ use crate::avm1::{Avm1, Error, Object, ObjectPtr, ScriptObject, TDisplayObject,
 use crate::context::UpdateContext;
 use crate::display_object::{DisplayObject, MovieClip};
 use crate::property_map::PropertyMap;
+use crate::stage::StageQuality;
 use enumset::EnumSet;
 use gc_arena::{Collect, GcCell, MutationContext};
 use std::fmt;
@@ -569,7 +570,7 @@ fn current_frame<'gc>(
 ) -> Result<Value<'gc>, Error> {
     Ok(this
         .as_movie_clip()
-        .map(MovieClip::current_frame)
+        .map(MovieClip::current_frame_in_scene)
         .map(Value::from)
         .unwrap_or(Value::Undefined))
 }
@@ -756,39 +757,53 @@ fn url<'gc>(
 
 fn high_quality<'gc>(
     _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: DisplayObject<'gc>,
 ) -> Result<Value<'gc>, Error> {
-    log::warn!("Unimplemented property _highquality");
-    Ok(1.into())
+    let high_quality = match *context.stage_quality {
+        StageQuality::Low => 0,
+        StageQuality::Medium | StageQuality::High => 1,
+        StageQuality::Best => 2,
+    };
+    Ok(high_quality.into())
 }
 
 fn set_high_quality<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: DisplayObject<'gc>,
-    _val: Value<'gc>,
+    val: Value<'gc>,
 ) -> Result<(), Error> {
-    log::warn!("Unimplemented property _highquality");
+    let val = val.as_number(avm, context)?;
+    *context.stage_quality = if val == 0.0 {
+        StageQuality::Low
+    } else if val >= 2.0 {
+        StageQuality::Best
+    } else {
+        StageQuality::High
+    };
     Ok(())
 }
 
 fn focus_rect<'gc>(
     _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: DisplayObject<'gc>,
 ) -> Result<Value<'gc>, Error> {
-    log::warn!("Unimplemented property _focusrect");
-    Ok(Value::Null)
+    Ok(context.focus_tracker.show_focus_rect().into())
 }
 
 fn set_focus_rect<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: DisplayObject<'gc>,
-    _val: Value<'gc>,
+    val: Value<'gc>,
 ) -> Result<(), Error> {
-    log::warn!("Unimplemented property _focusrect");
+    // TODO: In SWFv6+, `_focusrect` can be set on individual buttons and clips.
+    let show = val.as_bool(avm.current_swf_version());
+    context
+        .focus_tracker
+        .set_show_focus_rect(context.gc_context, show);
     Ok(())
 }
 
@@ -813,20 +828,23 @@ fn set_sound_buf_time<'gc>(
 
 fn quality<'gc>(
     _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: DisplayObject<'gc>,
 ) -> Result<Value<'gc>, Error> {
-    log::warn!("Unimplemented property _quality");
-    Ok("HIGH".into())
+    Ok(context.stage_quality.name().into())
 }
 
 fn set_quality<'gc>(
-    _avm: &mut Avm1<'gc>,
-    _context: &mut UpdateContext<'_, 'gc, '_>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
     _this: DisplayObject<'gc>,
-    _val: Value<'gc>,
+    val: Value<'gc>,
 ) -> Result<(), Error> {
-    log::warn!("Unimplemented property _quality");
+    // Unknown quality levels are ignored.
+    let name = val.coerce_to_string(avm, context)?;
+    if let Some(quality) = StageQuality::from_name(&name) {
+        *context.stage_quality = quality;
+    }
     Ok(())
 }
 
@@ -1,15 +1,19 @@
 use crate::avm1::activation::Activation;
 use crate::avm1::{Avm1, Object, UpdateContext};
 use crate::backend::audio::NullAudioBackend;
+use crate::backend::font::NullFontBackend;
 use crate::backend::input::NullInputBackend;
 use crate::backend::navigator::NullNavigatorBackend;
 use crate::backend::render::NullRenderer;
 use crate::context::ActionQueue;
 use crate::display_object::{MovieClip, TDisplayObject};
+use crate::focus_tracker::FocusTracker;
 use crate::library::Library;
 use crate::loader::LoadManager;
 use crate::prelude::*;
+use crate::stage::{StageQuality, StageScaleMode};
 use crate::tag_utils::{SwfMovie, SwfSlice};
+use enumset::EnumSet;
 use gc_arena::{rootless_arena, GcCell, MutationContext};
 use rand::{rngs::SmallRng, SeedableRng};
 use std::collections::BTreeMap;
@@ -40,6 +44,7 @@ where
             rng: &mut SmallRng::from_seed([0u8; 16]),
             audio: &mut NullAudioBackend::new(),
             input: &mut NullInputBackend::new(),
+            fonts: &mut NullFontBackend::new(),
             action_queue: &mut ActionQueue::new(),
             background_color: &mut Color {
                 r: 0,
@@ -52,9 +57,15 @@ where
             renderer: &mut NullRenderer::new(),
             system_prototypes: avm.prototypes().clone(),
             mouse_hovered_object: None,
+            focus_tracker: FocusTracker::new(gc_context),
             mouse_position: &(Twips::new(0), Twips::new(0)),
             drag_object: &mut None,
             stage_size: (Twips::from_pixels(550.0), Twips::from_pixels(400.0)),
+            stage_scale_mode: &mut StageScaleMode::default(),
+            stage_align: &mut EnumSet::empty(),
+            stage_quality: &mut StageQuality::default(),
+            show_menu: &mut true,
+            context_menu_callbacks: &mut Vec::new(),
             player: None,
             load_manager: &mut LoadManager::new(),
         };
@@ -1,4 +1,5 @@
 pub mod audio;
+pub mod font;
 pub mod input;
 pub mod navigator;
 pub mod render;
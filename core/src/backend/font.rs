@@ -0,0 +1,68 @@
+//! System fonts for device text
+
+use std::collections::HashMap;
+use std::io::{Read, Seek};
+
+/// Provides the system fonts used to render device text.
+///
+/// Text fields that don't embed their font ask for fonts by family name, such as `Arial`.
+/// The backend returns the contents of a TrueType or OpenType font file, and the player
+/// converts its glyphs into shapes as they are needed.
+pub trait FontBackend {
+    /// Returns the font file for the given family name, or `None` if the font isn't
+    /// available. Names should be matched case insensitively.
+    fn load_font(&mut self, name: &str) -> Option<Vec<u8>>;
+}
+
+/// Reads the family name of a TrueType or OpenType font file, such as `Arial`.
+/// Useful for backends that index a collection of font files, as only the tables naming
+/// the font are read.
+pub fn font_family_name<R: Read + Seek>(file: &mut R) -> Option<String> {
+    crate::font::opentype::read_family_name(file)
+}
+
+/// Font backend without any system fonts.
+/// All device text is rendered with the built-in font.
+pub struct NullFontBackend {}
+
+impl NullFontBackend {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl FontBackend for NullFontBackend {
+    fn load_font(&mut self, _name: &str) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+impl Default for NullFontBackend {
+    fn default() -> Self {
+        NullFontBackend::new()
+    }
+}
+
+/// Font backend that serves font files added from memory.
+#[derive(Default)]
+pub struct MemoryFontBackend {
+    /// The font files by lowercase family name.
+    fonts: HashMap<String, Vec<u8>>,
+}
+
+impl MemoryFontBackend {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds a font file under the given family name, replacing any font of the same name.
+    pub fn add_font(&mut self, name: &str, data: Vec<u8>) {
+        self.fonts.insert(name.to_lowercase(), data);
+    }
+}
+
+impl FontBackend for MemoryFontBackend {
+    fn load_font(&mut self, name: &str) -> Option<Vec<u8>> {
+        self.fonts.get(&name.to_lowercase()).cloned()
+    }
+}
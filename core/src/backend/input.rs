@@ -6,6 +6,14 @@ pub trait InputBackend: Downcast {
 
     fn get_last_key_code(&self) -> KeyCode;
 
+    /// The character generated by the last key press, if any.
+    /// Used by `Key.getAscii`.
+    fn get_last_key_char(&self) -> Option<char>;
+
+    /// Whether a lock key (Caps Lock or Num Lock) is currently toggled on.
+    /// Returns `false` for all other keys.
+    fn is_key_toggled(&self, key: KeyCode) -> bool;
+
     fn mouse_visible(&self) -> bool;
 
     fn hide_mouse(&mut self);
@@ -35,6 +43,14 @@ impl InputBackend for NullInputBackend {
         KeyCode::Unknown
     }
 
+    fn get_last_key_char(&self) -> Option<char> {
+        None
+    }
+
+    fn is_key_toggled(&self, _key: KeyCode) -> bool {
+        false
+    }
+
     fn mouse_visible(&self) -> bool {
         true
     }
@@ -1,4 +1,5 @@
 use crate::shape_utils::DistilledShape;
+use crate::stage::StageQuality;
 pub use crate::{transform::Transform, Color};
 use downcast_rs::Downcast;
 use std::io::Read;
@@ -6,7 +7,25 @@ pub use swf;
 
 pub trait RenderBackend: Downcast {
     fn set_viewport_dimensions(&mut self, width: u32, height: u32);
+
+    /// Changes the anti-aliasing and bitmap smoothing used for future frames.
+    /// This is never called between `begin_frame` and `end_frame`.
+    fn set_quality(&mut self, quality: StageQuality);
     fn register_shape(&mut self, shape: DistilledShape) -> ShapeHandle;
+
+    /// Registers a shape drawn with the drawing API. The `id` of its bitmap fills is an
+    /// index into `bitmaps` rather than the character ID of a bitmap in the SWF.
+    fn register_drawing(&mut self, shape: DistilledShape, bitmaps: &[BitmapHandle])
+        -> ShapeHandle;
+
+    /// Replaces a shape registered with `register_drawing` after the drawing has changed.
+    fn replace_drawing(
+        &mut self,
+        handle: ShapeHandle,
+        shape: DistilledShape,
+        bitmaps: &[BitmapHandle],
+    );
+
     fn register_glyph_shape(&mut self, shape: &swf::Glyph) -> ShapeHandle;
     fn register_bitmap_jpeg(
         &mut self,
@@ -23,14 +42,58 @@ pub trait RenderBackend: Downcast {
     ) -> BitmapInfo;
     fn register_bitmap_png(&mut self, swf_tag: &swf::DefineBitsLossless) -> BitmapInfo;
 
+    /// Registers a bitmap from raw, premultiplied RGBA pixel data.
+    fn register_bitmap_raw(&mut self, width: u32, height: u32, rgba: Vec<u8>) -> BitmapHandle;
+
+    /// Replaces the contents of an existing bitmap with new premultiplied RGBA pixel data.
+    /// Used by `BitmapData` to push CPU-side changes to the GPU.
+    fn update_texture(
+        &mut self,
+        bitmap: BitmapHandle,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> BitmapHandle;
+
+    /// Returns a copy of the pixels of a registered bitmap, if the backend still has them.
+    fn get_bitmap_pixels(&mut self, bitmap: BitmapHandle) -> Option<Bitmap>;
+
+    /// Releases the texture of a bitmap registered with `register_bitmap_raw`.
+    /// The handle must not be rendered or updated afterwards.
+    fn unregister_bitmap(&mut self, bitmap: BitmapHandle);
+
     fn begin_frame(&mut self, clear: Color);
     fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform);
     fn render_shape(&mut self, shape: ShapeHandle, transform: &Transform);
     fn end_frame(&mut self);
     fn draw_letterbox(&mut self, letterbox: Letterbox);
+
+    /// Draws a solid rectangle over everything rendered so far this frame.
+    /// The rectangle is given in viewport pixels. Used for player UI such as the
+    /// focus rectangle.
+    fn draw_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color);
     fn push_mask(&mut self);
     fn activate_mask(&mut self);
     fn pop_mask(&mut self);
+
+    /// Redirects all following render calls into an offscreen bitmap of the given size,
+    /// cleared to `clear`.
+    /// If `target` is given, its existing contents are drawn into the bitmap first, and the
+    /// result will replace the contents of that bitmap.
+    /// This may be called outside of `begin_frame`/`end_frame`, but may not be nested.
+    fn begin_render_to_bitmap(
+        &mut self,
+        target: Option<BitmapHandle>,
+        width: u32,
+        height: u32,
+        clear: Color,
+    );
+
+    /// Finishes rendering into the offscreen bitmap started by `begin_render_to_bitmap`.
+    /// If a target bitmap was given, its texture is replaced with the result; otherwise no
+    /// bitmap is registered.
+    /// Returns the rendered pixels, or `None` if the backend is unable to render offscreen.
+    fn end_render_to_bitmap(&mut self) -> Option<Bitmap>;
 }
 impl_downcast!(RenderBackend);
 
@@ -73,9 +136,24 @@ impl Default for NullRenderer {
 
 impl RenderBackend for NullRenderer {
     fn set_viewport_dimensions(&mut self, _width: u32, _height: u32) {}
+    fn set_quality(&mut self, _quality: StageQuality) {}
     fn register_shape(&mut self, _shape: DistilledShape) -> ShapeHandle {
         ShapeHandle(0)
     }
+    fn register_drawing(
+        &mut self,
+        _shape: DistilledShape,
+        _bitmaps: &[BitmapHandle],
+    ) -> ShapeHandle {
+        ShapeHandle(0)
+    }
+    fn replace_drawing(
+        &mut self,
+        _handle: ShapeHandle,
+        _shape: DistilledShape,
+        _bitmaps: &[BitmapHandle],
+    ) {
+    }
     fn register_glyph_shape(&mut self, _shape: &swf::Glyph) -> ShapeHandle {
         ShapeHandle(0)
     }
@@ -117,14 +195,42 @@ impl RenderBackend for NullRenderer {
             height: 0,
         }
     }
+    fn register_bitmap_raw(&mut self, _width: u32, _height: u32, _rgba: Vec<u8>) -> BitmapHandle {
+        BitmapHandle(0)
+    }
+    fn update_texture(
+        &mut self,
+        bitmap: BitmapHandle,
+        _width: u32,
+        _height: u32,
+        _rgba: Vec<u8>,
+    ) -> BitmapHandle {
+        bitmap
+    }
+    fn get_bitmap_pixels(&mut self, _bitmap: BitmapHandle) -> Option<Bitmap> {
+        None
+    }
+    fn unregister_bitmap(&mut self, _bitmap: BitmapHandle) {}
     fn begin_frame(&mut self, _clear: Color) {}
     fn end_frame(&mut self) {}
     fn render_bitmap(&mut self, _bitmap: BitmapHandle, _transform: &Transform) {}
     fn render_shape(&mut self, _shape: ShapeHandle, _transform: &Transform) {}
     fn draw_letterbox(&mut self, _letterbox: Letterbox) {}
+    fn draw_rect(&mut self, _x: f32, _y: f32, _width: f32, _height: f32, _color: Color) {}
     fn push_mask(&mut self) {}
     fn activate_mask(&mut self) {}
     fn pop_mask(&mut self) {}
+    fn begin_render_to_bitmap(
+        &mut self,
+        _target: Option<BitmapHandle>,
+        _width: u32,
+        _height: u32,
+        _clear: Color,
+    ) {
+    }
+    fn end_render_to_bitmap(&mut self) -> Option<Bitmap> {
+        None
+    }
 }
 
 /// The format of image data in a DefineBitsJpeg2/3 tag.
@@ -139,7 +245,7 @@ pub enum JpegTagFormat {
 }
 
 /// Decoded bitmap data from an SWF tag.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Bitmap {
     pub width: u32,
     pub height: u32,
@@ -148,7 +254,7 @@ pub struct Bitmap {
 
 /// Decoded bitmap data from an SWF tag.
 /// The image data will have pre-multiplied alpha.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum BitmapFormat {
     Rgb(Vec<u8>),
     Rgba(Vec<u8>),
@@ -0,0 +1,902 @@
+//! CPU-side pixel storage for `BitmapData`.
+//!
+//! Pixels are stored as unmultiplied ARGB and are converted to premultiplied RGBA
+//! when they are uploaded to the render backend.
+
+use crate::backend::render::{Bitmap, BitmapFormat, BitmapHandle, RenderBackend};
+use crate::prelude::*;
+use gc_arena::Collect;
+use std::cell::Cell;
+use std::collections::VecDeque;
+
+/// Channel flags used by `noise`, `perlinNoise` and friends.
+/// Matches the values of `flash.display.BitmapDataChannel`.
+pub mod channel {
+    pub const RED: u8 = 1;
+    pub const GREEN: u8 = 2;
+    pub const BLUE: u8 = 4;
+    pub const ALPHA: u8 = 8;
+}
+
+/// The largest width or height allowed for a `BitmapData` in SWFv8.
+pub const MAX_DIMENSION: u32 = 2880;
+
+/// The most octaves computed by `perlinNoise`.
+/// Each octave doubles the frequency, so further octaves are finer than a pixel.
+pub const MAX_PERLIN_OCTAVES: usize = 32;
+
+/// A rectangle of pixels in a `BitmapData`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PixelRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl PixelRect {
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// The comparison used by `BitmapData::threshold`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThresholdOperation {
+    LessThan,
+    LessThanOrEqual,
+    GreaterThan,
+    GreaterThanOrEqual,
+    Equal,
+    NotEqual,
+}
+
+impl ThresholdOperation {
+    pub fn from_operator(op: &str) -> Option<Self> {
+        match op {
+            "<" => Some(Self::LessThan),
+            "<=" => Some(Self::LessThanOrEqual),
+            ">" => Some(Self::GreaterThan),
+            ">=" => Some(Self::GreaterThanOrEqual),
+            "==" => Some(Self::Equal),
+            "!=" => Some(Self::NotEqual),
+            _ => None,
+        }
+    }
+
+    fn matches(self, value: u32, threshold: u32) -> bool {
+        match self {
+            Self::LessThan => value < threshold,
+            Self::LessThanOrEqual => value <= threshold,
+            Self::GreaterThan => value > threshold,
+            Self::GreaterThanOrEqual => value >= threshold,
+            Self::Equal => value == threshold,
+            Self::NotEqual => value != threshold,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Collect)]
+#[collect(require_static)]
+pub struct BitmapData {
+    width: u32,
+    height: u32,
+    transparent: bool,
+
+    /// The pixels of this bitmap, as unmultiplied ARGB in row-major order.
+    pixels: Vec<u32>,
+
+    /// The handle of this bitmap in the render backend, once it has been registered.
+    bitmap_handle: Option<BitmapHandle>,
+
+    /// Whether the pixels have changed since they were last uploaded to the render backend.
+    dirty: Cell<bool>,
+
+    /// Whether `dispose` has been called on this bitmap.
+    disposed: bool,
+}
+
+impl BitmapData {
+    pub fn new(width: u32, height: u32, transparent: bool, fill_color: u32) -> Self {
+        let fill_color = if transparent {
+            fill_color
+        } else {
+            fill_color | 0xFF00_0000
+        };
+        Self {
+            width,
+            height,
+            transparent,
+            pixels: vec![fill_color; width as usize * height as usize],
+            bitmap_handle: None,
+            dirty: Cell::new(true),
+            disposed: false,
+        }
+    }
+
+    /// Creates a `BitmapData` from a bitmap decoded by the render backend.
+    pub fn from_bitmap(bitmap: Bitmap) -> Self {
+        let (transparent, pixels) = match bitmap.data {
+            BitmapFormat::Rgb(data) => (
+                false,
+                data.chunks_exact(3)
+                    .map(|p| argb(0xFF, p[0], p[1], p[2]))
+                    .collect(),
+            ),
+            BitmapFormat::Rgba(data) => (
+                true,
+                data.chunks_exact(4)
+                    .map(|p| unmultiply(argb(p[3], p[0], p[1], p[2])))
+                    .collect(),
+            ),
+        };
+        Self {
+            width: bitmap.width,
+            height: bitmap.height,
+            transparent,
+            pixels,
+            bitmap_handle: None,
+            dirty: Cell::new(true),
+            disposed: false,
+        }
+    }
+
+    /// Creates a copy of these pixels that is not yet registered with the render backend.
+    pub fn clone_pixels(&self) -> Self {
+        Self {
+            bitmap_handle: None,
+            dirty: Cell::new(true),
+            ..self.clone()
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn transparent(&self) -> bool {
+        self.transparent
+    }
+
+    pub fn disposed(&self) -> bool {
+        self.disposed
+    }
+
+    pub fn bitmap_handle(&self) -> Option<BitmapHandle> {
+        self.bitmap_handle
+    }
+
+    /// Frees the pixels of this bitmap and its texture in the render backend.
+    /// All further operations on it are no-ops.
+    pub fn dispose(&mut self, renderer: &mut dyn RenderBackend) {
+        if let Some(handle) = self.bitmap_handle.take() {
+            renderer.unregister_bitmap(handle);
+        }
+        self.width = 0;
+        self.height = 0;
+        self.pixels = Vec::new();
+        self.disposed = true;
+        self.dirty.set(false);
+    }
+
+    fn is_point_in_bounds(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && (x as u32) < self.width && (y as u32) < self.height
+    }
+
+    fn index(&self, x: i32, y: i32) -> usize {
+        y as usize * self.width as usize + x as usize
+    }
+
+    /// Returns the raw unmultiplied ARGB value of a pixel, if it is in bounds.
+    pub fn get_pixel_raw(&self, x: i32, y: i32) -> Option<u32> {
+        if self.is_point_in_bounds(x, y) {
+            Some(self.pixels[self.index(x, y)])
+        } else {
+            None
+        }
+    }
+
+    fn set_pixel_raw(&mut self, x: i32, y: i32, color: u32) {
+        if self.is_point_in_bounds(x, y) {
+            let color = if self.transparent {
+                color
+            } else {
+                color | 0xFF00_0000
+            };
+            let index = self.index(x, y);
+            self.pixels[index] = color;
+            self.dirty.set(true);
+        }
+    }
+
+    /// Returns the RGB value of a pixel, or 0 if it is out of bounds.
+    pub fn get_pixel(&self, x: i32, y: i32) -> i32 {
+        self.get_pixel_raw(x, y)
+            .map(|color| (color & 0x00FF_FFFF) as i32)
+            .unwrap_or(0)
+    }
+
+    /// Returns the ARGB value of a pixel, or 0 if it is out of bounds.
+    pub fn get_pixel32(&self, x: i32, y: i32) -> i32 {
+        self.get_pixel_raw(x, y)
+            .map(|color| color as i32)
+            .unwrap_or(0)
+    }
+
+    /// Sets the RGB value of a pixel, leaving its alpha untouched.
+    pub fn set_pixel(&mut self, x: i32, y: i32, color: u32) {
+        if let Some(old) = self.get_pixel_raw(x, y) {
+            self.set_pixel_raw(x, y, (old & 0xFF00_0000) | (color & 0x00FF_FFFF));
+        }
+    }
+
+    /// Sets the ARGB value of a pixel.
+    pub fn set_pixel32(&mut self, x: i32, y: i32, color: u32) {
+        self.set_pixel_raw(x, y, color);
+    }
+
+    /// Clips a rectangle to the bounds of this bitmap.
+    fn clip_rect(&self, rect: PixelRect) -> Option<(i32, i32, i32, i32)> {
+        let x_min = rect.x.max(0);
+        let y_min = rect.y.max(0);
+        let x_max = rect.x.saturating_add(rect.width).min(self.width as i32);
+        let y_max = rect.y.saturating_add(rect.height).min(self.height as i32);
+        if x_min < x_max && y_min < y_max {
+            Some((x_min, y_min, x_max, y_max))
+        } else {
+            None
+        }
+    }
+
+    /// Clips a copy of `src_rect` from `source` to `dest` in this bitmap, so that both the read
+    /// and the written pixels are in bounds.
+    /// Returns the source position, the destination position and the size of the clipped copy.
+    fn clip_copy_rect(
+        &self,
+        source: &BitmapData,
+        src_rect: PixelRect,
+        dest: (i32, i32),
+    ) -> Option<(i32, i32, i32, i32, i32, i32)> {
+        let (src_x_min, src_y_min, src_x_max, src_y_max) = source.clip_rect(src_rect)?;
+
+        // Translate the clipped source rectangle to the destination and clip it again.
+        let offset_x = i64::from(dest.0) - i64::from(src_rect.x);
+        let offset_y = i64::from(dest.1) - i64::from(src_rect.y);
+        let dest_x_min = (i64::from(src_x_min) + offset_x).max(0);
+        let dest_y_min = (i64::from(src_y_min) + offset_y).max(0);
+        let dest_x_max = (i64::from(src_x_max) + offset_x).min(i64::from(self.width));
+        let dest_y_max = (i64::from(src_y_max) + offset_y).min(i64::from(self.height));
+        if dest_x_min >= dest_x_max || dest_y_min >= dest_y_max {
+            return None;
+        }
+
+        Some((
+            (dest_x_min - offset_x) as i32,
+            (dest_y_min - offset_y) as i32,
+            dest_x_min as i32,
+            dest_y_min as i32,
+            (dest_x_max - dest_x_min) as i32,
+            (dest_y_max - dest_y_min) as i32,
+        ))
+    }
+
+    pub fn fill_rect(&mut self, rect: PixelRect, color: u32) {
+        if let Some((x_min, y_min, x_max, y_max)) = self.clip_rect(rect) {
+            for y in y_min..y_max {
+                for x in x_min..x_max {
+                    self.set_pixel_raw(x, y, color);
+                }
+            }
+        }
+    }
+
+    /// Copies a rectangle of pixels from `source` into this bitmap at `dest`.
+    /// If `merge_alpha` is set, the source pixels are alpha blended over the destination.
+    pub fn copy_pixels(
+        &mut self,
+        source: &BitmapData,
+        src_rect: PixelRect,
+        dest: (i32, i32),
+        merge_alpha: bool,
+    ) {
+        let (src_x, src_y, dest_x, dest_y, width, height) =
+            match self.clip_copy_rect(source, src_rect, dest) {
+                Some(rect) => rect,
+                None => return,
+            };
+        for y in 0..height {
+            for x in 0..width {
+                let src_pixel = source.pixels[source.index(src_x + x, src_y + y)];
+                let dest_pixel = self.pixels[self.index(dest_x + x, dest_y + y)];
+                let color = if merge_alpha && source.transparent {
+                    blend_over(src_pixel, dest_pixel)
+                } else if source.transparent {
+                    src_pixel
+                } else {
+                    src_pixel | 0xFF00_0000
+                };
+                self.set_pixel_raw(dest_x + x, dest_y + y, color);
+            }
+        }
+    }
+
+    /// Draws `source` into this bitmap, transformed by the given matrix.
+    /// Pixels are sampled using nearest-neighbor filtering and alpha blended over this bitmap.
+    pub fn draw_bitmap(&mut self, source: &BitmapData, matrix: Matrix, clip: Option<PixelRect>) {
+        let mut inverse = matrix;
+        inverse.invert();
+
+        let clip =
+            clip.unwrap_or_else(|| PixelRect::new(0, 0, self.width as i32, self.height as i32));
+        if let Some((x_min, y_min, x_max, y_max)) = self.clip_rect(clip) {
+            for y in y_min..y_max {
+                for x in x_min..x_max {
+                    let (src_x, src_y) = inverse
+                        * (
+                            Twips::from_pixels(f64::from(x) + 0.5),
+                            Twips::from_pixels(f64::from(y) + 0.5),
+                        );
+                    let src_x = src_x.to_pixels().floor() as i32;
+                    let src_y = src_y.to_pixels().floor() as i32;
+                    if let Some(src_pixel) = source.get_pixel_raw(src_x, src_y) {
+                        let dest_pixel = self.pixels[self.index(x, y)];
+                        self.set_pixel_raw(x, y, blend_over(src_pixel, dest_pixel));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Replaces the pixels of this bitmap with the output of the render backend,
+    /// limited to `clip` if given.
+    pub fn copy_from_rendered(&mut self, bitmap: Bitmap, clip: Option<PixelRect>) {
+        let rendered = BitmapData::from_bitmap(bitmap);
+        let clip =
+            clip.unwrap_or_else(|| PixelRect::new(0, 0, self.width as i32, self.height as i32));
+        self.copy_pixels(&rendered, clip, (clip.x, clip.y), false);
+    }
+
+    /// Fills this bitmap with random noise.
+    pub fn noise(&mut self, seed: i32, low: u8, high: u8, channels: u8, gray_scale: bool) {
+        let (low, high) = if low <= high {
+            (low, high)
+        } else {
+            (high, low)
+        };
+        let range = u32::from(high - low) + 1;
+        let mut rng = ParkMiller::new(seed);
+        let mut random = || low.wrapping_add((rng.next() % range) as u8);
+
+        for i in 0..self.pixels.len() {
+            let color = if gray_scale {
+                let gray = random();
+                let alpha = if channels & channel::ALPHA != 0 {
+                    random()
+                } else {
+                    0xFF
+                };
+                argb(alpha, gray, gray, gray)
+            } else {
+                let r = if channels & channel::RED != 0 {
+                    random()
+                } else {
+                    0
+                };
+                let g = if channels & channel::GREEN != 0 {
+                    random()
+                } else {
+                    0
+                };
+                let b = if channels & channel::BLUE != 0 {
+                    random()
+                } else {
+                    0
+                };
+                let a = if channels & channel::ALPHA != 0 {
+                    random()
+                } else {
+                    0xFF
+                };
+                argb(a, r, g, b)
+            };
+            self.pixels[i] = if self.transparent {
+                color
+            } else {
+                color | 0xFF00_0000
+            };
+        }
+        self.dirty.set(true);
+    }
+
+    /// Fills this bitmap with Perlin noise.
+    #[allow(clippy::too_many_arguments)]
+    pub fn perlin_noise(
+        &mut self,
+        base: (f64, f64),
+        num_octaves: usize,
+        seed: i32,
+        stitch: bool,
+        fractal_noise: bool,
+        channels: u8,
+        gray_scale: bool,
+        offsets: &[(f64, f64)],
+    ) {
+        let noise = PerlinNoise::new(seed);
+        let (base_x, base_y) = base;
+        let channel_list: &[u8] = if gray_scale {
+            &[channel::RED, channel::ALPHA]
+        } else {
+            &[channel::RED, channel::GREEN, channel::BLUE, channel::ALPHA]
+        };
+
+        let num_octaves = num_octaves.min(MAX_PERLIN_OCTAVES);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let mut values = [0u8, 0, 0, 0xFF];
+                for (channel_index, channel_flag) in channel_list.iter().enumerate() {
+                    if channels & channel_flag == 0 {
+                        continue;
+                    }
+
+                    let mut sum = 0.0;
+                    let mut frequency = 1.0;
+                    let mut amplitude = 1.0;
+                    let mut total_amplitude = 0.0;
+                    for octave in 0..num_octaves {
+                        let (offset_x, offset_y) = offsets.get(octave).copied().unwrap_or_default();
+                        let px = (f64::from(x) + offset_x) * frequency / base_x;
+                        let py = (f64::from(y) + offset_y) * frequency / base_y;
+                        let period = if stitch {
+                            Some((
+                                ((f64::from(self.width) * frequency / base_x).round() as i32)
+                                    .max(1),
+                                ((f64::from(self.height) * frequency / base_y).round() as i32)
+                                    .max(1),
+                            ))
+                        } else {
+                            None
+                        };
+                        let n = noise.get(px, py, channel_index as f64, period);
+                        sum += if fractal_noise { n } else { n.abs() } * amplitude;
+                        total_amplitude += amplitude;
+                        frequency *= 2.0;
+                        amplitude *= 0.5;
+                    }
+                    let value = if total_amplitude > 0.0 {
+                        sum / total_amplitude
+                    } else {
+                        0.0
+                    };
+                    let value = if fractal_noise {
+                        (value + 1.0) * 0.5
+                    } else {
+                        value
+                    };
+                    let value = (value * 255.0).max(0.0).min(255.0) as u8;
+
+                    if gray_scale && *channel_flag == channel::RED {
+                        values[0] = value;
+                        values[1] = value;
+                        values[2] = value;
+                    } else {
+                        let i = match *channel_flag {
+                            channel::RED => 0,
+                            channel::GREEN => 1,
+                            channel::BLUE => 2,
+                            _ => 3,
+                        };
+                        values[i] = value;
+                    }
+                }
+                let [r, g, b, a] = values;
+                self.set_pixel_raw(x as i32, y as i32, argb(a, r, g, b));
+            }
+        }
+        self.dirty.set(true);
+    }
+
+    /// Tests pixels of `source` against a threshold, setting matching pixels in this bitmap to
+    /// `color`. Returns the number of pixels that passed the test.
+    #[allow(clippy::too_many_arguments)]
+    pub fn threshold(
+        &mut self,
+        source: &BitmapData,
+        src_rect: PixelRect,
+        dest: (i32, i32),
+        operation: ThresholdOperation,
+        threshold: u32,
+        color: u32,
+        mask: u32,
+        copy_source: bool,
+    ) -> u32 {
+        let (src_x, src_y, dest_x, dest_y, width, height) =
+            match self.clip_copy_rect(source, src_rect, dest) {
+                Some(rect) => rect,
+                None => return 0,
+            };
+        let mut count = 0;
+        for y in 0..height {
+            for x in 0..width {
+                let src_pixel = source.pixels[source.index(src_x + x, src_y + y)];
+                if operation.matches(src_pixel & mask, threshold & mask) {
+                    self.set_pixel_raw(dest_x + x, dest_y + y, color);
+                    count += 1;
+                } else if copy_source {
+                    self.set_pixel_raw(dest_x + x, dest_y + y, src_pixel);
+                }
+            }
+        }
+        count
+    }
+
+    /// Replaces the contiguous area of same-colored pixels around `(x, y)` with `color`.
+    pub fn flood_fill(&mut self, x: i32, y: i32, color: u32) {
+        let target = if let Some(target) = self.get_pixel_raw(x, y) {
+            target
+        } else {
+            return;
+        };
+        let color = if self.transparent {
+            color
+        } else {
+            color | 0xFF00_0000
+        };
+        if target == color {
+            return;
+        }
+
+        let mut pending = VecDeque::new();
+        pending.push_back((x, y));
+        while let Some((x, y)) = pending.pop_front() {
+            if self.get_pixel_raw(x, y) == Some(target) {
+                self.set_pixel_raw(x, y, color);
+                pending.push_back((x - 1, y));
+                pending.push_back((x + 1, y));
+                pending.push_back((x, y - 1));
+                pending.push_back((x, y + 1));
+            }
+        }
+    }
+
+    /// Returns the pixels of this bitmap as premultiplied RGBA, as expected by the render backend.
+    pub fn to_rgba_premultiplied(&self) -> Vec<u8> {
+        let mut rgba = Vec::with_capacity(self.pixels.len() * 4);
+        for &color in &self.pixels {
+            let a = (color >> 24) as u8;
+            let r = (color >> 16) as u8;
+            let g = (color >> 8) as u8;
+            let b = color as u8;
+            rgba.push(premultiply(r, a));
+            rgba.push(premultiply(g, a));
+            rgba.push(premultiply(b, a));
+            rgba.push(a);
+        }
+        rgba
+    }
+
+    /// Registers this bitmap with the render backend if it hasn't been already,
+    /// returning its handle.
+    pub fn register(&mut self, renderer: &mut dyn RenderBackend) -> BitmapHandle {
+        if let Some(handle) = self.bitmap_handle {
+            handle
+        } else {
+            let handle =
+                renderer.register_bitmap_raw(self.width, self.height, self.to_rgba_premultiplied());
+            self.bitmap_handle = Some(handle);
+            self.dirty.set(false);
+            handle
+        }
+    }
+
+    /// Uploads the pixels of this bitmap to the render backend if they have changed.
+    /// Returns whether they were uploaded.
+    pub fn update_texture_if_dirty(&self, renderer: &mut dyn RenderBackend) -> bool {
+        if let Some(handle) = self.bitmap_handle {
+            if self.dirty.get() {
+                renderer.update_texture(
+                    handle,
+                    self.width,
+                    self.height,
+                    self.to_rgba_premultiplied(),
+                );
+                self.dirty.set(false);
+                return true;
+            }
+        }
+        false
+    }
+}
+
+fn argb(a: u8, r: u8, g: u8, b: u8) -> u32 {
+    (u32::from(a) << 24) | (u32::from(r) << 16) | (u32::from(g) << 8) | u32::from(b)
+}
+
+fn premultiply(c: u8, a: u8) -> u8 {
+    (u16::from(c) * u16::from(a) / 255) as u8
+}
+
+/// Converts a premultiplied ARGB color to an unmultiplied one.
+fn unmultiply(color: u32) -> u32 {
+    let a = (color >> 24) as u8;
+    if a == 0 {
+        return 0;
+    }
+    let unmultiply = |c: u8| (u16::from(c) * 255 / u16::from(a)).min(255) as u8;
+    argb(
+        a,
+        unmultiply((color >> 16) as u8),
+        unmultiply((color >> 8) as u8),
+        unmultiply(color as u8),
+    )
+}
+
+/// Blends an unmultiplied ARGB color over another.
+fn blend_over(src: u32, dest: u32) -> u32 {
+    let src_a = src >> 24;
+    if src_a == 0xFF {
+        return src;
+    } else if src_a == 0 {
+        return dest;
+    }
+
+    let dest_a = dest >> 24;
+    let out_a = src_a + dest_a * (255 - src_a) / 255;
+    if out_a == 0 {
+        return 0;
+    }
+    let channel = |shift: u32| {
+        let s = (src >> shift) & 0xFF;
+        let d = (dest >> shift) & 0xFF;
+        (s * src_a + d * dest_a * (255 - src_a) / 255) / out_a
+    };
+    (out_a << 24) | (channel(16) << 16) | (channel(8) << 8) | channel(0)
+}
+
+/// The Park-Miller "minimal standard" PRNG, which Flash uses to generate noise.
+struct ParkMiller(u32);
+
+impl ParkMiller {
+    fn new(seed: i32) -> Self {
+        let seed = (seed as u32) % 0x7FFF_FFFF;
+        Self(if seed == 0 { 1 } else { seed })
+    }
+
+    fn next(&mut self) -> u32 {
+        self.0 = ((u64::from(self.0) * 16807) % 0x7FFF_FFFF) as u32;
+        self.0
+    }
+}
+
+/// Improved Perlin noise with a permutation table derived from a seed.
+struct PerlinNoise {
+    permutation: [u8; 512],
+}
+
+impl PerlinNoise {
+    fn new(seed: i32) -> Self {
+        let mut table = [0u8; 256];
+        for (i, v) in table.iter_mut().enumerate() {
+            *v = i as u8;
+        }
+
+        let mut rng = ParkMiller::new(seed);
+        for i in (1..256).rev() {
+            let j = rng.next() as usize % (i + 1);
+            table.swap(i, j);
+        }
+
+        let mut permutation = [0u8; 512];
+        for i in 0..512 {
+            permutation[i] = table[i & 255];
+        }
+        Self { permutation }
+    }
+
+    /// Returns noise in the range [-1, 1].
+    /// If `period` is given, the noise tiles seamlessly every `period` lattice cells.
+    fn get(&self, x: f64, y: f64, z: f64, period: Option<(i32, i32)>) -> f64 {
+        // Lattice coordinates are i64 so that the neighbouring cell of a huge coordinate,
+        // which saturates when cast, doesn't overflow.
+        let wrap = |v: i64, p: Option<i32>| match p {
+            Some(p) => (v.rem_euclid(i64::from(p)) & 255) as usize,
+            None => (v & 255) as usize,
+        };
+
+        let (x0, y0, z0) = (x.floor(), y.floor(), z.floor());
+        let (fx, fy, fz) = (x - x0, y - y0, z - z0);
+        let (x0, y0, z0) = (x0 as i64, y0 as i64, z0 as i64);
+        let px = period.map(|p| p.0);
+        let py = period.map(|p| p.1);
+        let (xi, xj) = (wrap(x0, px), wrap(x0.wrapping_add(1), px));
+        let (yi, yj) = (wrap(y0, py), wrap(y0.wrapping_add(1), py));
+        let (zi, zj) = (wrap(z0, None), wrap(z0.wrapping_add(1), None));
+
+        let p = &self.permutation;
+        let hash = |x: usize, y: usize, z: usize| p[p[p[x] as usize + y] as usize + z];
+
+        let (u, v, w) = (fade(fx), fade(fy), fade(fz));
+        lerp(
+            w,
+            lerp(
+                v,
+                lerp(
+                    u,
+                    grad(hash(xi, yi, zi), fx, fy, fz),
+                    grad(hash(xj, yi, zi), fx - 1.0, fy, fz),
+                ),
+                lerp(
+                    u,
+                    grad(hash(xi, yj, zi), fx, fy - 1.0, fz),
+                    grad(hash(xj, yj, zi), fx - 1.0, fy - 1.0, fz),
+                ),
+            ),
+            lerp(
+                v,
+                lerp(
+                    u,
+                    grad(hash(xi, yi, zj), fx, fy, fz - 1.0),
+                    grad(hash(xj, yi, zj), fx - 1.0, fy, fz - 1.0),
+                ),
+                lerp(
+                    u,
+                    grad(hash(xi, yj, zj), fx, fy - 1.0, fz - 1.0),
+                    grad(hash(xj, yj, zj), fx - 1.0, fy - 1.0, fz - 1.0),
+                ),
+            ),
+        )
+    }
+}
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+    a + t * (b - a)
+}
+
+fn grad(hash: u8, x: f64, y: f64, z: f64) -> f64 {
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 {
+        y
+    } else if h == 12 || h == 14 {
+        x
+    } else {
+        z
+    };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::render::NullRenderer;
+
+    #[test]
+    fn set_and_get_pixels() {
+        let mut bitmap = BitmapData::new(4, 4, true, 0);
+        bitmap.set_pixel32(1, 2, 0x80FF_0000);
+        assert_eq!(bitmap.get_pixel32(1, 2), 0x80FF_0000u32 as i32);
+        assert_eq!(bitmap.get_pixel(1, 2), 0xFF_0000);
+        bitmap.set_pixel(1, 2, 0x00FF);
+        assert_eq!(bitmap.get_pixel32(1, 2), 0x8000_00FFu32 as i32);
+        assert_eq!(bitmap.get_pixel32(10, 2), 0);
+
+        let mut opaque = BitmapData::new(2, 2, false, 0x1234_5678);
+        assert_eq!(opaque.get_pixel32(0, 0), 0xFF34_5678u32 as i32);
+        opaque.set_pixel32(1, 1, 0);
+        assert_eq!(opaque.get_pixel32(1, 1), 0xFF00_0000u32 as i32);
+    }
+
+    #[test]
+    fn fill_and_flood_fill() {
+        let mut bitmap = BitmapData::new(5, 5, false, 0xFFFF_FFFF);
+        bitmap.fill_rect(PixelRect::new(2, -1, 1, 10), 0xFF00_0000);
+        bitmap.flood_fill(0, 0, 0xFFFF_0000);
+        assert_eq!(bitmap.get_pixel(1, 4), 0xFF_0000);
+        assert_eq!(bitmap.get_pixel(2, 4), 0);
+        assert_eq!(bitmap.get_pixel(3, 0), 0xFF_FFFF);
+    }
+
+    #[test]
+    fn threshold_counts_matches() {
+        let source = BitmapData::new(3, 3, true, 0xFF00_0080);
+        let mut dest = BitmapData::new(3, 3, true, 0);
+        let count = dest.threshold(
+            &source,
+            PixelRect::new(0, 0, 3, 2),
+            (0, 0),
+            ThresholdOperation::GreaterThan,
+            0x40,
+            0xFFFF_FFFF,
+            0xFF,
+            false,
+        );
+        assert_eq!(count, 6);
+        assert_eq!(dest.get_pixel32(2, 1), -1);
+        assert_eq!(dest.get_pixel32(2, 2), 0);
+    }
+
+    #[test]
+    fn copy_pixels_clips_to_both_bitmaps() {
+        let mut source = BitmapData::new(4, 4, false, 0xFF00_00FF);
+        source.set_pixel32(3, 3, 0xFFFF_0000);
+        let mut dest = BitmapData::new(3, 3, false, 0);
+        dest.copy_pixels(
+            &source,
+            PixelRect::new(1, 1, 1_000_000_000, 1_000_000_000),
+            (0, 0),
+            false,
+        );
+        assert_eq!(dest.get_pixel(0, 0), 0x00_00FF);
+        assert_eq!(dest.get_pixel(2, 2), 0xFF_0000);
+
+        let mut dest = BitmapData::new(3, 3, false, 0);
+        let count = dest.threshold(
+            &source,
+            PixelRect::new(0, 0, i32::MAX, i32::MAX),
+            (-1, -1),
+            ThresholdOperation::Equal,
+            0xFF,
+            0xFFFF_FFFF,
+            0xFF,
+            false,
+        );
+        assert_eq!(count, 8);
+        assert_eq!(dest.get_pixel(0, 0), 0xFF_FFFF);
+        assert_eq!(dest.get_pixel(2, 2), 0);
+    }
+
+    #[test]
+    fn perlin_noise_with_many_octaves() {
+        // Huge frequencies saturate the lattice coordinates, which must not overflow.
+        for &stitch in &[false, true] {
+            let mut bitmap = BitmapData::new(4, 4, false, 0);
+            bitmap.perlin_noise(
+                (1.0, 1.0),
+                1000,
+                7,
+                stitch,
+                true,
+                channel::RED | channel::GREEN | channel::BLUE,
+                false,
+                &[(1.0e12, -1.0e12)],
+            );
+            let mut again = BitmapData::new(4, 4, false, 0);
+            again.perlin_noise(
+                (1.0, 1.0),
+                MAX_PERLIN_OCTAVES,
+                7,
+                stitch,
+                true,
+                channel::RED | channel::GREEN | channel::BLUE,
+                false,
+                &[(1.0e12, -1.0e12)],
+            );
+            assert_eq!(bitmap.get_pixel32(3, 3), again.get_pixel32(3, 3));
+        }
+    }
+
+    #[test]
+    fn dispose_releases_texture() {
+        let mut renderer = NullRenderer::new();
+        let mut bitmap = BitmapData::new(2, 2, true, 0);
+        bitmap.register(&mut renderer);
+        assert!(bitmap.bitmap_handle().is_some());
+
+        bitmap.dispose(&mut renderer);
+        assert!(bitmap.disposed());
+        assert!(bitmap.bitmap_handle().is_none());
+        assert_eq!(bitmap.get_pixel32(0, 0), 0);
+    }
+}
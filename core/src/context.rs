@@ -4,14 +4,20 @@ use crate::avm1;
 use crate::avm1::listeners::SystemListener;
 use crate::avm1::{Object, Value};
 use crate::backend::input::InputBackend;
-use crate::backend::{audio::AudioBackend, navigator::NavigatorBackend, render::RenderBackend};
+use crate::backend::{
+    audio::AudioBackend, font::FontBackend, navigator::NavigatorBackend, render::RenderBackend,
+};
+use crate::context_menu::ContextMenuCallback;
+use crate::focus_tracker::FocusTracker;
 use crate::library::Library;
 use crate::loader::LoadManager;
 use crate::player::Player;
 use crate::prelude::*;
+use crate::stage::{StageAlign, StageQuality, StageScaleMode};
 use crate::tag_utils::{SwfMovie, SwfSlice};
 use crate::transform::TransformStack;
 use core::fmt;
+use enumset::EnumSet;
 use gc_arena::{Collect, MutationContext};
 use rand::rngs::SmallRng;
 use std::collections::BTreeMap;
@@ -63,6 +69,9 @@ pub struct UpdateContext<'a, 'gc, 'gc_context> {
     /// The input backend, used to detect user interactions.
     pub input: &'a mut dyn InputBackend,
 
+    /// The font backend, used to load the system fonts for device text.
+    pub fonts: &'a mut dyn FontBackend,
+
     /// The RNG, used by the AVM `RandomNumber` opcode,  `Math.random(),` and `random()`.
     pub rng: &'a mut SmallRng,
 
@@ -76,6 +85,9 @@ pub struct UpdateContext<'a, 'gc, 'gc_context> {
     /// The display object that the mouse is currently hovering over.
     pub mouse_hovered_object: Option<DisplayObject<'gc>>,
 
+    /// The display object that has keyboard focus.
+    pub focus_tracker: FocusTracker<'gc>,
+
     /// The location of the mouse when it was last over the player.
     pub mouse_position: &'a (Twips, Twips),
 
@@ -83,8 +95,27 @@ pub struct UpdateContext<'a, 'gc, 'gc_context> {
     pub drag_object: &'a mut Option<crate::player::DragObject<'gc>>,
 
     /// The dimensions of the stage.
+    /// Under `StageScaleMode::NoScale`, these are the dimensions of the viewport.
     pub stage_size: (Twips, Twips),
 
+    /// How the stage is scaled into the viewport.
+    /// Changes are applied by the player after the update.
+    pub stage_scale_mode: &'a mut StageScaleMode,
+
+    /// How the stage is aligned within the viewport.
+    /// Changes are applied by the player after the update.
+    pub stage_align: &'a mut EnumSet<StageAlign>,
+
+    /// The rendering quality of the movie.
+    /// Changes are passed to the render backend by the player after the update.
+    pub stage_quality: &'a mut StageQuality,
+
+    /// Whether the built-in items of the context menu are shown. Equivalent to `Stage.showMenu`.
+    pub show_menu: &'a mut bool,
+
+    /// The actions of the items of the context menu that was last prepared by the player.
+    pub context_menu_callbacks: &'a mut Vec<ContextMenuCallback<'gc>>,
+
     /// Weak reference to the player.
     ///
     /// Recipients of an update context may upgrade the reference to ensure
@@ -0,0 +1,440 @@
+//! The right-click context menu.
+//!
+//! The player builds the menu with `Player::prepare_context_menu`, and the frontend displays
+//! it, either natively or with a `ContextMenuOverlay` drawn by the player, and then calls
+//! `Player::run_context_menu_callback` with the index of the chosen item.
+
+use crate::avm1::object::get_property_or_undefined;
+use crate::avm1::{Avm1, Object, TObject, Value};
+use crate::backend::render::RenderBackend;
+use crate::context::{ActionType, UpdateContext};
+use crate::font::{Font, Glyph};
+use crate::prelude::*;
+use crate::stage::StageQuality;
+use crate::transform::Transform;
+use gc_arena::{Collect, CollectionContext};
+
+/// An item of the context menu.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContextMenuItem {
+    pub caption: String,
+
+    /// Disabled items are shown grayed out and can't be selected.
+    pub enabled: bool,
+
+    /// Whether a separator line is drawn above this item.
+    pub separator_before: bool,
+
+    /// Whether this item is shown with a check mark.
+    pub checked: bool,
+}
+
+/// What happens when an item of the context menu is selected.
+#[derive(Clone, Copy)]
+pub enum ContextMenuCallback<'gc> {
+    /// Toggles playback of the root movie.
+    Play,
+
+    /// Goes to the first frame of the root movie.
+    Rewind,
+
+    /// Goes to the next frame of the root movie.
+    Forward,
+
+    /// Goes to the previous frame of the root movie.
+    Back,
+
+    /// Changes the rendering quality.
+    Quality(StageQuality),
+
+    /// Calls `onSelect` of an ActionScript `ContextMenuItem`.
+    Avm1 {
+        item: Object<'gc>,
+
+        /// The object that was right-clicked.
+        target: DisplayObject<'gc>,
+    },
+}
+
+unsafe impl<'gc> Collect for ContextMenuCallback<'gc> {
+    #[inline]
+    fn trace(&self, cc: CollectionContext) {
+        if let ContextMenuCallback::Avm1 { item, target } = self {
+            item.trace(cc);
+            target.trace(cc);
+        }
+    }
+}
+
+/// Returns the `menu` of `target` or of its nearest ancestor that has one.
+pub(crate) fn find_menu<'gc>(
+    target: DisplayObject<'gc>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Option<Object<'gc>> {
+    let mut display_object = Some(target);
+    while let Some(object) = display_object {
+        if let Value::Object(menu) =
+            get_property_or_undefined(object.object(), "menu", avm, context)
+        {
+            return Some(menu);
+        }
+        display_object = object.parent();
+    }
+    None
+}
+
+/// Builds the items of the context menu for a right click on `target`, and stores their
+/// actions in the update context.
+///
+/// Visible items of `menu.customItems` come first, followed by the built-in items
+/// enabled in `menu.builtInItems`. The built-in items are hidden by `Stage.showMenu`.
+pub(crate) fn build_items<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    target: DisplayObject<'gc>,
+    menu: Option<Object<'gc>>,
+) -> Vec<ContextMenuItem> {
+    let swf_version = avm.current_swf_version();
+    let mut items = vec![];
+    let mut callbacks = vec![];
+
+    let mut built_in_items = None;
+    if let Some(menu) = menu {
+        if let Value::Object(custom_items) =
+            get_property_or_undefined(menu.into(), "customItems", avm, context)
+        {
+            for i in 0..custom_items.length() {
+                let item = match custom_items.array_element(i) {
+                    Value::Object(item) => item,
+                    _ => continue,
+                };
+                let mut flag = |name: &str| {
+                    get_property_or_undefined(item.into(), name, avm, context).as_bool(swf_version)
+                };
+                if !flag("visible") {
+                    continue;
+                }
+                let enabled = flag("enabled");
+                let separator_before = flag("separatorBefore");
+                let caption = get_property_or_undefined(item.into(), "caption", avm, context)
+                    .coerce_to_string(avm, context)
+                    .unwrap_or_default();
+                items.push(ContextMenuItem {
+                    caption,
+                    enabled,
+                    separator_before,
+                    checked: false,
+                });
+                callbacks.push(ContextMenuCallback::Avm1 { item, target });
+            }
+        }
+        if let Value::Object(object) =
+            get_property_or_undefined(menu.into(), "builtInItems", avm, context)
+        {
+            built_in_items = Some(object);
+        }
+    }
+
+    if *context.show_menu {
+        let mut is_shown = |name: &str| {
+            built_in_items.map_or(true, |built_in_items| {
+                get_property_or_undefined(built_in_items.into(), name, avm, context)
+                    .as_bool(swf_version)
+            })
+        };
+        let show_play = is_shown("play");
+        let show_rewind = is_shown("rewind");
+        let show_forward_back = is_shown("forward_back");
+        let show_quality = is_shown("quality");
+
+        let built_in = |caption: &str, separator_before: bool, checked: bool| ContextMenuItem {
+            caption: caption.to_string(),
+            enabled: true,
+            separator_before,
+            checked,
+        };
+
+        let root = context.levels.get(&0).and_then(|root| root.as_movie_clip());
+        if let Some(root) = root.filter(|root| root.total_frames() > 1) {
+            if show_play {
+                items.push(built_in("Play", true, root.playing()));
+                callbacks.push(ContextMenuCallback::Play);
+            }
+            if show_rewind {
+                items.push(built_in("Rewind", true, false));
+                callbacks.push(ContextMenuCallback::Rewind);
+            }
+            if show_forward_back {
+                items.push(built_in("Forward", false, false));
+                callbacks.push(ContextMenuCallback::Forward);
+                items.push(built_in("Back", false, false));
+                callbacks.push(ContextMenuCallback::Back);
+            }
+        }
+
+        if show_quality {
+            let qualities = [
+                ("Low Quality", StageQuality::Low),
+                ("Medium Quality", StageQuality::Medium),
+                ("High Quality", StageQuality::High),
+            ];
+            for (i, &(caption, quality)) in qualities.iter().enumerate() {
+                items.push(built_in(caption, i == 0, *context.stage_quality == quality));
+                callbacks.push(ContextMenuCallback::Quality(quality));
+            }
+        }
+    }
+
+    *context.context_menu_callbacks = callbacks;
+    items
+}
+
+/// Runs the action of the item at `index` of the last built context menu.
+pub(crate) fn run_callback<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    index: usize,
+) {
+    let callback = match context.context_menu_callbacks.get(index) {
+        Some(callback) => *callback,
+        None => {
+            log::warn!("Context menu item {} does not exist", index);
+            return;
+        }
+    };
+
+    let root = context.levels.get(&0).and_then(|root| root.as_movie_clip());
+    match (callback, root) {
+        (ContextMenuCallback::Play, Some(root)) => {
+            if root.playing() {
+                root.stop(context);
+            } else {
+                root.play(context);
+            }
+        }
+        (ContextMenuCallback::Rewind, Some(root)) => root.goto_frame(avm, context, 1, true),
+        (ContextMenuCallback::Forward, Some(root)) => root.next_frame(avm, context),
+        (ContextMenuCallback::Back, Some(root)) => root.prev_frame(avm, context),
+        (ContextMenuCallback::Quality(quality), _) => *context.stage_quality = quality,
+        (ContextMenuCallback::Avm1 { item, target }, _) => {
+            context.action_queue.queue_actions(
+                target,
+                ActionType::Method {
+                    object: item,
+                    name: "onSelect",
+                    args: vec![target.object(), item.into()],
+                },
+                false,
+            );
+        }
+        _ => (),
+    }
+}
+
+/// The height of an item, in viewport pixels.
+const ITEM_HEIGHT: f32 = 22.0;
+
+/// The extra space taken by a separator line, in viewport pixels.
+const SEPARATOR_HEIGHT: f32 = 8.0;
+
+/// The space left of the captions, where check marks are drawn.
+const LEFT_PADDING: f32 = 24.0;
+
+const RIGHT_PADDING: f32 = 16.0;
+
+const MIN_WIDTH: f32 = 120.0;
+
+/// The size of the caption text, in viewport pixels.
+const FONT_SIZE: f64 = 12.0;
+
+/// The distance from the top of an item to the baseline of its caption.
+const BASELINE: f32 = 15.0;
+
+const BACKGROUND_COLOR: Color = Color {
+    r: 240,
+    g: 240,
+    b: 240,
+    a: 255,
+};
+
+const BORDER_COLOR: Color = Color {
+    r: 128,
+    g: 128,
+    b: 128,
+    a: 255,
+};
+
+const HIGHLIGHT_COLOR: Color = Color {
+    r: 51,
+    g: 153,
+    b: 255,
+    a: 255,
+};
+
+const TEXT_COLOR: Color = Color {
+    r: 0,
+    g: 0,
+    b: 0,
+    a: 255,
+};
+
+const DISABLED_TEXT_COLOR: Color = Color {
+    r: 160,
+    g: 160,
+    b: 160,
+    a: 255,
+};
+
+const HIGHLIGHT_TEXT_COLOR: Color = Color {
+    r: 255,
+    g: 255,
+    b: 255,
+    a: 255,
+};
+
+/// An open context menu drawn by the player on top of the stage, for frontends that
+/// can't show native menus. Positions are in viewport pixels.
+pub struct ContextMenuOverlay {
+    items: Vec<ContextMenuItem>,
+
+    /// The top of each item.
+    item_tops: Vec<f32>,
+
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+
+    /// The item under the mouse cursor.
+    hovered: Option<usize>,
+}
+
+impl ContextMenuOverlay {
+    /// Lays out `items` with the top left corner at the given position, moving the menu
+    /// as needed to keep it within the viewport.
+    pub(crate) fn new(
+        items: Vec<ContextMenuItem>,
+        position: (f32, f32),
+        viewport_size: (f32, f32),
+        font: Font<'_>,
+        renderer: &mut dyn RenderBackend,
+    ) -> Self {
+        let mut width = MIN_WIDTH;
+        let mut item_tops = Vec::with_capacity(items.len());
+        let mut height = 0.0;
+        for (i, item) in items.iter().enumerate() {
+            if item.separator_before && i > 0 {
+                height += SEPARATOR_HEIGHT;
+            }
+            item_tops.push(height);
+            height += ITEM_HEIGHT;
+
+            font.load_glyphs(renderer, &item.caption);
+            let (text_width, _) = font.measure(&item.caption, Twips::from_pixels(FONT_SIZE));
+            width = width.max(LEFT_PADDING + text_width.to_pixels() as f32 + RIGHT_PADDING);
+        }
+
+        let x = position.0.min(viewport_size.0 - width).max(0.0);
+        let y = position.1.min(viewport_size.1 - height).max(0.0);
+        for top in &mut item_tops {
+            *top += y;
+        }
+
+        Self {
+            items,
+            item_tops,
+            x,
+            y,
+            width,
+            height,
+            hovered: None,
+        }
+    }
+
+    pub fn items(&self) -> &[ContextMenuItem] {
+        &self.items
+    }
+
+    /// Returns the index of the enabled item at the given position.
+    pub fn item_at(&self, x: f32, y: f32) -> Option<usize> {
+        if x < self.x || x >= self.x + self.width {
+            return None;
+        }
+        self.item_tops
+            .iter()
+            .position(|&top| y >= top && y < top + ITEM_HEIGHT)
+            .filter(|&i| self.items[i].enabled)
+    }
+
+    /// Returns whether the given position is within the menu.
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    /// Highlights the item under the mouse cursor.
+    /// Returns `true` if the highlighted item changed.
+    pub(crate) fn set_hovered(&mut self, hovered: Option<usize>) -> bool {
+        let changed = self.hovered != hovered;
+        self.hovered = hovered;
+        changed
+    }
+
+    pub(crate) fn render(&self, renderer: &mut dyn RenderBackend, font: Font<'_>) {
+        let (x, y, width, height) = (self.x, self.y, self.width, self.height);
+        renderer.draw_rect(x, y, width, height, BACKGROUND_COLOR);
+        renderer.draw_rect(x, y, width, 1.0, BORDER_COLOR);
+        renderer.draw_rect(x, y + height - 1.0, width, 1.0, BORDER_COLOR);
+        renderer.draw_rect(x, y, 1.0, height, BORDER_COLOR);
+        renderer.draw_rect(x + width - 1.0, y, 1.0, height, BORDER_COLOR);
+
+        for (i, (item, &top)) in self.items.iter().zip(&self.item_tops).enumerate() {
+            if item.separator_before && i > 0 {
+                let line_y = top - SEPARATOR_HEIGHT / 2.0;
+                renderer.draw_rect(x + 4.0, line_y, width - 8.0, 1.0, BORDER_COLOR);
+            }
+
+            let hovered = self.hovered == Some(i);
+            if hovered {
+                renderer.draw_rect(x + 1.0, top, width - 2.0, ITEM_HEIGHT, HIGHLIGHT_COLOR);
+            }
+
+            let text_color = if !item.enabled {
+                DISABLED_TEXT_COLOR
+            } else if hovered {
+                HIGHLIGHT_TEXT_COLOR
+            } else {
+                TEXT_COLOR
+            };
+
+            if item.checked {
+                let size = 6.0;
+                let center = LEFT_PADDING / 2.0;
+                renderer.draw_rect(
+                    x + center - size / 2.0,
+                    top + (ITEM_HEIGHT - size) / 2.0,
+                    size,
+                    size,
+                    text_color.clone(),
+                );
+            }
+
+            let height = Twips::from_pixels(FONT_SIZE);
+            let mut transform = Transform::default();
+            transform.matrix.tx = Twips::from_pixels((x + LEFT_PADDING).into());
+            transform.matrix.ty = Twips::from_pixels((top + BASELINE).into()) - height;
+            transform.color_transform.r_mult = f32::from(text_color.r) / 255.0;
+            transform.color_transform.g_mult = f32::from(text_color.g) / 255.0;
+            transform.color_transform.b_mult = f32::from(text_color.b) / 255.0;
+            transform.color_transform.a_mult = f32::from(text_color.a) / 255.0;
+            font.evaluate(
+                &item.caption,
+                transform,
+                height,
+                |transform, glyph: &Glyph| {
+                    renderer.render_shape(glyph.shape, transform);
+                },
+            );
+        }
+    }
+}
@@ -1,9 +1,11 @@
 use crate::avm1::{Avm1, Object, TObject, Value};
+use crate::backend::render::{self, BitmapHandle, RenderBackend};
 use crate::context::{RenderContext, UpdateContext};
+use crate::library::Library;
 use crate::player::NEWEST_PLAYER_VERSION;
 use crate::prelude::*;
 use crate::tag_utils::SwfMovie;
-use crate::transform::Transform;
+use crate::transform::{Transform, TransformStack};
 use enumset::{EnumSet, EnumSetType};
 use gc_arena::{Collect, MutationContext};
 use ruffle_macros::enum_trait_object;
@@ -38,6 +40,9 @@ pub struct DisplayObjectBase<'gc> {
     name: String,
     clip_depth: Depth,
 
+    /// The bitmap filters applied to this display object.
+    filters: Vec<swf::Filter>,
+
     // Cached transform properties `_xscale`, `_yscale`, `_rotation`.
     // These are expensive to calculate, so they will be calculated and cached when AS requests
     // one of these properties.
@@ -69,6 +74,7 @@ impl<'gc> Default for DisplayObjectBase<'gc> {
             transform: Default::default(),
             name: Default::default(),
             clip_depth: Default::default(),
+            filters: Vec::new(),
             rotation: 0.0,
             scale_x: 1.0,
             scale_y: 1.0,
@@ -274,6 +280,12 @@ impl<'gc> DisplayObjectBase<'gc> {
     fn set_clip_depth(&mut self, _context: MutationContext<'gc, '_>, depth: Depth) {
         self.clip_depth = depth;
     }
+    fn filters(&self) -> &[swf::Filter] {
+        &self.filters
+    }
+    fn set_filters(&mut self, _context: MutationContext<'gc, '_>, filters: Vec<swf::Filter>) {
+        self.filters = filters;
+    }
     fn parent(&self) -> Option<DisplayObject<'gc>> {
         self.parent
     }
@@ -630,6 +642,11 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
 
     fn clip_depth(&self) -> Depth;
     fn set_clip_depth(&mut self, context: MutationContext<'gc, '_>, depth: Depth);
+
+    /// The bitmap filters applied to this display object.
+    fn filters(&self) -> Ref<[swf::Filter]>;
+    fn set_filters(&mut self, context: MutationContext<'gc, '_>, filters: Vec<swf::Filter>);
+
     fn parent(&self) -> Option<DisplayObject<'gc>>;
     fn set_parent(&mut self, context: MutationContext<'gc, '_>, parent: Option<DisplayObject<'gc>>);
     fn first_child(&self) -> Option<DisplayObject<'gc>>;
@@ -778,6 +795,9 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
             if let Some(clip_depth) = place_object.clip_depth {
                 self.set_clip_depth(gc_context, clip_depth.into());
             }
+            if !place_object.filters.is_empty() {
+                self.set_filters(gc_context, place_object.filters.clone());
+            }
             if let Some(ratio) = place_object.ratio {
                 if let Some(mut morph_shape) = self.as_morph_shape() {
                     morph_shape.set_ratio(gc_context, ratio);
@@ -809,6 +829,7 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
         self.set_matrix(gc_context, &*other.matrix());
         self.set_color_transform(gc_context, &*other.color_transform());
         self.set_clip_depth(gc_context, other.clip_depth());
+        self.set_filters(gc_context, other.filters().to_vec());
         self.set_name(gc_context, &*other.name());
         if let (Some(mut me), Some(other)) = (self.as_morph_shape(), other.as_morph_shape()) {
             me.set_ratio(gc_context, other.ratio());
@@ -837,6 +858,15 @@ pub trait TDisplayObject<'gc>: 'gc + Collect + Debug + Into<DisplayObject<'gc>>
         None
     }
 
+    /// Returns the topmost text field under the given stage position, if any.
+    fn text_field_pick(
+        &self,
+        _self_node: DisplayObject<'gc>,
+        _pos: (Twips, Twips),
+    ) -> Option<EditText<'gc>> {
+        None
+    }
+
     fn post_instantiation(
         &mut self,
         _avm: &mut Avm1<'gc>,
@@ -1005,6 +1035,16 @@ macro_rules! impl_display_object {
         ) {
             self.0.write(context).$field.set_clip_depth(context, depth)
         }
+        fn filters(&self) -> std::cell::Ref<[swf::Filter]> {
+            std::cell::Ref::map(self.0.read(), |o| o.$field.filters())
+        }
+        fn set_filters(
+            &mut self,
+            context: gc_arena::MutationContext<'gc, '_>,
+            filters: Vec<swf::Filter>,
+        ) {
+            self.0.write(context).$field.set_filters(context, filters)
+        }
         fn parent(&self) -> Option<crate::display_object::DisplayObject<'gc>> {
             self.0.read().$field.parent()
         }
@@ -1125,6 +1165,55 @@ pub fn render_children<'gc>(
     }
 }
 
+/// Renders a display object and its children into an offscreen bitmap.
+///
+/// The display object's own matrix is replaced by the matrix of `transform`, as with
+/// `BitmapData.draw`. If `target` is given, the display object is drawn over the existing
+/// contents of that bitmap, and the texture of that bitmap is updated with the result.
+///
+/// Returns the rendered pixels, or `None` if the render backend does not support offscreen
+/// rendering.
+#[allow(clippy::too_many_arguments)]
+pub fn render_to_bitmap<'gc>(
+    display_object: DisplayObject<'gc>,
+    renderer: &mut dyn RenderBackend,
+    library: &Library<'gc>,
+    transform: &Transform,
+    target: Option<BitmapHandle>,
+    width: u32,
+    height: u32,
+    clear: Color,
+) -> Option<render::Bitmap> {
+    let mut inverse_matrix = *display_object.matrix();
+    inverse_matrix.invert();
+
+    let mut transform_stack = TransformStack::new();
+    transform_stack.push(&Transform {
+        matrix: transform.matrix * inverse_matrix,
+        color_transform: transform.color_transform,
+    });
+
+    renderer.begin_render_to_bitmap(target, width, height, clear);
+    {
+        let mut render_context = RenderContext {
+            renderer,
+            library,
+            transform_stack: &mut transform_stack,
+            // The display object isn't drawn in stage space, so disable culling.
+            view_bounds: BoundingBox {
+                x_min: Twips::new(i32::min_value()),
+                y_min: Twips::new(i32::min_value()),
+                x_max: Twips::new(i32::max_value()),
+                y_max: Twips::new(i32::max_value()),
+                valid: true,
+            },
+            clip_depth_stack: vec![],
+        };
+        display_object.render(&mut render_context);
+    }
+    renderer.end_render_to_bitmap()
+}
+
 impl<'gc> DisplayObject<'gc> {
     pub fn ptr_eq(a: DisplayObject<'gc>, b: DisplayObject<'gc>) -> bool {
         a.as_ptr() == b.as_ptr()
@@ -1,6 +1,6 @@
 //! Bitmap display object
 
-use crate::avm1::Avm1;
+use crate::avm1::{Avm1, BitmapDataObject};
 use crate::backend::render::BitmapHandle;
 use crate::context::{RenderContext, UpdateContext};
 use crate::display_object::{DisplayObjectBase, TDisplayObject};
@@ -13,7 +13,8 @@ use gc_arena::{Collect, Gc, GcCell};
 /// Normally bitmaps are drawn in Flash as part of a Shape tag (`Graphic`),
 /// but starting in AVM2, a raw `Bitmap` display object can be crated
 /// with the `PlaceObject3` tag.
-/// It can also be crated in ActionScript using the `Bitmap` class.
+/// It can also be crated in ActionScript using the `Bitmap` class,
+/// or in AVM1 using `MovieClip.attachBitmap`.
 #[derive(Clone, Debug, Collect, Copy)]
 #[collect(no_drop)]
 pub struct Bitmap<'gc>(GcCell<'gc, BitmapData<'gc>>);
@@ -22,6 +23,10 @@ pub struct Bitmap<'gc>(GcCell<'gc, BitmapData<'gc>>);
 pub struct BitmapData<'gc> {
     base: DisplayObjectBase<'gc>,
     static_data: Gc<'gc, BitmapStatic>,
+
+    /// The AVM1 `BitmapData` backing this bitmap, if it was created by `attachBitmap`.
+    /// Changes to its pixels are uploaded to the renderer when this bitmap is drawn.
+    bitmap_data: Option<BitmapDataObject<'gc>>,
 }
 
 impl<'gc> Bitmap<'gc> {
@@ -45,21 +50,45 @@ impl<'gc> Bitmap<'gc> {
                         height,
                     },
                 ),
+                bitmap_data: None,
             },
         ))
     }
 
+    /// Creates a bitmap that displays the pixels of an AVM1 `BitmapData`.
+    pub fn new_with_bitmap_data(
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        bitmap_data: BitmapDataObject<'gc>,
+    ) -> Self {
+        let (bitmap_handle, width, height) = {
+            let mut data = bitmap_data.bitmap_data_mut(context.gc_context);
+            let bitmap_handle = data.register(context.renderer);
+            (bitmap_handle, data.width() as u16, data.height() as u16)
+        };
+        let bitmap = Self::new(context, 0, bitmap_handle, width, height);
+        bitmap.0.write(context.gc_context).bitmap_data = Some(bitmap_data);
+        bitmap
+    }
+
     #[allow(dead_code)]
     pub fn bitmap_handle(self) -> BitmapHandle {
         self.0.read().static_data.bitmap_handle
     }
 
     pub fn width(self) -> u16 {
-        self.0.read().static_data.width
+        let bitmap = self.0.read();
+        match bitmap.bitmap_data {
+            Some(bitmap_data) => bitmap_data.bitmap_data().width() as u16,
+            None => bitmap.static_data.width,
+        }
     }
 
     pub fn height(self) -> u16 {
-        self.0.read().static_data.height
+        let bitmap = self.0.read();
+        match bitmap.bitmap_data {
+            Some(bitmap_data) => bitmap_data.bitmap_data().height() as u16,
+            None => bitmap.static_data.height,
+        }
     }
 }
 
@@ -90,6 +119,14 @@ impl<'gc> TDisplayObject<'gc> for Bitmap<'gc> {
             return;
         }
 
+        if let Some(bitmap_data) = self.0.read().bitmap_data {
+            let bitmap_data = bitmap_data.bitmap_data();
+            if bitmap_data.disposed() {
+                return;
+            }
+            bitmap_data.update_texture_if_dirty(context.renderer);
+        }
+
         context.transform_stack.push(&*self.transform());
 
         context.renderer.render_bitmap(
@@ -105,6 +142,7 @@ unsafe impl<'gc> gc_arena::Collect for BitmapData<'gc> {
     fn trace(&self, cc: gc_arena::CollectionContext) {
         self.base.trace(cc);
         self.static_data.trace(cc);
+        self.bitmap_data.trace(cc);
     }
 }
 
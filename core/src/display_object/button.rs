@@ -23,6 +23,15 @@ pub struct ButtonData<'gc> {
     tracking: ButtonTracking,
     object: Option<Object<'gc>>,
     initialized: bool,
+
+    /// Whether the button responds to the mouse and keyboard. Equivalent to `enabled`.
+    enabled: bool,
+
+    /// Whether the hand cursor is shown over the button. Equivalent to `useHandCursor`.
+    use_hand_cursor: bool,
+
+    /// The position of the button in the tab order. Equivalent to `tabIndex`.
+    tab_index: Option<i32>,
 }
 
 impl<'gc> Button<'gc> {
@@ -34,7 +43,9 @@ impl<'gc> Button<'gc> {
     ) -> Self {
         let mut actions = vec![];
         for action in &button.actions {
-            let action_data = source_movie.owned_subslice(action.action_data.clone());
+            let action_data = source_movie
+                .to_subslice(action.action_data)
+                .unwrap_or_else(|| source_movie.owned_subslice(action.action_data.to_vec()));
             for condition in &action.conditions {
                 let button_action = ButtonAction {
                     action_data: action_data.clone(),
@@ -68,6 +79,9 @@ impl<'gc> Button<'gc> {
                 state: self::ButtonState::Up,
                 initialized: false,
                 object: None,
+                enabled: true,
+                use_hand_cursor: true,
+                tab_index: None,
                 tracking: if button.is_track_as_menu {
                     ButtonTracking::Menu
                 } else {
@@ -88,6 +102,62 @@ impl<'gc> Button<'gc> {
             .handle_button_event((*self).into(), avm, context, event)
     }
 
+    /// Shows the up state of this button without running any events, such as after it is
+    /// clicked with the keyboard while the mouse isn't over it.
+    pub fn show_up_state(
+        &mut self,
+        avm: &mut Avm1<'gc>,
+        context: &mut crate::context::UpdateContext<'_, 'gc, '_>,
+    ) {
+        let mut button = self.0.write(context.gc_context);
+        if button.state != ButtonState::Up {
+            button.set_state((*self).into(), avm, context, ButtonState::Up);
+        }
+    }
+
+    pub fn enabled(self) -> bool {
+        self.0.read().enabled
+    }
+
+    pub fn set_enabled(self, gc_context: MutationContext<'gc, '_>, enabled: bool) {
+        self.0.write(gc_context).enabled = enabled;
+    }
+
+    pub fn use_hand_cursor(self) -> bool {
+        self.0.read().use_hand_cursor
+    }
+
+    pub fn set_use_hand_cursor(self, gc_context: MutationContext<'gc, '_>, use_hand_cursor: bool) {
+        self.0.write(gc_context).use_hand_cursor = use_hand_cursor;
+    }
+
+    /// Whether the button tracks as a menu item: it can be pressed by dragging onto it
+    /// with the mouse down, instead of capturing the mouse while pressed.
+    pub fn is_track_as_menu(self) -> bool {
+        self.0.read().tracking == ButtonTracking::Menu
+    }
+
+    pub fn set_track_as_menu(self, gc_context: MutationContext<'gc, '_>, track_as_menu: bool) {
+        self.0.write(gc_context).tracking = if track_as_menu {
+            ButtonTracking::Menu
+        } else {
+            ButtonTracking::Push
+        };
+    }
+
+    pub fn tab_index(self) -> Option<i32> {
+        self.0.read().tab_index
+    }
+
+    pub fn set_tab_index(self, gc_context: MutationContext<'gc, '_>, tab_index: Option<i32>) {
+        self.0.write(gc_context).tab_index = tab_index;
+    }
+
+    /// Whether the button is pressed with the mouse over it.
+    pub fn is_down(self) -> bool {
+        self.0.read().state == ButtonState::Down
+    }
+
     pub fn set_sounds(self, gc_context: MutationContext<'gc, '_>, sounds: swf::ButtonSounds) {
         let button = self.0.write(gc_context);
         let mut static_data = button.static_data.write(gc_context);
@@ -198,11 +268,13 @@ impl<'gc> TDisplayObject<'gc> for Button<'gc> {
             }
         }
         match event {
-            ButtonEvent::KeyPress { key_code } => self.0.write(context.gc_context).run_actions(
-                context,
-                swf::ButtonActionCondition::KeyPress,
-                Some(key_code),
-            ),
+            ButtonEvent::KeyPress { key_code } if self.enabled() => {
+                self.0.write(context.gc_context).run_actions(
+                    context,
+                    swf::ButtonActionCondition::KeyPress,
+                    Some(key_code),
+                )
+            }
             _ => ButtonEventResult::NotHandled,
         }
     }
@@ -312,55 +384,89 @@ impl<'gc> ButtonData<'gc> {
         context: &mut crate::context::UpdateContext<'_, 'gc, '_>,
         event: ButtonEvent,
     ) {
-        let cur_state = self.state;
-        let new_state = match event {
-            ButtonEvent::RollOut => ButtonState::Up,
-            ButtonEvent::RollOver => ButtonState::Over,
-            ButtonEvent::Press => ButtonState::Down,
-            ButtonEvent::Release => ButtonState::Over,
+        use swf::ButtonActionCondition as Condition;
+
+        if !self.enabled || !self.is_transition(event) {
+            return;
+        }
+
+        let static_data = self.static_data;
+        let static_data = static_data.read();
+        let is_menu = self.tracking == ButtonTracking::Menu;
+        let (new_state, condition, sound, handler) = match event {
             ButtonEvent::KeyPress { key_code } => {
-                self.run_actions(
-                    context,
-                    swf::ButtonActionCondition::KeyPress,
-                    Some(key_code),
-                );
-                cur_state
+                self.run_actions(context, Condition::KeyPress, Some(key_code));
+                return;
             }
+            ButtonEvent::RollOver => (
+                ButtonState::Over,
+                Condition::IdleToOverUp,
+                static_data.up_to_over_sound.as_ref(),
+                "onRollOver",
+            ),
+            ButtonEvent::RollOut => (
+                ButtonState::Up,
+                Condition::OverUpToIdle,
+                static_data.over_to_up_sound.as_ref(),
+                "onRollOut",
+            ),
+            ButtonEvent::Press => (
+                ButtonState::Down,
+                Condition::OverUpToOverDown,
+                static_data.over_to_down_sound.as_ref(),
+                "onPress",
+            ),
+            ButtonEvent::Release => (
+                ButtonState::Over,
+                Condition::OverDownToOverUp,
+                static_data.down_to_over_sound.as_ref(),
+                "onRelease",
+            ),
+            // A pushed button shows its over state while the mouse is dragged outside.
+            ButtonEvent::DragOut if is_menu => (
+                ButtonState::Up,
+                Condition::OverDownToIdle,
+                None,
+                "onDragOut",
+            ),
+            ButtonEvent::DragOut => (
+                ButtonState::Over,
+                Condition::OverDownToOutDown,
+                None,
+                "onDragOut",
+            ),
+            ButtonEvent::DragOver if is_menu => (
+                ButtonState::Down,
+                Condition::IdleToOverDown,
+                None,
+                "onDragOver",
+            ),
+            ButtonEvent::DragOver => (
+                ButtonState::Down,
+                Condition::OutDownToOverDown,
+                None,
+                "onDragOver",
+            ),
+            ButtonEvent::ReleaseOutside => (
+                ButtonState::Up,
+                Condition::OutDownToIdle,
+                static_data.over_to_up_sound.as_ref(),
+                "onReleaseOutside",
+            ),
         };
 
-        let button_event_handler = match (cur_state, new_state) {
-            (ButtonState::Up, ButtonState::Over) => {
-                self.run_actions(context, swf::ButtonActionCondition::IdleToOverUp, None);
-                self.play_sound(context, self.static_data.read().up_to_over_sound.as_ref());
-                Some("onRollOver")
-            }
-            (ButtonState::Over, ButtonState::Up) => {
-                self.run_actions(context, swf::ButtonActionCondition::OverUpToIdle, None);
-                self.play_sound(context, self.static_data.read().over_to_up_sound.as_ref());
-                Some("onRollOut")
-            }
-            (ButtonState::Over, ButtonState::Down) => {
-                self.run_actions(context, swf::ButtonActionCondition::OverUpToOverDown, None);
-                self.play_sound(context, self.static_data.read().over_to_down_sound.as_ref());
-                Some("onPress")
-            }
-            (ButtonState::Down, ButtonState::Over) => {
-                self.run_actions(context, swf::ButtonActionCondition::OverDownToOverUp, None);
-                self.play_sound(context, self.static_data.read().down_to_over_sound.as_ref());
-                Some("onRelease")
-            }
-            _ => None,
-        };
+        self.run_actions(context, condition, None);
+        self.play_sound(context, sound);
 
         // Queue ActionScript-defined event handlers after the SWF defined ones.
         // (e.g., clip.onRelease = foo).
         if context.swf.version() >= 6 {
-            if let Some(name) = button_event_handler {
+            if let Some(object) = self.object {
                 context.action_queue.queue_actions(
                     self_display_object,
                     ActionType::Method {
-                        object: self.object.unwrap(),
-                        name,
+                        object,
+                        name: handler,
                         args: vec![],
                     },
                     false,
@@ -368,7 +474,24 @@ impl<'gc> ButtonData<'gc> {
             }
         }
 
-        self.set_state(self_display_object, avm, context, new_state);
+        if new_state != self.state {
+            self.set_state(self_display_object, avm, context, new_state);
+        }
+    }
+
+    /// Whether `event` leaves the current state of this button. Events that don't, such as
+    /// a second press without a release, run no actions or handlers.
+    ///
+    /// A pushed button dragged out shows its over state, which is told apart from the mouse
+    /// being over the button by the mouse being down.
+    fn is_transition(&self, event: ButtonEvent) -> bool {
+        match event {
+            ButtonEvent::KeyPress { .. } => true,
+            ButtonEvent::RollOver => self.state == ButtonState::Up,
+            ButtonEvent::RollOut | ButtonEvent::ReleaseOutside => self.state == ButtonState::Over,
+            ButtonEvent::Press | ButtonEvent::DragOver => self.state != ButtonState::Down,
+            ButtonEvent::Release | ButtonEvent::DragOut => self.state == ButtonState::Down,
+        }
     }
 
     fn play_sound(
@@ -47,6 +47,9 @@ pub struct EditTextData<'gc> {
     /// Cached breakpoints of where to make newlines.
     cached_break_points: Option<Vec<usize>>,
 
+    /// The one-based index of the topmost visible line.
+    scroll: usize,
+
     // The AVM1 object handle
     object: Option<Object<'gc>>,
 }
@@ -84,7 +87,7 @@ impl<'gc> EditText<'gc> {
             swf_tag.initial_text.clone().unwrap_or_default()
         };
 
-        EditText(GcCell::allocate(
+        let edit_text = EditText(GcCell::allocate(
             context.gc_context,
             EditTextData {
                 base: Default::default(),
@@ -101,8 +104,11 @@ impl<'gc> EditText<'gc> {
                 is_word_wrap,
                 object: None,
                 cached_break_points: None,
+                scroll: 1,
             },
-        ))
+        ));
+        edit_text.load_device_font(context);
+        edit_text
     }
 
     /// Create a new, dynamic `EditText`.
@@ -170,9 +176,10 @@ impl<'gc> EditText<'gc> {
         self.0.read().new_format.clone()
     }
 
-    pub fn set_new_text_format(self, tf: TextFormat, gc_context: MutationContext<'gc, '_>) {
-        self.0.write(gc_context).cached_break_points = None;
-        self.0.write(gc_context).new_format = tf;
+    pub fn set_new_text_format(self, tf: TextFormat, context: &mut UpdateContext<'_, 'gc, '_>) {
+        self.0.write(context.gc_context).cached_break_points = None;
+        self.0.write(context.gc_context).new_format = tf;
+        self.load_device_font(context);
     }
 
     pub fn is_multiline(self) -> bool {
@@ -193,6 +200,66 @@ impl<'gc> EditText<'gc> {
         self.0.write(gc_context).is_word_wrap = is_word_wrap;
     }
 
+    /// Whether the user can edit the text of this text field.
+    pub fn is_editable(self) -> bool {
+        !self.0.read().static_data.text.is_read_only
+    }
+
+    /// The one-based index of the topmost visible line of text.
+    pub fn scroll(self) -> usize {
+        self.0.read().scroll
+    }
+
+    /// Scrolls the text so that the given line is the topmost visible line.
+    /// The line is clamped between 1 and `maxscroll`.
+    pub fn set_scroll(self, scroll: f64, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let max_scroll = self.maxscroll(context);
+        let scroll = if scroll.is_nan() {
+            1
+        } else {
+            scroll.clamp(1.0, max_scroll as f64) as usize
+        };
+        self.0.write(context.gc_context).scroll = scroll;
+    }
+
+    /// The largest value of `scroll`, which shows the last line of text at the
+    /// bottom of the text field.
+    pub fn maxscroll(self, context: &mut UpdateContext<'_, 'gc, '_>) -> usize {
+        let num_lines = self
+            .line_breaks_cached(context.gc_context, context.library)
+            .len()
+            + 1;
+        let visible_lines = self.visible_lines(context.library);
+        (num_lines + 1).saturating_sub(visible_lines).max(1)
+    }
+
+    /// The height of a line of text, including the leading between lines.
+    fn line_height(self, font: Font<'gc>) -> Twips {
+        let static_data = &self.0.read().static_data;
+        let mut height = static_data
+            .text
+            .height
+            .unwrap_or_else(|| Twips::from_pixels(font.scale().into()));
+        if let Some(layout) = &static_data.text.layout {
+            height += layout.leading;
+        }
+        height
+    }
+
+    /// The number of lines that fully fit in the bounds of this text field, at least one.
+    fn visible_lines(self, library: &Library<'gc>) -> usize {
+        let line_height = match self.font(library) {
+            Some(font) => self.line_height(font),
+            None => return 1,
+        };
+        if line_height <= Twips::new(0) {
+            return 1;
+        }
+        let bounds = &self.0.read().static_data.text.bounds;
+        let height = bounds.y_max - bounds.y_min;
+        ((height.get() / line_height.get()) as usize).max(1)
+    }
+
     /// Construct a base text transform for this `EditText`, to be used for
     /// evaluating fonts.
     ///
@@ -352,6 +419,9 @@ impl<'gc> EditText<'gc> {
     ///
     /// The returned tuple should be interpreted as width, then height.
     pub fn measure_text(self, context: &mut UpdateContext<'_, 'gc, '_>) -> (Twips, Twips) {
+        if let Some(font) = self.font(context.library) {
+            font.load_glyphs(context.renderer, &self.0.read().text);
+        }
         let breakpoints = self.line_breaks_cached(context.gc_context, context.library);
 
         let edit_text = self.0.read();
@@ -392,20 +462,71 @@ impl<'gc> EditText<'gc> {
     /// or if the font is not found.
     fn font(self, library: &Library<'gc>) -> Option<Font<'gc>> {
         let static_data = self.0.read().static_data;
-        let library = library.library_for_movie(static_data.swf.clone()).unwrap();
         if static_data.text.is_device_font {
             // We're cheating a bit and not actually rendering "device text" using the OS/web.
-            // Instead, we embed an SWF version of Noto Sans to use as the "device font", and render
-            // it the same as any other SWF outline text.
-            library.device_font()
+            // Instead, we convert the glyphs of the fonts given by the font backend, or of an
+            // embedded SWF version of Noto Sans, and render them like any other outline text.
+            library.device_fonts().get(&self.device_font_name(library))
         } else {
+            // Movies without any characters, such as one that creates all of its text
+            // fields with `createTextField`, have no library.
             let font_id = static_data.text.font_id.unwrap_or_default();
             library
-                .get_font(font_id)
+                .library_for_movie(static_data.swf.clone())
+                .and_then(|movie_library| movie_library.get_font(font_id))
                 .filter(|font| font.has_glyphs())
-                .or_else(|| library.device_font())
+                .or_else(|| library.device_fonts().get(&self.device_font_name(library)))
         }
     }
+
+    /// The name of the device font used by this text field.
+    ///
+    /// This is the font of the text format if one is set, otherwise the name of the font
+    /// referenced by the `DefineEditText` tag, falling back to `_sans`.
+    fn device_font_name(self, library: &Library<'gc>) -> String {
+        let edit_text = self.0.read();
+        if let Some(name) = edit_text.new_format.font() {
+            return name.to_string();
+        }
+        let static_data = &edit_text.static_data;
+        static_data
+            .text
+            .font_id
+            .and_then(|font_id| {
+                library
+                    .library_for_movie(static_data.swf.clone())?
+                    .get_font(font_id)
+            })
+            .map(|font| font.name().to_string())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "_sans".to_string())
+    }
+
+    /// Loads the device font of this text field from the font backend, so that it is
+    /// available when the text is drawn. Does nothing if the text uses an embedded font.
+    fn load_device_font(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let static_data = self.0.read().static_data;
+        let has_embedded_font = !static_data.text.is_device_font
+            && static_data
+                .text
+                .font_id
+                .and_then(|font_id| {
+                    context
+                        .library
+                        .library_for_movie(static_data.swf.clone())?
+                        .get_font(font_id)
+                })
+                .map_or(false, |font| font.has_glyphs());
+        if has_embedded_font {
+            return;
+        }
+
+        let name = self.device_font_name(context.library);
+        context
+            .library
+            .device_fonts_mut()
+            .load(context.gc_context, context.fonts, &name);
+    }
 }
 
 impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
@@ -474,6 +595,7 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
         // Instead, we embed an SWF version of Noto Sans to use as the "device font", and render
         // it the same as any other SWF outline text.
         if let Some(font) = self.font(context.library) {
+            font.load_glyphs(context.renderer, &edit_text.text);
             let height = static_data
                 .text
                 .height
@@ -492,7 +614,10 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
 
             chunks.push(&edit_text.text[start..]);
 
-            for chunk in chunks {
+            // Skip the lines that are scrolled out of view.
+            // TODO: Clip the lines below the bottom of the text field.
+            let scroll = edit_text.scroll.min(chunks.len());
+            for chunk in chunks.into_iter().skip(scroll - 1) {
                 font.evaluate(
                     chunk,
                     text_transform.clone(),
@@ -517,6 +642,22 @@ impl<'gc> TDisplayObject<'gc> for EditText<'gc> {
     fn allow_as_mask(&self) -> bool {
         false
     }
+
+    fn hit_test(&self, point: (Twips, Twips)) -> bool {
+        self.world_bounds().contains(point)
+    }
+
+    fn text_field_pick(
+        &self,
+        _self_node: DisplayObject<'gc>,
+        point: (Twips, Twips),
+    ) -> Option<EditText<'gc>> {
+        if self.visible() && self.hit_test(point) {
+            Some(*self)
+        } else {
+            None
+        }
+    }
 }
 
 unsafe impl<'gc> gc_arena::Collect for EditTextData<'gc> {
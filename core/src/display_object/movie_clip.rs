@@ -1,11 +1,13 @@
 //! `MovieClip` display object and support code.
 use crate::avm1::{Avm1, Object, StageObject, TObject, Value};
 use crate::backend::audio::AudioStreamHandle;
+use crate::backend::navigator::RequestOptions;
 use crate::character::Character;
 use crate::context::{ActionType, RenderContext, UpdateContext};
 use crate::display_object::{
     Bitmap, Button, DisplayObjectBase, EditText, Graphic, MorphShapeStatic, TDisplayObject, Text,
 };
+use crate::drawing::Drawing;
 use crate::events::{ButtonKeyCode, ClipEvent};
 use crate::font::Font;
 use crate::prelude::*;
@@ -13,7 +15,7 @@ use crate::tag_utils::{self, DecodeResult, SwfMovie, SwfSlice, SwfStream};
 use enumset::{EnumSet, EnumSetType};
 use gc_arena::{Collect, Gc, GcCell, MutationContext};
 use smallvec::SmallVec;
-use std::cell::Ref;
+use std::cell::{Ref, RefMut};
 use std::collections::{BTreeMap, HashMap};
 use std::convert::TryFrom;
 use std::sync::Arc;
@@ -42,6 +44,11 @@ pub struct MovieClipData<'gc> {
     clip_actions: SmallVec<[ClipAction; 2]>,
     flags: EnumSet<MovieClipFlags>,
     avm1_constructor: Option<Object<'gc>>,
+    drawing: Drawing<'gc>,
+
+    /// The number of shared libraries still being imported.
+    /// Frames do not advance until this is zero.
+    pending_imports: u16,
 }
 
 impl<'gc> MovieClip<'gc> {
@@ -60,6 +67,8 @@ impl<'gc> MovieClip<'gc> {
                 clip_actions: SmallVec::new(),
                 flags: EnumSet::empty(),
                 avm1_constructor: None,
+                drawing: Drawing::new(),
+                pending_imports: 0,
             },
         ))
     }
@@ -82,6 +91,8 @@ impl<'gc> MovieClip<'gc> {
                         total_frames: num_frames,
                         audio_stream_info: None,
                         frame_labels: HashMap::new(),
+                        scenes: Vec::new(),
+                        imports: Vec::new(),
                     },
                 ),
                 tag_stream_pos: 0,
@@ -92,6 +103,8 @@ impl<'gc> MovieClip<'gc> {
                 clip_actions: SmallVec::new(),
                 flags: MovieClipFlags::Playing.into(),
                 avm1_constructor: None,
+                drawing: Drawing::new(),
+                pending_imports: 0,
             },
         ))
     }
@@ -155,15 +168,15 @@ impl<'gc> MovieClip<'gc> {
             TagCode::DefineBitsLossless => self
                 .0
                 .write(context.gc_context)
-                .define_bits_lossless(context, reader, 1),
+                .define_bits_lossless(context, reader, tag_len, 1),
             TagCode::DefineBitsLossless2 => self
                 .0
                 .write(context.gc_context)
-                .define_bits_lossless(context, reader, 2),
+                .define_bits_lossless(context, reader, tag_len, 2),
             TagCode::DefineButton => self
                 .0
                 .write(context.gc_context)
-                .define_button_1(context, reader),
+                .define_button_1(context, reader, tag_len),
             TagCode::DefineButton2 => self
                 .0
                 .write(context.gc_context)
@@ -192,7 +205,10 @@ impl<'gc> MovieClip<'gc> {
                 .0
                 .write(context.gc_context)
                 .define_font_3(context, reader),
-            TagCode::DefineFont4 => unimplemented!(),
+            TagCode::DefineFont4 => self
+                .0
+                .write(context.gc_context)
+                .define_font_4(context, reader, tag_len),
             TagCode::DefineMorphShape => self.0.write(context.gc_context).define_morph_shape(
                 context,
                 reader,
@@ -240,6 +256,10 @@ impl<'gc> MovieClip<'gc> {
                 .0
                 .write(context.gc_context)
                 .define_text(context, reader, 2),
+            TagCode::DefineSceneAndFrameLabelData => self
+                .0
+                .write(context.gc_context)
+                .scene_and_frame_labels(context, reader, &mut static_data),
             TagCode::DoInitAction => self.do_init_action(avm, context, reader, tag_len),
             TagCode::ExportAssets => self
                 .0
@@ -252,6 +272,16 @@ impl<'gc> MovieClip<'gc> {
                 cur_frame,
                 &mut static_data,
             ),
+            TagCode::ImportAssets => {
+                self.0
+                    .write(context.gc_context)
+                    .import_assets(context, reader, &mut static_data, 1)
+            }
+            TagCode::ImportAssets2 => {
+                self.0
+                    .write(context.gc_context)
+                    .import_assets(context, reader, &mut static_data, 2)
+            }
             TagCode::JpegTables => self
                 .0
                 .write(context.gc_context)
@@ -368,7 +398,6 @@ impl<'gc> MovieClip<'gc> {
         Ok(())
     }
 
-    #[allow(dead_code)]
     pub fn playing(self) -> bool {
         self.0.read().playing()
     }
@@ -393,6 +422,39 @@ impl<'gc> MovieClip<'gc> {
         self.0.write(context.gc_context).stop(context)
     }
 
+    /// Goes to the first frame of the next scene, if there is one.
+    pub fn next_scene(self, avm: &mut Avm1<'gc>, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let current_frame = self.current_frame();
+        let next_scene = self
+            .0
+            .read()
+            .static_data
+            .scenes
+            .iter()
+            .find(|scene| scene.start > current_frame)
+            .map(|scene| scene.start);
+        if let Some(start) = next_scene {
+            self.goto_frame(avm, context, start, true);
+        }
+    }
+
+    /// Goes to the first frame of the previous scene, if there is one.
+    pub fn prev_scene(self, avm: &mut Avm1<'gc>, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let current_frame = self.current_frame();
+        let prev_scene = {
+            let read = self.0.read();
+            let scenes = &read.static_data.scenes;
+            scenes
+                .iter()
+                .rposition(|scene| scene.start <= current_frame)
+                .and_then(|current| current.checked_sub(1))
+                .map(|prev| scenes[prev].start)
+        };
+        if let Some(start) = prev_scene {
+            self.goto_frame(avm, context, start, true);
+        }
+    }
+
     /// Queues up a goto to the specified frame.
     /// `frame` should be 1-based.
     pub fn goto_frame(
@@ -411,6 +473,19 @@ impl<'gc> MovieClip<'gc> {
         self.0.read().current_frame
     }
 
+    /// Returns the current frame counted from the start of the current scene, as returned by
+    /// `_currentframe`. Clips without scenes count from their first frame.
+    pub fn current_frame_in_scene(self) -> FrameNumber {
+        let read = self.0.read();
+        let current_frame = read.current_frame;
+        read.static_data
+            .scenes
+            .iter()
+            .rev()
+            .find(|scene| scene.start <= current_frame)
+            .map_or(current_frame, |scene| current_frame - scene.start + 1)
+    }
+
     pub fn total_frames(self) -> FrameNumber {
         self.0.read().static_data.total_frames
     }
@@ -428,13 +503,70 @@ impl<'gc> MovieClip<'gc> {
         self.0.write(gc_context).avm1_constructor = prototype;
     }
 
+    /// The shapes drawn on this clip with the drawing API.
+    pub fn drawing(&self, gc_context: MutationContext<'gc, '_>) -> RefMut<'_, Drawing<'gc>> {
+        RefMut::map(self.0.write(gc_context), |mc| &mut mc.drawing)
+    }
+
     pub fn frame_label_to_number(self, frame_label: &str) -> Option<FrameNumber> {
         // Frame labels are case insensitive.
         let label = frame_label.to_ascii_lowercase();
         self.0.read().static_data.frame_labels.get(&label).copied()
     }
 
+    /// Starts fetching the shared libraries named by this movie's `ImportAssets` tags.
+    /// This clip's frames will not advance until every import has resolved or failed.
+    ///
+    /// This must be called after `preload`, once the player can be referenced.
+    pub fn load_imports(self, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let (player, movie) = match (context.player.clone(), self.movie()) {
+            (Some(player), Some(movie)) => (player, movie),
+            _ => return,
+        };
+
+        let static_data = self.0.read().static_data;
+        for import in &static_data.imports {
+            let fetch = context
+                .navigator
+                .fetch(import.url.clone(), RequestOptions::get());
+            let process = context.load_manager.load_imports_into_movie(
+                player.clone(),
+                self.into(),
+                movie.clone(),
+                import.url.clone(),
+                import.assets.clone(),
+                fetch,
+            );
+            self.0.write(context.gc_context).pending_imports += 1;
+            context.navigator.spawn_future(process);
+        }
+    }
+
+    /// Marks one of this clip's shared library imports as finished.
+    pub fn finish_import(self, gc_context: MutationContext<'gc, '_>) {
+        let mut write = self.0.write(gc_context);
+        write.pending_imports = write.pending_imports.saturating_sub(1);
+    }
+
+    /// Returns the 1-based frame number that the named scene starts on.
+    /// Scene names are case sensitive.
+    pub fn scene_label_to_number(self, scene_label: &str) -> Option<FrameNumber> {
+        self.0
+            .read()
+            .static_data
+            .scenes
+            .iter()
+            .find(|scene| scene.name == scene_label)
+            .map(|scene| scene.start)
+    }
+
     /// Returns the highest depth in use by this movie clip, or `None` if there are no children.
+    /// Runs the handlers of this clip for a clip event, without propagating it to the
+    /// children of the clip.
+    pub fn run_clip_event(self, context: &mut UpdateContext<'_, 'gc, '_>, event: ClipEvent) {
+        self.0.read().run_clip_action(self.into(), context, event);
+    }
+
     pub fn highest_depth(self) -> Option<Depth> {
         self.0.read().children.keys().copied().rev().next()
     }
@@ -580,7 +712,8 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
         }
 
         // Run my SWF tags.
-        if mc.playing() {
+        // Playback waits for any shared libraries to finish importing.
+        if mc.playing() && mc.pending_imports == 0 {
             mc.run_frame_internal((*self).into(), avm, context, true);
         }
 
@@ -591,13 +724,14 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
 
     fn render(&self, context: &mut RenderContext<'_, 'gc>) {
         context.transform_stack.push(&*self.transform());
+        self.0.read().drawing.render(context);
         crate::display_object::render_children(context, &self.0.read().children);
         context.transform_stack.pop();
     }
 
     fn self_bounds(&self) -> BoundingBox {
-        // No inherent bounds; contains child DisplayObjects.
-        BoundingBox::default()
+        // Only the shapes drawn with the drawing API; children add their own bounds.
+        self.0.read().drawing.self_bounds()
     }
 
     fn hit_test(&self, point: (Twips, Twips)) -> bool {
@@ -619,6 +753,25 @@ impl<'gc> TDisplayObject<'gc> for MovieClip<'gc> {
         None
     }
 
+    fn text_field_pick(
+        &self,
+        _self_node: DisplayObject<'gc>,
+        point: (Twips, Twips),
+    ) -> Option<EditText<'gc>> {
+        // Hidden clips can't be scrolled, and neither can anything inside of them.
+        if !self.visible() {
+            return None;
+        }
+        for child in self.0.read().children.values().rev() {
+            let result = child.text_field_pick(*child, point);
+            if result.is_some() {
+                return result;
+            }
+        }
+
+        None
+    }
+
     fn propagate_clip_event(&self, context: &mut UpdateContext<'_, 'gc, '_>, event: ClipEvent) {
         for child in self.children() {
             child.propagate_clip_event(context, event);
@@ -745,6 +898,7 @@ unsafe impl<'gc> Collect for MovieClipData<'gc> {
         self.static_data.trace(cc);
         self.object.trace(cc);
         self.avm1_constructor.trace(cc);
+        self.drawing.trace(cc);
     }
 }
 
@@ -774,12 +928,15 @@ impl<'gc> MovieClipData<'gc> {
                 total_frames,
                 audio_stream_info: None,
                 frame_labels: HashMap::new(),
+                scenes: Vec::new(),
+                imports: Vec::new(),
             },
         );
         self.tag_stream_pos = 0;
         self.flags = MovieClipFlags::Playing.into();
         self.current_frame = 0;
         self.audio_stream = None;
+        self.pending_imports = 0;
         self.children = BTreeMap::new();
     }
 
@@ -1182,7 +1339,7 @@ impl<'gc> MovieClipData<'gc> {
         reader: &mut SwfStream<&'a [u8]>,
         tag_len: usize,
         version: u8,
-        goto_commands: &mut Vec<GotoPlaceObject>,
+        goto_commands: &mut Vec<GotoPlaceObject<'a>>,
         is_rewind: bool,
         index: usize,
     ) -> DecodeResult {
@@ -1364,8 +1521,11 @@ impl<'gc, 'a> MovieClipData<'gc> {
         &mut self,
         context: &mut UpdateContext<'_, 'gc, '_>,
         reader: &mut SwfStream<&'a [u8]>,
+        tag_len: usize,
         version: u8,
     ) -> DecodeResult {
+        // The bitmap data runs to the end of the tag, so limit the reader to this tag.
+        let mut reader = tag_utils::tag_reader(reader, tag_len, self.static_data.swf.version());
         let define_bits_lossless = reader.read_define_bits_lossless(version)?;
         let bitmap_info = context.renderer.register_bitmap_png(&define_bits_lossless);
         let bitmap = crate::display_object::Bitmap::new(
@@ -1508,17 +1668,12 @@ impl<'gc, 'a> MovieClipData<'gc> {
         reader: &mut SwfStream<&'a [u8]>,
         tag_len: usize,
     ) -> DecodeResult {
-        use std::io::Read;
+        use swf::read::SliceRead;
         let id = reader.read_u16()?;
-        let data_len = tag_len - 2;
-        let mut jpeg_data = Vec::with_capacity(data_len);
-        reader
-            .get_mut()
-            .take(data_len as u64)
-            .read_to_end(&mut jpeg_data)?;
+        let jpeg_data = reader.get_mut().read_slice(tag_len - 2)?;
         let bitmap_info = context.renderer.register_bitmap_jpeg(
             id,
-            &jpeg_data,
+            jpeg_data,
             context
                 .library
                 .library_for_movie_mut(self.movie())
@@ -1545,15 +1700,10 @@ impl<'gc, 'a> MovieClipData<'gc> {
         reader: &mut SwfStream<&'a [u8]>,
         tag_len: usize,
     ) -> DecodeResult {
-        use std::io::Read;
+        use swf::read::SliceRead;
         let id = reader.read_u16()?;
-        let data_len = tag_len - 2;
-        let mut jpeg_data = Vec::with_capacity(data_len);
-        reader
-            .get_mut()
-            .take(data_len as u64)
-            .read_to_end(&mut jpeg_data)?;
-        let bitmap_info = context.renderer.register_bitmap_jpeg_2(id, &jpeg_data);
+        let jpeg_data = reader.get_mut().read_slice(tag_len - 2)?;
+        let bitmap_info = context.renderer.register_bitmap_jpeg_2(id, jpeg_data);
         let bitmap = crate::display_object::Bitmap::new(
             context,
             id,
@@ -1575,23 +1725,15 @@ impl<'gc, 'a> MovieClipData<'gc> {
         reader: &mut SwfStream<&'a [u8]>,
         tag_len: usize,
     ) -> DecodeResult {
-        use std::io::Read;
+        use swf::read::SliceRead;
         let id = reader.read_u16()?;
         let jpeg_len = reader.read_u32()? as usize;
         let alpha_len = tag_len - 6 - jpeg_len;
-        let mut jpeg_data = Vec::with_capacity(jpeg_len);
-        let mut alpha_data = Vec::with_capacity(alpha_len);
-        reader
-            .get_mut()
-            .take(jpeg_len as u64)
-            .read_to_end(&mut jpeg_data)?;
-        reader
-            .get_mut()
-            .take(alpha_len as u64)
-            .read_to_end(&mut alpha_data)?;
+        let jpeg_data = reader.get_mut().read_slice(jpeg_len)?;
+        let alpha_data = reader.get_mut().read_slice(alpha_len)?;
         let bitmap_info = context
             .renderer
-            .register_bitmap_jpeg_3(id, &jpeg_data, &alpha_data);
+            .register_bitmap_jpeg_3(id, jpeg_data, alpha_data);
         let bitmap = Bitmap::new(
             context,
             id,
@@ -1613,24 +1755,16 @@ impl<'gc, 'a> MovieClipData<'gc> {
         reader: &mut SwfStream<&'a [u8]>,
         tag_len: usize,
     ) -> DecodeResult {
-        use std::io::Read;
+        use swf::read::SliceRead;
         let id = reader.read_u16()?;
         let jpeg_len = reader.read_u32()? as usize;
         let _deblocking = reader.read_u16()?;
         let alpha_len = tag_len - 6 - jpeg_len;
-        let mut jpeg_data = Vec::with_capacity(jpeg_len);
-        let mut alpha_data = Vec::with_capacity(alpha_len);
-        reader
-            .get_mut()
-            .take(jpeg_len as u64)
-            .read_to_end(&mut jpeg_data)?;
-        reader
-            .get_mut()
-            .take(alpha_len as u64)
-            .read_to_end(&mut alpha_data)?;
+        let jpeg_data = reader.get_mut().read_slice(jpeg_len)?;
+        let alpha_data = reader.get_mut().read_slice(alpha_len)?;
         let bitmap_info = context
             .renderer
-            .register_bitmap_jpeg_3(id, &jpeg_data, &alpha_data);
+            .register_bitmap_jpeg_3(id, jpeg_data, alpha_data);
         let bitmap = Bitmap::new(
             context,
             id,
@@ -1650,7 +1784,10 @@ impl<'gc, 'a> MovieClipData<'gc> {
         &mut self,
         context: &mut UpdateContext<'_, 'gc, '_>,
         reader: &mut SwfStream<&'a [u8]>,
+        tag_len: usize,
     ) -> DecodeResult {
+        // The button actions run to the end of the tag, so limit the reader to this tag.
+        let mut reader = tag_utils::tag_reader(reader, tag_len, self.static_data.swf.version());
         let swf_button = reader.read_define_button_1()?;
         let button = Button::from_swf_tag(
             &swf_button,
@@ -1830,6 +1967,37 @@ impl<'gc, 'a> MovieClipData<'gc> {
         Ok(())
     }
 
+    #[inline]
+    fn define_font_4(
+        &mut self,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<&'a [u8]>,
+        tag_len: usize,
+    ) -> DecodeResult {
+        // The font data runs to the end of the tag, so limit the reader to this tag.
+        let mut reader = tag_utils::tag_reader(reader, tag_len, self.static_data.swf.version());
+        let mut font = reader.read_define_font_4()?;
+        let font_object = match Font::from_font4_tag(context.gc_context, context.renderer, &font) {
+            Ok(font_object) => Some(font_object),
+            Err(e) => {
+                // Fall back to a device font rather than failing the whole movie.
+                log::warn!("Unable to parse DefineFont4 font {}: {}", font.id, e);
+                font.data = None;
+                Font::from_font4_tag(context.gc_context, context.renderer, &font).ok()
+            }
+        };
+        if let Some(font_object) = font_object {
+            context
+                .library
+                .library_for_movie_mut(self.movie())
+                .register_character(font.id, Character::Font(font_object));
+        } else {
+            log::warn!("Skipping DefineFont4 font {}", font.id);
+        }
+
+        Ok(())
+    }
+
     #[inline]
     fn define_sound(
         &mut self,
@@ -1837,12 +2005,8 @@ impl<'gc, 'a> MovieClipData<'gc> {
         reader: &mut SwfStream<&'a [u8]>,
         tag_len: usize,
     ) -> DecodeResult {
-        // TODO(Herschel): Can we use a slice of the sound data instead of copying the data?
-        use std::io::Read;
-        let mut reader = swf::read::Reader::new(
-            reader.get_mut().take(tag_len as u64),
-            self.static_data.swf.version(),
-        );
+        // The sound data runs to the end of the tag, so limit the reader to this tag.
+        let mut reader = tag_utils::tag_reader(reader, tag_len, self.static_data.swf.version());
         let sound = reader.read_define_sound()?;
         let handle = context.audio.register_sound(&sound).unwrap();
         context
@@ -1941,6 +2105,58 @@ impl<'gc, 'a> MovieClipData<'gc> {
         Ok(())
     }
 
+    #[inline]
+    fn import_assets(
+        &mut self,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<&'a [u8]>,
+        static_data: &mut MovieClipStatic,
+        version: u8,
+    ) -> DecodeResult {
+        let (url, assets) = if version == 1 {
+            reader.read_import_assets()?
+        } else {
+            reader.read_import_assets_2()?
+        };
+        static_data.imports.push(ImportedAssets { url, assets });
+        Ok(())
+    }
+
+    #[inline]
+    fn scene_and_frame_labels(
+        &mut self,
+        _context: &mut UpdateContext<'_, 'gc, '_>,
+        reader: &mut SwfStream<&'a [u8]>,
+        static_data: &mut MovieClipStatic,
+    ) -> DecodeResult {
+        let data = reader.read_define_scene_and_frame_label_data()?;
+
+        // Frame numbers in this tag are 0-based.
+        let mut scenes: Vec<_> = data
+            .scenes
+            .into_iter()
+            .map(|scene| Scene {
+                name: scene.label,
+                start: u16::try_from(scene.frame_num.saturating_add(1))
+                    .unwrap_or(FrameNumber::max_value()),
+            })
+            .collect();
+        scenes.sort_by_key(|scene| scene.start);
+        static_data.scenes = scenes;
+
+        // These duplicate any `FrameLabel` tags in the movie.
+        for mut frame_label in data.frame_labels {
+            frame_label.label.make_ascii_lowercase();
+            let frame = u16::try_from(frame_label.frame_num.saturating_add(1))
+                .unwrap_or(FrameNumber::max_value());
+            static_data
+                .frame_labels
+                .entry(frame_label.label)
+                .or_insert(frame);
+        }
+        Ok(())
+    }
+
     #[inline]
     fn jpeg_tables(
         &mut self,
@@ -2163,6 +2379,26 @@ impl<'gc, 'a> MovieClipData<'gc> {
     }
 }
 
+/// A scene of a movie clip, as defined by a `DefineSceneAndFrameLabelData` tag.
+#[derive(Clone, Debug)]
+pub struct Scene {
+    /// The name of the scene, such as `Scene 1`.
+    pub name: String,
+
+    /// The 1-based frame number that this scene starts on.
+    pub start: FrameNumber,
+}
+
+/// The symbols that an `ImportAssets` tag imports from a shared library.
+#[derive(Clone, Debug)]
+struct ImportedAssets {
+    /// The URL of the shared library movie.
+    url: String,
+
+    /// The local character IDs and the export names they are bound to.
+    assets: Vec<swf::ExportedAsset>,
+}
+
 /// Static data shared between all instances of a movie clip.
 #[allow(dead_code)]
 #[derive(Clone)]
@@ -2170,6 +2406,8 @@ struct MovieClipStatic {
     id: CharacterId,
     swf: SwfSlice,
     frame_labels: HashMap<String, FrameNumber>,
+    scenes: Vec<Scene>,
+    imports: Vec<ImportedAssets>,
     audio_stream_info: Option<swf::SoundStreamHead>,
     total_frames: FrameNumber,
 }
@@ -2181,6 +2419,8 @@ impl MovieClipStatic {
             swf,
             total_frames: 1,
             frame_labels: HashMap::new(),
+            scenes: Vec::new(),
+            imports: Vec::new(),
             audio_stream_info: None,
         }
     }
@@ -2196,19 +2436,19 @@ unsafe impl<'gc> Collect for MovieClipStatic {
 /// Stores the placement settings for display objects during a
 /// goto command.
 #[derive(Debug)]
-struct GotoPlaceObject {
+struct GotoPlaceObject<'a> {
     /// The frame number that this character was first placed on.
     frame: FrameNumber,
     /// The display properties of the object.
-    place_object: swf::PlaceObject,
+    place_object: swf::PlaceObject<'a>,
     /// Increasing index of this place command, for sorting.
     index: usize,
 }
 
-impl GotoPlaceObject {
+impl<'a> GotoPlaceObject<'a> {
     fn new(
         frame: FrameNumber,
-        mut place_object: swf::PlaceObject,
+        mut place_object: swf::PlaceObject<'a>,
         is_rewind: bool,
         index: usize,
     ) -> Self {
@@ -2264,7 +2504,7 @@ impl GotoPlaceObject {
         self.place_object.depth.into()
     }
 
-    fn merge(&mut self, next: &mut GotoPlaceObject) {
+    fn merge(&mut self, next: &mut GotoPlaceObject<'a>) {
         use swf::PlaceObjectAction;
         let cur_place = &mut self.place_object;
         let next_place = &mut next.place_object;
@@ -2326,14 +2566,16 @@ pub struct ClipAction {
 impl ClipAction {
     /// Build a clip action from a SWF movie and a parsed ClipAction.
     ///
-    /// TODO: Our underlying SWF parser currently does not yield slices of the
-    /// underlying movie, so we cannot convert those slices into a `SwfSlice`.
-    /// Instead, we have to construct a fake `SwfMovie` just to hold one clip
-    /// action.
+    /// The action data of the parsed ClipAction is a slice of the movie, so it
+    /// is converted into a `SwfSlice` of that movie. If it does not belong to
+    /// the movie, it is copied into a new one instead.
     pub fn from_action_and_movie(other: swf::ClipAction, movie: Arc<SwfMovie>) -> Self {
         use swf::ClipEventFlag;
 
-        let len = other.action_data.len();
+        let movie = SwfSlice::from(movie);
+        let action_data = movie
+            .to_subslice(other.action_data)
+            .unwrap_or_else(|| movie.owned_subslice(other.action_data.to_vec()));
         Self {
             events: other
                 .events
@@ -2365,11 +2607,7 @@ impl ClipAction {
                     ClipEventFlag::Unload => ClipEvent::Unload,
                 })
                 .collect(),
-            action_data: SwfSlice {
-                movie: Arc::new(movie.from_movie_and_subdata(other.action_data)),
-                start: 0,
-                end: len,
-            },
+            action_data,
         }
     }
 }
@@ -0,0 +1,267 @@
+//! Shapes drawn at runtime with the `MovieClip` drawing API.
+
+use crate::avm1::{BitmapDataObject, TObject};
+use crate::backend::render::{BitmapHandle, ShapeHandle};
+use crate::context::RenderContext;
+use crate::prelude::*;
+use crate::shape_utils::{DistilledShape, DrawCommand, DrawPath};
+use gc_arena::{Collect, CollectionContext};
+use std::cell::Cell;
+use swf::{FillStyle, LineStyle};
+
+/// The fills and lines drawn on a movie clip with `beginFill`, `lineTo` and friends.
+///
+/// The `id` of a bitmap fill is an index into `bitmaps` rather than the character ID
+/// of a bitmap in the SWF, so that fills can show the pixels of a `BitmapData`.
+#[derive(Clone, Debug, Default)]
+pub struct Drawing<'gc> {
+    render_handle: Cell<Option<ShapeHandle>>,
+
+    /// Whether the paths have changed since they were last registered with the renderer.
+    dirty: Cell<bool>,
+
+    bounds: BoundingBox,
+    paths: Vec<DrawingPath>,
+    current_fill: Option<(FillStyle, Vec<DrawCommand>)>,
+    current_line: Option<(LineStyle, Vec<DrawCommand>)>,
+
+    /// Lines finished while a fill is in progress. They go above the fill once it ends.
+    pending_lines: Vec<(LineStyle, Vec<DrawCommand>)>,
+
+    cursor: (Twips, Twips),
+    bitmaps: Vec<(BitmapDataObject<'gc>, BitmapHandle)>,
+}
+
+#[derive(Clone, Debug)]
+enum DrawingPath {
+    Fill(FillStyle, Vec<DrawCommand>),
+    Line(LineStyle, Vec<DrawCommand>),
+}
+
+impl<'gc> Drawing<'gc> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The bounds of everything drawn so far, including the fill in progress.
+    pub fn self_bounds(&self) -> BoundingBox {
+        self.bounds.clone()
+    }
+
+    /// Removes all fills and lines, and resets the line style.
+    pub fn clear(&mut self) {
+        let render_handle = self.render_handle.get();
+        *self = Self::new();
+        self.render_handle.set(render_handle);
+        self.dirty.set(true);
+    }
+
+    /// Starts a new fill at the current position. Any fill in progress is closed first.
+    pub fn begin_fill(&mut self, style: FillStyle) {
+        self.end_fill();
+        let (x, y) = self.cursor;
+        self.current_fill = Some((style, vec![DrawCommand::MoveTo { x, y }]));
+        self.dirty.set(true);
+    }
+
+    /// Starts a new fill showing the pixels of a `BitmapData`.
+    /// `matrix` maps bitmap pixels to the coordinate space of the drawing.
+    pub fn begin_bitmap_fill(
+        &mut self,
+        bitmap_data: BitmapDataObject<'gc>,
+        handle: BitmapHandle,
+        matrix: swf::Matrix,
+        is_repeating: bool,
+        is_smoothed: bool,
+    ) {
+        let index = match self
+            .bitmaps
+            .iter()
+            .position(|(other, _)| other.as_ptr() == bitmap_data.as_ptr())
+        {
+            Some(index) => index,
+            None => {
+                self.bitmaps.push((bitmap_data, handle));
+                self.bitmaps.len() - 1
+            }
+        };
+        self.begin_fill(FillStyle::Bitmap {
+            id: index as CharacterId,
+            matrix,
+            is_repeating,
+            is_smoothed,
+        });
+    }
+
+    /// Closes the fill in progress by drawing a line back to its start.
+    pub fn end_fill(&mut self) {
+        if let Some((style, mut commands)) = self.current_fill.take() {
+            if let Some(&DrawCommand::MoveTo { x, y }) = commands.first() {
+                if self.cursor != (x, y) {
+                    commands.push(DrawCommand::LineTo { x, y });
+                }
+            }
+            self.paths.push(DrawingPath::Fill(style, commands));
+
+            // Lines drawn during the fill go above it.
+            for (line_style, line_commands) in self.pending_lines.drain(..) {
+                self.paths
+                    .push(DrawingPath::Line(line_style, line_commands));
+            }
+            if let Some((line_style, line_commands)) = self.current_line.take() {
+                self.paths
+                    .push(DrawingPath::Line(line_style.clone(), line_commands));
+                let (x, y) = self.cursor;
+                self.current_line = Some((line_style, vec![DrawCommand::MoveTo { x, y }]));
+            }
+            self.dirty.set(true);
+        }
+    }
+
+    /// Sets the style of the lines drawn from now on, or stops drawing lines if `None`.
+    pub fn set_line_style(&mut self, style: Option<LineStyle>) {
+        if let Some((line_style, commands)) = self.current_line.take() {
+            if self.current_fill.is_some() {
+                self.pending_lines.push((line_style, commands));
+            } else {
+                self.paths.push(DrawingPath::Line(line_style, commands));
+            }
+        }
+        let (x, y) = self.cursor;
+        self.current_line = style.map(|style| (style, vec![DrawCommand::MoveTo { x, y }]));
+    }
+
+    pub fn move_to(&mut self, x: Twips, y: Twips) {
+        self.cursor = (x, y);
+        self.push_command(DrawCommand::MoveTo { x, y }, &[]);
+    }
+
+    pub fn line_to(&mut self, x: Twips, y: Twips) {
+        let from = self.cursor;
+        self.cursor = (x, y);
+        self.push_command(DrawCommand::LineTo { x, y }, &[from, (x, y)]);
+    }
+
+    pub fn curve_to(&mut self, x1: Twips, y1: Twips, x2: Twips, y2: Twips) {
+        let from = self.cursor;
+        self.cursor = (x2, y2);
+        self.push_command(
+            DrawCommand::CurveTo { x1, y1, x2, y2 },
+            &[from, (x1, y1), (x2, y2)],
+        );
+    }
+
+    /// Adds a command to the fill and line in progress, and grows the bounds by `points`.
+    fn push_command(&mut self, command: DrawCommand, points: &[(Twips, Twips)]) {
+        if let Some((_, commands)) = &mut self.current_fill {
+            commands.push(command.clone());
+            for &point in points {
+                self.bounds.union(&point_bounds(point, Twips::new(0)));
+            }
+        }
+        if let Some((style, commands)) = &mut self.current_line {
+            commands.push(command);
+            let half_width = Twips::new(style.width.get() / 2);
+            for &point in points {
+                self.bounds.union(&point_bounds(point, half_width));
+            }
+        }
+        self.dirty.set(true);
+    }
+
+    pub fn render(&self, context: &mut RenderContext) {
+        // Bitmap fills show the current pixels of their `BitmapData`.
+        // Renderers may keep the textures a shape was built with, so rebuild it on changes.
+        let mut bitmaps_changed = false;
+        for (bitmap_data, _) in &self.bitmaps {
+            bitmaps_changed |= bitmap_data
+                .bitmap_data()
+                .update_texture_if_dirty(context.renderer);
+        }
+
+        if self.dirty.get() || bitmaps_changed {
+            self.dirty.set(false);
+            let mut paths = Vec::with_capacity(self.paths.len() + self.pending_lines.len() + 2);
+            for path in &self.paths {
+                paths.push(match path {
+                    DrawingPath::Fill(style, commands) => DrawPath::Fill {
+                        style,
+                        commands: commands.clone(),
+                    },
+                    DrawingPath::Line(style, commands) => DrawPath::Stroke {
+                        style,
+                        is_closed: false,
+                        commands: commands.clone(),
+                    },
+                });
+            }
+            if let Some((style, commands)) = &self.current_fill {
+                paths.push(DrawPath::Fill {
+                    style,
+                    commands: commands.clone(),
+                });
+            }
+            for (style, commands) in &self.pending_lines {
+                paths.push(DrawPath::Stroke {
+                    style,
+                    is_closed: false,
+                    commands: commands.clone(),
+                });
+            }
+            if let Some((style, commands)) = &self.current_line {
+                paths.push(DrawPath::Stroke {
+                    style,
+                    is_closed: false,
+                    commands: commands.clone(),
+                });
+            }
+
+            let bounds = swf::Rectangle {
+                x_min: self.bounds.x_min,
+                x_max: self.bounds.x_max,
+                y_min: self.bounds.y_min,
+                y_max: self.bounds.y_max,
+            };
+            let shape = DistilledShape {
+                paths,
+                shape_bounds: bounds.clone(),
+                edge_bounds: bounds,
+                id: 0,
+            };
+            let bitmaps: Vec<BitmapHandle> =
+                self.bitmaps.iter().map(|(_, handle)| *handle).collect();
+            if let Some(handle) = self.render_handle.get() {
+                context.renderer.replace_drawing(handle, shape, &bitmaps);
+            } else {
+                let handle = context.renderer.register_drawing(shape, &bitmaps);
+                self.render_handle.set(Some(handle));
+            }
+        }
+
+        if let Some(handle) = self.render_handle.get() {
+            context
+                .renderer
+                .render_shape(handle, context.transform_stack.transform());
+        }
+    }
+}
+
+/// The bounds of a point, grown by `radius` on every side.
+fn point_bounds((x, y): (Twips, Twips), radius: Twips) -> BoundingBox {
+    BoundingBox {
+        x_min: x - radius,
+        y_min: y - radius,
+        x_max: x + radius,
+        y_max: y + radius,
+        valid: true,
+    }
+}
+
+unsafe impl<'gc> Collect for Drawing<'gc> {
+    #[inline]
+    fn trace(&self, cc: CollectionContext) {
+        for (bitmap_data, _) in &self.bitmaps {
+            bitmap_data.trace(cc);
+        }
+    }
+}
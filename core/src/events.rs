@@ -1,5 +1,9 @@
 use num_enum::{IntoPrimitive, TryFromPrimitive};
 
+/// An input event sent to the player by the frontend.
+///
+/// Mouse positions are in pixels relative to the viewport. The `delta` of `MouseWheel` is
+/// the number of lines scrolled, where positive values scroll up, away from the user.
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug)]
 pub enum PlayerEvent {
@@ -9,6 +13,7 @@ pub enum PlayerEvent {
     MouseUp { x: f64, y: f64 },
     MouseDown { x: f64, y: f64 },
     MouseLeft,
+    MouseWheel { delta: i32 },
     TextInput { codepoint: char },
 }
 
@@ -25,8 +30,11 @@ pub enum PlayerEvent {
 pub enum ButtonEvent {
     Press,
     Release,
+    ReleaseOutside,
     RollOut,
     RollOver,
+    DragOut,
+    DragOver,
     KeyPress { key_code: ButtonKeyCode },
 }
 
@@ -69,6 +77,7 @@ pub enum ClipEvent {
 pub enum KeyCode {
     Unknown = 0,
     Backspace = 8,
+    Tab = 9,
     Return = 13,
     Shift = 16,
     Control = 17,
@@ -149,6 +158,7 @@ pub enum KeyCode {
     Insert = 45,
     Delete = 46,
     Pause = 19,
+    NumLock = 144,
     ScrollLock = 145,
     F1 = 112,
     F2 = 113,
@@ -299,6 +309,7 @@ pub fn key_code_to_button_key_code(key_code: KeyCode) -> Option<ButtonKeyCode> {
         KeyCode::PgUp => ButtonKeyCode::PgUp,
         KeyCode::PgDown => ButtonKeyCode::PgDown,
         KeyCode::Escape => ButtonKeyCode::Escape,
+        KeyCode::Tab => ButtonKeyCode::Tab,
         _ => return None,
     };
     Some(out)
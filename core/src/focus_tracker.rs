@@ -0,0 +1,303 @@
+//! Keyboard focus and Tab navigation.
+
+use crate::avm1::listeners::SystemListener;
+use crate::avm1::object::get_property_or_undefined;
+use crate::avm1::{Avm1, Value};
+use crate::backend::render::RenderBackend;
+use crate::context::{ActionType, UpdateContext};
+use crate::prelude::*;
+use gc_arena::{Collect, GcCell, MutationContext};
+
+/// The color of the rectangle drawn around the focused object.
+const FOCUS_RECT_COLOR: Color = Color {
+    r: 255,
+    g: 255,
+    b: 0,
+    a: 255,
+};
+
+/// The line width of the focus rectangle, in viewport pixels.
+const FOCUS_RECT_WIDTH: f32 = 2.0;
+
+/// Tracks the display object with keyboard focus.
+///
+/// Buttons, editable text fields and movie clips with button handlers can be focused,
+/// either by ActionScript via `Selection.setFocus`, or by the user with Tab and Shift-Tab.
+/// The focused button or clip is activated by pressing Enter or Space.
+#[derive(Clone, Copy, Collect, Debug)]
+#[collect(no_drop)]
+pub struct FocusTracker<'gc>(GcCell<'gc, FocusTrackerData<'gc>>);
+
+#[derive(Clone, Collect, Debug)]
+#[collect(no_drop)]
+struct FocusTrackerData<'gc> {
+    /// The object that has keyboard focus.
+    focus: Option<DisplayObject<'gc>>,
+
+    /// Whether a rectangle is drawn around objects focused with the keyboard.
+    /// Equivalent to `_focusrect`.
+    show_focus_rect: bool,
+
+    /// Whether the current focus was given by Tab navigation.
+    /// The focus rectangle is hidden again once the mouse is used.
+    highlight: bool,
+}
+
+impl<'gc> FocusTracker<'gc> {
+    pub fn new(gc_context: MutationContext<'gc, '_>) -> Self {
+        Self(GcCell::allocate(
+            gc_context,
+            FocusTrackerData {
+                focus: None,
+                show_focus_rect: true,
+                highlight: false,
+            },
+        ))
+    }
+
+    /// The object that has keyboard focus, if it is still on the stage.
+    pub fn get(self) -> Option<DisplayObject<'gc>> {
+        self.0.read().focus.filter(|focus| !focus.removed())
+    }
+
+    /// Moves the keyboard focus to `focus`.
+    ///
+    /// This queues `onKillFocus` on the previously focused object, `onSetFocus` on the newly
+    /// focused object, and notifies the `Selection` listeners.
+    pub fn set(self, focus: Option<DisplayObject<'gc>>, context: &mut UpdateContext<'_, 'gc, '_>) {
+        let old = self.get();
+        {
+            let mut data = self.0.write(context.gc_context);
+            data.focus = focus;
+            data.highlight = false;
+        }
+        if old.map(|o| o.as_ptr()) == focus.map(|o| o.as_ptr()) {
+            return;
+        }
+
+        let to_value = |object: Option<DisplayObject<'gc>>| {
+            object.map_or(Value::Null, |object| object.object())
+        };
+        if let Some(old) = old {
+            queue_method(context, old, "onKillFocus", vec![to_value(focus)]);
+        }
+        if let Some(focus) = focus {
+            queue_method(context, focus, "onSetFocus", vec![to_value(old)]);
+        }
+        context.action_queue.queue_actions(
+            *context.levels.get(&0).expect("root level"),
+            ActionType::NotifyListeners {
+                listener: SystemListener::Selection,
+                method: "onSetFocus",
+                args: vec![to_value(old), to_value(focus)],
+            },
+            false,
+        );
+    }
+
+    /// Whether the focus rectangle is enabled. Equivalent to `_focusrect`.
+    pub fn show_focus_rect(self) -> bool {
+        self.0.read().show_focus_rect
+    }
+
+    pub fn set_show_focus_rect(self, gc_context: MutationContext<'gc, '_>, show: bool) {
+        self.0.write(gc_context).show_focus_rect = show;
+    }
+
+    /// Hides the focus rectangle until the next Tab navigation.
+    pub fn clear_highlight(self, gc_context: MutationContext<'gc, '_>) {
+        self.0.write(gc_context).highlight = false;
+    }
+
+    /// Moves the focus to the next object in the tab order, or the previous one if
+    /// `reverse` is set. Returns `true` if there was anything to focus.
+    pub fn cycle(
+        self,
+        avm: &mut Avm1<'gc>,
+        context: &mut UpdateContext<'_, 'gc, '_>,
+        reverse: bool,
+    ) -> bool {
+        let tab_order = tab_order(avm, context);
+        if tab_order.is_empty() {
+            return false;
+        }
+
+        let current = self
+            .get()
+            .and_then(|focus| tab_order.iter().position(|o| o.as_ptr() == focus.as_ptr()));
+        let len = tab_order.len();
+        let next = match (current, reverse) {
+            (Some(i), false) => (i + 1) % len,
+            (Some(i), true) => (i + len - 1) % len,
+            (None, false) => 0,
+            (None, true) => len - 1,
+        };
+        self.set(Some(tab_order[next]), context);
+        self.0.write(context.gc_context).highlight = true;
+        true
+    }
+
+    /// Draws the focus rectangle around the focused object, if it was focused with the
+    /// keyboard. `view_matrix` transforms from stage coordinates to viewport pixels.
+    pub fn render_highlight(self, renderer: &mut dyn RenderBackend, view_matrix: &Matrix) {
+        let data = self.0.read();
+        if !data.show_focus_rect || !data.highlight {
+            return;
+        }
+        let focus = match self.get() {
+            Some(focus) if focus.visible() => focus,
+            _ => return,
+        };
+
+        let bounds = focus.world_bounds().transform(view_matrix);
+        if !bounds.valid {
+            return;
+        }
+        let x = bounds.x_min.to_pixels() as f32;
+        let y = bounds.y_min.to_pixels() as f32;
+        let width = (bounds.x_max - bounds.x_min).to_pixels() as f32;
+        let height = (bounds.y_max - bounds.y_min).to_pixels() as f32;
+        let line = FOCUS_RECT_WIDTH;
+        let color = FOCUS_RECT_COLOR;
+        renderer.draw_rect(x - line, y - line, width + 2.0 * line, line, color.clone());
+        renderer.draw_rect(
+            x - line,
+            y + height,
+            width + 2.0 * line,
+            line,
+            color.clone(),
+        );
+        renderer.draw_rect(x - line, y, line, height, color.clone());
+        renderer.draw_rect(x + width, y, line, height, color);
+    }
+}
+
+/// Returns whether `object` can be given focus by `Selection.setFocus`.
+pub fn is_focusable<'gc>(
+    object: DisplayObject<'gc>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> bool {
+    if object.as_movie_clip().is_some()
+        && get_property_or_undefined(object.object(), "focusEnabled", avm, context)
+            .as_bool(avm.current_swf_version())
+    {
+        return true;
+    }
+    is_tab_enabled(object, avm, context)
+}
+
+/// Returns whether `object` is included in the tab order.
+///
+/// An explicit `tabEnabled` property takes precedence. Otherwise enabled buttons, editable text
+/// fields and movie clips with `onPress` or `onRelease` handlers are included.
+fn is_tab_enabled<'gc>(
+    object: DisplayObject<'gc>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> bool {
+    let tab_enabled = get_property_or_undefined(object.object(), "tabEnabled", avm, context);
+    if tab_enabled != Value::Undefined {
+        return tab_enabled.as_bool(avm.current_swf_version());
+    }
+
+    if let Some(button) = object.as_button() {
+        button.enabled()
+    } else if let Some(text_field) = object.as_edit_text() {
+        text_field.is_editable()
+    } else if object.as_movie_clip().is_some() {
+        get_property_or_undefined(object.object(), "onPress", avm, context) != Value::Undefined
+            || get_property_or_undefined(object.object(), "onRelease", avm, context)
+                != Value::Undefined
+    } else {
+        false
+    }
+}
+
+/// Returns the objects that Tab cycles through, in order.
+///
+/// If any object has a `tabIndex`, only the objects with a `tabIndex` are included, ordered
+/// by it. Otherwise, the objects are ordered by their position on the stage, from top to
+/// bottom and left to right.
+fn tab_order<'gc>(
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+) -> Vec<DisplayObject<'gc>> {
+    let mut candidates = vec![];
+    let levels: Vec<DisplayObject<'gc>> = context.levels.values().copied().collect();
+    for level in levels {
+        collect_tab_candidates(level, avm, context, &mut candidates);
+    }
+
+    let has_tab_index = candidates.iter().any(|(_, tab_index)| tab_index.is_some());
+    if has_tab_index {
+        let mut indexed: Vec<_> = candidates
+            .into_iter()
+            .filter_map(|(object, tab_index)| Some((object, tab_index?)))
+            .collect();
+        indexed.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        indexed.into_iter().map(|(object, _)| object).collect()
+    } else {
+        let mut objects: Vec<_> = candidates
+            .into_iter()
+            .map(|(object, _)| {
+                let bounds = object.world_bounds();
+                (object, (bounds.y_min, bounds.x_min))
+            })
+            .collect();
+        objects.sort_by_key(|(_, position)| *position);
+        objects.into_iter().map(|(object, _)| object).collect()
+    }
+}
+
+/// Collects the tab enabled descendants of `parent` along with their `tabIndex`.
+/// The children of a movie clip are skipped if its `tabChildren` is `false`.
+fn collect_tab_candidates<'gc>(
+    parent: DisplayObject<'gc>,
+    avm: &mut Avm1<'gc>,
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    candidates: &mut Vec<(DisplayObject<'gc>, Option<f64>)>,
+) {
+    for child in parent.children() {
+        if !child.visible() {
+            continue;
+        }
+
+        if is_tab_enabled(child, avm, context) {
+            let tab_index =
+                match get_property_or_undefined(child.object(), "tabIndex", avm, context) {
+                    Value::Undefined | Value::Null => None,
+                    tab_index => tab_index.as_number(avm, context).ok(),
+                };
+            candidates.push((child, tab_index));
+        }
+
+        if child.as_movie_clip().is_some() {
+            let tab_children =
+                get_property_or_undefined(child.object(), "tabChildren", avm, context);
+            if tab_children == Value::Undefined || tab_children.as_bool(avm.current_swf_version()) {
+                collect_tab_candidates(child, avm, context, candidates);
+            }
+        }
+    }
+}
+
+/// Queues a call to the event handler `name` of a display object.
+fn queue_method<'gc>(
+    context: &mut UpdateContext<'_, 'gc, '_>,
+    object: DisplayObject<'gc>,
+    name: &'static str,
+    args: Vec<Value<'gc>>,
+) {
+    if let Ok(avm_object) = object.object().as_object() {
+        context.action_queue.queue_actions(
+            object,
+            ActionType::Method {
+                object: avm_object,
+                name,
+                args,
+            },
+            false,
+        );
+    }
+}
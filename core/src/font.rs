@@ -2,11 +2,16 @@ use crate::backend::render::{RenderBackend, ShapeHandle};
 use crate::prelude::*;
 use crate::transform::Transform;
 use gc_arena::{Collect, Gc, MutationContext};
+use std::cell::RefCell;
+use std::sync::Arc;
 
 type Error = Box<dyn std::error::Error>;
 
+mod device;
+pub(crate) mod opentype;
 mod text_format;
 
+pub use device::DeviceFonts;
 pub use text_format::TextFormat;
 
 #[derive(Debug, Clone, Collect, Copy)]
@@ -16,13 +21,16 @@ pub struct Font<'gc>(Gc<'gc, FontData>);
 #[derive(Debug, Clone, Collect)]
 #[collect(require_static)]
 struct FontData {
+    /// The name of the font.
+    name: String,
+
     /// The list of glyphs defined in the font.
     /// Used directly by `DefineText` tags.
-    glyphs: Vec<Glyph>,
+    glyphs: RefCell<Vec<Glyph>>,
 
     /// A map from a Unicode code point to glyph in the `glyphs` array.
     /// Used by `DefineEditText` tags.
-    code_point_to_glyph: fnv::FnvHashMap<u16, usize>,
+    code_point_to_glyph: RefCell<fnv::FnvHashMap<u16, usize>>,
 
     /// The scaling applied to the font height to render at the proper size.
     /// This depends on the DefineFont tag version.
@@ -31,6 +39,35 @@ struct FontData {
     /// Kerning infomration.
     /// Maps from a pair of unicode code points to horizontal offset value.
     kerning_pairs: fnv::FnvHashMap<(u16, u16), Twips>,
+
+    /// The fonts that glyphs are converted from as they are first needed, in order of
+    /// preference. Empty for fonts defined in a SWF, whose glyphs are converted up front.
+    glyph_sources: Vec<Arc<GlyphSource>>,
+
+    /// Code points that none of the glyph sources have a glyph for.
+    missing_code_points: RefCell<fnv::FnvHashSet<u16>>,
+}
+
+/// A font that device font glyphs are converted from on demand.
+#[derive(Debug)]
+pub enum GlyphSource {
+    /// A TrueType or OpenType font file provided by the `FontBackend`.
+    File(opentype::FontFile),
+
+    /// The glyphs of a SWF font, such as the built-in device font, by code point.
+    Swf(fnv::FnvHashMap<u16, swf::Glyph>),
+}
+
+impl GlyphSource {
+    fn glyphs(&self, codes: &[u16]) -> Result<Vec<swf::Glyph>, Error> {
+        match self {
+            GlyphSource::File(file) => file.glyphs(codes.iter().cloned()),
+            GlyphSource::Swf(glyphs) => Ok(codes
+                .iter()
+                .filter_map(|code| glyphs.get(code).cloned())
+                .collect()),
+        }
+    }
 }
 
 impl<'gc> Font<'gc> {
@@ -62,27 +99,137 @@ impl<'gc> Font<'gc> {
         Ok(Font(Gc::allocate(
             gc_context,
             FontData {
-                glyphs,
-                code_point_to_glyph,
+                name: tag.name.clone(),
+                glyphs: RefCell::new(glyphs),
+                code_point_to_glyph: RefCell::new(code_point_to_glyph),
 
                 /// DefineFont3 stores coordinates at 20x the scale of DefineFont1/2.
                 /// (SWF19 p.164)
                 scale: if tag.version >= 3 { 20480.0 } else { 1024.0 },
                 kerning_pairs,
+                glyph_sources: vec![],
+                missing_code_points: Default::default(),
             },
         )))
     }
 
+    /// Creates a device font whose glyphs are converted from `glyph_sources` as they are
+    /// needed. Each glyph comes from the first source that has it.
+    pub fn from_glyph_sources(
+        gc_context: MutationContext<'gc, '_>,
+        name: &str,
+        glyph_sources: Vec<Arc<GlyphSource>>,
+    ) -> Font<'gc> {
+        Font(Gc::allocate(
+            gc_context,
+            FontData {
+                name: name.to_string(),
+                glyphs: Default::default(),
+                code_point_to_glyph: Default::default(),
+                // Glyph sources are converted to the DefineFont3 EM square.
+                scale: 20480.0,
+                kerning_pairs: Default::default(),
+                glyph_sources,
+                missing_code_points: Default::default(),
+            },
+        ))
+    }
+
+    /// Creates a font from a `DefineFont4` tag by converting its embedded OpenType CFF data.
+    /// Fonts without embedded data have no glyphs and are rendered as device fonts.
+    pub fn from_font4_tag(
+        gc_context: MutationContext<'gc, '_>,
+        renderer: &mut dyn RenderBackend,
+        tag: &swf::Font4,
+    ) -> Result<Font<'gc>, Error> {
+        let glyphs = match &tag.data {
+            Some(data) => opentype::parse_glyphs(data)?,
+            None => vec![],
+        };
+        let font = swf::Font {
+            version: 4,
+            id: tag.id,
+            name: tag.name.clone(),
+            language: swf::Language::Unknown,
+            layout: None,
+            glyphs,
+            is_small_text: false,
+            is_shift_jis: false,
+            is_ansi: false,
+            is_bold: tag.is_bold,
+            is_italic: tag.is_italic,
+        };
+        Self::from_swf_tag(gc_context, renderer, &font)
+    }
+
+    /// The name of this font, such as `Arial`.
+    pub fn name(&self) -> &str {
+        &self.0.name
+    }
+
     /// Returns whether this font contains glyph shapes.
     /// If not, this font should be rendered as a device font.
     pub fn has_glyphs(self) -> bool {
-        !self.0.glyphs.is_empty()
+        !self.0.glyphs.borrow().is_empty() || !self.0.glyph_sources.is_empty()
+    }
+
+    /// Converts the glyphs needed to draw `text` from the glyph sources of a device font.
+    /// This must be called before the text is measured or drawn. Fonts defined in a SWF
+    /// already have all of their glyphs, so this does nothing for them.
+    pub fn load_glyphs(self, renderer: &mut dyn RenderBackend, text: &str) {
+        if self.0.glyph_sources.is_empty() {
+            return;
+        }
+
+        // TODO: Properly handle UTF-16/out-of-bounds code points.
+        let mut missing: Vec<u16> = {
+            let code_point_to_glyph = self.0.code_point_to_glyph.borrow();
+            let missing_code_points = self.0.missing_code_points.borrow();
+            text.chars()
+                .map(|c| c as u16)
+                .filter(|code_point| {
+                    !code_point_to_glyph.contains_key(code_point)
+                        && !missing_code_points.contains(code_point)
+                })
+                .collect()
+        };
+        missing.sort_unstable();
+        missing.dedup();
+
+        for source in &self.0.glyph_sources {
+            if missing.is_empty() {
+                break;
+            }
+            let swf_glyphs = match source.glyphs(&missing) {
+                Ok(swf_glyphs) => swf_glyphs,
+                Err(e) => {
+                    log::warn!(
+                        "Unable to load glyphs of device font {}: {}",
+                        self.name(),
+                        e
+                    );
+                    continue;
+                }
+            };
+            let mut glyphs = self.0.glyphs.borrow_mut();
+            let mut code_point_to_glyph = self.0.code_point_to_glyph.borrow_mut();
+            for swf_glyph in swf_glyphs {
+                code_point_to_glyph.insert(swf_glyph.code, glyphs.len());
+                glyphs.push(Glyph {
+                    shape: renderer.register_glyph_shape(&swf_glyph),
+                    advance: swf_glyph.advance.unwrap_or(0),
+                });
+            }
+            missing.retain(|code_point| !code_point_to_glyph.contains_key(code_point));
+        }
+
+        self.0.missing_code_points.borrow_mut().extend(missing);
     }
 
     /// Returns a glyph entry by index.
     /// Used by `Text` display objects.
     pub fn get_glyph(self, i: usize) -> Option<Glyph> {
-        self.0.glyphs.get(i).cloned()
+        self.0.glyphs.borrow().get(i).cloned()
     }
 
     /// Returns a glyph entry by character.
@@ -90,11 +237,13 @@ impl<'gc> Font<'gc> {
     pub fn get_glyph_for_char(self, c: char) -> Option<Glyph> {
         // TODO: Properly handle UTF-16/out-of-bounds code points.
         let code_point = c as u16;
-        if let Some(index) = self.0.code_point_to_glyph.get(&code_point) {
-            self.get_glyph(*index)
-        } else {
-            None
-        }
+        let index = self
+            .0
+            .code_point_to_glyph
+            .borrow()
+            .get(&code_point)
+            .cloned();
+        index.and_then(|index| self.get_glyph(index))
     }
 
     /// Given a pair of characters, applies the offset that should be applied
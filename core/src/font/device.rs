@@ -0,0 +1,240 @@
+//! Device fonts, used by text fields that don't embed their font.
+
+use crate::backend::font::FontBackend;
+use crate::font::opentype::FontFile;
+use crate::font::{Font, GlyphSource};
+use gc_arena::{Collect, MutationContext};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The fonts tried, in order, for the `_sans` device font.
+const SANS_FONTS: &[&str] = &[
+    "Arial",
+    "Helvetica",
+    "Liberation Sans",
+    "DejaVu Sans",
+    "Noto Sans",
+];
+
+/// The fonts tried, in order, for the `_serif` device font.
+const SERIF_FONTS: &[&str] = &[
+    "Times New Roman",
+    "Times",
+    "Liberation Serif",
+    "DejaVu Serif",
+    "Noto Serif",
+];
+
+/// The fonts tried, in order, for the `_typewriter` device font.
+const TYPEWRITER_FONTS: &[&str] = &[
+    "Courier New",
+    "Courier",
+    "Liberation Mono",
+    "DejaVu Sans Mono",
+    "Noto Sans Mono",
+];
+
+/// Fonts with wide Unicode coverage, tried for characters that the requested font lacks.
+const FALLBACK_FONTS: &[&str] = &[
+    "Arial Unicode MS",
+    "Noto Sans CJK SC",
+    "Noto Sans CJK JP",
+    "Microsoft YaHei",
+    "MS Gothic",
+    "Droid Sans Fallback",
+];
+
+/// Returns the names of the fonts to try, in order, for the device font `name`.
+///
+/// The generic names `_sans`, `_serif` and `_typewriter` (and their Japanese equivalents)
+/// map to a list of common fonts. Any other font falls back to the `_sans` fonts when it
+/// isn't installed.
+fn fallback_chain(name: &str) -> Vec<&str> {
+    let mut chain = match name.to_lowercase().as_str() {
+        "_sans" | "_ゴシック" => SANS_FONTS.to_vec(),
+        "_serif" | "_明朝" => SERIF_FONTS.to_vec(),
+        "_typewriter" | "_等幅" => TYPEWRITER_FONTS.to_vec(),
+        _ => {
+            let mut chain = vec![name];
+            chain.extend_from_slice(SANS_FONTS);
+            chain
+        }
+    };
+    chain.extend_from_slice(FALLBACK_FONTS);
+    chain
+}
+
+/// The device fonts of the player, keyed by the font name requested by text fields.
+///
+/// Each device font draws its glyphs from the first font in its fallback chain that has
+/// them, with the built-in font as the last resort.
+pub struct DeviceFonts<'gc> {
+    /// The built-in font, used when no installed font has a glyph.
+    builtin: Option<Arc<GlyphSource>>,
+
+    /// Font files from the font backend by lowercase family name.
+    /// `None` if the backend doesn't have the font.
+    files: HashMap<String, Option<Arc<GlyphSource>>>,
+
+    /// The device fonts that have been loaded, by lowercase name.
+    fonts: HashMap<String, Font<'gc>>,
+}
+
+unsafe impl<'gc> Collect for DeviceFonts<'gc> {
+    #[inline]
+    fn trace(&self, cc: gc_arena::CollectionContext) {
+        for font in self.fonts.values() {
+            font.trace(cc);
+        }
+    }
+}
+
+impl<'gc> DeviceFonts<'gc> {
+    /// Creates the device fonts, using the glyphs of `builtin` as the last fallback.
+    pub fn new(builtin: Option<swf::Font>) -> Self {
+        let builtin = builtin.map(|font| {
+            let glyphs = font
+                .glyphs
+                .into_iter()
+                .map(|glyph| (glyph.code, glyph))
+                .collect();
+            Arc::new(GlyphSource::Swf(glyphs))
+        });
+        Self {
+            builtin,
+            files: HashMap::new(),
+            fonts: HashMap::new(),
+        }
+    }
+
+    /// Returns the device font for `name`, if it has been loaded with `load`.
+    pub fn get(&self, name: &str) -> Option<Font<'gc>> {
+        self.fonts.get(&name.to_lowercase()).cloned()
+    }
+
+    /// Returns the device font for `name`, first loading the fonts of its fallback chain
+    /// from the font backend if needed.
+    pub fn load(
+        &mut self,
+        gc_context: MutationContext<'gc, '_>,
+        backend: &mut dyn FontBackend,
+        name: &str,
+    ) -> Font<'gc> {
+        let key = name.to_lowercase();
+        if let Some(font) = self.fonts.get(&key) {
+            return *font;
+        }
+
+        let mut glyph_sources = vec![];
+        for font_name in fallback_chain(name) {
+            if let Some(file) = self.load_file(backend, font_name) {
+                if !glyph_sources
+                    .iter()
+                    .any(|source| Arc::ptr_eq(source, &file))
+                {
+                    glyph_sources.push(file);
+                }
+            }
+        }
+        glyph_sources.extend(self.builtin.clone());
+
+        let font = Font::from_glyph_sources(gc_context, name, glyph_sources);
+        self.fonts.insert(key, font);
+        font
+    }
+
+    fn load_file(&mut self, backend: &mut dyn FontBackend, name: &str) -> Option<Arc<GlyphSource>> {
+        self.files
+            .entry(name.to_lowercase())
+            .or_insert_with(|| {
+                let data = backend.load_font(name)?;
+                match FontFile::parse(data) {
+                    Ok(file) => Some(Arc::new(GlyphSource::File(file))),
+                    Err(e) => {
+                        log::warn!("Unable to parse device font {}: {}", name, e);
+                        None
+                    }
+                }
+            })
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::font::MemoryFontBackend;
+    use crate::backend::render::NullRenderer;
+    use gc_arena::rootless_arena;
+    use swf::{ShapeRecord, StyleChangeData, Twips};
+
+    /// A TrueType font with 2048 units per EM, so glyphs are scaled by 10 into the
+    /// `DefineFont3` EM square. `A` is a 500x700 square and `B` a triangle with a curve.
+    const TEST_FONT: &[u8] = include_bytes!("../../tests/fonts/ruffle_test.ttf");
+
+    #[test]
+    fn generic_font_chains() {
+        assert_eq!(fallback_chain("_serif")[0], "Times New Roman");
+        assert_eq!(fallback_chain("_TYPEWRITER")[0], "Courier New");
+
+        let chain = fallback_chain("Verdana");
+        assert_eq!(&chain[..2], &["Verdana", "Arial"]);
+        assert_eq!(chain.last(), FALLBACK_FONTS.last());
+    }
+
+    #[test]
+    fn font_file_glyphs() {
+        let mut backend = MemoryFontBackend::new();
+        backend.add_font("Ruffle Test", TEST_FONT.to_vec());
+        let file = rootless_arena(|mc| {
+            let mut fonts = DeviceFonts::new(None);
+            let font = fonts.load(mc, &mut backend, "ruffle test");
+            font.load_glyphs(&mut NullRenderer::new(), "AB?");
+            let advance = |c| font.get_glyph_for_char(c).map(|glyph| glyph.advance);
+            assert_eq!(advance('A'), Some(7000));
+            assert_eq!(advance('B'), Some(6000));
+            assert_eq!(advance('?'), None);
+            fonts.load_file(&mut backend, "Ruffle Test").unwrap()
+        });
+        let glyphs = file.glyphs(&[0x41, 0x42]).unwrap();
+        let move_to = |x: i32, y: i32| {
+            ShapeRecord::StyleChange(StyleChangeData {
+                move_to: Some((Twips::new(x), Twips::new(y))),
+                fill_style_0: None,
+                fill_style_1: Some(1),
+                line_style: None,
+                new_styles: None,
+            })
+        };
+        let line = |x: i32, y: i32| ShapeRecord::StraightEdge {
+            delta_x: Twips::new(x),
+            delta_y: Twips::new(y),
+        };
+        assert_eq!(glyphs[0].code, 0x41);
+        assert_eq!(
+            glyphs[0].shape_records,
+            vec![
+                move_to(1000, 0),
+                line(5000, 0),
+                line(0, -7000),
+                line(-5000, 0),
+                line(0, 7000),
+            ]
+        );
+        // The off-curve point of the triangle is the control point of its last edge.
+        assert_eq!(glyphs[1].code, 0x42);
+        assert_eq!(
+            glyphs[1].shape_records,
+            vec![
+                move_to(0, 0),
+                line(5000, 0),
+                ShapeRecord::CurvedEdge {
+                    control_delta_x: Twips::new(-2500),
+                    control_delta_y: Twips::new(-5000),
+                    anchor_delta_x: Twips::new(-2500),
+                    anchor_delta_y: Twips::new(5000),
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,1532 @@
+//! Support for TrueType and OpenType fonts, as embedded by `DefineFont4` tags or provided
+//! by a `FontBackend` for device text.
+//!
+//! Only what is needed to draw text is parsed: `cmap` to map code points to glyphs,
+//! `head`, `hhea` and `hmtx` for metrics, and the outlines from either the Type 2
+//! charstrings in the `CFF ` table or the `glyf` table. Outlines are converted into SWF
+//! glyph shapes in the same 20480 unit EM square used by `DefineFont3`, with cubic curves
+//! approximated by quadratic ones.
+
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::io::{Read, Seek, SeekFrom};
+use swf::{Glyph, ShapeRecord, StyleChangeData, Twips};
+
+type Error = Box<dyn std::error::Error>;
+
+/// The size of the EM square of the converted glyphs.
+const EM_SQUARE: f64 = 20480.0;
+
+/// The maximum nesting of charstring subroutine calls (Adobe Technical Note #5177).
+const MAX_SUBR_DEPTH: usize = 10;
+
+/// The number of quadratic curves used to approximate each cubic curve.
+const CUBIC_SUBDIVISIONS: usize = 4;
+
+/// The maximum nesting of TrueType composite glyphs.
+const MAX_COMPONENT_DEPTH: usize = 8;
+
+/// The largest `name` table read by `read_family_name`. Real tables are a few kilobytes.
+const MAX_NAME_TABLE_LEN: u32 = 1 << 20;
+
+/// A TrueType or OpenType font file, from which glyphs are converted into SWF glyphs.
+///
+/// Only the tables needed to map code points to glyphs are parsed up front, so glyph
+/// outlines can be converted as they are needed.
+#[derive(Debug)]
+pub struct FontFile {
+    data: Vec<u8>,
+
+    /// The scale from font units to the EM square of the converted glyphs.
+    scale: f64,
+    num_h_metrics: usize,
+    code_points: BTreeMap<u16, u16>,
+    outline_format: OutlineFormat,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum OutlineFormat {
+    /// Cubic outlines in a `CFF ` table.
+    Cff,
+
+    /// Quadratic outlines in the `glyf` table, located using the `loca` table.
+    TrueType { long_offsets: bool },
+}
+
+impl FontFile {
+    pub fn parse(data: Vec<u8>) -> Result<Self, Error> {
+        let head = find_table(&data, b"head")?;
+        let units_per_em = read_u16(head, 18)?;
+        if units_per_em == 0 {
+            return Err("Invalid font units per EM".into());
+        }
+        let outline_format = if find_table(&data, b"CFF ").is_ok() {
+            OutlineFormat::Cff
+        } else {
+            OutlineFormat::TrueType {
+                long_offsets: read_u16(head, 50)? != 0,
+            }
+        };
+        let num_h_metrics = usize::from(read_u16(find_table(&data, b"hhea")?, 34)?);
+        let code_points = parse_cmap(find_table(&data, b"cmap")?)?;
+        Ok(Self {
+            scale: EM_SQUARE / f64::from(units_per_em),
+            num_h_metrics,
+            code_points,
+            outline_format,
+            data,
+        })
+    }
+
+    /// Returns whether the font has a glyph for the given code point.
+    pub fn has_glyph(&self, code: u16) -> bool {
+        self.code_points.contains_key(&code)
+    }
+
+    /// Returns every code point mapped by this font, in order.
+    pub fn code_points(&self) -> impl Iterator<Item = u16> + '_ {
+        self.code_points.keys().cloned()
+    }
+
+    /// Converts the glyphs for the given code points into SWF glyphs.
+    /// Code points without a glyph in this font are skipped.
+    pub fn glyphs<I: IntoIterator<Item = u16>>(&self, codes: I) -> Result<Vec<Glyph>, Error> {
+        let outlines = match self.outline_format {
+            OutlineFormat::Cff => Outlines::Cff(Cff::parse(find_table(&self.data, b"CFF ")?)?),
+            OutlineFormat::TrueType { long_offsets } => Outlines::TrueType(TrueTypeOutlines {
+                loca: find_table(&self.data, b"loca")?,
+                glyf: find_table(&self.data, b"glyf")?,
+                long_offsets,
+            }),
+        };
+        let hmtx = find_table(&self.data, b"hmtx")?;
+
+        let mut glyphs = vec![];
+        for code in codes {
+            let glyph_id = match self.code_points.get(&code) {
+                Some(&glyph_id) => glyph_id,
+                None => continue,
+            };
+            let mut builder = ShapeBuilder::new(self.scale);
+            match &outlines {
+                Outlines::Cff(cff) => cff.draw_glyph(glyph_id, &mut builder)?,
+                Outlines::TrueType(true_type) => {
+                    true_type.draw_glyph(glyph_id, &mut builder, &IDENTITY, 0)?
+                }
+            }
+
+            let advance = advance_width(hmtx, self.num_h_metrics, glyph_id)?;
+            let advance = (f64::from(advance) * self.scale)
+                .round()
+                .min(f64::from(i16::MAX));
+            glyphs.push(Glyph {
+                shape_records: builder.finish(),
+                code,
+                advance: Some(advance as i16),
+                bounds: None,
+            });
+        }
+        Ok(glyphs)
+    }
+}
+
+/// Parses an OpenType font and converts every mapped glyph into a SWF glyph.
+///
+/// Glyphs are returned in code point order. Code points outside of the Basic Multilingual
+/// Plane are skipped, as SWF glyph codes are only 16 bits.
+pub fn parse_glyphs(data: &[u8]) -> Result<Vec<Glyph>, Error> {
+    let font = FontFile::parse(data.to_vec())?;
+    font.glyphs(font.code_points())
+}
+
+/// Reads the family name of a font, such as `Arial`, from its `name` table.
+///
+/// Only the table directory and the `name` table are read, so this is cheap even for
+/// large font files.
+pub fn read_family_name<R: Read + Seek>(reader: &mut R) -> Option<String> {
+    let mut header = [0; 12];
+    reader.read_exact(&mut header).ok()?;
+    let num_tables = usize::from(read_u16(&header, 4).ok()?);
+    let mut records = vec![0; num_tables * 16];
+    reader.read_exact(&mut records).ok()?;
+    let record = records
+        .chunks_exact(16)
+        .find(|record| &record[..4] == b"name")?;
+    let offset = read_u32(record, 8).ok()?;
+    let length = read_u32(record, 12).ok()?;
+    if length > MAX_NAME_TABLE_LEN {
+        return None;
+    }
+    let mut name = vec![0; length as usize];
+    reader.seek(SeekFrom::Start(u64::from(offset))).ok()?;
+    reader.read_exact(&mut name).ok()?;
+    family_name(&name)
+}
+
+/// Returns the family name from a `name` table.
+fn family_name(name: &[u8]) -> Option<String> {
+    const FAMILY_NAME: u16 = 1;
+    const TYPOGRAPHIC_FAMILY_NAME: u16 = 16;
+
+    let count = usize::from(read_u16(name, 2).ok()?);
+    let strings = usize::from(read_u16(name, 4).ok()?);
+    let mut family = None;
+    for i in 0..count {
+        let record = 6 + i * 12;
+        let platform_id = read_u16(name, record).ok()?;
+        let encoding_id = read_u16(name, record + 2).ok()?;
+        let name_id = read_u16(name, record + 6).ok()?;
+        if name_id != FAMILY_NAME && name_id != TYPOGRAPHIC_FAMILY_NAME {
+            continue;
+        }
+        let length = usize::from(read_u16(name, record + 8).ok()?);
+        let offset = usize::from(read_u16(name, record + 10).ok()?);
+        let bytes = match sub_slice(name, strings + offset, length) {
+            Ok(bytes) => bytes,
+            Err(_) => continue,
+        };
+        let value = match (platform_id, encoding_id) {
+            (0, _) | (3, _) => {
+                let units: Vec<u16> = bytes
+                    .chunks_exact(2)
+                    .map(|c| u16::from(c[0]) << 8 | u16::from(c[1]))
+                    .collect();
+                String::from_utf16_lossy(&units)
+            }
+            (1, 0) => String::from_utf8_lossy(bytes).into_owned(),
+            _ => continue,
+        };
+        // The typographic family groups all styles of a family, so it is preferred.
+        if name_id == TYPOGRAPHIC_FAMILY_NAME {
+            return Some(value);
+        }
+        family.get_or_insert(value);
+    }
+    family
+}
+
+enum Outlines<'a> {
+    Cff(Cff<'a>),
+    TrueType(TrueTypeOutlines<'a>),
+}
+
+fn read_u8(data: &[u8], offset: usize) -> Result<u8, Error> {
+    data.get(offset)
+        .cloned()
+        .ok_or_else(|| "Unexpected end of font data".into())
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, Error> {
+    Ok(u16::from(read_u8(data, offset)?) << 8 | u16::from(read_u8(data, offset + 1)?))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, Error> {
+    Ok(u32::from(read_u16(data, offset)?) << 16 | u32::from(read_u16(data, offset + 2)?))
+}
+
+/// Reads an unsigned big-endian integer of `size` bytes, as used by CFF offsets.
+fn read_offset(data: &[u8], offset: usize, size: usize) -> Result<usize, Error> {
+    let mut value = 0;
+    for i in 0..size {
+        value = value << 8 | usize::from(read_u8(data, offset + i)?);
+    }
+    Ok(value)
+}
+
+fn sub_slice(data: &[u8], start: usize, len: usize) -> Result<&[u8], Error> {
+    start
+        .checked_add(len)
+        .and_then(|end| data.get(start..end))
+        .ok_or_else(|| "Unexpected end of font data".into())
+}
+
+/// Returns the contents of the table with the given tag from the sfnt table directory.
+fn find_table<'a>(data: &'a [u8], tag: &[u8; 4]) -> Result<&'a [u8], Error> {
+    let num_tables = usize::from(read_u16(data, 4)?);
+    for i in 0..num_tables {
+        let record = 12 + i * 16;
+        if sub_slice(data, record, 4)? == tag {
+            let offset = read_u32(data, record + 8)? as usize;
+            let length = read_u32(data, record + 12)? as usize;
+            return sub_slice(data, offset, length);
+        }
+    }
+    Err(format!("Missing font table '{}'", String::from_utf8_lossy(tag)).into())
+}
+
+fn advance_width(hmtx: &[u8], num_h_metrics: usize, glyph_id: u16) -> Result<u16, Error> {
+    // Glyphs past the end of the metrics array share the last advance width.
+    let index = std::cmp::min(usize::from(glyph_id), num_h_metrics.saturating_sub(1));
+    read_u16(hmtx, index * 4)
+}
+
+/// Parses the Unicode mapping of the `cmap` table into a map from code point to glyph ID.
+fn parse_cmap(cmap: &[u8]) -> Result<BTreeMap<u16, u16>, Error> {
+    // Prefer the full Unicode mapping (format 12) over the BMP only one (format 4).
+    let mut best = None;
+    let num_tables = usize::from(read_u16(cmap, 2)?);
+    for i in 0..num_tables {
+        let record = 4 + i * 8;
+        let platform_id = read_u16(cmap, record)?;
+        let encoding_id = read_u16(cmap, record + 2)?;
+        let is_unicode = platform_id == 0 || (platform_id == 3 && [1, 10].contains(&encoding_id));
+        if !is_unicode {
+            continue;
+        }
+        let offset = read_u32(cmap, record + 4)? as usize;
+        let format = read_u16(cmap, offset)?;
+        match (format, best) {
+            (12, _) => best = Some((format, offset)),
+            (4, None) => best = Some((format, offset)),
+            _ => (),
+        }
+    }
+
+    let mut code_points = BTreeMap::new();
+    match best {
+        Some((4, offset)) => {
+            let subtable = &cmap[offset..];
+            let seg_count_x2 = usize::from(read_u16(subtable, 6)?);
+            let end_codes = 14;
+            let start_codes = end_codes + seg_count_x2 + 2;
+            let id_deltas = start_codes + seg_count_x2;
+            let id_range_offsets = id_deltas + seg_count_x2;
+            for segment in (0..seg_count_x2).step_by(2) {
+                let end = read_u16(subtable, end_codes + segment)?;
+                let start = read_u16(subtable, start_codes + segment)?;
+                let delta = read_u16(subtable, id_deltas + segment)?;
+                let range_offset = usize::from(read_u16(subtable, id_range_offsets + segment)?);
+                for code in u32::from(start)..=u32::from(end) {
+                    if code == 0xFFFF {
+                        break;
+                    }
+                    let code = code as u16;
+                    let glyph_id = if range_offset == 0 {
+                        code.wrapping_add(delta)
+                    } else {
+                        // The offset is relative to the location of the range offset itself.
+                        let address = id_range_offsets
+                            + segment
+                            + range_offset
+                            + usize::from(code - start) * 2;
+                        match read_u16(subtable, address)? {
+                            0 => 0,
+                            glyph_id => glyph_id.wrapping_add(delta),
+                        }
+                    };
+                    if glyph_id != 0 {
+                        code_points.insert(code, glyph_id);
+                    }
+                }
+            }
+        }
+        Some((12, offset)) => {
+            let subtable = &cmap[offset..];
+            let num_groups = read_u32(subtable, 12)? as usize;
+            for i in 0..num_groups {
+                let group = 16 + i * 12;
+                let start = read_u32(subtable, group)?;
+                let end = std::cmp::min(read_u32(subtable, group + 4)?, 0xFFFF);
+                let start_glyph_id = read_u32(subtable, group + 8)?;
+                for code in start..=end {
+                    let glyph_id = start_glyph_id.saturating_add(code - start);
+                    if glyph_id != 0 && glyph_id <= 0xFFFF {
+                        code_points.insert(code as u16, glyph_id as u16);
+                    }
+                }
+            }
+        }
+        _ => return Err("Font has no supported Unicode cmap".into()),
+    }
+    Ok(code_points)
+}
+
+/// A CFF INDEX structure: an array of variable-sized objects.
+struct Index<'a> {
+    data: &'a [u8],
+    offsets: Vec<usize>,
+}
+
+impl<'a> Index<'a> {
+    fn empty() -> Self {
+        Self {
+            data: &[],
+            offsets: vec![0],
+        }
+    }
+
+    /// Parses the INDEX at `start`, returning it with the offset of the byte following it.
+    fn parse(cff: &'a [u8], start: usize) -> Result<(Self, usize), Error> {
+        let count = usize::from(read_u16(cff, start)?);
+        if count == 0 {
+            return Ok((Self::empty(), start + 2));
+        }
+        let offset_size = usize::from(read_u8(cff, start + 2)?);
+        if !(1..=4).contains(&offset_size) {
+            return Err("Invalid CFF INDEX offset size".into());
+        }
+        let offsets = (0..=count)
+            .map(|i| read_offset(cff, start + 3 + i * offset_size, offset_size))
+            .collect::<Result<Vec<_>, _>>()?;
+        // Offsets are relative to the byte preceding the object data.
+        let data_start = start + 3 + (count + 1) * offset_size - 1;
+        let data = sub_slice(cff, data_start, offsets[count])?;
+        let end = data_start + offsets[count];
+        Ok((Self { data, offsets }, end))
+    }
+
+    fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    fn get(&self, i: usize) -> Option<&'a [u8]> {
+        let start = *self.offsets.get(i)?;
+        let end = *self.offsets.get(i + 1)?;
+        self.data.get(start..end)
+    }
+
+    /// The bias added to subroutine numbers when this INDEX holds subroutines.
+    fn subr_bias(&self) -> i32 {
+        match self.len() {
+            0..=1239 => 107,
+            1240..=33899 => 1131,
+            _ => 32768,
+        }
+    }
+}
+
+/// A parsed CFF DICT, mapping operators to their operands.
+/// Two-byte operators are stored as `1200 + second byte`.
+struct Dict(Vec<(u16, Vec<f64>)>);
+
+impl Dict {
+    fn parse(data: &[u8]) -> Result<Self, Error> {
+        let mut entries = vec![];
+        let mut operands = vec![];
+        let mut pos = 0;
+        while pos < data.len() {
+            let b0 = data[pos];
+            pos += 1;
+            match b0 {
+                0..=11 | 13..=21 => entries.push((u16::from(b0), std::mem::take(&mut operands))),
+                12 => {
+                    let b1 = read_u8(data, pos)?;
+                    pos += 1;
+                    entries.push((1200 + u16::from(b1), std::mem::take(&mut operands)));
+                }
+                28 => {
+                    operands.push(f64::from(read_u16(data, pos)? as i16));
+                    pos += 2;
+                }
+                29 => {
+                    operands.push(f64::from(read_u32(data, pos)? as i32));
+                    pos += 4;
+                }
+                30 => {
+                    // Real numbers are only used for values we don't need, such as the font
+                    // matrix, so they are skipped.
+                    while read_u8(data, pos)? & 0x0F != 0x0F && data[pos] & 0xF0 != 0xF0 {
+                        pos += 1;
+                    }
+                    pos += 1;
+                    operands.push(0.0);
+                }
+                32..=246 => operands.push(f64::from(i32::from(b0) - 139)),
+                247..=250 => {
+                    let b1 = i32::from(read_u8(data, pos)?);
+                    pos += 1;
+                    operands.push(f64::from((i32::from(b0) - 247) * 256 + b1 + 108));
+                }
+                251..=254 => {
+                    let b1 = i32::from(read_u8(data, pos)?);
+                    pos += 1;
+                    operands.push(f64::from(-(i32::from(b0) - 251) * 256 - b1 - 108));
+                }
+                _ => return Err("Invalid CFF DICT data".into()),
+            }
+        }
+        Ok(Dict(entries))
+    }
+
+    fn get(&self, operator: u16) -> Option<&[f64]> {
+        self.0
+            .iter()
+            .find(|(op, _)| *op == operator)
+            .map(|(_, operands)| &operands[..])
+    }
+
+    fn get_offset(&self, operator: u16) -> Option<usize> {
+        self.get(operator)
+            .and_then(|operands| operands.last())
+            .map(|offset| *offset as usize)
+    }
+}
+
+const DICT_CHAR_STRINGS: u16 = 17;
+const DICT_PRIVATE: u16 = 18;
+const DICT_SUBRS: u16 = 19;
+const DICT_FD_ARRAY: u16 = 1236;
+const DICT_FD_SELECT: u16 = 1237;
+
+/// The parts of a CFF font needed to draw its glyphs.
+struct Cff<'a> {
+    char_strings: Index<'a>,
+    global_subrs: Index<'a>,
+
+    /// The local subroutines of each font DICT. Non-CID fonts have a single one.
+    local_subrs: Vec<Index<'a>>,
+
+    /// The FDSelect data of CID fonts, mapping each glyph to a font DICT.
+    fd_select: Option<&'a [u8]>,
+}
+
+impl<'a> Cff<'a> {
+    fn parse(cff: &'a [u8]) -> Result<Self, Error> {
+        let header_size = usize::from(read_u8(cff, 2)?);
+        let (_names, pos) = Index::parse(cff, header_size)?;
+        let (top_dicts, pos) = Index::parse(cff, pos)?;
+        let (_strings, pos) = Index::parse(cff, pos)?;
+        let (global_subrs, _) = Index::parse(cff, pos)?;
+
+        let top_dict = Dict::parse(top_dicts.get(0).ok_or("Missing CFF top DICT")?)?;
+        let char_strings_offset = top_dict
+            .get_offset(DICT_CHAR_STRINGS)
+            .ok_or("Missing CFF CharStrings")?;
+        let (char_strings, _) = Index::parse(cff, char_strings_offset)?;
+
+        let (local_subrs, fd_select) = match (
+            top_dict.get_offset(DICT_FD_ARRAY),
+            top_dict.get_offset(DICT_FD_SELECT),
+        ) {
+            (Some(fd_array_offset), Some(fd_select_offset)) => {
+                let (fd_array, _) = Index::parse(cff, fd_array_offset)?;
+                let local_subrs = (0..fd_array.len())
+                    .map(|i| {
+                        let font_dict = Dict::parse(fd_array.get(i).unwrap_or_default())?;
+                        Self::parse_local_subrs(cff, &font_dict)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                (
+                    local_subrs,
+                    Some(cff.get(fd_select_offset..).unwrap_or_default()),
+                )
+            }
+            _ => (vec![Self::parse_local_subrs(cff, &top_dict)?], None),
+        };
+
+        Ok(Self {
+            char_strings,
+            global_subrs,
+            local_subrs,
+            fd_select,
+        })
+    }
+
+    /// Returns the local subroutines of the Private DICT referenced by `dict`.
+    fn parse_local_subrs(cff: &'a [u8], dict: &Dict) -> Result<Index<'a>, Error> {
+        let (size, offset) = match dict.get(DICT_PRIVATE) {
+            Some(operands) if operands.len() == 2 => (operands[0] as usize, operands[1] as usize),
+            _ => return Ok(Index::empty()),
+        };
+        let private_dict = Dict::parse(sub_slice(cff, offset, size)?)?;
+        match private_dict.get_offset(DICT_SUBRS) {
+            // The Subrs offset is relative to the start of the Private DICT.
+            Some(subrs_offset) => Ok(Index::parse(cff, offset + subrs_offset)?.0),
+            None => Ok(Index::empty()),
+        }
+    }
+
+    /// Returns the index of the font DICT used by a glyph in a CID font.
+    fn fd_index(&self, glyph_id: u16) -> Result<usize, Error> {
+        let fd_select = match self.fd_select {
+            Some(fd_select) => fd_select,
+            None => return Ok(0),
+        };
+        match read_u8(fd_select, 0)? {
+            0 => Ok(usize::from(read_u8(fd_select, 1 + usize::from(glyph_id))?)),
+            3 => {
+                let num_ranges = usize::from(read_u16(fd_select, 1)?);
+                for i in 0..num_ranges {
+                    let range = 3 + i * 3;
+                    let next_first = read_u16(fd_select, range + 3)?;
+                    if glyph_id < next_first {
+                        return Ok(usize::from(read_u8(fd_select, range + 2)?));
+                    }
+                }
+                Err("Glyph is missing from CFF FDSelect".into())
+            }
+            _ => Err("Unsupported CFF FDSelect format".into()),
+        }
+    }
+
+    fn draw_glyph(&self, glyph_id: u16, builder: &mut ShapeBuilder) -> Result<(), Error> {
+        let char_string = self
+            .char_strings
+            .get(usize::from(glyph_id))
+            .ok_or("Invalid glyph ID")?;
+        let local_subrs = self
+            .local_subrs
+            .get(self.fd_index(glyph_id)?)
+            .ok_or("Invalid CFF font DICT index")?;
+        let mut interpreter = CharStringInterpreter {
+            global_subrs: &self.global_subrs,
+            local_subrs,
+            builder,
+            stack: vec![],
+            x: 0.0,
+            y: 0.0,
+            stem_count: 0,
+            width_parsed: false,
+            ended: false,
+        };
+        interpreter.execute(char_string, 0)
+    }
+}
+
+/// Executes Type 2 charstrings (Adobe Technical Note #5177), drawing into a `ShapeBuilder`.
+struct CharStringInterpreter<'a, 'b> {
+    global_subrs: &'a Index<'a>,
+    local_subrs: &'a Index<'a>,
+    builder: &'b mut ShapeBuilder,
+    stack: Vec<f64>,
+    x: f64,
+    y: f64,
+    stem_count: usize,
+
+    /// Whether the optional advance width at the start of the charstring has been handled.
+    width_parsed: bool,
+    ended: bool,
+}
+
+impl<'a, 'b> CharStringInterpreter<'a, 'b> {
+    fn execute(&mut self, data: &[u8], depth: usize) -> Result<(), Error> {
+        if depth > MAX_SUBR_DEPTH {
+            return Err("Charstring subroutines nested too deeply".into());
+        }
+        let mut pos = 0;
+        while pos < data.len() && !self.ended {
+            let b0 = data[pos];
+            pos += 1;
+            match b0 {
+                // hstem, vstem, hstemhm, vstemhm
+                1 | 3 | 18 | 23 => self.add_stems(),
+                // hintmask, cntrmask
+                19 | 20 => {
+                    // Any operands are implied vstems.
+                    self.add_stems();
+                    pos += self.stem_count.div_ceil(8);
+                }
+                // rmoveto
+                21 => {
+                    self.parse_width(self.stack.len() > 2);
+                    let args = self.take_args(2)?;
+                    self.move_to(args[0], args[1]);
+                }
+                // hmoveto
+                22 => {
+                    self.parse_width(self.stack.len() > 1);
+                    let args = self.take_args(1)?;
+                    self.move_to(args[0], 0.0);
+                }
+                // vmoveto
+                4 => {
+                    self.parse_width(self.stack.len() > 1);
+                    let args = self.take_args(1)?;
+                    self.move_to(0.0, args[0]);
+                }
+                // rlineto
+                5 => {
+                    for args in std::mem::take(&mut self.stack).chunks_exact(2) {
+                        self.line_to(args[0], args[1]);
+                    }
+                }
+                // hlineto, vlineto
+                6 | 7 => {
+                    let mut horizontal = b0 == 6;
+                    for &delta in &std::mem::take(&mut self.stack) {
+                        if horizontal {
+                            self.line_to(delta, 0.0);
+                        } else {
+                            self.line_to(0.0, delta);
+                        }
+                        horizontal = !horizontal;
+                    }
+                }
+                // rrcurveto
+                8 => {
+                    for args in std::mem::take(&mut self.stack).chunks_exact(6) {
+                        self.curve_to(args[0], args[1], args[2], args[3], args[4], args[5]);
+                    }
+                }
+                // callsubr
+                10 => {
+                    let subrs = self.local_subrs;
+                    self.call_subr(subrs, depth)?;
+                }
+                // callgsubr
+                29 => {
+                    let subrs = self.global_subrs;
+                    self.call_subr(subrs, depth)?;
+                }
+                // return
+                11 => return Ok(()),
+                // endchar
+                14 => {
+                    // Accented characters built with the deprecated `seac` form are not
+                    // supported; only the base outline is drawn.
+                    let len = self.stack.len();
+                    self.parse_width(len == 1 || len == 5);
+                    self.stack.clear();
+                    self.builder.close_contour();
+                    self.ended = true;
+                }
+                // rcurveline
+                24 => {
+                    let args = std::mem::take(&mut self.stack);
+                    if args.len() >= 2 {
+                        let (curves, line) = args.split_at(args.len() - 2);
+                        for c in curves.chunks_exact(6) {
+                            self.curve_to(c[0], c[1], c[2], c[3], c[4], c[5]);
+                        }
+                        self.line_to(line[0], line[1]);
+                    }
+                }
+                // rlinecurve
+                25 => {
+                    let args = std::mem::take(&mut self.stack);
+                    if args.len() >= 6 {
+                        let (lines, c) = args.split_at(args.len() - 6);
+                        for line in lines.chunks_exact(2) {
+                            self.line_to(line[0], line[1]);
+                        }
+                        self.curve_to(c[0], c[1], c[2], c[3], c[4], c[5]);
+                    }
+                }
+                // vvcurveto
+                26 => {
+                    let args = std::mem::take(&mut self.stack);
+                    let (mut dx1, args) = if args.len() % 2 == 1 {
+                        (args[0], &args[1..])
+                    } else {
+                        (0.0, &args[..])
+                    };
+                    for c in args.chunks_exact(4) {
+                        self.curve_to(dx1, c[0], c[1], c[2], 0.0, c[3]);
+                        dx1 = 0.0;
+                    }
+                }
+                // hhcurveto
+                27 => {
+                    let args = std::mem::take(&mut self.stack);
+                    let (mut dy1, args) = if args.len() % 2 == 1 {
+                        (args[0], &args[1..])
+                    } else {
+                        (0.0, &args[..])
+                    };
+                    for c in args.chunks_exact(4) {
+                        self.curve_to(c[0], dy1, c[1], c[2], c[3], 0.0);
+                        dy1 = 0.0;
+                    }
+                }
+                // vhcurveto, hvcurveto
+                30 | 31 => {
+                    let args = std::mem::take(&mut self.stack);
+                    let mut horizontal = b0 == 31;
+                    let mut i = 0;
+                    while i + 4 <= args.len() {
+                        // The last curve may have an extra final delta.
+                        let last = if args.len() - i == 5 {
+                            args[i + 4]
+                        } else {
+                            0.0
+                        };
+                        let c = &args[i..i + 4];
+                        if horizontal {
+                            self.curve_to(c[0], 0.0, c[1], c[2], last, c[3]);
+                        } else {
+                            self.curve_to(0.0, c[0], c[1], c[2], c[3], last);
+                        }
+                        horizontal = !horizontal;
+                        i += 4;
+                    }
+                }
+                12 => {
+                    let b1 = read_u8(data, pos)?;
+                    pos += 1;
+                    self.execute_escape(b1)?;
+                }
+                28 => {
+                    self.stack.push(f64::from(read_u16(data, pos)? as i16));
+                    pos += 2;
+                }
+                32..=246 => self.stack.push(f64::from(i32::from(b0) - 139)),
+                247..=250 => {
+                    let b1 = i32::from(read_u8(data, pos)?);
+                    pos += 1;
+                    self.stack
+                        .push(f64::from((i32::from(b0) - 247) * 256 + b1 + 108));
+                }
+                251..=254 => {
+                    let b1 = i32::from(read_u8(data, pos)?);
+                    pos += 1;
+                    self.stack
+                        .push(f64::from(-(i32::from(b0) - 251) * 256 - b1 - 108));
+                }
+                255 => {
+                    // 16.16 fixed point.
+                    self.stack
+                        .push(f64::from(read_u32(data, pos)? as i32) / 65536.0);
+                    pos += 4;
+                }
+                _ => return Err(format!("Unknown charstring operator {}", b0).into()),
+            }
+        }
+        Ok(())
+    }
+
+    /// Executes the two-byte operator `12 op`.
+    fn execute_escape(&mut self, op: u8) -> Result<(), Error> {
+        match op {
+            // flex
+            35 => {
+                let a = self.take_args(13)?;
+                self.curve_to(a[0], a[1], a[2], a[3], a[4], a[5]);
+                self.curve_to(a[6], a[7], a[8], a[9], a[10], a[11]);
+            }
+            // hflex
+            34 => {
+                let a = self.take_args(7)?;
+                self.curve_to(a[0], 0.0, a[1], a[2], a[3], 0.0);
+                self.curve_to(a[4], 0.0, a[5], -a[2], a[6], 0.0);
+            }
+            // hflex1
+            36 => {
+                let a = self.take_args(9)?;
+                self.curve_to(a[0], a[1], a[2], a[3], a[4], 0.0);
+                let dy = -(a[1] + a[3] + a[7]);
+                self.curve_to(a[5], 0.0, a[6], a[7], a[8], dy);
+            }
+            // flex1
+            37 => {
+                let a = self.take_args(11)?;
+                let dx = a[0] + a[2] + a[4] + a[6] + a[8];
+                let dy = a[1] + a[3] + a[5] + a[7] + a[9];
+                self.curve_to(a[0], a[1], a[2], a[3], a[4], a[5]);
+                // The curve returns to either the starting x or y, whichever moved less.
+                let (last_x, last_y) = if dx.abs() > dy.abs() {
+                    (a[10], -dy)
+                } else {
+                    (-dx, a[10])
+                };
+                self.curve_to(a[6], a[7], a[8], a[9], last_x, last_y);
+            }
+            // The arithmetic and storage operators are essentially unused in practice.
+            _ => self.stack.clear(),
+        }
+        Ok(())
+    }
+
+    fn call_subr(&mut self, subrs: &Index<'_>, depth: usize) -> Result<(), Error> {
+        let index = self.stack.pop().ok_or("Charstring stack underflow")? as i32;
+        let subr = usize::try_from(index + subrs.subr_bias())
+            .ok()
+            .and_then(|i| subrs.get(i))
+            .ok_or("Invalid charstring subroutine")?;
+        self.execute(subr, depth + 1)
+    }
+
+    /// Handles the advance width that may precede the first stack-clearing operator.
+    /// The width itself is ignored in favor of the `hmtx` table.
+    fn parse_width(&mut self, has_width: bool) {
+        if !self.width_parsed {
+            if has_width && !self.stack.is_empty() {
+                self.stack.remove(0);
+            }
+            self.width_parsed = true;
+        }
+    }
+
+    fn add_stems(&mut self) {
+        self.parse_width(self.stack.len() % 2 == 1);
+        self.stem_count += self.stack.len() / 2;
+        self.stack.clear();
+    }
+
+    /// Clears the stack, returning its contents if it holds at least `count` operands.
+    fn take_args(&mut self, count: usize) -> Result<Vec<f64>, Error> {
+        let args = std::mem::take(&mut self.stack);
+        if args.len() < count {
+            return Err("Charstring stack underflow".into());
+        }
+        Ok(args)
+    }
+
+    fn move_to(&mut self, dx: f64, dy: f64) {
+        self.x += dx;
+        self.y += dy;
+        self.builder.move_to(self.x, self.y);
+    }
+
+    fn line_to(&mut self, dx: f64, dy: f64) {
+        self.x += dx;
+        self.y += dy;
+        self.builder.line_to(self.x, self.y);
+    }
+
+    /// Draws a cubic curve. Each point is relative to the previous one.
+    fn curve_to(&mut self, dx1: f64, dy1: f64, dx2: f64, dy2: f64, dx3: f64, dy3: f64) {
+        let c1 = (self.x + dx1, self.y + dy1);
+        let c2 = (c1.0 + dx2, c1.1 + dy2);
+        self.x = c2.0 + dx3;
+        self.y = c2.1 + dy3;
+        self.builder.curve_to(c1, c2, (self.x, self.y));
+    }
+}
+
+/// An affine transform `[a, b, c, d, e, f]`, mapping `(x, y)` to
+/// `(a * x + c * y + e, b * x + d * y + f)`.
+type Transform2D = [f64; 6];
+
+const IDENTITY: Transform2D = [1.0, 0.0, 0.0, 1.0, 0.0, 0.0];
+
+fn transform_point(t: &Transform2D, x: f64, y: f64) -> (f64, f64) {
+    (t[0] * x + t[2] * y + t[4], t[1] * x + t[3] * y + t[5])
+}
+
+/// Returns the transform applying `inner` and then `outer`.
+fn concat_transforms(outer: &Transform2D, inner: &Transform2D) -> Transform2D {
+    [
+        outer[0] * inner[0] + outer[2] * inner[1],
+        outer[1] * inner[0] + outer[3] * inner[1],
+        outer[0] * inner[2] + outer[2] * inner[3],
+        outer[1] * inner[2] + outer[3] * inner[3],
+        outer[0] * inner[4] + outer[2] * inner[5] + outer[4],
+        outer[1] * inner[4] + outer[3] * inner[5] + outer[5],
+    ]
+}
+
+/// Reads a signed 2.14 fixed point number.
+fn read_f2dot14(data: &[u8], offset: usize) -> Result<f64, Error> {
+    Ok(f64::from(read_u16(data, offset)? as i16) / 16384.0)
+}
+
+/// The quadratic glyph outlines of a TrueType font.
+struct TrueTypeOutlines<'a> {
+    loca: &'a [u8],
+    glyf: &'a [u8],
+    long_offsets: bool,
+}
+
+impl<'a> TrueTypeOutlines<'a> {
+    const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+    const ARGS_ARE_XY_VALUES: u16 = 0x0002;
+    const WE_HAVE_A_SCALE: u16 = 0x0008;
+    const MORE_COMPONENTS: u16 = 0x0020;
+    const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+    const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+    fn glyph_data(&self, glyph_id: u16) -> Result<&'a [u8], Error> {
+        let index = usize::from(glyph_id);
+        let (start, end) = if self.long_offsets {
+            (
+                read_u32(self.loca, index * 4)? as usize,
+                read_u32(self.loca, index * 4 + 4)? as usize,
+            )
+        } else {
+            (
+                usize::from(read_u16(self.loca, index * 2)?) * 2,
+                usize::from(read_u16(self.loca, index * 2 + 2)?) * 2,
+            )
+        };
+        if end <= start {
+            // Glyphs without outlines, such as spaces, have no data.
+            return Ok(&[]);
+        }
+        sub_slice(self.glyf, start, end - start)
+    }
+
+    fn draw_glyph(
+        &self,
+        glyph_id: u16,
+        builder: &mut ShapeBuilder,
+        transform: &Transform2D,
+        depth: usize,
+    ) -> Result<(), Error> {
+        if depth > MAX_COMPONENT_DEPTH {
+            return Err("Composite glyphs nested too deeply".into());
+        }
+        let data = self.glyph_data(glyph_id)?;
+        if data.is_empty() {
+            return Ok(());
+        }
+        let num_contours = read_u16(data, 0)? as i16;
+        if num_contours >= 0 {
+            Self::draw_simple_glyph(data, num_contours as usize, builder, transform)
+        } else {
+            self.draw_composite_glyph(data, builder, transform, depth)
+        }
+    }
+
+    fn draw_simple_glyph(
+        data: &[u8],
+        num_contours: usize,
+        builder: &mut ShapeBuilder,
+        transform: &Transform2D,
+    ) -> Result<(), Error> {
+        const ON_CURVE: u8 = 0x01;
+        const X_SHORT: u8 = 0x02;
+        const Y_SHORT: u8 = 0x04;
+        const REPEAT: u8 = 0x08;
+        const X_SAME_OR_POSITIVE: u8 = 0x10;
+        const Y_SAME_OR_POSITIVE: u8 = 0x20;
+
+        let end_points = (0..num_contours)
+            .map(|i| read_u16(data, 10 + i * 2).map(usize::from))
+            .collect::<Result<Vec<_>, _>>()?;
+        let num_points = end_points.last().map_or(0, |end| end + 1);
+        let instructions_len = usize::from(read_u16(data, 10 + num_contours * 2)?);
+        let mut pos = 12 + num_contours * 2 + instructions_len;
+
+        let mut flags = Vec::with_capacity(num_points);
+        while flags.len() < num_points {
+            let flag = read_u8(data, pos)?;
+            pos += 1;
+            flags.push(flag);
+            if flag & REPEAT != 0 {
+                let count = read_u8(data, pos)?;
+                pos += 1;
+                for _ in 0..count {
+                    flags.push(flag);
+                }
+            }
+        }
+        flags.truncate(num_points);
+
+        // Coordinates are stored as deltas from the previous point.
+        let mut read_coordinates = |short: u8, same_or_positive: u8| {
+            let mut value = 0i32;
+            let mut coordinates = Vec::with_capacity(num_points);
+            for &flag in &flags {
+                if flag & short != 0 {
+                    let delta = i32::from(read_u8(data, pos)?);
+                    pos += 1;
+                    value += if flag & same_or_positive != 0 {
+                        delta
+                    } else {
+                        -delta
+                    };
+                } else if flag & same_or_positive == 0 {
+                    value += i32::from(read_u16(data, pos)? as i16);
+                    pos += 2;
+                }
+                coordinates.push(value);
+            }
+            Ok::<_, Error>(coordinates)
+        };
+        let xs = read_coordinates(X_SHORT, X_SAME_OR_POSITIVE)?;
+        let ys = read_coordinates(Y_SHORT, Y_SAME_OR_POSITIVE)?;
+
+        let mut start = 0;
+        for &end in &end_points {
+            if end < start || end >= num_points {
+                return Err("Invalid TrueType contour".into());
+            }
+            let points: Vec<_> = (start..=end)
+                .map(|i| {
+                    let point = transform_point(transform, f64::from(xs[i]), f64::from(ys[i]));
+                    (point, flags[i] & ON_CURVE != 0)
+                })
+                .collect();
+            draw_quadratic_contour(builder, &points);
+            start = end + 1;
+        }
+        Ok(())
+    }
+
+    fn draw_composite_glyph(
+        &self,
+        data: &[u8],
+        builder: &mut ShapeBuilder,
+        transform: &Transform2D,
+        depth: usize,
+    ) -> Result<(), Error> {
+        let mut pos = 10;
+        loop {
+            let flags = read_u16(data, pos)?;
+            let glyph_id = read_u16(data, pos + 2)?;
+            pos += 4;
+
+            let (arg1, arg2) = if flags & Self::ARG_1_AND_2_ARE_WORDS != 0 {
+                pos += 4;
+                (
+                    f64::from(read_u16(data, pos - 4)? as i16),
+                    f64::from(read_u16(data, pos - 2)? as i16),
+                )
+            } else {
+                pos += 2;
+                (
+                    f64::from(read_u8(data, pos - 2)? as i8),
+                    f64::from(read_u8(data, pos - 1)? as i8),
+                )
+            };
+
+            let mut component = IDENTITY;
+            if flags & Self::WE_HAVE_A_SCALE != 0 {
+                component[0] = read_f2dot14(data, pos)?;
+                component[3] = component[0];
+                pos += 2;
+            } else if flags & Self::WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+                component[0] = read_f2dot14(data, pos)?;
+                component[3] = read_f2dot14(data, pos + 2)?;
+                pos += 4;
+            } else if flags & Self::WE_HAVE_A_TWO_BY_TWO != 0 {
+                for (i, value) in component[..4].iter_mut().enumerate() {
+                    *value = read_f2dot14(data, pos + i * 2)?;
+                }
+                pos += 8;
+            }
+            // Components positioned by matching points are rare, and are drawn unshifted.
+            if flags & Self::ARGS_ARE_XY_VALUES != 0 {
+                component[4] = arg1;
+                component[5] = arg2;
+            }
+
+            let component_transform = concat_transforms(transform, &component);
+            self.draw_glyph(glyph_id, builder, &component_transform, depth + 1)?;
+
+            if flags & Self::MORE_COMPONENTS == 0 {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Draws a closed TrueType contour of on-curve and off-curve points.
+/// Two consecutive off-curve points imply an on-curve point halfway between them.
+fn draw_quadratic_contour(builder: &mut ShapeBuilder, points: &[((f64, f64), bool)]) {
+    let midpoint = |a: (f64, f64), b: (f64, f64)| ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+    let (first, last) = match (points.first(), points.last()) {
+        (Some(&first), Some(&last)) => (first, last),
+        _ => return,
+    };
+    let (start, rest) = if first.1 {
+        (first.0, &points[1..])
+    } else if last.1 {
+        (last.0, &points[..points.len() - 1])
+    } else {
+        (midpoint(first.0, last.0), points)
+    };
+
+    builder.move_to(start.0, start.1);
+    let mut control = None;
+    for &(point, on_curve) in rest {
+        match (control, on_curve) {
+            (Some(c), true) => {
+                builder.quad_to(c, point);
+                control = None;
+            }
+            (None, true) => builder.line_to(point.0, point.1),
+            (Some(c), false) => {
+                builder.quad_to(c, midpoint(c, point));
+                control = Some(point);
+            }
+            (None, false) => control = Some(point),
+        }
+    }
+    if let Some(c) = control {
+        builder.quad_to(c, start);
+    }
+    builder.close_contour();
+}
+
+/// Builds SWF shape records from outlines in font units.
+///
+/// Points are rounded to twips as they are added and edges are emitted relative to the
+/// rounded position, so rounding errors do not accumulate along a contour.
+struct ShapeBuilder {
+    records: Vec<ShapeRecord>,
+    scale: f64,
+
+    /// The last point in font units, used to approximate curves.
+    last: (f64, f64),
+
+    /// The current pen position in twips.
+    pen: (i32, i32),
+
+    /// The start of the current contour in twips, if one is open.
+    contour_start: Option<(i32, i32)>,
+}
+
+impl ShapeBuilder {
+    fn new(scale: f64) -> Self {
+        Self {
+            records: vec![],
+            scale,
+            last: (0.0, 0.0),
+            pen: (0, 0),
+            contour_start: None,
+        }
+    }
+
+    /// Converts a point in font units to twips. The y axis points down in SWF shapes.
+    fn to_twips(&self, (x, y): (f64, f64)) -> (i32, i32) {
+        (
+            (x * self.scale).round() as i32,
+            (-y * self.scale).round() as i32,
+        )
+    }
+
+    fn move_to(&mut self, x: f64, y: f64) {
+        self.close_contour();
+        let point = self.to_twips((x, y));
+        self.records.push(ShapeRecord::StyleChange(StyleChangeData {
+            move_to: Some((Twips::new(point.0), Twips::new(point.1))),
+            fill_style_0: None,
+            fill_style_1: Some(1),
+            line_style: None,
+            new_styles: None,
+        }));
+        self.last = (x, y);
+        self.pen = point;
+        self.contour_start = Some(point);
+    }
+
+    fn line_to(&mut self, x: f64, y: f64) {
+        let point = self.to_twips((x, y));
+        self.last = (x, y);
+        self.straight_edge(point);
+    }
+
+    fn straight_edge(&mut self, point: (i32, i32)) {
+        if self.contour_start.is_none() || point == self.pen {
+            return;
+        }
+        self.records.push(ShapeRecord::StraightEdge {
+            delta_x: Twips::new(point.0 - self.pen.0),
+            delta_y: Twips::new(point.1 - self.pen.1),
+        });
+        self.pen = point;
+    }
+
+    /// Approximates a cubic curve with quadratic curves.
+    ///
+    /// The cubic is split into equal parts, and each part is replaced by the quadratic
+    /// whose control point best matches it: `(3 * (c1 + c2) - (p0 + p3)) / 4`.
+    fn curve_to(&mut self, c1: (f64, f64), c2: (f64, f64), end: (f64, f64)) {
+        if self.contour_start.is_none() {
+            return;
+        }
+        let start = self.last;
+        let cubic = |t: f64| {
+            let mt = 1.0 - t;
+            let a = mt * mt * mt;
+            let b = 3.0 * mt * mt * t;
+            let c = 3.0 * mt * t * t;
+            let d = t * t * t;
+            (
+                a * start.0 + b * c1.0 + c * c2.0 + d * end.0,
+                a * start.1 + b * c1.1 + c * c2.1 + d * end.1,
+            )
+        };
+        // The derivative, used to find the control points of each part.
+        let derivative = |t: f64| {
+            let mt = 1.0 - t;
+            let a = 3.0 * mt * mt;
+            let b = 6.0 * mt * t;
+            let c = 3.0 * t * t;
+            (
+                a * (c1.0 - start.0) + b * (c2.0 - c1.0) + c * (end.0 - c2.0),
+                a * (c1.1 - start.1) + b * (c2.1 - c1.1) + c * (end.1 - c2.1),
+            )
+        };
+
+        let step = 1.0 / CUBIC_SUBDIVISIONS as f64;
+        for i in 0..CUBIC_SUBDIVISIONS {
+            let t0 = i as f64 * step;
+            let t1 = t0 + step;
+            let p0 = cubic(t0);
+            let p3 = if i + 1 == CUBIC_SUBDIVISIONS {
+                end
+            } else {
+                cubic(t1)
+            };
+            let d0 = derivative(t0);
+            let d1 = derivative(t1);
+            let q1 = (p0.0 + d0.0 * step / 3.0, p0.1 + d0.1 * step / 3.0);
+            let q2 = (p3.0 - d1.0 * step / 3.0, p3.1 - d1.1 * step / 3.0);
+            let control = (
+                (3.0 * (q1.0 + q2.0) - (p0.0 + p3.0)) / 4.0,
+                (3.0 * (q1.1 + q2.1) - (p0.1 + p3.1)) / 4.0,
+            );
+
+            let control = self.to_twips(control);
+            let anchor = self.to_twips(p3);
+            self.curved_edge(control, anchor);
+        }
+        self.last = end;
+    }
+
+    fn quad_to(&mut self, control: (f64, f64), end: (f64, f64)) {
+        if self.contour_start.is_none() {
+            return;
+        }
+        let control = self.to_twips(control);
+        let anchor = self.to_twips(end);
+        self.curved_edge(control, anchor);
+        self.last = end;
+    }
+
+    fn curved_edge(&mut self, control: (i32, i32), anchor: (i32, i32)) {
+        if control == self.pen || control == anchor {
+            self.straight_edge(anchor);
+            return;
+        }
+        self.records.push(ShapeRecord::CurvedEdge {
+            control_delta_x: Twips::new(control.0 - self.pen.0),
+            control_delta_y: Twips::new(control.1 - self.pen.1),
+            anchor_delta_x: Twips::new(anchor.0 - control.0),
+            anchor_delta_y: Twips::new(anchor.1 - control.1),
+        });
+        self.pen = anchor;
+    }
+
+    /// Closes the current contour with a straight edge back to its start.
+    fn close_contour(&mut self) {
+        if let Some(start) = self.contour_start {
+            self.straight_edge(start);
+            self.contour_start = None;
+        }
+    }
+
+    fn finish(mut self) -> Vec<ShapeRecord> {
+        self.close_contour();
+        self.records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draw(char_string: &[u8]) -> Vec<ShapeRecord> {
+        let subrs = Index::empty();
+        let mut builder = ShapeBuilder::new(1.0);
+        let mut interpreter = CharStringInterpreter {
+            global_subrs: &subrs,
+            local_subrs: &subrs,
+            builder: &mut builder,
+            stack: vec![],
+            x: 0.0,
+            y: 0.0,
+            stem_count: 0,
+            width_parsed: false,
+            ended: false,
+        };
+        interpreter.execute(char_string, 0).unwrap();
+        builder.finish()
+    }
+
+    fn edge(x: i32, y: i32) -> ShapeRecord {
+        ShapeRecord::StraightEdge {
+            delta_x: Twips::new(x),
+            delta_y: Twips::new(y),
+        }
+    }
+
+    fn u16s(values: &[u16]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|v| v.to_be_bytes().to_vec())
+            .collect()
+    }
+
+    /// A format 4 subtable mapping `A`-`C` to glyphs 1-3 with a delta, `a` to glyph 5 and
+    /// `b` to the missing glyph through the glyph ID array.
+    fn cmap_format_4() -> Vec<u8> {
+        u16s(&[
+            4, 42, 0, 6, 4, 1, 2, // Header
+            0x43, 0x62, 0xFFFF, // End codes
+            0,      // Reserved padding
+            0x41, 0x61, 0xFFFF, // Start codes
+            0xFFC0, 0, 1, // ID deltas
+            0, 4, 0, // ID range offsets
+            5, 0, // Glyph ID array
+        ])
+    }
+
+    /// A format 12 subtable mapping `0`-`2` to glyphs 10-12, and one group that is outside
+    /// of the Basic Multilingual Plane.
+    fn cmap_format_12() -> Vec<u8> {
+        let mut subtable = u16s(&[12, 0]);
+        for value in &[40u32, 0, 2, 0x30, 0x32, 10, 0x1F600, 0x1F601, 20] {
+            subtable.extend_from_slice(&value.to_be_bytes());
+        }
+        subtable
+    }
+
+    /// A `cmap` table with the given (platform ID, encoding ID, subtable) encoding records.
+    fn cmap(subtables: &[(u16, u16, &[u8])]) -> Vec<u8> {
+        let mut cmap = u16s(&[0, subtables.len() as u16]);
+        let mut offset = 4 + subtables.len() * 8;
+        for (platform_id, encoding_id, subtable) in subtables {
+            cmap.extend(u16s(&[*platform_id, *encoding_id]));
+            cmap.extend_from_slice(&(offset as u32).to_be_bytes());
+            offset += subtable.len();
+        }
+        for (_, _, subtable) in subtables {
+            cmap.extend_from_slice(subtable);
+        }
+        cmap
+    }
+
+    #[test]
+    fn cmap_format_4_segments() {
+        let code_points = parse_cmap(&cmap(&[(3, 1, &cmap_format_4())])).unwrap();
+        let expected: BTreeMap<u16, u16> = [(0x41, 1), (0x42, 2), (0x43, 3), (0x61, 5)]
+            .iter()
+            .cloned()
+            .collect();
+        assert_eq!(code_points, expected);
+    }
+
+    #[test]
+    fn cmap_prefers_format_12() {
+        let code_points = parse_cmap(&cmap(&[
+            (3, 1, &cmap_format_4()),
+            (0, 4, &cmap_format_12()),
+            // Symbol encodings are ignored.
+            (3, 0, &cmap_format_4()),
+        ]))
+        .unwrap();
+        let expected: BTreeMap<u16, u16> = [(0x30, 10), (0x31, 11), (0x32, 12)]
+            .iter()
+            .cloned()
+            .collect();
+        assert_eq!(code_points, expected);
+    }
+
+    #[test]
+    fn cmap_without_unicode() {
+        assert!(parse_cmap(&cmap(&[(1, 0, &cmap_format_4())])).is_err());
+        assert!(parse_cmap(&cmap(&[(3, 1, &[0, 6])])).is_err());
+    }
+
+    #[test]
+    fn cff_index() {
+        // A byte of padding, then 2 objects with 1 byte offsets, then a trailing byte.
+        let cff = [0xFF, 0, 2, 1, 1, 3, 6, b'a', b'b', b'c', b'd', b'e', 0xFF];
+        let (index, end) = Index::parse(&cff, 1).unwrap();
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.get(0), Some(&b"ab"[..]));
+        assert_eq!(index.get(1), Some(&b"cde"[..]));
+        assert_eq!(index.get(2), None);
+        assert_eq!(end, 12);
+
+        let (index, end) = Index::parse(&[0, 0], 0).unwrap();
+        assert_eq!(index.len(), 0);
+        assert_eq!(end, 2);
+
+        assert!(Index::parse(&[0, 1, 5, 1, 1], 0).is_err());
+        // The data runs past the end of the table.
+        assert!(Index::parse(&[0, 1, 1, 1, 9, b'a'], 0).is_err());
+    }
+
+    #[test]
+    fn cff_dict() {
+        let dict = Dict::parse(&[
+            139, // 0
+            247, 0, // 108
+            28, 0x01, 0x00, // 256
+            17,   // CharStrings
+            251, 0, // -108
+            29, 0x00, 0x01, 0x00, 0x00, // 65536
+            30, 0x1A, 0x2F, // 1.2, which is skipped
+            12, 36, // FDArray
+        ])
+        .unwrap();
+        assert_eq!(dict.get(DICT_CHAR_STRINGS), Some(&[0.0, 108.0, 256.0][..]));
+        assert_eq!(dict.get_offset(DICT_CHAR_STRINGS), Some(256));
+        assert_eq!(dict.get(DICT_FD_ARRAY), Some(&[-108.0, 65536.0, 0.0][..]));
+        assert_eq!(dict.get(DICT_PRIVATE), None);
+
+        assert!(Dict::parse(&[255]).is_err());
+        assert!(Dict::parse(&[28, 0]).is_err());
+    }
+
+    fn cid_font(fd_select: &[u8]) -> Cff<'_> {
+        Cff {
+            char_strings: Index::empty(),
+            global_subrs: Index::empty(),
+            local_subrs: vec![Index::empty(), Index::empty()],
+            fd_select: Some(fd_select),
+        }
+    }
+
+    #[test]
+    fn cff_fd_select() {
+        let cff = cid_font(&[0, 1, 0, 1]);
+        assert_eq!(cff.fd_index(0).unwrap(), 1);
+        assert_eq!(cff.fd_index(1).unwrap(), 0);
+        assert!(cff.fd_index(3).is_err());
+
+        // Glyphs 0-4 use font DICT 0 and glyphs 5-9 use font DICT 1.
+        let cff = cid_font(&[3, 0, 2, 0, 0, 0, 0, 5, 1, 0, 10]);
+        assert_eq!(cff.fd_index(3).unwrap(), 0);
+        assert_eq!(cff.fd_index(5).unwrap(), 1);
+        assert_eq!(cff.fd_index(9).unwrap(), 1);
+        assert!(cff.fd_index(10).is_err());
+
+        assert!(cid_font(&[1, 0]).fd_index(0).is_err());
+        assert_eq!(
+            Cff {
+                fd_select: None,
+                ..cid_font(&[])
+            }
+            .fd_index(7)
+            .unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn font_file_family_name() {
+        let data = include_bytes!("../../tests/fonts/ruffle_test.ttf");
+        let name = read_family_name(&mut std::io::Cursor::new(&data[..]));
+        assert_eq!(name.as_deref(), Some("Ruffle Test"));
+
+        assert_eq!(
+            read_family_name(&mut std::io::Cursor::new(&data[..40])),
+            None
+        );
+    }
+
+    #[test]
+    fn char_string_lines() {
+        // width 500, 100 100 rmoveto, 50 hlineto, 50 vlineto, endchar
+        let records = draw(&[248, 136, 239, 239, 21, 189, 6, 189, 7, 14]);
+        assert_eq!(
+            records,
+            vec![
+                ShapeRecord::StyleChange(StyleChangeData {
+                    move_to: Some((Twips::new(100), Twips::new(-100))),
+                    fill_style_0: None,
+                    fill_style_1: Some(1),
+                    line_style: None,
+                    new_styles: None,
+                }),
+                edge(50, 0),
+                edge(0, -50),
+                edge(-50, 50),
+            ]
+        );
+    }
+
+    #[test]
+    fn char_string_hint_mask() {
+        // Two hstems, a hintmask with one mask byte, then a move and a line.
+        let records = draw(&[139, 149, 159, 169, 1, 19, 0xC0, 149, 149, 21, 149, 6, 14]);
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1], edge(10, 0));
+        assert_eq!(records[2], edge(-10, 0));
+    }
+}
@@ -137,6 +137,11 @@ impl TextFormat {
         })
     }
 
+    /// The name of the font, if set.
+    pub fn font(&self) -> Option<&str> {
+        self.font.as_deref()
+    }
+
     /// Construct a `TextFormat` AVM1 object from this text format object.
     pub fn as_avm1_object<'gc>(
         &self,
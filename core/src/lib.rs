@@ -10,11 +10,15 @@ extern crate smallvec;
 extern crate downcast_rs;
 
 mod avm1;
+mod bitmap_data;
 mod bounding_box;
 mod character;
 pub mod color_transform;
 mod context;
+pub mod context_menu;
+mod drawing;
 pub mod events;
+mod focus_tracker;
 mod font;
 mod library;
 mod loader;
@@ -23,6 +27,7 @@ mod player;
 mod prelude;
 mod property_map;
 pub mod shape_utils;
+pub mod stage;
 pub mod string_utils;
 pub mod tag_utils;
 mod transform;
@@ -1,7 +1,7 @@
 use crate::backend::audio::SoundHandle;
 use crate::character::Character;
 use crate::display_object::TDisplayObject;
-use crate::font::Font;
+use crate::font::{DeviceFonts, Font};
 use crate::prelude::*;
 use crate::tag_utils::SwfMovie;
 use gc_arena::MutationContext;
@@ -15,7 +15,10 @@ pub struct MovieLibrary<'gc> {
     characters: HashMap<CharacterId, Character<'gc>>,
     export_characters: HashMap<String, Character<'gc>>,
     jpeg_tables: Option<Vec<u8>>,
-    device_font: Option<Font<'gc>>,
+
+    /// Shared libraries that this movie has imported characters from.
+    /// The imported characters still refer to the libraries of these movies.
+    shared_libraries: Vec<Arc<SwfMovie>>,
 }
 
 impl<'gc> MovieLibrary<'gc> {
@@ -24,7 +27,7 @@ impl<'gc> MovieLibrary<'gc> {
             characters: HashMap::new(),
             export_characters: HashMap::new(),
             jpeg_tables: None,
-            device_font: None,
+            shared_libraries: Vec::new(),
         }
     }
 
@@ -62,6 +65,11 @@ impl<'gc> MovieLibrary<'gc> {
         }
     }
 
+    /// Keeps the library of a shared library movie alive for as long as this library.
+    pub fn add_shared_library(&mut self, movie: Arc<SwfMovie>) {
+        self.shared_libraries.push(movie);
+    }
+
     pub fn contains_character(&self, id: CharacterId) -> bool {
         self.characters.contains_key(&id)
     }
@@ -163,16 +171,6 @@ impl<'gc> MovieLibrary<'gc> {
     pub fn jpeg_tables(&self) -> Option<&[u8]> {
         self.jpeg_tables.as_ref().map(|data| &data[..])
     }
-
-    /// Returns the device font for use when a font is unavailable.
-    pub fn device_font(&self) -> Option<Font<'gc>> {
-        self.device_font
-    }
-
-    /// Sets the device font.
-    pub fn set_device_font(&mut self, font: Option<Font<'gc>>) {
-        self.device_font = font;
-    }
 }
 
 unsafe impl<'gc> gc_arena::Collect for MovieLibrary<'gc> {
@@ -181,7 +179,6 @@ unsafe impl<'gc> gc_arena::Collect for MovieLibrary<'gc> {
         for character in self.characters.values() {
             character.trace(cc);
         }
-        self.device_font.trace(cc);
     }
 }
 
@@ -195,6 +192,9 @@ impl Default for MovieLibrary<'_> {
 pub struct Library<'gc> {
     /// All the movie libraries.
     movie_libraries: PtrWeakKeyHashMap<Weak<SwfMovie>, MovieLibrary<'gc>>,
+
+    /// The fonts used for text that doesn't embed its font, shared by all movies.
+    device_fonts: DeviceFonts<'gc>,
 }
 
 unsafe impl<'gc> gc_arena::Collect for Library<'gc> {
@@ -203,10 +203,18 @@ unsafe impl<'gc> gc_arena::Collect for Library<'gc> {
         for (_, val) in self.movie_libraries.iter() {
             val.trace(cc);
         }
+        self.device_fonts.trace(cc);
     }
 }
 
 impl<'gc> Library<'gc> {
+    pub fn new(device_fonts: DeviceFonts<'gc>) -> Self {
+        Self {
+            movie_libraries: PtrWeakKeyHashMap::new(),
+            device_fonts,
+        }
+    }
+
     pub fn library_for_movie(&self, movie: Arc<SwfMovie>) -> Option<&MovieLibrary<'gc>> {
         self.movie_libraries.get(&movie)
     }
@@ -219,12 +227,19 @@ impl<'gc> Library<'gc> {
 
         self.movie_libraries.get_mut(&movie).unwrap()
     }
+
+    /// Returns the device fonts, used when a font is unavailable.
+    pub fn device_fonts(&self) -> &DeviceFonts<'gc> {
+        &self.device_fonts
+    }
+
+    pub fn device_fonts_mut(&mut self) -> &mut DeviceFonts<'gc> {
+        &mut self.device_fonts
+    }
 }
 
 impl<'gc> Default for Library<'gc> {
     fn default() -> Self {
-        Self {
-            movie_libraries: PtrWeakKeyHashMap::new(),
-        }
+        Self::new(DeviceFonts::new(None))
     }
 }
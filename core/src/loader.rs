@@ -2,8 +2,9 @@
 
 use crate::avm1::{Object, TObject, Value};
 use crate::backend::navigator::OwnedFuture;
+use crate::character::Character;
 use crate::context::{ActionQueue, ActionType};
-use crate::display_object::{DisplayObject, MorphShape, TDisplayObject};
+use crate::display_object::{DisplayObject, MorphShape, MovieClip, TDisplayObject};
 use crate::player::{Player, NEWEST_PLAYER_VERSION};
 use crate::tag_utils::SwfMovie;
 use crate::xml::XMLNode;
@@ -59,6 +60,11 @@ impl<'gc> LoadManager<'gc> {
         self.0.get_mut(handle)
     }
 
+    /// Remove a finished loader, invalidating its handle.
+    pub fn remove_loader(&mut self, handle: Handle) -> Option<Loader<'gc>> {
+        self.0.remove(handle)
+    }
+
     /// Kick off a movie clip load.
     ///
     /// Returns the loader's async process, which you will need to spawn.
@@ -105,6 +111,33 @@ impl<'gc> LoadManager<'gc> {
         }
     }
 
+    /// Kick off the load of a shared library for an `ImportAssets` tag.
+    ///
+    /// Returns the loader's async process, which you will need to spawn.
+    pub fn load_imports_into_movie(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        target_clip: DisplayObject<'gc>,
+        importing_movie: Arc<SwfMovie>,
+        url: String,
+        imports: Vec<swf::ExportedAsset>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+    ) -> OwnedFuture<(), Error> {
+        let loader = Loader::ImportAssets {
+            self_handle: None,
+            target_clip,
+            importing_movie,
+            url,
+            imports,
+        };
+        let handle = self.add_loader(loader);
+
+        let loader = self.get_loader_mut(handle).unwrap();
+        loader.introduce_loader_handle(handle);
+
+        loader.import_assets_loader(player, fetch)
+    }
+
     /// Kick off a form data load into an AVM1 object.
     ///
     /// Returns the loader's async process, which you will need to spawn.
@@ -181,6 +214,26 @@ pub enum Loader<'gc> {
         load_complete: bool,
     },
 
+    /// Loader that is loading a shared library to resolve an `ImportAssets` tag.
+    ImportAssets {
+        /// The handle to refer to this loader instance.
+        self_handle: Option<Handle>,
+
+        /// The clip playing the importing movie. It will not advance frames
+        /// until this loader finishes.
+        target_clip: DisplayObject<'gc>,
+
+        /// The movie whose library will receive the imported characters.
+        importing_movie: Arc<SwfMovie>,
+
+        /// The URL of the shared library.
+        url: String,
+
+        /// The character IDs in the importing movie, and the export names in
+        /// the shared library that they refer to.
+        imports: Vec<swf::ExportedAsset>,
+    },
+
     /// Loader that is loading form data into an AVM1 object scope.
     Form {
         /// The handle to refer to this loader instance.
@@ -219,6 +272,7 @@ unsafe impl<'gc> Collect for Loader<'gc> {
                 target_clip.trace(cc);
                 target_broadcaster.trace(cc);
             }
+            Loader::ImportAssets { target_clip, .. } => target_clip.trace(cc),
             Loader::Form { target_object, .. } => target_object.trace(cc),
             Loader::XML { target_node, .. } => target_node.trace(cc),
         }
@@ -233,6 +287,7 @@ impl<'gc> Loader<'gc> {
     pub fn introduce_loader_handle(&mut self, handle: Handle) {
         match self {
             Loader::Movie { self_handle, .. } => *self_handle = Some(handle),
+            Loader::ImportAssets { self_handle, .. } => *self_handle = Some(handle),
             Loader::Form { self_handle, .. } => *self_handle = Some(handle),
             Loader::XML { self_handle, .. } => *self_handle = Some(handle),
         }
@@ -350,6 +405,8 @@ impl<'gc> Loader<'gc> {
                                 );
                         }
 
+                        mc.load_imports(uc);
+
                         if let Some(broadcaster) = broadcaster {
                             avm.insert_stack_frame_for_method(
                                 clip,
@@ -417,6 +474,99 @@ impl<'gc> Loader<'gc> {
         })
     }
 
+    /// Construct a future for the given shared library loader.
+    ///
+    /// The shared library is preloaded into its own `MovieLibrary`, and each
+    /// imported export is then registered in the importing movie's library
+    /// under its local character ID. If the library can't be loaded, the
+    /// importing movie resumes without the imported characters.
+    pub fn import_assets_loader(
+        &mut self,
+        player: Weak<Mutex<Player>>,
+        fetch: OwnedFuture<Vec<u8>, Error>,
+    ) -> OwnedFuture<(), Error> {
+        let handle = match self {
+            Loader::ImportAssets { self_handle, .. } => {
+                self_handle.expect("Loader not self-introduced")
+            }
+            _ => {
+                return Box::pin(async { Err("Non-import loader spawned as import loader".into()) })
+            }
+        };
+
+        let player = player
+            .upgrade()
+            .expect("Could not upgrade weak reference to player");
+
+        Box::pin(async move {
+            let library_movie = (fetch.await).and_then(|data| SwfMovie::from_data(&data));
+
+            player
+                .lock()
+                .expect("Could not lock player!!")
+                .update(|avm, uc| -> Result<(), Error> {
+                    let (clip, importing_movie, url, imports) =
+                        match uc.load_manager.remove_loader(handle) {
+                            Some(Loader::ImportAssets {
+                                target_clip,
+                                importing_movie,
+                                url,
+                                imports,
+                                ..
+                            }) => (target_clip, importing_movie, url, imports),
+                            None => return Err("Load cancelled".into()),
+                            _ => unreachable!(),
+                        };
+
+                    match library_movie {
+                        Ok(library_movie) => {
+                            let library_movie = Arc::new(library_movie);
+                            let library_clip =
+                                MovieClip::from_movie(uc.gc_context, library_movie.clone());
+
+                            let mut morph_shapes = fnv::FnvHashMap::default();
+                            library_clip.preload(avm, uc, &mut morph_shapes);
+
+                            // Finalize morph shapes.
+                            for (id, static_data) in morph_shapes {
+                                let morph_shape = MorphShape::new(uc.gc_context, static_data);
+                                uc.library
+                                    .library_for_movie_mut(library_movie.clone())
+                                    .register_character(id, Character::MorphShape(morph_shape));
+                            }
+
+                            let mut imported = Vec::with_capacity(imports.len());
+                            let shared_library =
+                                uc.library.library_for_movie_mut(library_movie.clone());
+                            for asset in imports {
+                                if let Some(character) =
+                                    shared_library.get_character_by_export_name(&asset.name)
+                                {
+                                    imported.push((asset, character.clone()));
+                                } else {
+                                    log::warn!("{} doesn't export {}", url, asset.name);
+                                }
+                            }
+
+                            let library = uc.library.library_for_movie_mut(importing_movie);
+                            library.add_shared_library(library_movie);
+                            for (asset, character) in imported {
+                                library.register_character(asset.id, character);
+                                library.register_export(asset.id, &asset.name);
+                            }
+                        }
+                        Err(e) => log::warn!("Couldn't load shared library {}: {}", url, e),
+                    }
+
+                    if let Some(clip) = clip.as_movie_clip() {
+                        clip.finish_import(uc.gc_context);
+                    }
+
+                    Ok(())
+                })
+        })
+    }
+
     pub fn form_loader(
         &mut self,
         player: Weak<Mutex<Player>>,
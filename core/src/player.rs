@@ -3,18 +3,24 @@ use crate::avm1::listeners::SystemListener;
 use crate::avm1::{Activation, Avm1, TObject, Value};
 use crate::backend::input::{InputBackend, MouseCursor};
 use crate::backend::{
-    audio::AudioBackend, navigator::NavigatorBackend, render::Letterbox, render::RenderBackend,
+    audio::AudioBackend, font::FontBackend, navigator::NavigatorBackend, render::Bitmap,
+    render::Letterbox, render::RenderBackend,
 };
 use crate::context::{ActionQueue, ActionType, RenderContext, UpdateContext};
-use crate::display_object::{MorphShape, MovieClip};
+use crate::context_menu::{self, ContextMenuCallback, ContextMenuItem, ContextMenuOverlay};
+use crate::display_object::{EditText, MorphShape, MovieClip};
 use crate::events::{
     ButtonEvent, ButtonEventResult, ButtonKeyCode, ClipEvent, KeyCode, PlayerEvent,
 };
+use crate::focus_tracker::FocusTracker;
+use crate::font::DeviceFonts;
 use crate::library::Library;
 use crate::loader::LoadManager;
 use crate::prelude::*;
+use crate::stage::{StageAlign, StageQuality, StageScaleMode};
 use crate::tag_utils::SwfMovie;
 use crate::transform::TransformStack;
+use enumset::EnumSet;
 use gc_arena::{make_arena, ArenaParameters, Collect, GcCell};
 use log::info;
 use rand::{rngs::SmallRng, SeedableRng};
@@ -47,9 +53,15 @@ struct GcRootData<'gc> {
 
     mouse_hovered_object: Option<DisplayObject<'gc>>, // TODO: Remove GcCell wrapped inside GcCell.
 
+    /// The display object that has keyboard focus.
+    focus_tracker: FocusTracker<'gc>,
+
     /// The object being dragged via a `startDrag` action.
     drag_object: Option<DragObject<'gc>>,
 
+    /// The actions of the items of the last prepared context menu.
+    context_menu_callbacks: Vec<ContextMenuCallback<'gc>>,
+
     avm: Avm1<'gc>,
     action_queue: ActionQueue<'gc>,
 
@@ -69,6 +81,7 @@ impl<'gc> GcRootData<'gc> {
         &mut ActionQueue<'gc>,
         &mut Avm1<'gc>,
         &mut Option<DragObject<'gc>>,
+        &mut Vec<ContextMenuCallback<'gc>>,
         &mut LoadManager<'gc>,
     ) {
         (
@@ -77,6 +90,7 @@ impl<'gc> GcRootData<'gc> {
             &mut self.action_queue,
             &mut self.avm,
             &mut self.drag_object,
+            &mut self.context_menu_callbacks,
             &mut self.load_manager,
         )
     }
@@ -89,6 +103,7 @@ type Audio = Box<dyn AudioBackend>;
 type Navigator = Box<dyn NavigatorBackend>;
 type Renderer = Box<dyn RenderBackend>;
 type Input = Box<dyn InputBackend>;
+type Fonts = Box<dyn FontBackend>;
 
 pub struct Player {
     /// The version of the player we're emulating.
@@ -112,6 +127,7 @@ pub struct Player {
     renderer: Renderer,
     pub navigator: Navigator,
     input: Input,
+    fonts: Fonts,
     transform_stack: TransformStack,
     view_matrix: Matrix,
     inverse_view_matrix: Matrix,
@@ -131,6 +147,21 @@ pub struct Player {
     movie_height: u32,
     letterbox: Letterbox,
 
+    /// How the movie is scaled to fit the viewport.
+    stage_scale_mode: StageScaleMode,
+
+    /// How the movie is aligned within the viewport.
+    stage_align: EnumSet<StageAlign>,
+
+    /// The rendering quality of the movie.
+    quality: StageQuality,
+
+    /// Whether the built-in items of the context menu are shown.
+    show_menu: bool,
+
+    /// The context menu drawn over the stage, if open.
+    context_menu_overlay: Option<ContextMenuOverlay>,
+
     mouse_pos: (Twips, Twips),
     is_mouse_down: bool,
 
@@ -147,10 +178,11 @@ pub struct Player {
 
 impl Player {
     pub fn new(
-        mut renderer: Renderer,
+        renderer: Renderer,
         audio: Audio,
         navigator: Navigator,
         input: Input,
+        fonts: Fonts,
         movie: SwfMovie,
     ) -> Result<Arc<Mutex<Self>>, Error> {
         let movie = Arc::new(movie);
@@ -185,21 +217,16 @@ impl Player {
             rng: SmallRng::from_seed([0u8; 16]), // TODO(Herschel): Get a proper seed on all platforms.
 
             gc_arena: GcArena::new(ArenaParameters::default(), |gc_context| {
-                // Load and parse the device font.
-                let device_font =
-                    match Self::load_device_font(gc_context, DEVICE_FONT_TAG, &mut renderer) {
-                        Ok(font) => Some(font),
-                        Err(e) => {
-                            log::error!("Unable to load device font: {}", e);
-                            None
-                        }
-                    };
-
-                let mut library = Library::default();
+                // Load and parse the built-in device font.
+                let builtin_font = match Self::load_device_font(DEVICE_FONT_TAG) {
+                    Ok(font) => Some(font),
+                    Err(e) => {
+                        log::error!("Unable to load device font: {}", e);
+                        None
+                    }
+                };
 
-                library
-                    .library_for_movie_mut(movie.clone())
-                    .set_device_font(device_font);
+                let library = Library::new(DeviceFonts::new(builtin_font));
 
                 GcRoot(GcCell::allocate(
                     gc_context,
@@ -207,7 +234,9 @@ impl Player {
                         library,
                         levels: BTreeMap::new(),
                         mouse_hovered_object: None,
+                        focus_tracker: FocusTracker::new(gc_context),
                         drag_object: None,
+                        context_menu_callbacks: Vec::new(),
                         avm: Avm1::new(gc_context, NEWEST_PLAYER_VERSION),
                         action_queue: ActionQueue::new(),
                         load_manager: LoadManager::new(),
@@ -224,6 +253,11 @@ impl Player {
             viewport_width: movie_width,
             viewport_height: movie_height,
             letterbox: Letterbox::None,
+            stage_scale_mode: StageScaleMode::default(),
+            stage_align: EnumSet::empty(),
+            quality: StageQuality::default(),
+            show_menu: true,
+            context_menu_overlay: None,
 
             mouse_pos: (Twips::new(0), Twips::new(0)),
             is_mouse_down: false,
@@ -233,6 +267,7 @@ impl Player {
             audio,
             navigator,
             input,
+            fonts,
             self_reference: None,
         };
 
@@ -250,6 +285,13 @@ impl Player {
         let player_box = Arc::new(Mutex::new(player));
         let mut player_lock = player_box.lock().unwrap();
         player_lock.self_reference = Some(Arc::downgrade(&player_box));
+
+        // Shared libraries are fetched asynchronously, so this has to wait for the
+        // player to be referenceable.
+        player_lock.mutate_with_update_context(|_avm, context| {
+            let root = *context.levels.get(&0).expect("root level");
+            root.as_movie_clip().unwrap().load_imports(context);
+        });
         std::mem::drop(player_lock);
 
         Ok(player_box)
@@ -328,9 +370,151 @@ impl Player {
     }
 
     pub fn set_viewport_dimensions(&mut self, width: u32, height: u32) {
+        let size_changed = (self.viewport_width, self.viewport_height) != (width, height);
         self.viewport_width = width;
         self.viewport_height = height;
         self.build_matrices();
+
+        // The stage only changes size along with the viewport under noScale.
+        if size_changed && self.stage_scale_mode == StageScaleMode::NoScale {
+            self.update(|_avm, context| {
+                let root = match context.levels.get(&0) {
+                    Some(root) => *root,
+                    None => return,
+                };
+                context.action_queue.queue_actions(
+                    root,
+                    ActionType::NotifyListeners {
+                        listener: SystemListener::Stage,
+                        method: "onResize",
+                        args: vec![],
+                    },
+                    false,
+                );
+            });
+        }
+        self.needs_render = true;
+    }
+
+    pub fn stage_scale_mode(&self) -> StageScaleMode {
+        self.stage_scale_mode
+    }
+
+    pub fn set_stage_scale_mode(&mut self, scale_mode: StageScaleMode) {
+        self.stage_scale_mode = scale_mode;
+        self.build_matrices();
+        self.needs_render = true;
+    }
+
+    pub fn stage_align(&self) -> EnumSet<StageAlign> {
+        self.stage_align
+    }
+
+    pub fn set_stage_align(&mut self, align: EnumSet<StageAlign>) {
+        self.stage_align = align;
+        self.build_matrices();
+        self.needs_render = true;
+    }
+
+    pub fn quality(&self) -> StageQuality {
+        self.quality
+    }
+
+    pub fn set_quality(&mut self, quality: StageQuality) {
+        self.quality = quality;
+        self.renderer.set_quality(quality);
+        self.needs_render = true;
+    }
+
+    /// Builds the context menu for a right click at the current mouse position.
+    ///
+    /// The `menu` of the object under the mouse cursor, or of its nearest ancestor with one,
+    /// has its `onSelect` handler called and then supplies the custom items. The frontend
+    /// should display the returned items and call `run_context_menu_callback` with the index
+    /// of the chosen one.
+    pub fn prepare_context_menu(&mut self) -> Vec<ContextMenuItem> {
+        let mouse_pos = self.mouse_pos;
+        self.mutate_with_update_context(|avm, context| {
+            let root = match context.levels.get(&0) {
+                Some(root) => *root,
+                None => return vec![],
+            };
+            let target = context
+                .mouse_hovered_object
+                .or_else(|| Self::text_field_at(context, mouse_pos).map(DisplayObject::from))
+                .unwrap_or(root);
+
+            let menu = context_menu::find_menu(target, avm, context);
+            if let Some(menu) = menu {
+                context.action_queue.queue_actions(
+                    target,
+                    ActionType::Method {
+                        object: menu,
+                        name: "onSelect",
+                        args: vec![target.object(), menu.into()],
+                    },
+                    false,
+                );
+                Self::run_actions(avm, context);
+            }
+
+            context_menu::build_items(avm, context, target, menu)
+        })
+    }
+
+    /// Runs the action of the item at `index` of the last prepared context menu.
+    pub fn run_context_menu_callback(&mut self, index: usize) {
+        self.update(|avm, context| context_menu::run_callback(avm, context, index));
+        self.needs_render = true;
+    }
+
+    /// Closes the context menu overlay and forgets the actions of the last prepared menu.
+    pub fn clear_context_menu(&mut self) {
+        self.gc_arena.mutate(|gc_context, gc_root| {
+            gc_root.0.write(gc_context).context_menu_callbacks.clear();
+        });
+        if self.context_menu_overlay.take().is_some() {
+            self.needs_render = true;
+        }
+    }
+
+    /// Opens a context menu drawn over the stage, with its top left corner at the given
+    /// viewport position. For frontends that can't display native menus.
+    pub fn show_context_menu_overlay(&mut self, items: Vec<ContextMenuItem>, x: f64, y: f64) {
+        let viewport_size = (self.viewport_width as f32, self.viewport_height as f32);
+        let (renderer, fonts) = (&mut self.renderer, &mut self.fonts);
+        let overlay = self.gc_arena.mutate(|gc_context, gc_root| {
+            let mut root_data = gc_root.0.write(gc_context);
+            let font =
+                root_data
+                    .library
+                    .device_fonts_mut()
+                    .load(gc_context, fonts.deref_mut(), "_sans");
+            ContextMenuOverlay::new(
+                items,
+                (x as f32, y as f32),
+                viewport_size,
+                font,
+                renderer.deref_mut(),
+            )
+        });
+        self.context_menu_overlay = Some(overlay);
+        self.needs_render = true;
+    }
+
+    /// The open context menu overlay, if any.
+    pub fn context_menu_overlay(&self) -> Option<&ContextMenuOverlay> {
+        self.context_menu_overlay.as_ref()
+    }
+
+    /// Highlights the item of the context menu overlay at the given viewport position.
+    pub fn hover_context_menu_overlay(&mut self, x: f64, y: f64) {
+        if let Some(overlay) = &mut self.context_menu_overlay {
+            let hovered = overlay.item_at(x as f32, y as f32);
+            if overlay.set_hovered(hovered) {
+                self.needs_render = true;
+            }
+        }
     }
 
     pub fn handle_event(&mut self, event: PlayerEvent) {
@@ -379,6 +563,67 @@ impl Player {
             }
         }
 
+        // Keyboard focus navigation.
+        match event {
+            PlayerEvent::KeyDown {
+                key_code: KeyCode::Tab,
+            } => {
+                let reverse = self.input.is_key_down(KeyCode::Shift);
+                self.mutate_with_update_context(|avm, context| {
+                    if context.focus_tracker.cycle(avm, context, reverse) {
+                        needs_render = true;
+                    }
+                    Self::run_actions(avm, context);
+                });
+            }
+            PlayerEvent::KeyDown {
+                key_code: KeyCode::Return,
+            }
+            | PlayerEvent::KeyDown {
+                key_code: KeyCode::Space,
+            } => {
+                // Enter and Space click the focused button.
+                self.mutate_with_update_context(|avm, context| {
+                    if let Some(focus) = context.focus_tracker.get() {
+                        if let Some(mut button) = focus.as_button() {
+                            let is_hovered = context.mouse_hovered_object.map(|o| o.as_ptr())
+                                == Some(focus.as_ptr());
+                            button.handle_button_event(avm, context, ButtonEvent::Press);
+                            button.handle_button_event(avm, context, ButtonEvent::Release);
+                            if !is_hovered {
+                                button.show_up_state(avm, context);
+                            }
+                            needs_render = true;
+                        } else if let Some(clip) = focus.as_movie_clip() {
+                            clip.run_clip_event(context, ClipEvent::Press);
+                            clip.run_clip_event(context, ClipEvent::Release);
+                        }
+                    }
+                    Self::run_actions(avm, context);
+                });
+            }
+            PlayerEvent::MouseDown { .. } => {
+                // Clicking an editable text field focuses it, and clicking elsewhere
+                // removes the focus from a text field.
+                let mouse_pos = self.mouse_pos;
+                self.mutate_with_update_context(|_avm, context| {
+                    let text_field = Self::text_field_at(context, mouse_pos)
+                        .filter(|text_field| text_field.is_editable());
+
+                    let focus_tracker = context.focus_tracker;
+                    let has_text_focus = focus_tracker
+                        .get()
+                        .map_or(false, |focus| focus.as_edit_text().is_some());
+                    if text_field.is_some() || has_text_focus {
+                        focus_tracker.set(text_field.map(DisplayObject::from), context);
+                    }
+                    focus_tracker.clear_highlight(context.gc_context);
+                });
+                needs_render = true;
+            }
+            _ => (),
+        }
+
         // Propagate button events.
         let button_event = match event {
             // ASCII characters convert directly to keyPress button events.
@@ -416,16 +661,31 @@ impl Player {
         }
 
         // Propagte clip events.
-        let (clip_event, mouse_event_name) = match event {
-            PlayerEvent::KeyDown { .. } => (Some(ClipEvent::KeyDown), Some("onKeyDown")),
-            PlayerEvent::KeyUp { .. } => (Some(ClipEvent::KeyUp), Some("onKeyUp")),
-            PlayerEvent::MouseMove { .. } => (Some(ClipEvent::MouseMove), Some("onMouseMove")),
-            PlayerEvent::MouseUp { .. } => (Some(ClipEvent::MouseUp), Some("onMouseUp")),
-            PlayerEvent::MouseDown { .. } => (Some(ClipEvent::MouseDown), Some("onMouseDown")),
+        let (clip_event, listener) = match event {
+            PlayerEvent::KeyDown { .. } => (
+                Some(ClipEvent::KeyDown),
+                Some((SystemListener::Key, "onKeyDown")),
+            ),
+            PlayerEvent::KeyUp { .. } => (
+                Some(ClipEvent::KeyUp),
+                Some((SystemListener::Key, "onKeyUp")),
+            ),
+            PlayerEvent::MouseMove { .. } => (
+                Some(ClipEvent::MouseMove),
+                Some((SystemListener::Mouse, "onMouseMove")),
+            ),
+            PlayerEvent::MouseUp { .. } => (
+                Some(ClipEvent::MouseUp),
+                Some((SystemListener::Mouse, "onMouseUp")),
+            ),
+            PlayerEvent::MouseDown { .. } => (
+                Some(ClipEvent::MouseDown),
+                Some((SystemListener::Mouse, "onMouseDown")),
+            ),
             _ => (None, None),
         };
 
-        if clip_event.is_some() || mouse_event_name.is_some() {
+        if clip_event.is_some() || listener.is_some() {
             self.mutate_with_update_context(|_avm, context| {
                 let levels: Vec<DisplayObject<'_>> = context.levels.values().copied().collect();
 
@@ -435,12 +695,12 @@ impl Player {
                     }
                 }
 
-                if let Some(mouse_event_name) = mouse_event_name {
+                if let Some((listener, method)) = listener {
                     context.action_queue.queue_actions(
                         *context.levels.get(&0).expect("root level"),
                         ActionType::NotifyListeners {
-                            listener: SystemListener::Mouse,
-                            method: mouse_event_name,
+                            listener,
+                            method,
                             args: vec![],
                         },
                         false,
@@ -449,23 +709,67 @@ impl Player {
             });
         }
 
+        if let PlayerEvent::MouseWheel { delta } = event {
+            let mouse_pos = self.mouse_pos;
+            self.mutate_with_update_context(|_avm, context| {
+                let text_field = Self::text_field_at(context, mouse_pos);
+
+                // Mouse wheel scrolls the text field under the cursor.
+                if let Some(text_field) = text_field {
+                    let scroll = text_field.scroll() as f64 - f64::from(delta);
+                    text_field.set_scroll(scroll, context);
+                    needs_render = true;
+                }
+
+                let target = text_field
+                    .map(DisplayObject::from)
+                    .or(context.mouse_hovered_object)
+                    .map(|target| target.object())
+                    .unwrap_or(Value::Undefined);
+                context.action_queue.queue_actions(
+                    *context.levels.get(&0).expect("root level"),
+                    ActionType::NotifyListeners {
+                        listener: SystemListener::Mouse,
+                        method: "onMouseWheel",
+                        args: vec![delta.into(), target],
+                    },
+                    false,
+                );
+            });
+        }
+
         let mut is_mouse_down = self.is_mouse_down;
+        match event {
+            PlayerEvent::MouseDown { .. } => is_mouse_down = true,
+            PlayerEvent::MouseUp { .. } => is_mouse_down = false,
+            _ => (),
+        }
+        // A release without a press, such as after the mouse was pressed outside of the
+        // player, doesn't release anything.
+        let was_mouse_down = self.is_mouse_down;
         self.mutate_with_update_context(|avm, context| {
             if let Some(node) = context.mouse_hovered_object {
-                if let Some(mut button) = node.clone().as_button() {
+                if let Some(mut button) = node.as_button() {
                     match event {
+                        PlayerEvent::MouseUp { .. } if !was_mouse_down => (),
+
                         PlayerEvent::MouseDown { .. } => {
-                            is_mouse_down = true;
                             needs_render = true;
                             button.handle_button_event(avm, context, ButtonEvent::Press);
                         }
 
-                        PlayerEvent::MouseUp { .. } => {
-                            is_mouse_down = false;
+                        PlayerEvent::MouseUp { .. } if button.is_down() => {
                             needs_render = true;
                             button.handle_button_event(avm, context, ButtonEvent::Release);
                         }
 
+                        // The mouse was dragged out of the pressed button before release.
+                        PlayerEvent::MouseUp { .. } => {
+                            needs_render = true;
+                            button.handle_button_event(avm, context, ButtonEvent::ReleaseOutside);
+                            context.mouse_hovered_object = None;
+                        }
+
                         _ => (),
                     }
                 }
@@ -474,9 +778,28 @@ impl Player {
             Self::run_actions(avm, context);
         });
         self.is_mouse_down = is_mouse_down;
+
+        // Buttons under the mouse are rolled over once the mouse is released.
+        if let PlayerEvent::MouseUp { .. } = event {
+            if self.update_roll_over() {
+                needs_render = true;
+            }
+        }
         self.needs_render = needs_render;
     }
 
+    /// Returns the topmost text field at the given stage position.
+    fn text_field_at<'gc>(
+        context: &UpdateContext<'_, 'gc, '_>,
+        point: (Twips, Twips),
+    ) -> Option<EditText<'gc>> {
+        context
+            .levels
+            .values()
+            .rev()
+            .find_map(|level| level.text_field_pick(*level, point))
+    }
+
     /// Update dragged object, if any.
     fn update_drag(&mut self) {
         let mouse_pos = self.mouse_pos;
@@ -508,14 +831,33 @@ impl Player {
     /// Checks to see if a recent update has caused the current mouse hover
     /// node to change.
     fn update_roll_over(&mut self) -> bool {
-        // TODO: While the mouse is down, maintain the hovered node.
-        if self.is_mouse_down {
-            return false;
-        }
         let mouse_pos = self.mouse_pos;
+        let is_mouse_down = self.is_mouse_down;
 
-        let mut new_cursor = self.mouse_cursor;
         let hover_changed = self.mutate_with_update_context(|avm, context| {
+            let cur_hovered = context.mouse_hovered_object;
+
+            // A pressed button captures the mouse until it is released, and is notified
+            // when the mouse is dragged out of it and back over it.
+            if is_mouse_down {
+                if let Some(mut button) = cur_hovered.and_then(|node| node.as_button()) {
+                    if !button.is_track_as_menu() {
+                        let is_over = button.hit_test(mouse_pos);
+                        if is_over == button.is_down() {
+                            return false;
+                        }
+                        let event = if is_over {
+                            ButtonEvent::DragOver
+                        } else {
+                            ButtonEvent::DragOut
+                        };
+                        button.handle_button_event(avm, context, event);
+                        Self::run_actions(avm, context);
+                        return true;
+                    }
+                }
+            }
+
             // Check hovered object.
             let mut new_hovered = None;
             for (_depth, level) in context.levels.iter().rev() {
@@ -526,22 +868,36 @@ impl Player {
                 }
             }
 
-            let cur_hovered = context.mouse_hovered_object;
+            // While the mouse is down, only buttons that track as menu items can be entered.
+            if is_mouse_down {
+                new_hovered = new_hovered.filter(|node| {
+                    node.as_button()
+                        .map_or(false, |button| button.is_track_as_menu())
+                });
+            }
 
             if cur_hovered.map(|d| d.as_ptr()) != new_hovered.map(|d| d.as_ptr()) {
                 // RollOut of previous node.
                 if let Some(node) = cur_hovered {
                     if let Some(mut button) = node.as_button() {
-                        button.handle_button_event(avm, context, ButtonEvent::RollOut);
+                        let event = if is_mouse_down {
+                            ButtonEvent::DragOut
+                        } else {
+                            ButtonEvent::RollOut
+                        };
+                        button.handle_button_event(avm, context, event);
                     }
                 }
 
                 // RollOver on new node.
-                new_cursor = MouseCursor::Arrow;
                 if let Some(node) = new_hovered {
                     if let Some(mut button) = node.as_button() {
-                        button.handle_button_event(avm, context, ButtonEvent::RollOver);
-                        new_cursor = MouseCursor::Hand;
+                        let event = if is_mouse_down {
+                            ButtonEvent::DragOver
+                        } else {
+                            ButtonEvent::RollOver
+                        };
+                        button.handle_button_event(avm, context, event);
                     }
                 }
 
@@ -554,6 +910,18 @@ impl Player {
             }
         });
 
+        // The cursor is checked even if the hovered object is the same, as scripts can change
+        // `enabled` or `useHandCursor` of the button under the mouse.
+        let new_cursor = self.mutate_with_update_context(|_avm, context| {
+            match context
+                .mouse_hovered_object
+                .and_then(|node| node.as_button())
+            {
+                Some(button) if button.enabled() && button.use_hand_cursor() => MouseCursor::Hand,
+                _ => MouseCursor::Arrow,
+            }
+        });
+
         // Update mouse cursor if it has changed.
         if new_cursor != self.mouse_cursor {
             self.mouse_cursor = new_cursor;
@@ -602,13 +970,15 @@ impl Player {
     }
 
     pub fn render(&mut self) {
+        // The visible area of the stage, which may extend past the movie bounds.
         let view_bounds = BoundingBox {
             x_min: Twips::new(0),
             y_min: Twips::new(0),
-            x_max: Twips::from_pixels(self.movie_width.into()),
-            y_max: Twips::from_pixels(self.movie_height.into()),
+            x_max: Twips::from_pixels(self.viewport_width.into()),
+            y_max: Twips::from_pixels(self.viewport_height.into()),
             valid: true,
-        };
+        }
+        .transform(&self.inverse_view_matrix);
 
         self.renderer.begin_frame(self.background_color.clone());
 
@@ -634,11 +1004,91 @@ impl Player {
         });
         transform_stack.pop();
 
+        let (renderer, view_matrix) = (&mut self.renderer, &self.view_matrix);
+        self.gc_arena.mutate(|_gc_context, gc_root| {
+            let root_data = gc_root.0.read();
+            root_data
+                .focus_tracker
+                .render_highlight(renderer.deref_mut(), view_matrix);
+        });
+
+        if let Some(overlay) = &self.context_menu_overlay {
+            let (renderer, fonts) = (&mut self.renderer, &mut self.fonts);
+            self.gc_arena.mutate(|gc_context, gc_root| {
+                let mut root_data = gc_root.0.write(gc_context);
+                let font = root_data.library.device_fonts_mut().load(
+                    gc_context,
+                    fonts.deref_mut(),
+                    "_sans",
+                );
+                overlay.render(renderer.deref_mut(), font);
+            });
+        }
+
         self.renderer.draw_letterbox(self.letterbox);
         self.renderer.end_frame();
         self.needs_render = false;
     }
 
+    /// Renders the current state of the stage into an offscreen bitmap of the given size,
+    /// scaling the movie to fit. Used by hosts to capture thumbnails.
+    ///
+    /// Returns `None` if the render backend does not support rendering to bitmaps.
+    pub fn render_to_bitmap(&mut self, width: u32, height: u32) -> Option<Bitmap> {
+        if width == 0 || height == 0 || self.movie_width == 0 || self.movie_height == 0 {
+            return None;
+        }
+
+        let scale = f32::min(
+            width as f32 / self.movie_width as f32,
+            height as f32 / self.movie_height as f32,
+        );
+        let margin_x = (width as f32 - self.movie_width as f32 * scale) / 2.0;
+        let margin_y = (height as f32 - self.movie_height as f32 * scale) / 2.0;
+        let view_matrix = Matrix {
+            a: scale,
+            b: 0.0,
+            c: 0.0,
+            d: scale,
+            tx: Twips::from_pixels(margin_x.into()),
+            ty: Twips::from_pixels(margin_y.into()),
+        };
+        let view_bounds = BoundingBox {
+            x_min: Twips::new(0),
+            y_min: Twips::new(0),
+            x_max: Twips::from_pixels(self.movie_width.into()),
+            y_max: Twips::from_pixels(self.movie_height.into()),
+            valid: true,
+        };
+
+        self.renderer
+            .begin_render_to_bitmap(None, width, height, self.background_color.clone());
+
+        let (renderer, transform_stack) = (&mut self.renderer, &mut self.transform_stack);
+
+        transform_stack.push(&crate::transform::Transform {
+            matrix: view_matrix,
+            ..Default::default()
+        });
+        self.gc_arena.mutate(|_gc_context, gc_root| {
+            let root_data = gc_root.0.read();
+            let mut render_context = RenderContext {
+                renderer: renderer.deref_mut(),
+                library: &root_data.library,
+                transform_stack,
+                view_bounds,
+                clip_depth_stack: vec![],
+            };
+
+            for (_depth, level) in root_data.levels.iter() {
+                level.render(&mut render_context);
+            }
+        });
+        transform_stack.pop();
+
+        self.renderer.end_render_to_bitmap()
+    }
+
     pub fn audio(&self) -> &Audio {
         &self.audio
     }
@@ -668,6 +1118,10 @@ impl Player {
         self.input.deref_mut()
     }
 
+    pub fn fonts_mut(&mut self) -> &mut dyn FontBackend {
+        self.fonts.deref_mut()
+    }
+
     fn run_actions<'gc>(avm: &mut Avm1<'gc>, context: &mut UpdateContext<'_, 'gc, '_>) {
         // Note that actions can queue further actions, so a while loop is necessary here.
         while let Some(actions) = context.action_queue.pop_action() {
@@ -786,36 +1240,65 @@ impl Player {
         let (movie_width, movie_height) = (self.movie_width as f32, self.movie_height as f32);
         let (viewport_width, viewport_height) =
             (self.viewport_width as f32, self.viewport_height as f32);
-        let movie_aspect = movie_width / movie_height;
-        let viewport_aspect = viewport_width / viewport_height;
-        let (scale, margin_width, margin_height) = if viewport_aspect > movie_aspect {
-            let scale = viewport_height / movie_height;
-            (scale, (viewport_width - movie_width * scale) / 2.0, 0.0)
+        let (scale_x, scale_y) = match self.stage_scale_mode {
+            StageScaleMode::ShowAll => {
+                let scale = (viewport_width / movie_width).min(viewport_height / movie_height);
+                (scale, scale)
+            }
+            StageScaleMode::NoBorder => {
+                let scale = (viewport_width / movie_width).max(viewport_height / movie_height);
+                (scale, scale)
+            }
+            StageScaleMode::ExactFit => {
+                (viewport_width / movie_width, viewport_height / movie_height)
+            }
+            StageScaleMode::NoScale => (1.0, 1.0),
+        };
+
+        // Position the scaled movie according to the stage alignment.
+        let extra_width = viewport_width - movie_width * scale_x;
+        let extra_height = viewport_height - movie_height * scale_y;
+        let tx = if self.stage_align.contains(StageAlign::Left) {
+            0.0
+        } else if self.stage_align.contains(StageAlign::Right) {
+            extra_width
+        } else {
+            extra_width / 2.0
+        };
+        let ty = if self.stage_align.contains(StageAlign::Top) {
+            0.0
+        } else if self.stage_align.contains(StageAlign::Bottom) {
+            extra_height
         } else {
-            let scale = viewport_width / movie_width;
-            (scale, 0.0, (viewport_height - movie_height * scale) / 2.0)
+            extra_height / 2.0
         };
+
         self.view_matrix = Matrix {
-            a: scale,
+            a: scale_x,
             b: 0.0,
             c: 0.0,
-            d: scale,
-            tx: Twips::from_pixels(margin_width.into()),
-            ty: Twips::from_pixels(margin_height.into()),
+            d: scale_y,
+            tx: Twips::from_pixels(tx.into()),
+            ty: Twips::from_pixels(ty.into()),
         };
         self.inverse_view_matrix = self.view_matrix;
         self.inverse_view_matrix.invert();
 
         // Calculate letterbox dimensions.
+        // Only a centered showAll movie is letterboxed; in the other modes the extra area
+        // is part of the visible stage.
         // TODO: Letterbox should be an option; the original Flash Player defaults to showing content
         // in the extra margins.
-        self.letterbox = if margin_width > 0.0 {
-            Letterbox::Pillarbox(margin_width)
-        } else if margin_height > 0.0 {
-            Letterbox::Letterbox(margin_height)
-        } else {
-            Letterbox::None
-        };
+        self.letterbox =
+            if self.stage_scale_mode != StageScaleMode::ShowAll || !self.stage_align.is_empty() {
+                Letterbox::None
+            } else if extra_width > 0.0 {
+                Letterbox::Pillarbox(extra_width / 2.0)
+            } else if extra_height > 0.0 {
+                Letterbox::Letterbox(extra_height / 2.0)
+            } else {
+                Letterbox::None
+            };
     }
 
     /// Runs the closure `f` with an `UpdateContext`.
@@ -824,6 +1307,13 @@ impl Player {
     where
         F: for<'a, 'gc> FnOnce(&mut Avm1<'gc>, &mut UpdateContext<'a, 'gc, '_>) -> R,
     {
+        // Under noScale, the stage is the size of the viewport.
+        let (stage_width, stage_height) = if self.stage_scale_mode == StageScaleMode::NoScale {
+            (self.viewport_width, self.viewport_height)
+        } else {
+            (self.movie_width, self.movie_height)
+        };
+
         // We have to do this piecewise borrowing of fields before the closure to avoid
         // completely borrowing `self`.
         let (
@@ -835,10 +1325,15 @@ impl Player {
             audio,
             navigator,
             input,
+            fonts,
             rng,
             mouse_position,
             stage_width,
             stage_height,
+            stage_scale_mode,
+            stage_align,
+            stage_quality,
+            show_menu,
             player,
         ) = (
             self.player_version,
@@ -849,18 +1344,33 @@ impl Player {
             self.audio.deref_mut(),
             self.navigator.deref_mut(),
             self.input.deref_mut(),
+            self.fonts.deref_mut(),
             &mut self.rng,
             &self.mouse_pos,
-            Twips::from_pixels(self.movie_width.into()),
-            Twips::from_pixels(self.movie_height.into()),
+            Twips::from_pixels(stage_width.into()),
+            Twips::from_pixels(stage_height.into()),
+            &mut self.stage_scale_mode,
+            &mut self.stage_align,
+            &mut self.quality,
+            &mut self.show_menu,
             self.self_reference.clone(),
         );
+        let (old_scale_mode, old_align) = (*stage_scale_mode, *stage_align);
+        let old_quality = *stage_quality;
 
-        self.gc_arena.mutate(|gc_context, gc_root| {
+        let ret = self.gc_arena.mutate(|gc_context, gc_root| {
             let mut root_data = gc_root.0.write(gc_context);
             let mouse_hovered_object = root_data.mouse_hovered_object;
-            let (levels, library, action_queue, avm, drag_object, load_manager) =
-                root_data.update_context_params();
+            let focus_tracker = root_data.focus_tracker;
+            let (
+                levels,
+                library,
+                action_queue,
+                avm,
+                drag_object,
+                context_menu_callbacks,
+                load_manager,
+            ) = root_data.update_context_params();
 
             let mut update_context = UpdateContext {
                 player_version,
@@ -873,13 +1383,20 @@ impl Player {
                 audio,
                 navigator,
                 input,
+                fonts,
                 action_queue,
                 gc_context,
                 levels,
                 mouse_hovered_object,
+                focus_tracker,
                 mouse_position,
                 drag_object,
                 stage_size: (stage_width, stage_height),
+                stage_scale_mode,
+                stage_align,
+                stage_quality,
+                show_menu,
+                context_menu_callbacks,
                 system_prototypes: avm.prototypes().clone(),
                 player,
                 load_manager,
@@ -890,24 +1407,27 @@ impl Player {
             // Hovered object may have been updated; copy it back to the GC root.
             root_data.mouse_hovered_object = update_context.mouse_hovered_object;
             ret
-        })
+        });
+
+        // Apply any layout changes made by ActionScript.
+        if (self.stage_scale_mode, self.stage_align) != (old_scale_mode, old_align) {
+            self.build_matrices();
+            self.needs_render = true;
+        }
+        if self.quality != old_quality {
+            self.renderer.set_quality(self.quality);
+            self.needs_render = true;
+        }
+
+        ret
     }
 
     /// Loads font data from the given buffer.
     /// The buffer should be the `DefineFont3` info for the tag.
     /// The tag header should not be included.
-    fn load_device_font<'gc>(
-        gc_context: gc_arena::MutationContext<'gc, '_>,
-        data: &[u8],
-        renderer: &mut Renderer,
-    ) -> Result<crate::font::Font<'gc>, Error> {
+    fn load_device_font(data: &[u8]) -> Result<swf::Font, Error> {
         let mut reader = swf::read::Reader::new(data, 8);
-        let device_font = crate::font::Font::from_swf_tag(
-            gc_context,
-            renderer.deref_mut(),
-            &reader.read_define_font_2(3)?,
-        )?;
-        Ok(device_font)
+        Ok(reader.read_define_font_2(3)?)
     }
 
     /// Update the current state of the player.
@@ -0,0 +1,182 @@
+//! Stage settings shared between the player, ActionScript and the render backends.
+
+use enumset::{EnumSet, EnumSetType};
+
+/// Controls how the movie is scaled to fit the viewport.
+/// Equivalent to AS2 `Stage.scaleMode`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum StageScaleMode {
+    /// The whole movie is visible, preserving its aspect ratio.
+    /// This is the default.
+    #[default]
+    ShowAll,
+
+    /// The movie is stretched to fill the viewport, ignoring its aspect ratio.
+    ExactFit,
+
+    /// The movie fills the viewport, preserving its aspect ratio.
+    /// Parts of the movie may be cropped.
+    NoBorder,
+
+    /// The movie is not scaled. The stage dimensions are those of the viewport.
+    NoScale,
+}
+
+impl StageScaleMode {
+    /// Parses a scale mode as accepted by `Stage.scaleMode`.
+    /// Matching is case insensitive; returns `None` for unknown values.
+    pub fn from_name(s: &str) -> Option<Self> {
+        let mode = match s.to_ascii_lowercase().as_str() {
+            "showall" => StageScaleMode::ShowAll,
+            "exactfit" => StageScaleMode::ExactFit,
+            "noborder" => StageScaleMode::NoBorder,
+            "noscale" => StageScaleMode::NoScale,
+            _ => return None,
+        };
+        Some(mode)
+    }
+
+    /// The name of this scale mode as returned by `Stage.scaleMode`.
+    pub fn name(self) -> &'static str {
+        match self {
+            StageScaleMode::ShowAll => "showAll",
+            StageScaleMode::ExactFit => "exactFit",
+            StageScaleMode::NoBorder => "noBorder",
+            StageScaleMode::NoScale => "noScale",
+        }
+    }
+}
+
+/// The edges of the viewport that the movie is aligned to.
+/// An empty set centers the movie.
+/// Equivalent to AS2 `Stage.align`.
+#[derive(Debug, EnumSetType)]
+pub enum StageAlign {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl StageAlign {
+    /// Parses an alignment as accepted by `Stage.align`, such as `"TL"`.
+    /// Each of `T`, `B`, `L` and `R` may appear in any order and case; other characters
+    /// are ignored. If opposing edges are both given, top and left win.
+    pub fn parse(s: &str) -> EnumSet<Self> {
+        let mut align = EnumSet::empty();
+        for c in s.chars() {
+            match c.to_ascii_uppercase() {
+                'T' => align.insert(StageAlign::Top),
+                'B' => align.insert(StageAlign::Bottom),
+                'L' => align.insert(StageAlign::Left),
+                'R' => align.insert(StageAlign::Right),
+                _ => false,
+            };
+        }
+        if align.contains(StageAlign::Top) {
+            align.remove(StageAlign::Bottom);
+        }
+        if align.contains(StageAlign::Left) {
+            align.remove(StageAlign::Right);
+        }
+        align
+    }
+
+    /// Formats an alignment as returned by `Stage.align`, such as `"TL"`.
+    pub fn format(align: EnumSet<Self>) -> String {
+        let mut s = String::with_capacity(2);
+        if align.contains(StageAlign::Top) {
+            s.push('T');
+        } else if align.contains(StageAlign::Bottom) {
+            s.push('B');
+        }
+        if align.contains(StageAlign::Left) {
+            s.push('L');
+        } else if align.contains(StageAlign::Right) {
+            s.push('R');
+        }
+        s
+    }
+}
+
+/// The rendering quality of the movie.
+/// Equivalent to AS2 `_quality`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum StageQuality {
+    /// No anti-aliasing, and bitmaps are not smoothed.
+    Low,
+
+    /// Some anti-aliasing.
+    Medium,
+
+    /// Full anti-aliasing. This is the default.
+    #[default]
+    High,
+
+    /// Full anti-aliasing, and bitmaps are always smoothed.
+    Best,
+}
+
+impl StageQuality {
+    /// Parses a quality level as accepted by `_quality`.
+    /// Matching is case insensitive; returns `None` for unknown values.
+    pub fn from_name(s: &str) -> Option<Self> {
+        let quality = match s.to_ascii_uppercase().as_str() {
+            "LOW" => StageQuality::Low,
+            "MEDIUM" => StageQuality::Medium,
+            "HIGH" => StageQuality::High,
+            "BEST" => StageQuality::Best,
+            _ => return None,
+        };
+        Some(quality)
+    }
+
+    /// The name of this quality level as returned by `_quality`.
+    pub fn name(self) -> &'static str {
+        match self {
+            StageQuality::Low => "LOW",
+            StageQuality::Medium => "MEDIUM",
+            StageQuality::High => "HIGH",
+            StageQuality::Best => "BEST",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_scale_mode() {
+        assert_eq!(
+            StageScaleMode::from_name("noScale"),
+            Some(StageScaleMode::NoScale)
+        );
+        assert_eq!(
+            StageScaleMode::from_name("EXACTFIT"),
+            Some(StageScaleMode::ExactFit)
+        );
+        assert_eq!(StageScaleMode::from_name("stretch"), None);
+    }
+
+    #[test]
+    fn parse_quality() {
+        assert_eq!(
+            StageQuality::from_name("medium"),
+            Some(StageQuality::Medium)
+        );
+        assert_eq!(StageQuality::from_name("BEST"), Some(StageQuality::Best));
+        assert_eq!(StageQuality::from_name("AUTOHIGH"), None);
+    }
+
+    #[test]
+    fn parse_align() {
+        assert_eq!(StageAlign::parse(""), EnumSet::empty());
+        assert_eq!(StageAlign::parse("lt"), StageAlign::Top | StageAlign::Left);
+        assert_eq!(StageAlign::parse("TB"), EnumSet::from(StageAlign::Top));
+        assert_eq!(
+            StageAlign::format(StageAlign::Right | StageAlign::Bottom),
+            "BR"
+        );
+    }
+}
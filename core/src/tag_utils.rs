@@ -50,30 +50,7 @@ impl SwfMovie {
 
     /// Construct a movie based on the contents of the SWF datastream.
     pub fn from_data(swf_data: &[u8]) -> Result<Self, Error> {
-        let swf_stream = swf::read::read_swf_header(&swf_data[..])?;
-        let header = swf_stream.header;
-        let mut reader = swf_stream.reader;
-
-        // Decompress the entire SWF in memory.
-        // Sometimes SWFs will have an incorrectly compressed stream,
-        // but will otherwise decompress fine up to the End tag.
-        // So just warn on this case and try to continue gracefully.
-        let data = if header.compression == swf::Compression::Lzma {
-            // TODO: The LZMA decoder is still funky.
-            // It always errors, and doesn't return all the data if you use read_to_end,
-            // but read_exact at least returns the data... why?
-            // Does the decoder need to be flushed somehow?
-            let mut data = vec![0u8; swf_stream.uncompressed_length];
-            let _ = reader.get_mut().read_exact(&mut data);
-            data
-        } else {
-            let mut data = Vec::with_capacity(swf_stream.uncompressed_length);
-            if let Err(e) = reader.get_mut().read_to_end(&mut data) {
-                return Err(format!("Error decompressing SWF, may be corrupt: {}", e).into());
-            }
-            data
-        };
-
+        let swf::SwfBuf { header, data } = swf::decompress_swf(swf_data)?;
         Ok(Self { header, data })
     }
 
@@ -280,3 +257,19 @@ where
 
     Ok(())
 }
+
+/// Returns a reader over the contents of the current tag.
+///
+/// Tags whose payload runs to the end of the tag must be read through this
+/// reader, so that the payload does not include the tags following it. The
+/// payload borrows from the movie data rather than being copied.
+pub fn tag_reader<'a>(
+    reader: &SwfStream<&'a [u8]>,
+    tag_len: usize,
+    version: u8,
+) -> swf::read::Reader<&'a [u8]> {
+    let data: &'a [u8] = reader.get_ref().get_ref();
+    let end = std::cmp::min(reader.get_ref().position() as usize + tag_len, data.len());
+    let start = std::cmp::min(reader.get_ref().position() as usize, end);
+    swf::read::Reader::new(&data[start..end], version)
+}
@@ -1,16 +1,27 @@
 //! Tests running SWFs in a headless Ruffle instance.
 //!
 //! Trace output can be compared with correct output from the official Flash Payer.
+//!
+//! If the test folder contains an `input.txt`, the player events listed in it are sent
+//! after the frames have run. See `run_input` for the format.
 
 use approx::assert_abs_diff_eq;
 use log::{Metadata, Record};
 use ruffle_core::backend::navigator::{NullExecutor, NullNavigatorBackend};
+use ruffle_core::backend::render::{
+    Bitmap, BitmapHandle, BitmapInfo, Letterbox, RenderBackend, ShapeHandle, Transform,
+};
 use ruffle_core::backend::{
-    audio::NullAudioBackend, input::NullInputBackend, render::NullRenderer,
+    audio::NullAudioBackend, font::NullFontBackend, input::NullInputBackend, render::NullRenderer,
 };
+use ruffle_core::context_menu::ContextMenuItem;
+use ruffle_core::events::KeyCode;
+use ruffle_core::shape_utils::DistilledShape;
+use ruffle_core::stage::StageQuality;
 use ruffle_core::tag_utils::SwfMovie;
-use ruffle_core::Player;
+use ruffle_core::{Color, Player, PlayerEvent};
 use std::cell::RefCell;
+use std::convert::TryFrom;
 use std::path::Path;
 
 type Error = Box<dyn std::error::Error>;
@@ -167,6 +178,10 @@ swf_tests! {
     (undefined_to_string_swf6, "avm1/undefined_to_string_swf6", 1),
     (define_function2_preload, "avm1/define_function2_preload", 1),
     (define_function2_preload_order, "avm1/define_function2_preload_order", 1),
+    (as_broadcaster, "avm1/as_broadcaster", 1),
+    (as_broadcaster_error, "avm1/as_broadcaster_error", 1),
+    (stage_scale_mode, "avm1/stage_scale_mode", 1),
+    (quality, "avm1/quality", 1),
     (mcl_as_broadcaster, "avm1/mcl_as_broadcaster", 1),
     (loadmovie, "avm1/loadmovie", 2),
     (loadmovienum, "avm1/loadmovienum", 2),
@@ -192,6 +207,18 @@ swf_tests! {
     (as2_super_via_manual_prototype, "avm1/as2_super_via_manual_prototype", 1),
     (as1_constructor_v6, "avm1/as1_constructor_v6", 1),
     (as1_constructor_v7, "avm1/as1_constructor_v7", 1),
+    (bitmap_data_copy_pixels_clipped, "avm1/bitmap_data_copy_pixels_clipped", 1),
+    (drawing_bitmap_fill, "avm1/drawing_bitmap_fill", 1),
+    (bitmap_data_draw, "avm1/bitmap_data_draw", 1),
+    (filters, "avm1/filters", 1),
+    (key_listeners, "avm1/key_listeners", 1),
+    (goto_scene, "avm1/goto_scene", 1),
+    (import_assets, "avm1/import_assets", 3),
+    (mouse_wheel, "avm1/mouse_wheel", 1),
+    (context_menu, "avm1/context_menu", 1),
+    (focus_events, "avm1/focus_events", 1),
+    (tab_order, "avm1/tab_order", 1),
+    (button_transitions, "avm1/button_transitions", 1),
 }
 
 // TODO: These tests have some inaccuracies currently, so we use approx_eq to test that numeric values are close enough.
@@ -288,6 +315,7 @@ fn run_swf(swf_path: &str, num_frames: u32) -> Result<String, Error> {
         Box::new(NullAudioBackend::new()),
         Box::new(NullNavigatorBackend::with_base_path(base_path, channel)),
         Box::new(NullInputBackend::new()),
+        Box::new(NullFontBackend::new()),
         movie,
     )?;
 
@@ -296,11 +324,274 @@ fn run_swf(swf_path: &str, num_frames: u32) -> Result<String, Error> {
         executor.poll_all().unwrap();
     }
 
+    let input_path = base_path.join("input.txt");
+    if input_path.exists() {
+        let input = std::fs::read_to_string(input_path)?;
+        run_input(&mut player.lock().unwrap(), &input)?;
+    }
+
     executor.block_all().unwrap();
 
     Ok(trace_log())
 }
 
+/// Sends the player events listed in `input`, one per line:
+///
+/// ```text
+/// ; A comment.
+/// key_down 9
+/// key_up 9
+/// mouse_move 10 20
+/// mouse_down 10 20
+/// mouse_up 10 20
+/// mouse_wheel -3
+/// resize 800 600
+/// context_menu
+/// context_menu_select 0
+/// ```
+///
+/// Key codes are Flash key codes, and positions are in viewport pixels. The `mouse_wheel`
+/// delta is in lines, and is positive when scrolling up.
+/// `resize` changes the size of the viewport.
+/// `context_menu` prepares the context menu and writes its items to the trace log, and
+/// `context_menu_select` runs the item at the given index of the last prepared menu.
+fn run_input(player: &mut Player, input: &str) -> Result<(), Error> {
+    for line in input.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+
+        let mut words = line.split_whitespace();
+        let command = words.next().unwrap_or_default();
+        let args = words
+            .map(str::parse::<f64>)
+            .collect::<Result<Vec<_>, _>>()?;
+        let arg = |i: usize| -> Result<f64, Error> {
+            args.get(i)
+                .copied()
+                .ok_or_else(|| format!("Missing argument in input line: {}", line).into())
+        };
+        let key_code = || -> Result<KeyCode, Error> {
+            KeyCode::try_from(arg(0)? as u8)
+                .map_err(|_| format!("Unknown key code in input line: {}", line).into())
+        };
+
+        let event = match command {
+            "key_down" => PlayerEvent::KeyDown {
+                key_code: key_code()?,
+            },
+            "key_up" => PlayerEvent::KeyUp {
+                key_code: key_code()?,
+            },
+            "mouse_move" => PlayerEvent::MouseMove {
+                x: arg(0)?,
+                y: arg(1)?,
+            },
+            "mouse_down" => PlayerEvent::MouseDown {
+                x: arg(0)?,
+                y: arg(1)?,
+            },
+            "mouse_up" => PlayerEvent::MouseUp {
+                x: arg(0)?,
+                y: arg(1)?,
+            },
+            "mouse_wheel" => PlayerEvent::MouseWheel {
+                delta: arg(0)? as i32,
+            },
+            "resize" => {
+                player.set_viewport_dimensions(arg(0)? as u32, arg(1)? as u32);
+                continue;
+            }
+            "context_menu" => {
+                for item in player.prepare_context_menu() {
+                    trace_context_menu_item(&item);
+                }
+                continue;
+            }
+            "context_menu_select" => {
+                player.run_context_menu_callback(arg(0)? as usize);
+                continue;
+            }
+            _ => return Err(format!("Unknown input line: {}", line).into()),
+        };
+        player.handle_event(event);
+    }
+
+    Ok(())
+}
+
+/// Writes an item of the context menu to the trace log.
+fn trace_context_menu_item(item: &ContextMenuItem) {
+    let mut line = format!("context menu item: {}", item.caption);
+    if item.separator_before {
+        line.push_str(" (separator)");
+    }
+    if !item.enabled {
+        line.push_str(" (disabled)");
+    }
+    if item.checked {
+        line.push_str(" (checked)");
+    }
+    TRACE_LOG.with(|log| log.borrow_mut().push_str(&format!("{}\n", line)));
+}
+
+/// `DefineFont4.swf` has a `DefineFont4` font without font data named like the embedded
+/// font of its text field, which must still be drawn with the embedded glyphs.
+#[test]
+fn define_font_4_text() -> Result<(), Error> {
+    let (_executor, channel) = NullExecutor::new();
+    let movie = SwfMovie::from_path("../swf/tests/swfs/DefineFont4.swf")?;
+    let player = Player::new(
+        Box::new(GlyphRenderer::default()),
+        Box::new(NullAudioBackend::new()),
+        Box::new(NullNavigatorBackend::with_base_path(
+            "../swf/tests/swfs",
+            channel,
+        )),
+        Box::new(NullInputBackend::new()),
+        Box::new(NullFontBackend::new()),
+        movie,
+    )?;
+    let mut player = player.lock().unwrap();
+    player.run_frame();
+    player.render();
+
+    let renderer = player
+        .renderer()
+        .downcast_ref::<GlyphRenderer>()
+        .ok_or("Unexpected renderer")?;
+    assert_eq!(renderer.rendered_text, "AB");
+    Ok(())
+}
+
+/// The first shape handle given to glyphs by `GlyphRenderer`.
+const GLYPH_HANDLE_START: usize = 0x10000;
+
+/// A `NullRenderer` that records the characters of the glyphs it draws.
+#[derive(Default)]
+struct GlyphRenderer {
+    inner: NullRenderer,
+    glyph_codes: Vec<u16>,
+    rendered_text: String,
+}
+
+impl RenderBackend for GlyphRenderer {
+    fn set_viewport_dimensions(&mut self, width: u32, height: u32) {
+        self.inner.set_viewport_dimensions(width, height)
+    }
+    fn set_quality(&mut self, quality: StageQuality) {
+        self.inner.set_quality(quality)
+    }
+    fn register_shape(&mut self, shape: DistilledShape) -> ShapeHandle {
+        self.inner.register_shape(shape)
+    }
+    fn register_drawing(&mut self, shape: DistilledShape, bitmaps: &[BitmapHandle]) -> ShapeHandle {
+        self.inner.register_drawing(shape, bitmaps)
+    }
+    fn replace_drawing(
+        &mut self,
+        handle: ShapeHandle,
+        shape: DistilledShape,
+        bitmaps: &[BitmapHandle],
+    ) {
+        self.inner.replace_drawing(handle, shape, bitmaps)
+    }
+    fn register_glyph_shape(&mut self, shape: &swf::Glyph) -> ShapeHandle {
+        self.glyph_codes.push(shape.code);
+        ShapeHandle(GLYPH_HANDLE_START + self.glyph_codes.len() - 1)
+    }
+    fn register_bitmap_jpeg(
+        &mut self,
+        id: swf::CharacterId,
+        data: &[u8],
+        jpeg_tables: Option<&[u8]>,
+    ) -> BitmapInfo {
+        self.inner.register_bitmap_jpeg(id, data, jpeg_tables)
+    }
+    fn register_bitmap_jpeg_2(&mut self, id: swf::CharacterId, data: &[u8]) -> BitmapInfo {
+        self.inner.register_bitmap_jpeg_2(id, data)
+    }
+    fn register_bitmap_jpeg_3(
+        &mut self,
+        id: swf::CharacterId,
+        jpeg_data: &[u8],
+        alpha_data: &[u8],
+    ) -> BitmapInfo {
+        self.inner.register_bitmap_jpeg_3(id, jpeg_data, alpha_data)
+    }
+    fn register_bitmap_png(&mut self, swf_tag: &swf::DefineBitsLossless) -> BitmapInfo {
+        self.inner.register_bitmap_png(swf_tag)
+    }
+    fn register_bitmap_raw(&mut self, width: u32, height: u32, rgba: Vec<u8>) -> BitmapHandle {
+        self.inner.register_bitmap_raw(width, height, rgba)
+    }
+    fn update_texture(
+        &mut self,
+        bitmap: BitmapHandle,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> BitmapHandle {
+        self.inner.update_texture(bitmap, width, height, rgba)
+    }
+    fn get_bitmap_pixels(&mut self, bitmap: BitmapHandle) -> Option<Bitmap> {
+        self.inner.get_bitmap_pixels(bitmap)
+    }
+    fn unregister_bitmap(&mut self, bitmap: BitmapHandle) {
+        self.inner.unregister_bitmap(bitmap)
+    }
+    fn begin_frame(&mut self, clear: Color) {
+        self.rendered_text.clear();
+        self.inner.begin_frame(clear)
+    }
+    fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform) {
+        self.inner.render_bitmap(bitmap, transform)
+    }
+    fn render_shape(&mut self, shape: ShapeHandle, transform: &Transform) {
+        if let Some(code) = shape
+            .0
+            .checked_sub(GLYPH_HANDLE_START)
+            .and_then(|i| self.glyph_codes.get(i))
+        {
+            self.rendered_text
+                .extend(std::char::decode_utf16(Some(*code)).filter_map(Result::ok));
+        }
+        self.inner.render_shape(shape, transform)
+    }
+    fn end_frame(&mut self) {
+        self.inner.end_frame()
+    }
+    fn draw_letterbox(&mut self, letterbox: Letterbox) {
+        self.inner.draw_letterbox(letterbox)
+    }
+    fn draw_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color) {
+        self.inner.draw_rect(x, y, width, height, color)
+    }
+    fn push_mask(&mut self) {
+        self.inner.push_mask()
+    }
+    fn activate_mask(&mut self) {
+        self.inner.activate_mask()
+    }
+    fn pop_mask(&mut self) {
+        self.inner.pop_mask()
+    }
+    fn begin_render_to_bitmap(
+        &mut self,
+        target: Option<BitmapHandle>,
+        width: u32,
+        height: u32,
+        clear: Color,
+    ) {
+        self.inner
+            .begin_render_to_bitmap(target, width, height, clear)
+    }
+    fn end_render_to_bitmap(&mut self) -> Option<Bitmap> {
+        self.inner.end_render_to_bitmap()
+    }
+}
+
 thread_local! {
     static TRACE_LOG: RefCell<String> = RefCell::new(String::new());
 }
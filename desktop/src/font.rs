@@ -0,0 +1,97 @@
+//! Font backend for desktop
+
+use ruffle_core::backend::font::{font_family_name, FontBackend};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+/// Implementation of `FontBackend` that serves the TrueType and OpenType fonts found in
+/// a set of directories.
+pub struct DirectoryFontBackend {
+    /// The font files by lowercase family name.
+    fonts: HashMap<String, PathBuf>,
+}
+
+impl DirectoryFontBackend {
+    /// Indexes the fonts in the given directories and their subdirectories.
+    pub fn new(directories: &[PathBuf]) -> Self {
+        let mut backend = Self {
+            fonts: HashMap::new(),
+        };
+        let mut visited = HashSet::new();
+        for directory in directories {
+            backend.add_directory(directory, &mut visited);
+        }
+        log::info!("Found {} font families", backend.fonts.len());
+        backend
+    }
+
+    /// Indexes the fonts in `directory` and its subdirectories.
+    /// Directories already in `visited` are skipped, so symlink cycles are only followed once.
+    fn add_directory(&mut self, directory: &Path, visited: &mut HashSet<PathBuf>) {
+        match fs::canonicalize(directory) {
+            Ok(canonical) if !visited.insert(canonical) => return,
+            Ok(_) => (),
+            Err(e) => {
+                log::warn!("Unable to read font directory {:?}: {}", directory, e);
+                return;
+            }
+        }
+        let entries = match fs::read_dir(directory) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("Unable to read font directory {:?}: {}", directory, e);
+                return;
+            }
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            if path.is_dir() {
+                self.add_directory(&path, visited);
+            } else if Self::is_font_file(&path) {
+                self.add_font_file(path);
+            }
+        }
+    }
+
+    fn is_font_file(path: &Path) -> bool {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| ["ttf", "otf"].contains(&extension.to_lowercase().as_str()))
+            .unwrap_or(false)
+    }
+
+    fn add_font_file(&mut self, path: PathBuf) {
+        // Only the name of the font is read here. The font is read in full by `load_font`.
+        let name = match File::open(&path)
+            .ok()
+            .and_then(|mut file| font_family_name(&mut file))
+        {
+            Some(name) => name.to_lowercase(),
+            None => {
+                log::warn!("Unable to read font family name from {:?}", path);
+                return;
+            }
+        };
+        // Every style of a family shares its name. The regular style usually has the
+        // shortest file name, such as `Arial.ttf` rather than `Arial-Bold.ttf`.
+        let file_name_len = |path: &Path| path.file_name().map_or(0, |name| name.len());
+        let is_shorter = |existing: &PathBuf| file_name_len(&path) < file_name_len(existing);
+        if self.fonts.get(&name).map_or(true, is_shorter) {
+            self.fonts.insert(name, path);
+        }
+    }
+}
+
+impl FontBackend for DirectoryFontBackend {
+    fn load_font(&mut self, name: &str) -> Option<Vec<u8>> {
+        let path = self.fonts.get(&name.to_lowercase())?;
+        match fs::read(path) {
+            Ok(data) => Some(data),
+            Err(e) => {
+                log::warn!("Unable to load font {:?}: {}", path, e);
+                None
+            }
+        }
+    }
+}
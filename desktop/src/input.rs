@@ -10,6 +10,9 @@ pub struct WinitInputBackend {
     window: Rc<Window>,
     cursor_visible: bool,
     last_key: KeyCode,
+    last_char: Option<char>,
+    caps_lock: bool,
+    num_lock: bool,
 }
 
 impl WinitInputBackend {
@@ -18,6 +21,9 @@ impl WinitInputBackend {
             keys_down: HashSet::new(),
             cursor_visible: true,
             last_key: KeyCode::Unknown,
+            last_char: None,
+            caps_lock: false,
+            num_lock: false,
             window,
         }
     }
@@ -28,7 +34,17 @@ impl WinitInputBackend {
             WindowEvent::KeyboardInput { input, .. } => match input.state {
                 ElementState::Pressed => {
                     if let Some(key) = input.virtual_keycode {
+                        // Ignore auto-repeat when flipping the lock keys.
+                        if !self.keys_down.contains(&key) {
+                            match key {
+                                VirtualKeyCode::Capital => self.caps_lock = !self.caps_lock,
+                                VirtualKeyCode::Numlock => self.num_lock = !self.num_lock,
+                                _ => (),
+                            }
+                        }
                         self.keys_down.insert(key);
+                        self.last_char =
+                            winit_key_to_char(key, self.is_shift_down(), self.caps_lock);
                         if let Some(key_code) = winit_to_ruffle_key_code(key) {
                             self.last_key = key_code;
                             return Some(PlayerEvent::KeyDown { key_code });
@@ -50,12 +66,19 @@ impl WinitInputBackend {
                 }
             },
             WindowEvent::ReceivedCharacter(codepoint) => {
+                // This arrives after the key press, but respects the keyboard layout.
+                self.last_char = Some(codepoint);
                 return Some(PlayerEvent::TextInput { codepoint });
             }
             _ => (),
         }
         None
     }
+
+    fn is_shift_down(&self) -> bool {
+        self.keys_down.contains(&VirtualKeyCode::LShift)
+            || self.keys_down.contains(&VirtualKeyCode::RShift)
+    }
 }
 
 impl InputBackend for WinitInputBackend {
@@ -63,11 +86,9 @@ impl InputBackend for WinitInputBackend {
         match key {
             KeyCode::Unknown => false,
             KeyCode::Backspace => self.keys_down.contains(&VirtualKeyCode::Back),
+            KeyCode::Tab => self.keys_down.contains(&VirtualKeyCode::Tab),
             KeyCode::Return => self.keys_down.contains(&VirtualKeyCode::Return),
-            KeyCode::Shift => {
-                self.keys_down.contains(&VirtualKeyCode::LShift)
-                    || self.keys_down.contains(&VirtualKeyCode::RShift)
-            }
+            KeyCode::Shift => self.is_shift_down(),
             KeyCode::Control => {
                 self.keys_down.contains(&VirtualKeyCode::LControl)
                     || self.keys_down.contains(&VirtualKeyCode::RControl)
@@ -152,6 +173,7 @@ impl InputBackend for WinitInputBackend {
             KeyCode::Insert => self.keys_down.contains(&VirtualKeyCode::Insert),
             KeyCode::Delete => self.keys_down.contains(&VirtualKeyCode::Delete),
             KeyCode::Pause => self.keys_down.contains(&VirtualKeyCode::Pause),
+            KeyCode::NumLock => self.keys_down.contains(&VirtualKeyCode::Numlock),
             KeyCode::ScrollLock => self.keys_down.contains(&VirtualKeyCode::Scroll),
             KeyCode::F1 => self.keys_down.contains(&VirtualKeyCode::F1),
             KeyCode::F2 => self.keys_down.contains(&VirtualKeyCode::F2),
@@ -172,6 +194,18 @@ impl InputBackend for WinitInputBackend {
         self.last_key
     }
 
+    fn get_last_key_char(&self) -> Option<char> {
+        self.last_char
+    }
+
+    fn is_key_toggled(&self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::CapsLock => self.caps_lock,
+            KeyCode::NumLock => self.num_lock,
+            _ => false,
+        }
+    }
+
     fn mouse_visible(&self) -> bool {
         self.cursor_visible
     }
@@ -203,6 +237,7 @@ impl InputBackend for WinitInputBackend {
 fn winit_to_ruffle_key_code(key_code: VirtualKeyCode) -> Option<KeyCode> {
     let out = match key_code {
         VirtualKeyCode::Back => KeyCode::Backspace,
+        VirtualKeyCode::Tab => KeyCode::Tab,
         VirtualKeyCode::Return => KeyCode::Return,
         VirtualKeyCode::LShift | VirtualKeyCode::RShift => KeyCode::Shift,
         VirtualKeyCode::LControl | VirtualKeyCode::RControl => KeyCode::Control,
@@ -283,6 +318,7 @@ fn winit_to_ruffle_key_code(key_code: VirtualKeyCode) -> Option<KeyCode> {
         VirtualKeyCode::Insert => KeyCode::Insert,
         VirtualKeyCode::Delete => KeyCode::Delete,
         VirtualKeyCode::Pause => KeyCode::Pause,
+        VirtualKeyCode::Numlock => KeyCode::NumLock,
         VirtualKeyCode::Scroll => KeyCode::ScrollLock,
         VirtualKeyCode::F1 => KeyCode::F1,
         VirtualKeyCode::F2 => KeyCode::F2,
@@ -300,3 +336,96 @@ fn winit_to_ruffle_key_code(key_code: VirtualKeyCode) -> Option<KeyCode> {
     };
     Some(out)
 }
+
+/// Converts a winit `VirtualKeyCode` into the character it generates on a US keyboard layout.
+/// winit only reports the layout-aware character after the key press, so this is used
+/// to provide `Key.getAscii` during `onKeyDown`.
+/// Returns `None` if the key does not produce a character.
+fn winit_key_to_char(
+    key_code: VirtualKeyCode,
+    is_shift_down: bool,
+    is_caps_lock: bool,
+) -> Option<char> {
+    let (lower, upper) = match key_code {
+        VirtualKeyCode::Back => ('\u{8}', '\u{8}'),
+        VirtualKeyCode::Tab => ('\t', '\t'),
+        VirtualKeyCode::Return => ('\r', '\r'),
+        VirtualKeyCode::Escape => ('\u{1b}', '\u{1b}'),
+        VirtualKeyCode::Space => (' ', ' '),
+        VirtualKeyCode::Delete => ('\u{7f}', '\u{7f}'),
+        VirtualKeyCode::Key0 => ('0', ')'),
+        VirtualKeyCode::Key1 => ('1', '!'),
+        VirtualKeyCode::Key2 => ('2', '@'),
+        VirtualKeyCode::Key3 => ('3', '#'),
+        VirtualKeyCode::Key4 => ('4', '$'),
+        VirtualKeyCode::Key5 => ('5', '%'),
+        VirtualKeyCode::Key6 => ('6', '^'),
+        VirtualKeyCode::Key7 => ('7', '&'),
+        VirtualKeyCode::Key8 => ('8', '*'),
+        VirtualKeyCode::Key9 => ('9', '('),
+        VirtualKeyCode::A => ('a', 'A'),
+        VirtualKeyCode::B => ('b', 'B'),
+        VirtualKeyCode::C => ('c', 'C'),
+        VirtualKeyCode::D => ('d', 'D'),
+        VirtualKeyCode::E => ('e', 'E'),
+        VirtualKeyCode::F => ('f', 'F'),
+        VirtualKeyCode::G => ('g', 'G'),
+        VirtualKeyCode::H => ('h', 'H'),
+        VirtualKeyCode::I => ('i', 'I'),
+        VirtualKeyCode::J => ('j', 'J'),
+        VirtualKeyCode::K => ('k', 'K'),
+        VirtualKeyCode::L => ('l', 'L'),
+        VirtualKeyCode::M => ('m', 'M'),
+        VirtualKeyCode::N => ('n', 'N'),
+        VirtualKeyCode::O => ('o', 'O'),
+        VirtualKeyCode::P => ('p', 'P'),
+        VirtualKeyCode::Q => ('q', 'Q'),
+        VirtualKeyCode::R => ('r', 'R'),
+        VirtualKeyCode::S => ('s', 'S'),
+        VirtualKeyCode::T => ('t', 'T'),
+        VirtualKeyCode::U => ('u', 'U'),
+        VirtualKeyCode::V => ('v', 'V'),
+        VirtualKeyCode::W => ('w', 'W'),
+        VirtualKeyCode::X => ('x', 'X'),
+        VirtualKeyCode::Y => ('y', 'Y'),
+        VirtualKeyCode::Z => ('z', 'Z'),
+        VirtualKeyCode::Semicolon => (';', ':'),
+        VirtualKeyCode::Equals => ('=', '+'),
+        VirtualKeyCode::Comma => (',', '<'),
+        VirtualKeyCode::Minus => ('-', '_'),
+        VirtualKeyCode::Period => ('.', '>'),
+        VirtualKeyCode::Slash => ('/', '?'),
+        VirtualKeyCode::Grave => ('`', '~'),
+        VirtualKeyCode::LBracket => ('[', '{'),
+        VirtualKeyCode::Backslash => ('\\', '|'),
+        VirtualKeyCode::RBracket => (']', '}'),
+        VirtualKeyCode::Apostrophe => ('\'', '"'),
+        VirtualKeyCode::Numpad0 => ('0', '0'),
+        VirtualKeyCode::Numpad1 => ('1', '1'),
+        VirtualKeyCode::Numpad2 => ('2', '2'),
+        VirtualKeyCode::Numpad3 => ('3', '3'),
+        VirtualKeyCode::Numpad4 => ('4', '4'),
+        VirtualKeyCode::Numpad5 => ('5', '5'),
+        VirtualKeyCode::Numpad6 => ('6', '6'),
+        VirtualKeyCode::Numpad7 => ('7', '7'),
+        VirtualKeyCode::Numpad8 => ('8', '8'),
+        VirtualKeyCode::Numpad9 => ('9', '9'),
+        VirtualKeyCode::Multiply => ('*', '*'),
+        VirtualKeyCode::Add => ('+', '+'),
+        VirtualKeyCode::Subtract => ('-', '-'),
+        VirtualKeyCode::Decimal => ('.', '.'),
+        VirtualKeyCode::Divide => ('/', '/'),
+        _ => return None,
+    };
+    // Caps Lock only affects letters.
+    let is_upper = if lower.is_ascii_alphabetic() {
+        is_shift_down ^ is_caps_lock
+    } else {
+        is_shift_down
+    };
+    if is_upper {
+        Some(upper)
+    } else {
+        Some(lower)
+    }
+}
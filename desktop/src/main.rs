@@ -3,6 +3,7 @@
 mod audio;
 mod custom_event;
 mod executor;
+mod font;
 mod input;
 mod navigator;
 mod task;
@@ -21,15 +22,26 @@ use structopt::StructOpt;
 use ruffle_core::tag_utils::SwfMovie;
 use std::rc::Rc;
 use winit::dpi::{LogicalSize, PhysicalPosition};
-use winit::event::{ElementState, MouseButton, WindowEvent};
+use winit::event::{
+    ElementState, KeyboardInput, MouseButton, MouseScrollDelta, VirtualKeyCode, WindowEvent,
+};
 use winit::event_loop::{ControlFlow, EventLoop};
 use winit::window::WindowBuilder;
 
+/// The distance in pixels of a line of mouse wheel scrolling, for touchpads and
+/// other devices that scroll smoothly.
+const PIXELS_PER_LINE: f64 = 20.0;
+
 #[derive(StructOpt, Debug)]
 #[structopt(name = "basic")]
 struct Opt {
     #[structopt(name = "FILE", parse(from_os_str))]
     input_path: PathBuf,
+
+    /// A directory of TrueType or OpenType fonts to use for device text.
+    /// May be given more than once.
+    #[structopt(long = "font-dir", parse(from_os_str))]
+    font_dirs: Vec<PathBuf>,
 }
 
 fn main() {
@@ -37,7 +49,7 @@ fn main() {
 
     let opt = Opt::from_args();
 
-    let ret = run_player(opt.input_path);
+    let ret = run_player(opt.input_path, &opt.font_dirs);
 
     if let Err(e) = ret {
         eprintln!("Fatal error:\n{}", e);
@@ -45,7 +57,10 @@ fn main() {
     }
 }
 
-fn run_player(input_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+fn run_player(
+    input_path: PathBuf,
+    font_dirs: &[PathBuf],
+) -> Result<(), Box<dyn std::error::Error>> {
     let movie = SwfMovie::from_path(&input_path)?;
     let movie_size = LogicalSize::new(movie.width(), movie.height());
 
@@ -81,7 +96,8 @@ fn run_player(input_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
         event_loop.create_proxy(),
     )); //TODO: actually implement this backend type
     let input = Box::new(input::WinitInputBackend::new(window.clone()));
-    let player = Player::new(renderer, audio, navigator, input, movie)?;
+    let fonts = Box::new(font::DirectoryFontBackend::new(font_dirs));
+    let player = Player::new(renderer, audio, navigator, input, fonts, movie)?;
     player.lock().unwrap().set_is_playing(true); // Desktop player will auto-play.
 
     player
@@ -90,6 +106,8 @@ fn run_player(input_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
         .set_viewport_dimensions(viewport_size.width, viewport_size.height);
 
     let mut mouse_pos = PhysicalPosition::new(0.0, 0.0);
+    // The fraction of a line scrolled by the mouse wheel that hasn't been sent yet.
+    let mut mouse_wheel_remainder = 0.0;
     let mut time = Instant::now();
     let mut next_frame_time = Instant::now();
     loop {
@@ -128,11 +146,41 @@ fn run_player(input_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
                     WindowEvent::CursorMoved { position, .. } => {
                         let mut player_lock = player.lock().unwrap();
                         mouse_pos = position;
-                        let event = ruffle_core::PlayerEvent::MouseMove {
-                            x: position.x,
-                            y: position.y,
-                        };
-                        player_lock.handle_event(event);
+                        // The stage doesn't receive mouse events while the context menu is open.
+                        if player_lock.context_menu_overlay().is_some() {
+                            player_lock.hover_context_menu_overlay(position.x, position.y);
+                        } else {
+                            let event = ruffle_core::PlayerEvent::MouseMove {
+                                x: position.x,
+                                y: position.y,
+                            };
+                            player_lock.handle_event(event);
+                        }
+                        if player_lock.needs_render() {
+                            window.request_redraw();
+                        }
+                    }
+                    WindowEvent::MouseInput {
+                        button: MouseButton::Left,
+                        state,
+                        ..
+                    } if player.lock().unwrap().context_menu_overlay().is_some() => {
+                        let mut player_lock = player.lock().unwrap();
+                        let (x, y) = (mouse_pos.x as f32, mouse_pos.y as f32);
+                        let overlay = player_lock.context_menu_overlay().unwrap();
+                        let (item, contains) = (overlay.item_at(x, y), overlay.contains(x, y));
+                        match state {
+                            // Pressing outside of the menu closes it.
+                            ElementState::Pressed if !contains => {
+                                player_lock.clear_context_menu();
+                            }
+                            // Releasing over an item selects it.
+                            ElementState::Released if item.is_some() => {
+                                player_lock.run_context_menu_callback(item.unwrap());
+                                player_lock.clear_context_menu();
+                            }
+                            _ => (),
+                        }
                         if player_lock.needs_render() {
                             window.request_redraw();
                         }
@@ -159,6 +207,41 @@ fn run_player(input_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
                             window.request_redraw();
                         }
                     }
+                    WindowEvent::MouseInput {
+                        button: MouseButton::Right,
+                        state: ElementState::Pressed,
+                        ..
+                    } => {
+                        let mut player_lock = player.lock().unwrap();
+                        player_lock.clear_context_menu();
+                        let items = player_lock.prepare_context_menu();
+                        if !items.is_empty() {
+                            player_lock.show_context_menu_overlay(items, mouse_pos.x, mouse_pos.y);
+                        }
+                        if player_lock.needs_render() {
+                            window.request_redraw();
+                        }
+                    }
+                    WindowEvent::MouseWheel { delta, .. } => {
+                        let lines = match delta {
+                            MouseScrollDelta::LineDelta(_, dy) => f64::from(dy),
+                            MouseScrollDelta::PixelDelta(pos) => pos.y / PIXELS_PER_LINE,
+                        };
+                        // Smooth scrolling sends fractions of a line, which add up until
+                        // a whole line can be sent.
+                        let lines = mouse_wheel_remainder + lines;
+                        let delta = lines.trunc();
+                        mouse_wheel_remainder = lines - delta;
+                        let delta = delta as i32;
+                        if delta != 0 {
+                            let mut player_lock = player.lock().unwrap();
+                            player_lock
+                                .handle_event(ruffle_core::PlayerEvent::MouseWheel { delta });
+                            if player_lock.needs_render() {
+                                window.request_redraw();
+                            }
+                        }
+                    }
                     WindowEvent::CursorLeft { .. } => {
                         let mut player_lock = player.lock().unwrap();
                         player_lock.handle_event(ruffle_core::PlayerEvent::MouseLeft);
@@ -167,6 +250,18 @@ fn run_player(input_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
                         }
                     }
                     WindowEvent::CloseRequested => *control_flow = ControlFlow::Exit,
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                virtual_keycode: Some(VirtualKeyCode::Escape),
+                                ..
+                            },
+                        ..
+                    } if player.lock().unwrap().context_menu_overlay().is_some() => {
+                        let mut player_lock = player.lock().unwrap();
+                        player_lock.clear_context_menu();
+                        window.request_redraw();
+                    }
                     WindowEvent::KeyboardInput { .. } | WindowEvent::ReceivedCharacter(_) => {
                         let mut player_lock = player.lock().unwrap();
                         if let Some(event) = player_lock
@@ -0,0 +1,624 @@
+//! Extracts the assets of an SWF into a directory.
+//!
+//! Bitmaps are written as PNGs, sounds as WAVs or MP3s, binary data as-is, and shapes, morph
+//! shapes and font outlines as SVGs. A `manifest.json` lists every asset along with the
+//! dependency graph of the characters.
+//!
+//! The sound streamed by a timeline in its `SoundStreamBlock` tags is written as a single sound,
+//! listed with the ID of its sprite, or with ID 0 for the main timeline. Nellymoser and Speex
+//! sounds can't be decoded, so they are written out as-is.
+
+use crate::vector::SvgExporter;
+use ruffle_core::backend::audio::decoders::make_decoder;
+use ruffle_core::backend::render::{
+    decode_define_bits_jpeg, decode_define_bits_lossless, glue_tables_to_jpeg,
+    unmultiply_alpha_rgba, Bitmap, BitmapFormat,
+};
+use ruffle_core::shape_utils::{DistilledShape, DrawCommand, DrawPath};
+use ruffle_core::swf::dependencies::DependencyGraph;
+use ruffle_core::swf::{self, AudioCompression, CharacterId, ShapeRecord, Tag};
+use std::error::Error;
+use std::fmt::Write;
+use std::fs::create_dir_all;
+use std::io::Cursor;
+use std::path::Path;
+use svg::node::element::{path::Data, Group, Path as SvgPath, Title};
+use svg::node::Text;
+use svg::Document;
+
+/// A bitmap decoded from a tag, along with its character ID.
+pub(crate) type DecodedBitmap = (CharacterId, Result<Bitmap, Box<dyn Error>>);
+
+/// Properties of an asset, as JSON values.
+type Properties = Vec<(&'static str, String)>;
+
+/// An extracted asset, as listed in the manifest.
+struct Asset {
+    id: CharacterId,
+    kind: &'static str,
+    file: Option<String>,
+    properties: Properties,
+}
+
+impl Asset {
+    fn new(
+        id: CharacterId,
+        kind: &'static str,
+        file: String,
+        result: Result<Properties, Box<dyn Error>>,
+    ) -> Self {
+        match result {
+            Ok(properties) => Self {
+                id,
+                kind,
+                file: Some(file),
+                properties,
+            },
+            Err(e) => Self {
+                id,
+                kind,
+                file: None,
+                properties: vec![("error", json_string(&e.to_string()))],
+            },
+        }
+    }
+}
+
+/// Extracts every asset of the SWF at `swf_path` into the `output` directory.
+/// Returns the number of assets that were extracted successfully.
+pub fn extract_assets(swf_path: &Path, output: &Path) -> Result<usize, Box<dyn Error>> {
+    let data = std::fs::read(swf_path)?;
+    let swf_buf = swf::decompress_swf(&data[..])?;
+    let swf = swf::parse_swf(&swf_buf)?;
+    create_dir_all(output)?;
+
+    let exporter = SvgExporter::new(&swf);
+    let mut assets = vec![];
+    let mut jpeg_tables = None;
+    for tag in &swf.tags {
+        if let Some((id, bitmap)) = decode_bitmap(tag, jpeg_tables) {
+            assets.push(extract_bitmap(output, id, bitmap));
+            continue;
+        }
+        let asset = match tag {
+            Tag::JpegTables(data) => {
+                jpeg_tables = Some(*data);
+                continue;
+            }
+            Tag::DefineShape(shape) => {
+                let file = format!("shape_{}.svg", shape.id);
+                let result = write_svg(&output.join(&file), exporter.shape(shape.id));
+                Asset::new(shape.id, "shape", file, result)
+            }
+            Tag::DefineMorphShape(morph_shape) => {
+                let id = morph_shape.id;
+                let file = format!("morph_shape_{}_start.svg", id);
+                let end_file = format!("morph_shape_{}_end.svg", id);
+                let result = write_svg(&output.join(&file), exporter.morph_shape(id, 0))
+                    .and_then(|_| {
+                        write_svg(&output.join(&end_file), exporter.morph_shape(id, 65535))
+                    })
+                    .map(|_| vec![("end_file", json_string(&end_file))]);
+                Asset::new(id, "morph_shape", file, result)
+            }
+            Tag::DefineSound(sound) => extract_sound(output, sound),
+            Tag::DefineSprite(sprite) => match sound_stream(&sprite.tags) {
+                Some(stream) => extract_stream(output, sprite.id, &stream),
+                None => continue,
+            },
+            Tag::DefineBinaryData { id, data } => {
+                let file = format!("binary_{}.bin", id);
+                let result = std::fs::write(output.join(&file), data)
+                    .map(|_| vec![("size", data.len().to_string())]);
+                Asset::new(*id, "binary", file, result.map_err(Into::into))
+            }
+            Tag::DefineFont(font) => {
+                let glyphs: Vec<_> = font.glyphs.iter().map(|glyph| (None, &glyph[..])).collect();
+                extract_font(output, font.id, None, &glyphs, 1024.0)
+            }
+            Tag::DefineFont2(font) => {
+                let glyphs: Vec<_> = font
+                    .glyphs
+                    .iter()
+                    .map(|glyph| (Some(glyph.code), &glyph.shape_records[..]))
+                    .collect();
+                // DefineFont3 stores coordinates at 20x the scale of DefineFont1/2.
+                // (SWF19 p.164)
+                let em_square = if font.version >= 3 { 20480.0 } else { 1024.0 };
+                extract_font(
+                    output,
+                    font.id,
+                    Some(font.name.as_str()),
+                    &glyphs,
+                    em_square,
+                )
+            }
+            Tag::DefineFont4(font) => {
+                // The font data is an OpenType CFF font.
+                let file = format!("font_{}.otf", font.id);
+                let result = match font.data {
+                    Some(data) => std::fs::write(output.join(&file), data)
+                        .map(|_| vec![("name", json_string(&font.name))])
+                        .map_err(Into::into),
+                    None => Err("DefineFont4 has no font data".into()),
+                };
+                Asset::new(font.id, "font", file, result)
+            }
+            _ => continue,
+        };
+        assets.push(asset);
+    }
+    if let Some(stream) = sound_stream(&swf.tags) {
+        assets.push(extract_stream(output, 0, &stream));
+    }
+
+    let graph = DependencyGraph::new(&swf.tags);
+    let manifest = manifest_json(swf_path, &swf.header, &assets, &graph);
+    std::fs::write(output.join("manifest.json"), manifest)?;
+
+    Ok(assets.iter().filter(|asset| asset.file.is_some()).count())
+}
+
+/// Decodes the bitmap defined by a tag, or returns `None` if the tag doesn't define a bitmap.
+pub(crate) fn decode_bitmap(tag: &Tag, jpeg_tables: Option<&[u8]>) -> Option<DecodedBitmap> {
+    let bitmap = match tag {
+        Tag::DefineBits { id, jpeg_data } => {
+            let jpeg_data = glue_tables_to_jpeg(jpeg_data, jpeg_tables);
+            (*id, decode_define_bits_jpeg(&jpeg_data, None))
+        }
+        Tag::DefineBitsJpeg2 { id, jpeg_data } => (*id, decode_define_bits_jpeg(jpeg_data, None)),
+        Tag::DefineBitsJpeg3(jpeg) => (
+            jpeg.id,
+            decode_define_bits_jpeg(jpeg.data, Some(jpeg.alpha_data)),
+        ),
+        Tag::DefineBitsLossless(bitmap) => (bitmap.id, decode_lossless_bitmap(bitmap)),
+        _ => return None,
+    };
+    Some(bitmap)
+}
+
+/// Decodes a DefineBitsLossless tag. The decoder doesn't support 15-bit bitmaps and panics on
+/// them, so they are returned as errors instead.
+fn decode_lossless_bitmap(bitmap: &swf::DefineBitsLossless) -> Result<Bitmap, Box<dyn Error>> {
+    match bitmap.format {
+        swf::BitmapFormat::Rgb15 => Err("Unsupported bitmap format Rgb15".into()),
+        _ => decode_define_bits_lossless(bitmap),
+    }
+}
+
+fn extract_bitmap(output: &Path, id: CharacterId, bitmap: Result<Bitmap, Box<dyn Error>>) -> Asset {
+    let file = format!("bitmap_{}.png", id);
+    let result = bitmap.and_then(|bitmap| write_png(&output.join(&file), bitmap));
+    Asset::new(id, "bitmap", file, result)
+}
+
+fn write_png(path: &Path, bitmap: Bitmap) -> Result<Properties, Box<dyn Error>> {
+    let (width, height) = (bitmap.width, bitmap.height);
+    match bitmap.data {
+        BitmapFormat::Rgb(rgb) => image::RgbImage::from_raw(width, height, rgb)
+            .ok_or("Bitmap data doesn't match its size")?
+            .save(path)?,
+        BitmapFormat::Rgba(mut rgba) => {
+            unmultiply_alpha_rgba(&mut rgba);
+            image::RgbaImage::from_raw(width, height, rgba)
+                .ok_or("Bitmap data doesn't match its size")?
+                .save(path)?
+        }
+    }
+    Ok(vec![
+        ("width", width.to_string()),
+        ("height", height.to_string()),
+    ])
+}
+
+fn write_svg(path: &Path, document: Option<Document>) -> Result<Properties, Box<dyn Error>> {
+    let document = document.ok_or("Unable to render character")?;
+    svg::save(path, &document)?;
+    Ok(vec![])
+}
+
+fn extract_sound(output: &Path, sound: &swf::Sound) -> Asset {
+    let format = &sound.format;
+    // The MP3 frames are preceded by a 16-bit seek sample count.
+    let data = match format.compression {
+        AudioCompression::Mp3 => sound.data.get(2..).unwrap_or_default(),
+        _ => sound.data,
+    };
+    let name = format!("sound_{}", sound.id);
+    let (file, result) = write_audio(output, &name, format, &[data]);
+    let properties = result.map(|mut properties| {
+        properties.push(("num_samples", sound.num_samples.to_string()));
+        properties
+    });
+    Asset::new(sound.id, "sound", file, properties)
+}
+
+/// The sound streamed by a timeline.
+struct SoundStream<'a> {
+    format: swf::SoundFormat,
+    samples_per_block: u16,
+    blocks: Vec<&'a [u8]>,
+}
+
+/// Finds the sound stream of a timeline. Only the first `SoundStreamHead` of a timeline is used.
+fn sound_stream<'a>(tags: &[Tag<'a>]) -> Option<SoundStream<'a>> {
+    let mut stream: Option<SoundStream> = None;
+    for tag in tags {
+        match tag {
+            Tag::SoundStreamHead(head) | Tag::SoundStreamHead2(head) if stream.is_none() => {
+                stream = Some(SoundStream {
+                    format: head.stream_format.clone(),
+                    samples_per_block: head.num_samples_per_block,
+                    blocks: vec![],
+                });
+            }
+            Tag::SoundStreamBlock(block) => {
+                if let Some(stream) = &mut stream {
+                    stream.blocks.push(*block);
+                }
+            }
+            _ => (),
+        }
+    }
+    stream
+}
+
+fn extract_stream(output: &Path, id: CharacterId, stream: &SoundStream) -> Asset {
+    // MP3 stream blocks start with a 16-bit sample count and a 16-bit seek sample count.
+    // (SWF19 p.184, p.188)
+    let blocks: Vec<_> = match stream.format.compression {
+        AudioCompression::Mp3 => stream
+            .blocks
+            .iter()
+            .map(|block| block.get(4..).unwrap_or_default())
+            .collect(),
+        _ => stream.blocks.clone(),
+    };
+    let name = format!("stream_{}", id);
+    let (file, result) = write_audio(output, &name, &stream.format, &blocks);
+    let properties = result.map(|mut properties| {
+        properties.push(("samples_per_block", stream.samples_per_block.to_string()));
+        properties.push(("blocks", blocks.len().to_string()));
+        properties
+    });
+    Asset::new(id, "stream", file, properties)
+}
+
+/// Writes audio data as a WAV, an MP3 or, if it can't be decoded, as-is. The data is split into
+/// blocks, such as the `SoundStreamBlock`s of a stream, which are written one after another.
+/// Each ADPCM block starts with its own header. Returns the name of the written file.
+fn write_audio(
+    output: &Path,
+    name: &str,
+    format: &swf::SoundFormat,
+    blocks: &[&[u8]],
+) -> (String, Result<Properties, Box<dyn Error>>) {
+    let num_channels = if format.is_stereo { 2 } else { 1 };
+    let sample_rate = u32::from(format.sample_rate);
+    let (file, compression, result) = match format.compression {
+        AudioCompression::Mp3 => {
+            let file = format!("{}.mp3", name);
+            let result = std::fs::write(output.join(&file), blocks.concat());
+            (file, "mp3", result)
+        }
+        AudioCompression::Uncompressed | AudioCompression::UncompressedUnknownEndian => {
+            let file = format!("{}.wav", name);
+            let bits_per_sample = if format.is_16_bit { 16 } else { 8 };
+            let result = write_wav(
+                &output.join(&file),
+                num_channels,
+                sample_rate,
+                bits_per_sample,
+                &blocks.concat(),
+            );
+            (file, "pcm", result)
+        }
+        AudioCompression::Adpcm => {
+            // The decoder always outputs 16-bit stereo.
+            let mut data = Vec::new();
+            for block in blocks {
+                for frame in make_decoder(format, Cursor::new(*block)) {
+                    data.extend_from_slice(&frame[0].to_le_bytes());
+                    data.extend_from_slice(&frame[1].to_le_bytes());
+                }
+            }
+            let file = format!("{}.wav", name);
+            let result = write_wav(&output.join(&file), 2, sample_rate, 16, &data);
+            (file, "adpcm", result)
+        }
+        _ => {
+            let file = format!("{}.bin", name);
+            let result = std::fs::write(output.join(&file), blocks.concat());
+            let compression = match format.compression {
+                AudioCompression::Speex => "speex",
+                _ => "nellymoser",
+            };
+            (file, compression, result)
+        }
+    };
+    let properties = vec![
+        ("compression", json_string(compression)),
+        ("sample_rate", sample_rate.to_string()),
+        ("channels", num_channels.to_string()),
+    ];
+    (file, result.map(|_| properties).map_err(Into::into))
+}
+
+fn write_wav(
+    path: &Path,
+    num_channels: u16,
+    sample_rate: u32,
+    bits_per_sample: u16,
+    data: &[u8],
+) -> std::io::Result<()> {
+    let block_align = num_channels * bits_per_sample / 8;
+    let mut wav = Vec::with_capacity(44 + data.len());
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&num_channels.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * u32::from(block_align)).to_le_bytes());
+    wav.extend_from_slice(&block_align.to_le_bytes());
+    wav.extend_from_slice(&bits_per_sample.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    wav.extend_from_slice(data);
+    std::fs::write(path, wav)
+}
+
+/// Writes the glyphs of a font as an SVG, laid out in a grid of EM squares.
+fn extract_font(
+    output: &Path,
+    id: CharacterId,
+    name: Option<&str>,
+    glyphs: &[(Option<u16>, &[ShapeRecord])],
+    em_square: f32,
+) -> Asset {
+    const COLUMNS: usize = 16;
+    let rows = (glyphs.len() + COLUMNS - 1) / COLUMNS;
+    let mut document = Document::new().set(
+        "viewBox",
+        (0, 0, COLUMNS as f32 * em_square, rows as f32 * em_square),
+    );
+    for (i, (code, records)) in glyphs.iter().enumerate() {
+        // Glyphs are drawn upwards from the baseline, which is placed near the bottom of the cell.
+        let x = (i % COLUMNS) as f32 * em_square;
+        let y = ((i / COLUMNS) as f32 + 0.8) * em_square;
+        let title = match code.and_then(|code| std::char::from_u32(code.into())) {
+            Some(c) => format!("{} ({})", i, c),
+            None => i.to_string(),
+        };
+        let glyph = Group::new()
+            .set("transform", format!("translate({} {})", x, y))
+            .add(Title::new().add(Text::new(title)))
+            .add(glyph_path(records));
+        document = document.add(glyph);
+    }
+
+    let file = format!("font_{}.svg", id);
+    let result = svg::save(output.join(&file), &document).map(|_| {
+        let mut properties = vec![("glyphs", glyphs.len().to_string())];
+        if let Some(name) = name {
+            properties.push(("name", json_string(name)));
+        }
+        properties
+    });
+    Asset::new(id, "font", file, result.map_err(Into::into))
+}
+
+fn glyph_path(records: &[ShapeRecord]) -> SvgPath {
+    let shape = swf::Shape {
+        version: 2,
+        id: 0,
+        shape_bounds: Default::default(),
+        edge_bounds: Default::default(),
+        has_fill_winding_rule: false,
+        has_non_scaling_strokes: false,
+        has_scaling_strokes: true,
+        styles: swf::ShapeStyles {
+            fill_styles: vec![swf::FillStyle::Color(swf::Color {
+                r: 0,
+                g: 0,
+                b: 0,
+                a: 255,
+            })],
+            line_styles: vec![],
+        },
+        shape: records.to_vec(),
+    };
+    let mut data = Data::new();
+    for path in DistilledShape::from(&shape).paths {
+        if let DrawPath::Fill { commands, .. } = path {
+            for command in commands {
+                data = match command {
+                    DrawCommand::MoveTo { x, y } => data.move_to((x.get(), y.get())),
+                    DrawCommand::LineTo { x, y } => data.line_to((x.get(), y.get())),
+                    DrawCommand::CurveTo { x1, y1, x2, y2 } => {
+                        data.quadratic_curve_to((x1.get(), y1.get(), x2.get(), y2.get()))
+                    }
+                };
+            }
+        }
+    }
+    SvgPath::new().set("fill-rule", "evenodd").set("d", data)
+}
+
+fn manifest_json(
+    swf_path: &Path,
+    header: &swf::Header,
+    assets: &[Asset],
+    graph: &DependencyGraph,
+) -> String {
+    let mut json = String::new();
+    json.push_str("{\n");
+    let _ = writeln!(
+        json,
+        "  \"swf\": {},",
+        json_string(&swf_path.to_string_lossy())
+    );
+    let _ = writeln!(json, "  \"version\": {},", header.version);
+
+    json.push_str("  \"assets\": [");
+    for (i, asset) in assets.iter().enumerate() {
+        let separator = if i == 0 { "" } else { "," };
+        let _ = write!(
+            json,
+            "{}\n    {{\"id\": {}, \"type\": {}",
+            separator,
+            asset.id,
+            json_string(asset.kind)
+        );
+        if let Some(file) = &asset.file {
+            let _ = write!(json, ", \"file\": {}", json_string(file));
+        }
+        for (key, value) in &asset.properties {
+            let _ = write!(json, ", \"{}\": {}", key, value);
+        }
+        json.push('}');
+    }
+    json.push_str("\n  ],\n");
+
+    json.push_str("  \"dependencies\": {");
+    for (i, id) in graph.characters().enumerate() {
+        let separator = if i == 0 { "" } else { "," };
+        let dependencies = graph.dependencies(id).into_iter().flatten();
+        let _ = write!(
+            json,
+            "{}\n    \"{}\": {}",
+            separator,
+            id,
+            json_array(dependencies)
+        );
+    }
+    json.push_str("\n  },\n");
+
+    let _ = writeln!(
+        json,
+        "  \"timeline\": {},",
+        json_array(graph.timeline().iter())
+    );
+
+    json.push_str("  \"exports\": {");
+    for (i, (name, id)) in graph.exports().iter().enumerate() {
+        let separator = if i == 0 { "" } else { "," };
+        let _ = write!(json, "{}\n    {}: {}", separator, json_string(name), id);
+    }
+    json.push_str("\n  },\n");
+
+    json.push_str("  \"imports\": [");
+    for (i, (id, import)) in graph.imports().iter().enumerate() {
+        let separator = if i == 0 { "" } else { "," };
+        let _ = write!(
+            json,
+            "{}\n    {{\"id\": {}, \"url\": {}, \"name\": {}}}",
+            separator,
+            id,
+            json_string(&import.url),
+            json_string(&import.name)
+        );
+    }
+    json.push_str("\n  ]\n}\n");
+    json
+}
+
+fn json_array<'a>(ids: impl Iterator<Item = &'a CharacterId>) -> String {
+    let ids: Vec<_> = ids.map(|id| id.to_string()).collect();
+    format!("[{}]", ids.join(", "))
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c < ' ' => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// An empty directory for the output of a test.
+    fn output_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("ruffle_exporter_{}", name));
+        let _ = std::fs::remove_dir_all(&dir);
+        create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn json_strings() {
+        assert_eq!(json_string("clip"), r#""clip""#);
+        assert_eq!(json_string(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(json_string(r"C:\movies"), r#""C:\\movies""#);
+        assert_eq!(json_string("a\nb\r\tc"), r#""a\nb\r\tc""#);
+        assert_eq!(json_string("\u{0}\u{1f} é"), r#""\u0000\u001f é""#);
+    }
+
+    #[test]
+    fn wav_header() {
+        let path = output_dir("wav_header").join("sound.wav");
+        write_wav(&path, 2, 44100, 16, &[1, 2, 3, 4, 5, 6, 7, 8]).unwrap();
+        let wav = std::fs::read(&path).unwrap();
+        let u16_at = |i: usize| u16::from_le_bytes([wav[i], wav[i + 1]]);
+        let u32_at = |i: usize| u32::from_le_bytes([wav[i], wav[i + 1], wav[i + 2], wav[i + 3]]);
+        assert_eq!(wav.len(), 44 + 8);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(u32_at(4), 36 + 8);
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(u32_at(16), 16);
+        assert_eq!(u16_at(20), 1);
+        assert_eq!(u16_at(22), 2);
+        assert_eq!(u32_at(24), 44100);
+        assert_eq!(u32_at(28), 44100 * 4);
+        assert_eq!(u16_at(32), 4);
+        assert_eq!(u16_at(34), 16);
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(u32_at(40), 8);
+        assert_eq!(&wav[44..], &[1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn stream_manifest() {
+        let output = output_dir("stream_manifest");
+        let swf_path = Path::new("../swf/tests/swfs/SoundStreamHead2.swf");
+        assert_eq!(extract_assets(swf_path, &output).unwrap(), 1);
+        let manifest = std::fs::read_to_string(output.join("manifest.json")).unwrap();
+        assert_eq!(
+            manifest,
+            r#"{
+  "swf": "../swf/tests/swfs/SoundStreamHead2.swf",
+  "version": 31,
+  "assets": [
+    {"id": 0, "type": "stream", "file": "stream_0.wav", "compression": "pcm", "sample_rate": 5512, "channels": 1, "samples_per_block": 229, "blocks": 1}
+  ],
+  "dependencies": {
+  },
+  "timeline": [],
+  "exports": {
+  },
+  "imports": [
+  ]
+}
+"#
+        );
+        let wav = std::fs::read(output.join("stream_0.wav")).unwrap();
+        assert_eq!(wav.len(), 44 + 460);
+    }
+}
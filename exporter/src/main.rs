@@ -2,6 +2,7 @@ use futures::executor::block_on;
 use image::RgbaImage;
 use indicatif::{ProgressBar, ProgressStyle};
 use ruffle_core::backend::audio::NullAudioBackend;
+use ruffle_core::backend::font::NullFontBackend;
 use ruffle_core::backend::input::NullInputBackend;
 use ruffle_core::backend::navigator::NullNavigatorBackend;
 use ruffle_core::tag_utils::SwfMovie;
@@ -15,6 +16,9 @@ use std::rc::Rc;
 use structopt::StructOpt;
 use walkdir::{DirEntry, WalkDir};
 
+mod extract;
+mod vector;
+
 #[derive(StructOpt, Debug)]
 struct Opt {
     /// The file or directory of files to export frames from
@@ -36,6 +40,16 @@ struct Opt {
     /// Don't show a progress bar
     #[structopt(short, long)]
     silent: bool,
+
+    /// Extract the bitmaps, sounds, binary data and fonts of the swf into the output directory,
+    /// along with a manifest.json, instead of capturing frames
+    #[structopt(short = "x", long = "extract")]
+    extract: bool,
+
+    /// Render the frames as SVGs instead of PNGs. This doesn't need a GPU, but text and video
+    /// are not drawn
+    #[structopt(long = "svg")]
+    svg: bool,
 }
 
 fn take_screenshot(
@@ -53,6 +67,7 @@ fn take_screenshot(
         Box::new(NullAudioBackend::new()),
         Box::new(NullNavigatorBackend::new()),
         Box::new(NullInputBackend::new()),
+        Box::new(NullFontBackend::new()),
         movie,
     )?;
 
@@ -284,8 +299,150 @@ fn capture_multiple_swfs(
     Ok(())
 }
 
+fn extract_multiple_swfs(directory: &Path, output: &Path) -> Result<(), Box<dyn Error>> {
+    let files = find_files(directory, false);
+    let mut num_assets = 0;
+    let mut num_failed = 0;
+    for file in &files {
+        let mut relative_path = file
+            .path()
+            .strip_prefix(directory)
+            .unwrap_or_else(|_| &file.path())
+            .to_path_buf();
+        relative_path.set_extension("");
+        let mut destination = PathBuf::from(output);
+        destination.push(relative_path);
+        match extract::extract_assets(file.path(), &destination) {
+            Ok(assets) => num_assets += assets,
+            Err(e) => {
+                eprintln!("Unable to extract {}: {}", file.path().to_string_lossy(), e);
+                num_failed += 1;
+            }
+        }
+    }
+    println!(
+        "Extracted {} assets of {} files to {}",
+        num_assets,
+        files.len() - num_failed,
+        output.to_string_lossy()
+    );
+    if num_failed > 0 {
+        println!("{} files could not be extracted", num_failed);
+    }
+    Ok(())
+}
+
+fn render_single_swf(
+    swf: &Path,
+    frames: u32,
+    output: Option<PathBuf>,
+) -> Result<(), Box<dyn Error>> {
+    let output = output.unwrap_or_else(|| {
+        let mut result = PathBuf::new();
+        result.set_file_name(swf.file_stem().unwrap());
+        if frames == 1 {
+            result.set_extension("svg");
+        }
+        result
+    });
+
+    let documents = vector::render_frames(swf, frames)?;
+    if documents.len() == 1 {
+        svg::save(&output, &documents[0])?;
+    } else {
+        create_dir_all(&output)?;
+        for (frame, document) in documents.iter().enumerate() {
+            svg::save(output.join(format!("{}.svg", frame)), document)?;
+        }
+    }
+
+    println!(
+        "Rendered {} frames of {} to {}",
+        documents.len(),
+        swf.to_string_lossy(),
+        output.to_string_lossy()
+    );
+    Ok(())
+}
+
+fn render_multiple_swfs(
+    directory: &Path,
+    frames: u32,
+    output: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let files = find_files(directory, false);
+    for file in &files {
+        let documents = vector::render_frames(file.path(), frames)?;
+
+        let mut relative_path = file
+            .path()
+            .strip_prefix(directory)
+            .unwrap_or_else(|_| &file.path())
+            .to_path_buf();
+
+        if documents.len() == 1 {
+            let mut destination = PathBuf::from(output);
+            relative_path.set_extension("svg");
+            destination.push(relative_path);
+            if let Some(parent) = destination.parent() {
+                let _ = create_dir_all(parent);
+            }
+            svg::save(&destination, &documents[0])?;
+        } else {
+            let mut parent = PathBuf::from(output);
+            relative_path.set_extension("");
+            parent.push(&relative_path);
+            let _ = create_dir_all(&parent);
+            for (frame, document) in documents.iter().enumerate() {
+                svg::save(parent.join(format!("{}.svg", frame)), document)?;
+            }
+        }
+    }
+
+    println!(
+        "Rendered {} frames of {} files to {}",
+        frames,
+        files.len(),
+        output.to_string_lossy()
+    );
+    Ok(())
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
     let opt: Opt = Opt::from_args();
+
+    if opt.extract {
+        if opt.swf.is_file() {
+            let output = match opt.output_path {
+                Some(output) => output,
+                None => PathBuf::from(opt.swf.file_stem().unwrap()),
+            };
+            let num_assets = extract::extract_assets(&opt.swf, &output)?;
+            println!(
+                "Extracted {} assets of {} to {}",
+                num_assets,
+                opt.swf.to_string_lossy(),
+                output.to_string_lossy()
+            );
+        } else if let Some(output) = opt.output_path {
+            extract_multiple_swfs(&opt.swf, &output)?;
+        } else {
+            return Err("Output directory is required when extracting multiple files.".into());
+        }
+        return Ok(());
+    }
+
+    if opt.svg {
+        if opt.swf.is_file() {
+            render_single_swf(&opt.swf, opt.frames, opt.output_path)?;
+        } else if let Some(output) = opt.output_path {
+            render_multiple_swfs(&opt.swf, opt.frames, &output)?;
+        } else {
+            return Err("Output directory is required when exporting multiple files.".into());
+        }
+        return Ok(());
+    }
+
     let adapter = block_on(wgpu::Adapter::request(
         &wgpu::RequestAdapterOptions {
             power_preference: wgpu::PowerPreference::Default,
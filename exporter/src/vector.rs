@@ -0,0 +1,856 @@
+//! Renders the shapes, morph shapes and frames of an SWF as SVGs, without a GPU.
+//!
+//! Shapes are converted by `ShapeConverter` into paths in twips. Gradient and bitmap fills
+//! become paint servers in `<defs>`, every display object becomes a `<g>` carrying its matrix
+//! and color transform, and masks become `<clipPath>`s. Text and video are not drawn.
+
+use crate::extract::decode_bitmap;
+use ruffle_core::backend::render::{unmultiply_alpha_rgba, Bitmap, BitmapFormat};
+use ruffle_core::color_transform::ColorTransform;
+use ruffle_core::matrix::Matrix;
+use ruffle_core::shape_utils::{interpolate_morph_shape, DistilledShape, DrawCommand, DrawPath};
+use ruffle_core::swf::{
+    self, ButtonState, CharacterId, Color, Depth, FillStyle, Gradient, GradientInterpolation,
+    GradientSpread, LineCapStyle, LineJoinStyle, PlaceObjectAction, Tag,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::path::Path;
+use svg::node::element::{
+    path::Data, ClipPath, Definitions, Element, Filter, Group, Image, LinearGradient,
+    Path as SvgPath, Pattern, RadialGradient, Rectangle, Stop,
+};
+use svg::{Document, Node};
+
+/// A bitmap embedded as a PNG data URI.
+struct EmbeddedBitmap {
+    uri: String,
+    width: u32,
+    height: u32,
+}
+
+/// A character that can be drawn.
+enum Character<'a> {
+    Shape(&'a swf::Shape),
+    MorphShape(&'a swf::DefineMorphShape),
+    Sprite(&'a swf::Sprite<'a>),
+    Button(&'a swf::Button<'a>),
+}
+
+/// An object on the display list of a timeline.
+#[derive(Clone, Debug)]
+struct DisplayObject {
+    id: CharacterId,
+    matrix: Matrix,
+    color_transform: ColorTransform,
+    ratio: u16,
+    clip_depth: Depth,
+    is_visible: bool,
+    /// The frame of the parent timeline this object was placed on.
+    place_frame: u16,
+}
+
+impl DisplayObject {
+    fn new(id: CharacterId, place_frame: u16) -> Self {
+        Self {
+            id,
+            matrix: Default::default(),
+            color_transform: Default::default(),
+            ratio: 0,
+            clip_depth: 0,
+            is_visible: true,
+            place_frame,
+        }
+    }
+}
+
+/// Renders the characters and frames of a parsed SWF to SVG documents.
+pub struct SvgExporter<'a> {
+    header: &'a swf::Header,
+    tags: &'a [Tag<'a>],
+    characters: HashMap<CharacterId, Character<'a>>,
+    bitmaps: HashMap<CharacterId, EmbeddedBitmap>,
+    background_color: Option<Color>,
+}
+
+impl<'a> SvgExporter<'a> {
+    pub fn new(swf: &'a swf::Swf<'a>) -> Self {
+        let mut characters = HashMap::new();
+        let mut bitmaps = HashMap::new();
+        let mut background_color = None;
+        let mut jpeg_tables = None;
+        for tag in &swf.tags {
+            if let Some((id, bitmap)) = decode_bitmap(tag, jpeg_tables) {
+                match bitmap.and_then(embed_bitmap) {
+                    Ok(bitmap) => {
+                        bitmaps.insert(id, bitmap);
+                    }
+                    Err(e) => log::warn!("Unable to decode bitmap {}: {}", id, e),
+                }
+                continue;
+            }
+            let (id, character) = match tag {
+                Tag::JpegTables(data) => {
+                    jpeg_tables = Some(*data);
+                    continue;
+                }
+                Tag::SetBackgroundColor(color) => {
+                    background_color = Some(color.clone());
+                    continue;
+                }
+                Tag::DefineShape(shape) => (shape.id, Character::Shape(shape)),
+                Tag::DefineMorphShape(morph_shape) => {
+                    (morph_shape.id, Character::MorphShape(morph_shape))
+                }
+                Tag::DefineSprite(sprite) => (sprite.id, Character::Sprite(sprite)),
+                Tag::DefineButton(button) | Tag::DefineButton2(button) => {
+                    (button.id, Character::Button(button))
+                }
+                _ => continue,
+            };
+            characters.insert(id, character);
+        }
+        Self {
+            header: &swf.header,
+            tags: &swf.tags,
+            characters,
+            bitmaps,
+            background_color,
+        }
+    }
+
+    /// Renders the shape with the given ID, or returns `None` if it isn't a shape.
+    pub fn shape(&self, id: CharacterId) -> Option<Document> {
+        if let Some(Character::Shape(shape)) = self.characters.get(&id) {
+            let mut writer = SvgWriter::new(self);
+            let mut group = Group::new();
+            writer.shape(&mut group, shape);
+            Some(writer.into_document(&shape.shape_bounds, None, group))
+        } else {
+            None
+        }
+    }
+
+    /// Renders the morph shape with the given ID at a ratio between 0 (the start shape) and
+    /// 65535 (the end shape), or returns `None` if it isn't a morph shape.
+    pub fn morph_shape(&self, id: CharacterId, ratio: u16) -> Option<Document> {
+        if let Some(Character::MorphShape(morph_shape)) = self.characters.get(&id) {
+            let shape = morph_shape_at(morph_shape, ratio);
+            let mut writer = SvgWriter::new(self);
+            let mut group = Group::new();
+            writer.shape(&mut group, &shape);
+            Some(writer.into_document(&shape.shape_bounds, None, group))
+        } else {
+            None
+        }
+    }
+
+    /// Renders the stage at the given frame, counting from 0.
+    pub fn frame(&self, frame: u16) -> Document {
+        let mut writer = SvgWriter::new(self);
+        let mut group = Group::new();
+        writer.display_list(&mut group, &display_list(self.tags, frame), frame);
+        writer.into_document(
+            &self.header.stage_size,
+            self.background_color.as_ref(),
+            group,
+        )
+    }
+}
+
+/// Renders the first frames of the SWF at `swf_path`.
+pub fn render_frames(swf_path: &Path, frames: u32) -> Result<Vec<Document>, Box<dyn Error>> {
+    let data = std::fs::read(swf_path)?;
+    let swf_buf = swf::decompress_swf(&data[..])?;
+    let swf = swf::parse_swf(&swf_buf)?;
+    let exporter = SvgExporter::new(&swf);
+    let frames = u16::try_from(frames)?;
+    Ok((0..frames).map(|frame| exporter.frame(frame)).collect())
+}
+
+/// Builds the display list of a timeline as it is shown on the given frame.
+fn display_list(tags: &[Tag], frame: u16) -> BTreeMap<Depth, DisplayObject> {
+    let mut list = BTreeMap::new();
+    let mut current_frame = 0;
+    for tag in tags {
+        match tag {
+            Tag::ShowFrame => {
+                if current_frame == frame {
+                    break;
+                }
+                current_frame += 1;
+            }
+            Tag::PlaceObject(place_object) => {
+                let object = match place_object.action {
+                    PlaceObjectAction::Place(id) => {
+                        list.insert(place_object.depth, DisplayObject::new(id, current_frame));
+                        list.get_mut(&place_object.depth)
+                    }
+                    PlaceObjectAction::Replace(id) => {
+                        if let Some(object) = list.get_mut(&place_object.depth) {
+                            object.id = id;
+                            object.place_frame = current_frame;
+                        }
+                        list.get_mut(&place_object.depth)
+                    }
+                    PlaceObjectAction::Modify => list.get_mut(&place_object.depth),
+                };
+                if let Some(object) = object {
+                    if let Some(matrix) = &place_object.matrix {
+                        object.matrix = matrix.clone().into();
+                    }
+                    if let Some(color_transform) = &place_object.color_transform {
+                        object.color_transform = color_transform.clone().into();
+                    }
+                    if let Some(ratio) = place_object.ratio {
+                        object.ratio = ratio;
+                    }
+                    if let Some(clip_depth) = place_object.clip_depth {
+                        object.clip_depth = clip_depth;
+                    }
+                    object.is_visible = place_object.is_visible;
+                }
+            }
+            Tag::RemoveObject(remove_object) => {
+                list.remove(&remove_object.depth);
+            }
+            _ => (),
+        }
+    }
+    list
+}
+
+/// The display list of a button in its up state.
+fn button_display_list(button: &swf::Button) -> BTreeMap<Depth, DisplayObject> {
+    button
+        .records
+        .iter()
+        .filter(|record| record.states.contains(&ButtonState::Up))
+        .map(|record| {
+            let mut object = DisplayObject::new(record.id, 0);
+            object.matrix = record.matrix.clone().into();
+            object.color_transform = record.color_transform.clone().into();
+            (record.depth, object)
+        })
+        .collect()
+}
+
+/// Builds a single SVG document, collecting the paint servers and clip paths it needs.
+struct SvgWriter<'e, 'a> {
+    exporter: &'e SvgExporter<'a>,
+    defs: Definitions,
+    num_defs: usize,
+    bitmap_defs: HashSet<CharacterId>,
+    /// The characters currently being drawn, used to break cycles of sprites or buttons that
+    /// contain themselves.
+    ancestors: Vec<CharacterId>,
+}
+
+impl<'e, 'a> SvgWriter<'e, 'a> {
+    fn new(exporter: &'e SvgExporter<'a>) -> Self {
+        Self {
+            exporter,
+            defs: Definitions::new(),
+            num_defs: 0,
+            bitmap_defs: HashSet::new(),
+            ancestors: vec![],
+        }
+    }
+
+    fn into_document(
+        self,
+        bounds: &swf::Rectangle,
+        background_color: Option<&Color>,
+        content: Group,
+    ) -> Document {
+        let (x, y) = (bounds.x_min.get(), bounds.y_min.get());
+        let width = (bounds.x_max - bounds.x_min).get();
+        let height = (bounds.y_max - bounds.y_min).get();
+        let mut document = Document::new()
+            .set("width", f64::from(width) / 20.0)
+            .set("height", f64::from(height) / 20.0)
+            .set("viewBox", (x, y, width, height))
+            .set("xmlns:xlink", "http://www.w3.org/1999/xlink");
+        if self.num_defs > 0 {
+            document = document.add(self.defs);
+        }
+        if let Some(color) = background_color {
+            let background = Rectangle::new()
+                .set("x", x)
+                .set("y", y)
+                .set("width", width)
+                .set("height", height)
+                .set("fill", rgba(color));
+            document = document.add(background);
+        }
+        document.add(content)
+    }
+
+    fn next_id(&mut self, prefix: &str) -> String {
+        let id = format!("{}{}", prefix, self.num_defs);
+        self.num_defs += 1;
+        id
+    }
+
+    /// Draws the objects of a display list in depth order, clipping the objects covered by a mask.
+    fn display_list(
+        &mut self,
+        parent: &mut Group,
+        list: &BTreeMap<Depth, DisplayObject>,
+        frame: u16,
+    ) {
+        let mut clip: Option<(Depth, Group)> = None;
+        for (&depth, object) in list {
+            if let Some((clip_depth, _)) = clip {
+                if depth > clip_depth {
+                    parent.append(clip.take().unwrap().1);
+                }
+            }
+            if object.clip_depth > 0 {
+                if let Some((_, clipped)) = clip.take() {
+                    parent.append(clipped);
+                }
+                let id = self.next_id("c");
+                let mut clip_path = ClipPath::new().set("id", id.as_str());
+                let matrix = object.matrix;
+                self.clip_paths(&mut clip_path, object, matrix, frame);
+                self.defs.append(clip_path);
+                let clipped = Group::new().set("clip-path", format!("url(#{})", id));
+                clip = Some((object.clip_depth, clipped));
+            } else if object.is_visible {
+                let group = self.display_object(object, frame);
+                match &mut clip {
+                    Some((_, clipped)) => clipped.append(group),
+                    None => parent.append(group),
+                }
+            }
+        }
+        if let Some((_, clipped)) = clip {
+            parent.append(clipped);
+        }
+    }
+
+    /// Draws a display object as a group carrying its transform.
+    fn display_object(&mut self, object: &DisplayObject, frame: u16) -> Group {
+        if self.ancestors.contains(&object.id) {
+            log::warn!("Character {} contains itself", object.id);
+            return Group::new();
+        }
+        let mut group = Group::new().set("transform", svg_matrix(&object.matrix));
+        if !object.color_transform.is_identity() {
+            let id = self.next_id("t");
+            self.defs
+                .append(color_transform_filter(&id, &object.color_transform));
+            group.assign("filter", format!("url(#{})", id));
+        }
+        // Children are placed relative to the frame this object was placed on.
+        let frame = frame.saturating_sub(object.place_frame);
+        self.ancestors.push(object.id);
+        match self.exporter.characters.get(&object.id) {
+            Some(Character::Shape(shape)) => self.shape(&mut group, shape),
+            Some(Character::MorphShape(morph_shape)) => {
+                self.shape(&mut group, &morph_shape_at(morph_shape, object.ratio));
+            }
+            Some(Character::Sprite(sprite)) => {
+                let frame = frame % sprite.num_frames.max(1);
+                self.display_list(&mut group, &display_list(&sprite.tags, frame), frame);
+            }
+            Some(Character::Button(button)) => {
+                self.display_list(&mut group, &button_display_list(button), 0);
+            }
+            None => (),
+        }
+        self.ancestors.pop();
+        group
+    }
+
+    /// Adds the fill paths of a display object to a clip path, flattening its transforms because
+    /// a `<clipPath>` can't contain groups.
+    fn clip_paths(
+        &mut self,
+        clip_path: &mut ClipPath,
+        object: &DisplayObject,
+        matrix: Matrix,
+        frame: u16,
+    ) {
+        if self.ancestors.contains(&object.id) {
+            log::warn!("Character {} contains itself", object.id);
+            return;
+        }
+        let frame = frame.saturating_sub(object.place_frame);
+        let list = match self.exporter.characters.get(&object.id) {
+            Some(Character::Shape(shape)) => {
+                clip_shape(clip_path, shape, &matrix);
+                return;
+            }
+            Some(Character::MorphShape(morph_shape)) => {
+                clip_shape(
+                    clip_path,
+                    &morph_shape_at(morph_shape, object.ratio),
+                    &matrix,
+                );
+                return;
+            }
+            Some(Character::Sprite(sprite)) => {
+                let frame = frame % sprite.num_frames.max(1);
+                (display_list(&sprite.tags, frame), frame)
+            }
+            Some(Character::Button(button)) => (button_display_list(button), 0),
+            None => return,
+        };
+        let (list, frame) = list;
+        // Masks inside of masks are ignored.
+        self.ancestors.push(object.id);
+        for child in list.values().filter(|child| child.clip_depth == 0) {
+            self.clip_paths(clip_path, child, matrix * child.matrix, frame);
+        }
+        self.ancestors.pop();
+    }
+
+    fn shape(&mut self, parent: &mut Group, shape: &swf::Shape) {
+        for path in DistilledShape::from(shape).paths {
+            let svg_path = match path {
+                DrawPath::Fill { style, commands } => SvgPath::new()
+                    .set("fill", self.paint(style))
+                    .set("fill-rule", fill_rule(shape))
+                    .set("d", path_data(&commands, false)),
+                DrawPath::Stroke {
+                    style,
+                    is_closed,
+                    commands,
+                } => {
+                    let stroke = match &style.fill_style {
+                        Some(fill_style) => self.paint(fill_style),
+                        None => rgba(&style.color),
+                    };
+                    // Only DefineShape4 has scaling flags; the strokes of older shapes always
+                    // scale.
+                    let is_non_scaling =
+                        shape.version >= 4 && !style.allow_scale_x && !style.allow_scale_y;
+                    // Flash draws strokes at least 1 pixel (20 twips) wide, which matters for
+                    // the common "hairline" strokes of 1 twip. Non-scaling strokes are measured
+                    // in screen pixels instead of twips.
+                    let width = f64::from(style.width.get());
+                    let width = if is_non_scaling {
+                        (width / 20.0).max(1.0)
+                    } else {
+                        width.max(20.0)
+                    };
+                    let mut svg_path = SvgPath::new()
+                        .set("fill", "none")
+                        .set("stroke", stroke)
+                        .set("stroke-width", width)
+                        .set(
+                            "stroke-linecap",
+                            match style.start_cap {
+                                LineCapStyle::Round => "round",
+                                LineCapStyle::Square => "square",
+                                LineCapStyle::None => "butt",
+                            },
+                        )
+                        .set(
+                            "stroke-linejoin",
+                            match style.join_style {
+                                LineJoinStyle::Round => "round",
+                                LineJoinStyle::Bevel => "bevel",
+                                LineJoinStyle::Miter(_) => "miter",
+                            },
+                        )
+                        .set("d", path_data(&commands, is_closed));
+                    if let LineJoinStyle::Miter(miter_limit) = style.join_style {
+                        svg_path.assign("stroke-miterlimit", miter_limit);
+                    }
+                    if is_non_scaling {
+                        svg_path.assign("vector-effect", "non-scaling-stroke");
+                    }
+                    svg_path
+                }
+            };
+            parent.append(svg_path);
+        }
+    }
+
+    /// Returns the SVG paint for a fill style, adding a paint server to the definitions if needed.
+    fn paint(&mut self, style: &FillStyle) -> String {
+        let id = match style {
+            FillStyle::Color(color) => return rgba(color),
+            FillStyle::LinearGradient(gradient) => {
+                // Gradients are defined in a square from -16384 to 16384 twips.
+                let svg_gradient = LinearGradient::new()
+                    .set("x1", -16384)
+                    .set("x2", 16384)
+                    .set("y1", 0)
+                    .set("y2", 0);
+                self.gradient(svg_gradient, gradient)
+            }
+            FillStyle::RadialGradient(gradient) => {
+                let svg_gradient = RadialGradient::new()
+                    .set("cx", 0)
+                    .set("cy", 0)
+                    .set("r", 16384);
+                self.gradient(svg_gradient, gradient)
+            }
+            FillStyle::FocalGradient {
+                gradient,
+                focal_point,
+            } => {
+                let svg_gradient = RadialGradient::new()
+                    .set("cx", 0)
+                    .set("cy", 0)
+                    .set("r", 16384)
+                    .set("fx", focal_point * 16384.0)
+                    .set("fy", 0);
+                self.gradient(svg_gradient, gradient)
+            }
+            FillStyle::Bitmap {
+                id,
+                matrix,
+                is_smoothed,
+                is_repeating: _,
+            } => {
+                let bitmap = match self.exporter.bitmaps.get(id) {
+                    Some(bitmap) => bitmap,
+                    None => return "none".to_string(),
+                };
+                // Every use of a bitmap refers to a single pattern holding the image.
+                // SVG patterns always repeat, so clamped bitmap fills are drawn repeating as well.
+                if self.bitmap_defs.insert(*id) {
+                    let image = Image::new()
+                        .set("width", bitmap.width)
+                        .set("height", bitmap.height)
+                        .set("xlink:href", bitmap.uri.as_str());
+                    let pattern = Pattern::new()
+                        .set("id", format!("b{}", id))
+                        .set("patternUnits", "userSpaceOnUse")
+                        .set("width", bitmap.width)
+                        .set("height", bitmap.height)
+                        .add(image);
+                    self.defs.append(pattern);
+                }
+                let fill_id = self.next_id("f");
+                let mut pattern = Pattern::new()
+                    .set("id", fill_id.as_str())
+                    .set("xlink:href", format!("#b{}", id))
+                    .set(
+                        "patternTransform",
+                        svg_matrix(&Matrix::from(matrix.clone())),
+                    );
+                if !is_smoothed {
+                    pattern.assign("image-rendering", "optimizeSpeed");
+                }
+                self.defs.append(pattern);
+                fill_id
+            }
+        };
+        format!("url(#{})", id)
+    }
+
+    fn gradient<T: Node>(&mut self, mut svg_gradient: T, gradient: &Gradient) -> String {
+        let id = self.next_id("f");
+        svg_gradient.assign("id", id.as_str());
+        svg_gradient.assign("gradientUnits", "userSpaceOnUse");
+        svg_gradient.assign(
+            "gradientTransform",
+            svg_matrix(&Matrix::from(gradient.matrix.clone())),
+        );
+        svg_gradient.assign(
+            "spreadMethod",
+            match gradient.spread {
+                GradientSpread::Pad => "pad",
+                GradientSpread::Reflect => "reflect",
+                GradientSpread::Repeat => "repeat",
+            },
+        );
+        if gradient.interpolation == GradientInterpolation::LinearRGB {
+            svg_gradient.assign("color-interpolation", "linearRGB");
+        }
+        for record in &gradient.records {
+            let stop = Stop::new()
+                .set("offset", f32::from(record.ratio) / 255.0)
+                .set("stop-color", rgb(&record.color))
+                .set("stop-opacity", f32::from(record.color.a) / 255.0);
+            svg_gradient.append(stop);
+        }
+        self.defs.append(svg_gradient);
+        id
+    }
+}
+
+/// Interpolates a morph shape at a ratio between 0 (the start shape) and 65535 (the end shape).
+fn morph_shape_at(morph_shape: &swf::DefineMorphShape, ratio: u16) -> swf::Shape {
+    let mut shape = interpolate_morph_shape(&morph_shape.start, &morph_shape.end, ratio);
+    // Like DefineShape4, only DefineMorphShape2 has line styles with scaling flags.
+    if morph_shape.version < 2 {
+        shape.version = 3;
+    }
+    shape
+}
+
+/// Adds the fills of a shape to a clip path.
+fn clip_shape(clip_path: &mut ClipPath, shape: &swf::Shape, matrix: &Matrix) {
+    for path in DistilledShape::from(shape).paths {
+        if let DrawPath::Fill { commands, .. } = path {
+            let svg_path = SvgPath::new()
+                .set("transform", svg_matrix(matrix))
+                .set("clip-rule", fill_rule(shape))
+                .set("d", path_data(&commands, false));
+            clip_path.append(svg_path);
+        }
+    }
+}
+
+/// A filter that applies a color transform to straight (not premultiplied) colors.
+fn color_transform_filter(id: &str, color_transform: &ColorTransform) -> Filter {
+    let ColorTransform {
+        r_mult,
+        g_mult,
+        b_mult,
+        a_mult,
+        r_add,
+        g_add,
+        b_add,
+        a_add,
+    } = *color_transform;
+    let values = format!(
+        "{} 0 0 0 {} 0 {} 0 0 {} 0 0 {} 0 {} 0 0 0 {} {}",
+        r_mult, r_add, g_mult, g_add, b_mult, b_add, a_mult, a_add
+    );
+    let mut color_matrix = Element::new("feColorMatrix");
+    color_matrix.assign("type", "matrix");
+    color_matrix.assign("values", values);
+    Filter::new()
+        .set("id", id)
+        .set("color-interpolation-filters", "sRGB")
+        .add(color_matrix)
+}
+
+/// The SVG fill rule of a shape. Shapes use the even-odd rule unless they were drawn by
+/// DefineShape4 with the non-zero winding rule.
+fn fill_rule(shape: &swf::Shape) -> &'static str {
+    if shape.has_fill_winding_rule {
+        "nonzero"
+    } else {
+        "evenodd"
+    }
+}
+
+fn path_data(commands: &[DrawCommand], is_closed: bool) -> Data {
+    let mut data = Data::new();
+    for command in commands {
+        data = match command {
+            DrawCommand::MoveTo { x, y } => data.move_to((x.get(), y.get())),
+            DrawCommand::LineTo { x, y } => data.line_to((x.get(), y.get())),
+            DrawCommand::CurveTo { x1, y1, x2, y2 } => {
+                data.quadratic_curve_to((x1.get(), y1.get(), x2.get(), y2.get()))
+            }
+        };
+    }
+    if is_closed {
+        data = data.close();
+    }
+    data
+}
+
+fn svg_matrix(matrix: &Matrix) -> String {
+    format!(
+        "matrix({} {} {} {} {} {})",
+        matrix.a,
+        matrix.b,
+        matrix.c,
+        matrix.d,
+        matrix.tx.get(),
+        matrix.ty.get()
+    )
+}
+
+fn rgb(color: &Color) -> String {
+    format!("rgb({},{},{})", color.r, color.g, color.b)
+}
+
+fn rgba(color: &Color) -> String {
+    format!(
+        "rgba({},{},{},{})",
+        color.r,
+        color.g,
+        color.b,
+        f32::from(color.a) / 255.0
+    )
+}
+
+fn embed_bitmap(bitmap: Bitmap) -> Result<EmbeddedBitmap, Box<dyn Error>> {
+    let (width, height) = (bitmap.width, bitmap.height);
+    let image = match bitmap.data {
+        BitmapFormat::Rgb(rgb) => image::DynamicImage::ImageRgb8(
+            image::RgbImage::from_raw(width, height, rgb)
+                .ok_or("Bitmap data doesn't match its size")?,
+        ),
+        BitmapFormat::Rgba(mut rgba) => {
+            unmultiply_alpha_rgba(&mut rgba);
+            image::DynamicImage::ImageRgba8(
+                image::RgbaImage::from_raw(width, height, rgba)
+                    .ok_or("Bitmap data doesn't match its size")?,
+            )
+        }
+    };
+    let mut png = Vec::new();
+    image.write_to(&mut png, image::ImageOutputFormat::Png)?;
+    Ok(EmbeddedBitmap {
+        uri: format!("data:image/png;base64,{}", base64::encode(&png)),
+        width,
+        height,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ruffle_core::swf::{
+        GradientRecord, LineStyle, ShapeRecord, ShapeStyles, StyleChangeData, Twips,
+    };
+
+    const RED: Color = Color {
+        r: 255,
+        g: 0,
+        b: 0,
+        a: 255,
+    };
+
+    /// A DefineShape4 square of 100 twips drawn with the given fill and line styles.
+    fn square(fill_style: Option<FillStyle>, line_style: Option<LineStyle>) -> swf::Shape {
+        let bounds = swf::Rectangle {
+            x_min: Twips::new(0),
+            x_max: Twips::new(100),
+            y_min: Twips::new(0),
+            y_max: Twips::new(100),
+        };
+        let edge = |dx, dy| ShapeRecord::StraightEdge {
+            delta_x: Twips::new(dx),
+            delta_y: Twips::new(dy),
+        };
+        swf::Shape {
+            version: 4,
+            id: 1,
+            shape_bounds: bounds.clone(),
+            edge_bounds: bounds,
+            has_fill_winding_rule: false,
+            has_non_scaling_strokes: true,
+            has_scaling_strokes: true,
+            shape: vec![
+                ShapeRecord::StyleChange(StyleChangeData {
+                    move_to: Some((Twips::new(0), Twips::new(0))),
+                    fill_style_0: None,
+                    fill_style_1: fill_style.as_ref().map(|_| 1),
+                    line_style: line_style.as_ref().map(|_| 1),
+                    new_styles: None,
+                }),
+                edge(100, 0),
+                edge(0, 100),
+                edge(-100, 0),
+                edge(0, -100),
+            ],
+            styles: ShapeStyles {
+                fill_styles: fill_style.into_iter().collect(),
+                line_styles: line_style.into_iter().collect(),
+            },
+        }
+    }
+
+    fn render(shape: swf::Shape) -> String {
+        let swf = swf::Swf {
+            header: swf::Header {
+                version: 10,
+                compression: swf::Compression::None,
+                stage_size: Default::default(),
+                frame_rate: 24.0,
+                num_frames: 1,
+            },
+            tags: vec![Tag::DefineShape(shape)],
+        };
+        SvgExporter::new(&swf).shape(1).unwrap().to_string()
+    }
+
+    fn scaled_stroke(width: i32) -> LineStyle {
+        LineStyle {
+            allow_scale_x: true,
+            allow_scale_y: true,
+            ..LineStyle::new_v1(Twips::new(width), RED)
+        }
+    }
+
+    fn non_scaling_stroke(width: i32) -> LineStyle {
+        LineStyle::new_v1(Twips::new(width), RED)
+    }
+
+    #[test]
+    fn fill() {
+        let svg = render(square(Some(FillStyle::Color(RED)), None));
+        assert!(svg.contains(r#"viewBox="0 0 100 100""#));
+        assert!(svg.contains(r#"width="5""#));
+        assert!(svg.contains(r#"fill="rgba(255,0,0,1)""#));
+        assert!(svg.contains(r#"fill-rule="evenodd""#));
+        assert!(!svg.contains("stroke"));
+    }
+
+    #[test]
+    fn scaled_strokes() {
+        let svg = render(square(None, Some(scaled_stroke(40))));
+        assert!(svg.contains(r#"fill="none""#));
+        assert!(svg.contains(r#"stroke="rgba(255,0,0,1)""#));
+        assert!(svg.contains(r#"stroke-width="40""#));
+        assert!(!svg.contains("vector-effect"));
+
+        // Hairlines are drawn 1 pixel wide.
+        let svg = render(square(None, Some(scaled_stroke(1))));
+        assert!(svg.contains(r#"stroke-width="20""#));
+    }
+
+    #[test]
+    fn non_scaling_strokes() {
+        let svg = render(square(None, Some(non_scaling_stroke(60))));
+        assert!(svg.contains(r#"stroke-width="3""#));
+        assert!(svg.contains(r#"vector-effect="non-scaling-stroke""#));
+
+        let svg = render(square(None, Some(non_scaling_stroke(1))));
+        assert!(svg.contains(r#"stroke-width="1""#));
+
+        // Strokes of shapes older than DefineShape4 always scale.
+        let mut shape = square(None, Some(non_scaling_stroke(60)));
+        shape.version = 3;
+        let svg = render(shape);
+        assert!(svg.contains(r#"stroke-width="60""#));
+        assert!(!svg.contains("vector-effect"));
+    }
+
+    #[test]
+    fn gradient() {
+        let gradient = Gradient {
+            matrix: swf::Matrix::new(),
+            spread: GradientSpread::Reflect,
+            interpolation: GradientInterpolation::RGB,
+            records: vec![
+                GradientRecord {
+                    ratio: 0,
+                    color: RED,
+                },
+                GradientRecord {
+                    ratio: 255,
+                    color: Color {
+                        r: 0,
+                        g: 0,
+                        b: 255,
+                        a: 255,
+                    },
+                },
+            ],
+        };
+        let svg = render(square(Some(FillStyle::LinearGradient(gradient)), None));
+        assert!(svg.contains("<linearGradient"));
+        assert!(svg.contains(r#"id="f0""#));
+        assert!(svg.contains(r#"gradientUnits="userSpaceOnUse""#));
+        assert!(svg.contains(r#"spreadMethod="reflect""#));
+        assert!(svg.contains(r#"fill="url(#f0)""#));
+        assert!(svg.contains(r#"stop-color="rgb(255,0,0)""#));
+        assert!(svg.contains(r#"stop-color="rgb(0,0,255)""#));
+    }
+}
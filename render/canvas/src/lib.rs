@@ -4,13 +4,14 @@ use ruffle_core::backend::render::{
 };
 use ruffle_core::color_transform::ColorTransform;
 use ruffle_core::shape_utils::{DistilledShape, DrawCommand};
+use ruffle_core::stage::StageQuality;
 use ruffle_web_common::JsResult;
 use std::collections::HashMap;
 use std::convert::TryInto;
-use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen::{Clamped, JsCast, JsValue};
 use web_sys::{
     CanvasGradient, CanvasPattern, CanvasRenderingContext2d, Element, HtmlCanvasElement,
-    HtmlImageElement, Path2d, SvgsvgElement,
+    HtmlImageElement, ImageData, Path2d, SvgsvgElement,
 };
 
 type Error = Box<dyn std::error::Error>;
@@ -23,12 +24,25 @@ pub struct WebCanvasRenderBackend {
     cur_render_target: usize,
     color_matrix: Element,
     shapes: Vec<ShapeData>,
-    bitmaps: Vec<BitmapData>,
+    bitmaps: Vec<Option<BitmapData>>,
     id_to_bitmap: HashMap<CharacterId, BitmapHandle>,
     viewport_width: u32,
     viewport_height: u32,
     use_color_transform_hack: bool,
     pixelated_property_value: &'static str,
+
+    /// Whether the canvas smooths scaled images. Disabled at low quality.
+    image_smoothing: bool,
+
+    /// The state saved by `begin_render_to_bitmap`, restored once the bitmap is finished.
+    bitmap_render_state: Option<BitmapRenderState>,
+}
+
+/// The state of an in-progress render to a bitmap.
+struct BitmapRenderState {
+    target: Option<BitmapHandle>,
+    viewport_width: u32,
+    viewport_height: u32,
 }
 
 /// Canvas-drawable shape data extracted from an SWF file.
@@ -109,6 +123,10 @@ struct BitmapData {
     width: u32,
     height: u32,
     data: String,
+
+    /// The decoded pixels of this bitmap, used for reading back bitmap contents.
+    /// Not available for pure JPEG bitmaps, which are decoded by the browser.
+    pixels: Option<Bitmap>,
 }
 
 impl WebCanvasRenderBackend {
@@ -219,6 +237,8 @@ impl WebCanvasRenderBackend {
             } else {
                 "pixelated"
             },
+            image_smoothing: true,
+            bitmap_render_state: None,
         };
         Ok(renderer)
     }
@@ -251,6 +271,23 @@ impl WebCanvasRenderBackend {
         ))
     }
 
+    /// Converts a decoded bitmap into an image element that can be drawn to the canvas.
+    fn bitmap_to_bitmap_data(bitmap: Bitmap) -> Result<BitmapData, Box<dyn std::error::Error>> {
+        let (width, height) = (bitmap.width, bitmap.height);
+        let png = Self::bitmap_to_png_data_uri(bitmap.clone())?;
+
+        let image = HtmlImageElement::new().unwrap();
+        image.set_src(&png);
+
+        Ok(BitmapData {
+            image,
+            width,
+            height,
+            data: png,
+            pixels: Some(bitmap),
+        })
+    }
+
     // Pushes a fresh canvas onto the stack to use as a render target.
     fn push_render_target(&mut self) {
         self.cur_render_target += 1;
@@ -282,6 +319,9 @@ impl WebCanvasRenderBackend {
         canvas.set_height(self.viewport_height);
         self.canvas = canvas.clone();
         self.context = context.clone();
+        // Resizing the canvas resets the context state.
+        self.context
+            .set_image_smoothing_enabled(self.image_smoothing);
         let width = self.canvas.width();
         let height = self.canvas.height();
         self.context
@@ -382,12 +422,13 @@ impl WebCanvasRenderBackend {
         image.set_src(&jpeg_encoded);
 
         let handle = BitmapHandle(self.bitmaps.len());
-        self.bitmaps.push(BitmapData {
+        self.bitmaps.push(Some(BitmapData {
             image,
             width: metadata.width.into(),
             height: metadata.height.into(),
             data: jpeg_encoded,
-        });
+            pixels: None,
+        }));
         self.id_to_bitmap.insert(id, handle);
         Ok(BitmapInfo {
             handle,
@@ -396,24 +437,14 @@ impl WebCanvasRenderBackend {
         })
     }
 
-    fn register_bitmap_raw(
+    fn register_bitmap_decoded(
         &mut self,
         id: CharacterId,
         bitmap: Bitmap,
     ) -> Result<BitmapInfo, Box<Error>> {
         let (width, height) = (bitmap.width, bitmap.height);
-        let png = Self::bitmap_to_png_data_uri(bitmap)?;
-
-        let image = HtmlImageElement::new().unwrap();
-        image.set_src(&png);
-
         let handle = BitmapHandle(self.bitmaps.len());
-        self.bitmaps.push(BitmapData {
-            image,
-            width,
-            height,
-            data: png,
-        });
+        self.bitmaps.push(Some(Self::bitmap_to_bitmap_data(bitmap)?));
 
         self.id_to_bitmap.insert(id, handle);
         Ok(BitmapInfo {
@@ -422,6 +453,38 @@ impl WebCanvasRenderBackend {
             height: height.try_into().expect("JPEG dimensions too large"),
         })
     }
+
+    /// Converts a shape to canvas commands, or to an SVG image if it can't be drawn directly.
+    /// `bitmaps` maps the IDs used by the bitmap fills of the shape to bitmaps.
+    fn shape_data(
+        &self,
+        shape: DistilledShape,
+        bitmaps: &[(CharacterId, BitmapHandle)],
+    ) -> ShapeData {
+        let mut bitmap_data = HashMap::new();
+        for (id, handle) in bitmaps {
+            if let Some(Some(data)) = self.bitmaps.get(handle.0) {
+                bitmap_data.insert(*id, (&data.data[..], data.width, data.height));
+            }
+        }
+
+        swf_shape_to_canvas_commands(
+            &shape,
+            &bitmap_data,
+            self.pixelated_property_value,
+            &self.context,
+        )
+        .unwrap_or_else(|| swf_shape_to_svg(shape, &bitmap_data, self.pixelated_property_value))
+    }
+}
+
+/// The bitmap fills of shapes from the drawing API refer to their bitmaps by index.
+fn drawing_bitmap_ids(bitmaps: &[BitmapHandle]) -> Vec<(CharacterId, BitmapHandle)> {
+    bitmaps
+        .iter()
+        .enumerate()
+        .map(|(i, handle)| (i as CharacterId, *handle))
+        .collect()
 }
 
 impl RenderBackend for WebCanvasRenderBackend {
@@ -430,31 +493,43 @@ impl RenderBackend for WebCanvasRenderBackend {
         self.viewport_height = height;
     }
 
+    fn set_quality(&mut self, quality: StageQuality) {
+        self.image_smoothing = quality != StageQuality::Low;
+    }
+
     fn register_shape(&mut self, shape: DistilledShape) -> ShapeHandle {
         let handle = ShapeHandle(self.shapes.len());
-
-        let mut bitmaps = HashMap::new();
-        for (id, handle) in &self.id_to_bitmap {
-            let bitmap_data = &self.bitmaps[handle.0];
-            bitmaps.insert(
-                *id,
-                (&bitmap_data.data[..], bitmap_data.width, bitmap_data.height),
-            );
-        }
-
-        let data = swf_shape_to_canvas_commands(
-            &shape,
-            &bitmaps,
-            self.pixelated_property_value,
-            &self.context,
-        )
-        .unwrap_or_else(|| swf_shape_to_svg(shape, &bitmaps, self.pixelated_property_value));
-
+        let bitmaps: Vec<_> = self
+            .id_to_bitmap
+            .iter()
+            .map(|(id, handle)| (*id, *handle))
+            .collect();
+        let data = self.shape_data(shape, &bitmaps);
         self.shapes.push(data);
+        handle
+    }
 
+    fn register_drawing(&mut self, shape: DistilledShape, bitmaps: &[BitmapHandle]) -> ShapeHandle {
+        let handle = ShapeHandle(self.shapes.len());
+        let bitmaps = drawing_bitmap_ids(bitmaps);
+        let data = self.shape_data(shape, &bitmaps);
+        self.shapes.push(data);
         handle
     }
 
+    fn replace_drawing(
+        &mut self,
+        handle: ShapeHandle,
+        shape: DistilledShape,
+        bitmaps: &[BitmapHandle],
+    ) {
+        let bitmaps = drawing_bitmap_ids(bitmaps);
+        let data = self.shape_data(shape, &bitmaps);
+        if let Some(old) = self.shapes.get_mut(handle.0) {
+            *old = data;
+        }
+    }
+
     fn register_glyph_shape(&mut self, glyph: &swf::Glyph) -> ShapeHandle {
         // Per SWF19 p.164, the FontBoundsTable can contain empty bounds for every glyph (reserved).
         // SWF19 says this is true through SWFv7, but it seems like it might be generally true?
@@ -507,7 +582,7 @@ impl RenderBackend for WebCanvasRenderBackend {
         } else {
             let bitmap = ruffle_core::backend::render::decode_define_bits_jpeg(data, None)
                 .expect("Unable to decode DefineBitsJPEG2");
-            self.register_bitmap_raw(id, bitmap)
+            self.register_bitmap_decoded(id, bitmap)
                 .expect("Unable to register bitmap")
         }
     }
@@ -521,7 +596,7 @@ impl RenderBackend for WebCanvasRenderBackend {
         let bitmap =
             ruffle_core::backend::render::decode_define_bits_jpeg(jpeg_data, Some(alpha_data))
                 .expect("Unable to decode DefineBitsJPEG2");
-        self.register_bitmap_raw(id, bitmap)
+        self.register_bitmap_decoded(id, bitmap)
             .expect("Unable to register bitmap")
     }
 
@@ -529,30 +604,65 @@ impl RenderBackend for WebCanvasRenderBackend {
         let bitmap = ruffle_core::backend::render::decode_define_bits_lossless(swf_tag)
             .expect("Error decoding DefineBitsLossless");
 
-        let png =
-            Self::bitmap_to_png_data_uri(bitmap).expect("Unable to encode bitmap as PNG data URI");
-
-        let image = HtmlImageElement::new().unwrap();
-        image.set_src(&png);
+        self.register_bitmap_decoded(swf_tag.id, bitmap)
+            .expect("Unable to encode bitmap as PNG data URI")
+    }
 
+    fn register_bitmap_raw(&mut self, width: u32, height: u32, rgba: Vec<u8>) -> BitmapHandle {
+        let bitmap = Bitmap {
+            width,
+            height,
+            data: BitmapFormat::Rgba(rgba),
+        };
         let handle = BitmapHandle(self.bitmaps.len());
-        self.bitmaps.push(BitmapData {
-            image,
-            width: swf_tag.width.into(),
-            height: swf_tag.height.into(),
-            data: png,
-        });
-        self.id_to_bitmap.insert(swf_tag.id, handle);
-        BitmapInfo {
-            handle,
-            width: swf_tag.width,
-            height: swf_tag.height,
+        self.bitmaps.push(Some(
+            Self::bitmap_to_bitmap_data(bitmap).expect("Unable to encode bitmap as PNG data URI"),
+        ));
+        handle
+    }
+
+    fn update_texture(
+        &mut self,
+        handle: BitmapHandle,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> BitmapHandle {
+        let bitmap = Bitmap {
+            width,
+            height,
+            data: BitmapFormat::Rgba(rgba),
+        };
+        match Self::bitmap_to_bitmap_data(bitmap) {
+            Ok(bitmap_data) => {
+                if let Some(Some(old)) = self.bitmaps.get_mut(handle.0) {
+                    *old = bitmap_data;
+                }
+            }
+            Err(e) => log::error!("Unable to update bitmap: {}", e),
+        }
+        handle
+    }
+
+    fn get_bitmap_pixels(&mut self, handle: BitmapHandle) -> Option<Bitmap> {
+        self.bitmaps
+            .get(handle.0)
+            .and_then(|bitmap_data| bitmap_data.as_ref())
+            .and_then(|bitmap_data| bitmap_data.pixels.clone())
+    }
+
+    fn unregister_bitmap(&mut self, handle: BitmapHandle) {
+        // Keep the slot so that the other handles stay valid.
+        if let Some(bitmap_data) = self.bitmaps.get_mut(handle.0) {
+            *bitmap_data = None;
         }
     }
 
     fn begin_frame(&mut self, clear: Color) {
         // Reset canvas transform in case it was left in a dirty state.
         self.context.reset_transform().unwrap();
+        self.context
+            .set_image_smoothing_enabled(self.image_smoothing);
 
         let width = self.canvas.width();
         let height = self.canvas.height();
@@ -567,10 +677,105 @@ impl RenderBackend for WebCanvasRenderBackend {
         // Noop
     }
 
+    fn begin_render_to_bitmap(
+        &mut self,
+        target: Option<BitmapHandle>,
+        width: u32,
+        height: u32,
+        clear: Color,
+    ) {
+        if self.bitmap_render_state.is_some() {
+            log::error!("begin_render_to_bitmap called while already rendering to a bitmap");
+            return;
+        }
+
+        // Render into a fresh offscreen canvas the size of the bitmap.
+        self.bitmap_render_state = Some(BitmapRenderState {
+            target,
+            viewport_width: self.viewport_width,
+            viewport_height: self.viewport_height,
+        });
+        self.viewport_width = width;
+        self.viewport_height = height;
+        self.push_render_target();
+
+        let existing_pixels = target
+            .and_then(|handle| self.bitmaps.get(handle.0))
+            .and_then(|bitmap_data| bitmap_data.as_ref())
+            .and_then(|bitmap_data| bitmap_data.pixels.clone());
+        if let Some(Bitmap {
+            width: bitmap_width,
+            height: bitmap_height,
+            data: BitmapFormat::Rgba(mut data),
+        }) = existing_pixels
+        {
+            // Canvas image data is not premultiplied.
+            ruffle_core::backend::render::unmultiply_alpha_rgba(&mut data[..]);
+            if let Ok(image_data) = ImageData::new_with_u8_clamped_array_and_sh(
+                Clamped(&mut data[..]),
+                bitmap_width,
+                bitmap_height,
+            ) {
+                self.context
+                    .put_image_data(&image_data, 0.0, 0.0)
+                    .warn_on_error();
+            }
+        } else if clear.a > 0 {
+            let color = format!(
+                "rgba({}, {}, {}, {})",
+                clear.r,
+                clear.g,
+                clear.b,
+                f32::from(clear.a) / 255.0
+            );
+            self.context.set_fill_style(&color.into());
+            self.context
+                .fill_rect(0.0, 0.0, width.into(), height.into());
+        }
+    }
+
+    fn end_render_to_bitmap(&mut self) -> Option<Bitmap> {
+        let state = self.bitmap_render_state.take()?;
+        let width = self.viewport_width;
+        let height = self.viewport_height;
+
+        let image_data = self
+            .context
+            .get_image_data(0.0, 0.0, width.into(), height.into());
+        self.pop_render_target();
+        self.viewport_width = state.viewport_width;
+        self.viewport_height = state.viewport_height;
+
+        let mut rgba = match image_data {
+            Ok(image_data) => image_data.data().0,
+            Err(_) => {
+                log::error!("Unable to read back rendered bitmap");
+                return None;
+            }
+        };
+
+        // Convert to the premultiplied alpha used by bitmap handles.
+        for pixel in rgba.chunks_exact_mut(4) {
+            let a = u16::from(pixel[3]);
+            pixel[0] = (u16::from(pixel[0]) * a / 255) as u8;
+            pixel[1] = (u16::from(pixel[1]) * a / 255) as u8;
+            pixel[2] = (u16::from(pixel[2]) * a / 255) as u8;
+        }
+
+        if let Some(target) = state.target {
+            self.update_texture(target, width, height, rgba.clone());
+        }
+        Some(Bitmap {
+            width,
+            height,
+            data: BitmapFormat::Rgba(rgba),
+        })
+    }
+
     fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform) {
         self.set_transform(transform);
         self.set_color_filter(transform);
-        if let Some(bitmap) = self.bitmaps.get(bitmap.0) {
+        if let Some(Some(bitmap)) = self.bitmaps.get(bitmap.0) {
             let _ = self
                 .context
                 .draw_image_with_html_image_element(&bitmap.image, 0.0, 0.0);
@@ -667,6 +872,20 @@ impl RenderBackend for WebCanvasRenderBackend {
         }
     }
 
+    fn draw_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color) {
+        self.context.reset_transform().unwrap();
+        let color = format!(
+            "rgba({},{},{},{})",
+            color.r,
+            color.g,
+            color.b,
+            f32::from(color.a) / 255.0
+        );
+        self.context.set_fill_style(&color.into());
+        self.context
+            .fill_rect(x.into(), y.into(), width.into(), height.into());
+    }
+
     fn push_mask(&mut self) {
         // In the canvas backend, masks are implemented using two render targets.
         // We render the masker clips to the first render target.
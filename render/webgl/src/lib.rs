@@ -4,6 +4,7 @@ use ruffle_core::backend::render::{
     Transform,
 };
 use ruffle_core::shape_utils::DistilledShape;
+use ruffle_core::stage::StageQuality;
 use ruffle_render_common_tess::{GradientSpread, GradientType, ShapeTessellator, Vertex};
 use ruffle_web_common::JsResult;
 use wasm_bindgen::{JsCast, JsValue};
@@ -36,13 +37,19 @@ pub struct WebGlRenderBackend {
     msaa_buffers: Option<MsaaBuffers>,
     msaa_sample_count: u32,
 
+    /// The MSAA sample count used at high quality, limited by the device.
+    max_msaa_sample_count: u32,
+
+    /// Whether bitmaps may be smoothed. Disabled at low quality.
+    smooth_bitmaps: bool,
+
     color_program: ShaderProgram,
     bitmap_program: ShaderProgram,
     gradient_program: ShaderProgram,
 
     shape_tessellator: ShapeTessellator,
 
-    textures: Vec<(swf::CharacterId, Texture)>,
+    textures: Vec<(swf::CharacterId, Option<Texture>)>,
     meshes: Vec<Mesh>,
 
     quad_shape: ShapeHandle,
@@ -63,6 +70,9 @@ pub struct WebGlRenderBackend {
     viewport_width: f32,
     viewport_height: f32,
     view_matrix: [[f32; 4]; 4],
+
+    /// The offscreen target of an in-progress `begin_render_to_bitmap` call.
+    bitmap_render_target: Option<BitmapRenderTarget>,
 }
 
 impl WebGlRenderBackend {
@@ -163,6 +173,8 @@ impl WebGlRenderBackend {
 
             msaa_buffers: None,
             msaa_sample_count,
+            max_msaa_sample_count: msaa_sample_count,
+            smooth_bitmaps: true,
 
             color_program,
             gradient_program,
@@ -188,11 +200,13 @@ impl WebGlRenderBackend {
             blend_func: (Gl::SRC_ALPHA, Gl::ONE_MINUS_SRC_ALPHA),
             mult_color: None,
             add_color: None,
+
+            bitmap_render_target: None,
         };
 
         let quad_mesh = renderer.build_quad_mesh()?;
         renderer.meshes.push(quad_mesh);
-        renderer.build_msaa_buffers()?;
+        renderer.rebuild_msaa_buffers();
         renderer.build_matrices();
 
         Ok(renderer)
@@ -281,7 +295,7 @@ impl WebGlRenderBackend {
             draws: vec![Draw {
                 draw_type: DrawType::Bitmap(BitmapDraw {
                     matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
-                    id: 0,
+                    handle: None,
 
                     is_smoothed: true,
                     is_repeating: false,
@@ -306,17 +320,20 @@ impl WebGlRenderBackend {
         Ok(shader)
     }
 
-    fn build_msaa_buffers(&mut self) -> Result<(), Error> {
-        if self.gl2.is_none() || self.msaa_sample_count <= 1 {
-            self.gl.bind_framebuffer(Gl::FRAMEBUFFER, None);
-            self.gl.bind_renderbuffer(Gl::RENDERBUFFER, None);
-            return Ok(());
+    /// Rebuilds the MSAA buffers, falling back to rendering without MSAA if they can't be
+    /// created.
+    fn rebuild_msaa_buffers(&mut self) {
+        if let Err(e) = self.build_msaa_buffers() {
+            log::error!("Unable to create MSAA buffers, disabling MSAA: {}", e);
+            self.msaa_sample_count = 1;
+            let _ = self.build_msaa_buffers();
         }
+    }
 
-        let gl = self.gl2.as_ref().unwrap();
-
+    fn build_msaa_buffers(&mut self) -> Result<(), Error> {
         // Delete previous buffers, if they exist.
         if let Some(msaa_buffers) = self.msaa_buffers.take() {
+            let gl = &self.gl;
             gl.delete_renderbuffer(Some(&msaa_buffers.color_renderbuffer));
             gl.delete_renderbuffer(Some(&msaa_buffers.stencil_renderbuffer));
             gl.delete_framebuffer(Some(&msaa_buffers.render_framebuffer));
@@ -324,6 +341,14 @@ impl WebGlRenderBackend {
             gl.delete_texture(Some(&msaa_buffers.framebuffer_texture));
         }
 
+        if self.gl2.is_none() || self.msaa_sample_count <= 1 {
+            self.gl.bind_framebuffer(Gl::FRAMEBUFFER, None);
+            self.gl.bind_renderbuffer(Gl::RENDERBUFFER, None);
+            return Ok(());
+        }
+
+        let gl = self.gl2.as_ref().unwrap();
+
         // Create frame and render buffers.
         let render_framebuffer = gl
             .create_framebuffer()
@@ -342,7 +367,7 @@ impl WebGlRenderBackend {
         gl.bind_renderbuffer(Gl2::RENDERBUFFER, Some(&color_renderbuffer));
         gl.renderbuffer_storage_multisample(
             Gl2::RENDERBUFFER,
-            4,
+            self.msaa_sample_count as i32,
             Gl2::RGB8,
             self.viewport_width as i32,
             self.viewport_height as i32,
@@ -354,7 +379,7 @@ impl WebGlRenderBackend {
         gl.bind_renderbuffer(Gl2::RENDERBUFFER, Some(&stencil_renderbuffer));
         gl.renderbuffer_storage_multisample(
             Gl2::RENDERBUFFER,
-            4,
+            self.msaa_sample_count as i32,
             Gl2::STENCIL_INDEX8,
             self.viewport_width as i32,
             self.viewport_height as i32,
@@ -374,6 +399,17 @@ impl WebGlRenderBackend {
             Some(&stencil_renderbuffer),
         );
 
+        // The context may not support the sample count, or may have been lost.
+        if gl.check_framebuffer_status(Gl2::FRAMEBUFFER) != Gl2::FRAMEBUFFER_COMPLETE {
+            gl.bind_framebuffer(Gl2::FRAMEBUFFER, None);
+            gl.bind_renderbuffer(Gl2::RENDERBUFFER, None);
+            gl.delete_renderbuffer(Some(&color_renderbuffer));
+            gl.delete_renderbuffer(Some(&stencil_renderbuffer));
+            gl.delete_framebuffer(Some(&render_framebuffer));
+            gl.delete_framebuffer(Some(&color_framebuffer));
+            return Err("MSAA framebuffer is incomplete".into());
+        }
+
         let framebuffer_texture = gl.create_texture().ok_or("Unable to create texture")?;
         gl.bind_texture(Gl2::TEXTURE_2D, Some(&framebuffer_texture));
         gl.tex_parameteri(Gl2::TEXTURE_2D, Gl2::TEXTURE_MAG_FILTER, Gl::NEAREST as i32);
@@ -423,17 +459,17 @@ impl WebGlRenderBackend {
         Ok(())
     }
 
-    fn register_shape_internal(&mut self, shape: DistilledShape) -> ShapeHandle {
+    /// Tessellates a shape into a mesh. The bitmap fills of shapes from the drawing API
+    /// refer to `bitmaps` by index, while those of other shapes use character IDs.
+    fn build_mesh(&mut self, shape: DistilledShape, bitmaps: Option<&[BitmapHandle]>) -> Mesh {
         use ruffle_render_common_tess::DrawType as TessDrawType;
 
-        let handle = ShapeHandle(self.meshes.len());
-
         let textures = &self.textures;
         let lyon_mesh = self.shape_tessellator.tessellate_shape(shape, |id| {
-            textures
-                .iter()
-                .find(|(other_id, _tex)| *other_id == id)
-                .map(|tex| (tex.1.width, tex.1.height))
+            fill_bitmap_handle(textures, bitmaps, id)
+                .and_then(|handle| textures.get(handle.0))
+                .and_then(|(_id, tex)| tex.as_ref())
+                .map(|tex| (tex.width, tex.height))
         });
 
         let mut draws = Vec::with_capacity(lyon_mesh.len());
@@ -523,7 +559,7 @@ impl WebGlRenderBackend {
                     Draw {
                         draw_type: DrawType::Bitmap(BitmapDraw {
                             matrix: bitmap.matrix,
-                            id: bitmap.id,
+                            handle: fill_bitmap_handle(&self.textures, bitmaps, bitmap.id),
                             is_smoothed: bitmap.is_smoothed,
                             is_repeating: bitmap.is_repeating,
                         }),
@@ -573,8 +609,13 @@ impl WebGlRenderBackend {
             }
         }
 
-        self.meshes.push(Mesh { draws });
+        Mesh { draws }
+    }
 
+    fn register_shape_internal(&mut self, shape: DistilledShape) -> ShapeHandle {
+        let handle = ShapeHandle(self.meshes.len());
+        let mesh = self.build_mesh(shape, None);
+        self.meshes.push(mesh);
         handle
     }
 
@@ -604,6 +645,19 @@ impl WebGlRenderBackend {
         Ok(vao)
     }
 
+    /// Releases the buffers and vertex arrays of a mesh.
+    fn delete_mesh(&self, mesh: &Mesh) {
+        for draw in &mesh.draws {
+            self.gl.delete_buffer(Some(&draw.vertex_buffer));
+            self.gl.delete_buffer(Some(&draw.index_buffer));
+            if let Some(gl2) = &self.gl2 {
+                gl2.delete_vertex_array(Some(&draw.vao));
+            } else {
+                self.vao_ext.delete_vertex_array_oes(Some(&draw.vao));
+            }
+        }
+    }
+
     /// Binds a VAO.
     fn bind_vertex_array(&self, vao: Option<&WebGlVertexArrayObject>) {
         if let Some(gl2) = &self.gl2 {
@@ -647,9 +701,104 @@ impl WebGlRenderBackend {
         id: swf::CharacterId,
         bitmap: Bitmap,
     ) -> Result<BitmapInfo, Error> {
+        let (width, height) = (bitmap.width, bitmap.height);
+        let texture = self.create_texture(bitmap)?;
+
+        let handle = BitmapHandle(self.textures.len());
+        self.textures.push((id, Some(texture)));
+
+        Ok(BitmapInfo {
+            handle,
+            width: width as u16,
+            height: height as u16,
+        })
+    }
+
+    /// Creates and binds an offscreen framebuffer for `begin_render_to_bitmap`,
+    /// saving the state needed to restore rendering to the main framebuffer.
+    fn create_bitmap_render_target(
+        &mut self,
+        target: Option<BitmapHandle>,
+        width: u32,
+        height: u32,
+    ) -> Result<BitmapRenderTarget, Error> {
+        let gl = &self.gl;
+
+        let texture = gl.create_texture().ok_or("Unable to create texture")?;
+        gl.bind_texture(Gl::TEXTURE_2D, Some(&texture));
+        gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
+            Gl::TEXTURE_2D,
+            0,
+            Gl::RGBA as i32,
+            width as i32,
+            height as i32,
+            0,
+            Gl::RGBA,
+            Gl::UNSIGNED_BYTE,
+            None,
+        )
+        .into_js_result()?;
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MAG_FILTER, Gl::NEAREST as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MIN_FILTER, Gl::NEAREST as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_S, Gl::CLAMP_TO_EDGE as i32);
+        gl.tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_WRAP_T, Gl::CLAMP_TO_EDGE as i32);
+        gl.bind_texture(Gl::TEXTURE_2D, None);
+
+        let stencil_renderbuffer = gl
+            .create_renderbuffer()
+            .ok_or("Unable to create renderbuffer")?;
+        gl.bind_renderbuffer(Gl::RENDERBUFFER, Some(&stencil_renderbuffer));
+        gl.renderbuffer_storage(
+            Gl::RENDERBUFFER,
+            Gl::STENCIL_INDEX8,
+            width as i32,
+            height as i32,
+        );
+        gl.bind_renderbuffer(Gl::RENDERBUFFER, None);
+
+        let framebuffer = gl
+            .create_framebuffer()
+            .ok_or("Unable to create framebuffer")?;
+        gl.bind_framebuffer(Gl::FRAMEBUFFER, Some(&framebuffer));
+        gl.framebuffer_texture_2d(
+            Gl::FRAMEBUFFER,
+            Gl::COLOR_ATTACHMENT0,
+            Gl::TEXTURE_2D,
+            Some(&texture),
+            0,
+        );
+        gl.framebuffer_renderbuffer(
+            Gl::FRAMEBUFFER,
+            Gl::STENCIL_ATTACHMENT,
+            Gl::RENDERBUFFER,
+            Some(&stencil_renderbuffer),
+        );
+
+        Ok(BitmapRenderTarget {
+            target,
+            width,
+            height,
+            framebuffer,
+            texture,
+            stencil_renderbuffer,
+            viewport_width: self.viewport_width,
+            viewport_height: self.viewport_height,
+            view_matrix: self.view_matrix,
+            num_masks: self.num_masks,
+            num_masks_active: self.num_masks_active,
+            write_stencil_mask: self.write_stencil_mask,
+            test_stencil_mask: self.test_stencil_mask,
+            next_stencil_mask: self.next_stencil_mask,
+            mask_stack: std::mem::take(&mut self.mask_stack),
+        })
+    }
+
+    /// Uploads the given bitmap to a new WebGL texture.
+    /// The decoded bitmap is retained so that its pixels may be read back later.
+    fn create_texture(&mut self, bitmap: Bitmap) -> Result<Texture, Error> {
         let texture = self.gl.create_texture().unwrap();
         self.gl.bind_texture(Gl::TEXTURE_2D, Some(&texture));
-        match bitmap.data {
+        match &bitmap.data {
             BitmapFormat::Rgb(data) => self
                 .gl
                 .tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_opt_u8_array(
@@ -661,7 +810,7 @@ impl WebGlRenderBackend {
                     0,
                     Gl::RGB,
                     Gl::UNSIGNED_BYTE,
-                    Some(&data),
+                    Some(data),
                 )
                 .into_js_result()?,
             BitmapFormat::Rgba(data) => self
@@ -675,7 +824,7 @@ impl WebGlRenderBackend {
                     0,
                     Gl::RGBA,
                     Gl::UNSIGNED_BYTE,
-                    Some(&data),
+                    Some(data),
                 )
                 .into_js_result()?,
         }
@@ -690,20 +839,11 @@ impl WebGlRenderBackend {
         self.gl
             .tex_parameteri(Gl::TEXTURE_2D, Gl::TEXTURE_MAG_FILTER, Gl::LINEAR as i32);
 
-        let handle = BitmapHandle(self.textures.len());
-        self.textures.push((
-            id,
-            Texture {
-                texture,
-                width: bitmap.width,
-                height: bitmap.height,
-            },
-        ));
-
-        Ok(BitmapInfo {
-            handle,
-            width: bitmap.width as u16,
-            height: bitmap.height as u16,
+        Ok(Texture {
+            texture,
+            width: bitmap.width,
+            height: bitmap.height,
+            bitmap,
         })
     }
 }
@@ -713,14 +853,47 @@ impl RenderBackend for WebGlRenderBackend {
         self.viewport_width = width as f32;
         self.viewport_height = height as f32;
         self.gl.viewport(0, 0, width as i32, height as i32);
-        self.build_msaa_buffers().unwrap();
+        self.rebuild_msaa_buffers();
         self.build_matrices();
     }
 
+    fn set_quality(&mut self, quality: StageQuality) {
+        let msaa_sample_count = match quality {
+            StageQuality::Low => 1,
+            StageQuality::Medium => self.max_msaa_sample_count.min(2),
+            StageQuality::High | StageQuality::Best => self.max_msaa_sample_count,
+        };
+        self.smooth_bitmaps = quality != StageQuality::Low;
+        if msaa_sample_count != self.msaa_sample_count {
+            self.msaa_sample_count = msaa_sample_count;
+            self.rebuild_msaa_buffers();
+        }
+    }
+
     fn register_shape(&mut self, shape: DistilledShape) -> ShapeHandle {
         self.register_shape_internal(shape)
     }
 
+    fn register_drawing(&mut self, shape: DistilledShape, bitmaps: &[BitmapHandle]) -> ShapeHandle {
+        let handle = ShapeHandle(self.meshes.len());
+        let mesh = self.build_mesh(shape, Some(bitmaps));
+        self.meshes.push(mesh);
+        handle
+    }
+
+    fn replace_drawing(
+        &mut self,
+        handle: ShapeHandle,
+        shape: DistilledShape,
+        bitmaps: &[BitmapHandle],
+    ) {
+        let mesh = self.build_mesh(shape, Some(bitmaps));
+        if let Some(old) = self.meshes.get(handle.0) {
+            self.delete_mesh(old);
+            self.meshes[handle.0] = mesh;
+        }
+    }
+
     fn register_glyph_shape(&mut self, glyph: &swf::Glyph) -> ShapeHandle {
         let shape = swf::Shape {
             version: 2,
@@ -781,6 +954,57 @@ impl RenderBackend for WebGlRenderBackend {
             .expect("Unable to register bitmap")
     }
 
+    fn register_bitmap_raw(&mut self, width: u32, height: u32, rgba: Vec<u8>) -> BitmapHandle {
+        let bitmap = Bitmap {
+            width,
+            height,
+            data: BitmapFormat::Rgba(rgba),
+        };
+        self.register_bitmap(0, bitmap)
+            .expect("Unable to register bitmap")
+            .handle
+    }
+
+    fn update_texture(
+        &mut self,
+        handle: BitmapHandle,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> BitmapHandle {
+        let bitmap = Bitmap {
+            width,
+            height,
+            data: BitmapFormat::Rgba(rgba),
+        };
+        match self.create_texture(bitmap) {
+            Ok(texture) => {
+                if let Some((_, Some(old))) = self.textures.get_mut(handle.0) {
+                    self.gl.delete_texture(Some(&old.texture));
+                    *old = texture;
+                }
+            }
+            Err(e) => log::error!("Unable to update bitmap: {}", e),
+        }
+        handle
+    }
+
+    fn get_bitmap_pixels(&mut self, handle: BitmapHandle) -> Option<Bitmap> {
+        self.textures
+            .get(handle.0)
+            .and_then(|(_, texture)| texture.as_ref())
+            .map(|texture| texture.bitmap.clone())
+    }
+
+    fn unregister_bitmap(&mut self, handle: BitmapHandle) {
+        // Keep the slot so that the other handles stay valid.
+        if let Some((_id, texture)) = self.textures.get_mut(handle.0) {
+            if let Some(texture) = texture.take() {
+                self.gl.delete_texture(Some(&texture.texture));
+            }
+        }
+    }
+
     fn begin_frame(&mut self, clear: Color) {
         self.num_masks = 0;
         self.num_masks_active = 0;
@@ -811,6 +1035,126 @@ impl RenderBackend for WebGlRenderBackend {
         self.gl.clear(Gl::COLOR_BUFFER_BIT | Gl::STENCIL_BUFFER_BIT);
     }
 
+    fn begin_render_to_bitmap(
+        &mut self,
+        target: Option<BitmapHandle>,
+        width: u32,
+        height: u32,
+        clear: Color,
+    ) {
+        if self.bitmap_render_target.is_some() {
+            log::error!("begin_render_to_bitmap called while already rendering to a bitmap");
+            return;
+        }
+
+        let render_target = match self.create_bitmap_render_target(target, width, height) {
+            Ok(render_target) => render_target,
+            Err(e) => {
+                log::error!("Unable to create bitmap render target: {}", e);
+                return;
+            }
+        };
+        self.bitmap_render_target = Some(render_target);
+
+        self.gl.viewport(0, 0, width as i32, height as i32);
+        self.viewport_width = width as f32;
+        self.viewport_height = height as f32;
+        self.build_matrices();
+
+        self.num_masks = 0;
+        self.num_masks_active = 0;
+        self.write_stencil_mask = 0;
+        self.test_stencil_mask = 0;
+        self.next_stencil_mask = 1;
+
+        self.active_program = std::ptr::null();
+        self.mask_state_dirty = true;
+
+        self.mult_color = None;
+        self.add_color = None;
+
+        self.set_stencil_state();
+        self.gl.clear_color(
+            clear.r as f32 / 255.0,
+            clear.g as f32 / 255.0,
+            clear.b as f32 / 255.0,
+            clear.a as f32 / 255.0,
+        );
+        self.gl.stencil_mask(0xff);
+        self.gl.clear(Gl::COLOR_BUFFER_BIT | Gl::STENCIL_BUFFER_BIT);
+
+        // Start with the existing contents of the target bitmap.
+        if let Some(target) = target {
+            self.render_bitmap(target, &Default::default());
+        }
+    }
+
+    fn end_render_to_bitmap(&mut self) -> Option<Bitmap> {
+        let render_target = self.bitmap_render_target.take()?;
+        let (width, height) = (render_target.width, render_target.height);
+
+        // Read back the rendered pixels. WebGL returns the rows bottom-up.
+        let row_size = width as usize * 4;
+        let mut data = vec![0u8; row_size * height as usize];
+        self.gl
+            .read_pixels_with_opt_u8_array(
+                0,
+                0,
+                width as i32,
+                height as i32,
+                Gl::RGBA,
+                Gl::UNSIGNED_BYTE,
+                Some(&mut data),
+            )
+            .warn_on_error();
+        let rgba: Vec<u8> = data
+            .chunks_exact(row_size)
+            .rev()
+            .flatten()
+            .copied()
+            .collect();
+
+        // Restore the state of the main render target.
+        if let Some(msaa_buffers) = &self.msaa_buffers {
+            self.gl
+                .bind_framebuffer(Gl::FRAMEBUFFER, Some(&msaa_buffers.render_framebuffer));
+        } else {
+            self.gl.bind_framebuffer(Gl::FRAMEBUFFER, None);
+        }
+        self.gl.delete_framebuffer(Some(&render_target.framebuffer));
+        self.gl
+            .delete_renderbuffer(Some(&render_target.stencil_renderbuffer));
+        self.gl.delete_texture(Some(&render_target.texture));
+
+        self.viewport_width = render_target.viewport_width;
+        self.viewport_height = render_target.viewport_height;
+        self.gl.viewport(
+            0,
+            0,
+            self.viewport_width as i32,
+            self.viewport_height as i32,
+        );
+        self.view_matrix = render_target.view_matrix;
+        self.num_masks = render_target.num_masks;
+        self.num_masks_active = render_target.num_masks_active;
+        self.write_stencil_mask = render_target.write_stencil_mask;
+        self.test_stencil_mask = render_target.test_stencil_mask;
+        self.next_stencil_mask = render_target.next_stencil_mask;
+        self.mask_stack = render_target.mask_stack;
+        self.active_program = std::ptr::null();
+        self.mask_state_dirty = true;
+        self.set_stencil_state();
+
+        if let Some(target) = render_target.target {
+            self.update_texture(target, width, height, rgba.clone());
+        }
+        Some(Bitmap {
+            width,
+            height,
+            data: BitmapFormat::Rgba(rgba),
+        })
+    }
+
     fn end_frame(&mut self) {
         // Resolve MSAA, if we're using it (WebGL2).
         if let (Some(ref gl), Some(ref msaa_buffers)) = (&self.gl2, &self.msaa_buffers) {
@@ -892,14 +1236,14 @@ impl RenderBackend for WebGlRenderBackend {
         // TODO: Might be better to make this separate code to render the bitmap
         // instead of going through render_shape. But render_shape already handles
         // masking etc.
-        if let Some((id, bitmap)) = self.textures.get(bitmap.0) {
+        if let Some((_id, Some(texture))) = self.textures.get(bitmap.0) {
             // Adjust the quad draw to use the target bitmap.
             let mesh = &mut self.meshes[self.quad_shape.0];
             let draw = &mut mesh.draws[0];
-            let width = bitmap.width as f32;
-            let height = bitmap.height as f32;
-            if let DrawType::Bitmap(BitmapDraw { id: draw_id, .. }) = &mut draw.draw_type {
-                *draw_id = *id;
+            let width = texture.width as f32;
+            let height = texture.height as f32;
+            if let DrawType::Bitmap(BitmapDraw { handle, .. }) = &mut draw.draw_type {
+                *handle = Some(bitmap);
             }
 
             // Scale the quad to the bitmap's dimensions.
@@ -1026,12 +1370,14 @@ impl RenderBackend for WebGlRenderBackend {
                     );
                 }
                 DrawType::Bitmap(bitmap) => {
-                    let texture = &self
-                        .textures
-                        .iter()
-                        .find(|(id, _tex)| *id == bitmap.id)
-                        .unwrap()
-                        .1;
+                    let texture = match bitmap
+                        .handle
+                        .and_then(|handle| self.textures.get(handle.0))
+                        .and_then(|(_id, tex)| tex.as_ref())
+                    {
+                        Some(texture) => texture,
+                        None => continue,
+                    };
 
                     program.uniform_matrix3fv(
                         &self.gl,
@@ -1045,7 +1391,7 @@ impl RenderBackend for WebGlRenderBackend {
                     program.uniform1i(&self.gl, ShaderUniform::BitmapTexture, 0);
 
                     // Set texture parameters.
-                    let filter = if bitmap.is_smoothed {
+                    let filter = if bitmap.is_smoothed && self.smooth_bitmaps {
                         Gl::LINEAR as i32
                     } else {
                         Gl::NEAREST as i32
@@ -1117,6 +1463,27 @@ impl RenderBackend for WebGlRenderBackend {
         }
     }
 
+    fn draw_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color) {
+        self.set_stencil_state();
+
+        // Solid rectangles are cleared with a scissor box, which has its origin at the bottom left.
+        self.gl.clear_color(
+            f32::from(color.r) / 255.0,
+            f32::from(color.g) / 255.0,
+            f32::from(color.b) / 255.0,
+            f32::from(color.a) / 255.0,
+        );
+        self.gl.enable(Gl::SCISSOR_TEST);
+        self.gl.scissor(
+            x as i32,
+            (self.viewport_height - y - height) as i32,
+            width as i32,
+            height as i32,
+        );
+        self.gl.clear(Gl::COLOR_BUFFER_BIT);
+        self.gl.disable(Gl::SCISSOR_TEST);
+    }
+
     fn push_mask(&mut self) {
         // Desktop draws the masker to the stencil buffer, one bit per mask.
         // Masks-within-masks are handled as a bitmask.
@@ -1167,6 +1534,7 @@ struct Texture {
     width: u32,
     height: u32,
     texture: WebGlTexture,
+    bitmap: Bitmap,
 }
 
 #[derive(Clone, Debug)]
@@ -1183,7 +1551,7 @@ struct Gradient {
 #[derive(Clone, Debug)]
 struct BitmapDraw {
     matrix: [[f32; 3]; 3],
-    id: swf::CharacterId,
+    handle: Option<BitmapHandle>,
     is_repeating: bool,
     is_smoothed: bool,
 }
@@ -1192,6 +1560,22 @@ struct Mesh {
     draws: Vec<Draw>,
 }
 
+/// Finds the bitmap used by a bitmap fill. The bitmap fills of shapes from the drawing API
+/// refer to `bitmaps` by index, while those of other shapes use character IDs.
+fn fill_bitmap_handle(
+    textures: &[(swf::CharacterId, Option<Texture>)],
+    bitmaps: Option<&[BitmapHandle]>,
+    id: swf::CharacterId,
+) -> Option<BitmapHandle> {
+    match bitmaps {
+        Some(bitmaps) => bitmaps.get(usize::from(id)).copied(),
+        None => textures
+            .iter()
+            .position(|(other_id, _tex)| *other_id == id)
+            .map(BitmapHandle),
+    }
+}
+
 #[allow(dead_code)]
 struct Draw {
     draw_type: DrawType,
@@ -1207,6 +1591,27 @@ enum DrawType {
     Bitmap(BitmapDraw),
 }
 
+/// An offscreen framebuffer used by `begin_render_to_bitmap`, along with the
+/// state of the main render target to restore afterwards.
+struct BitmapRenderTarget {
+    target: Option<BitmapHandle>,
+    width: u32,
+    height: u32,
+    framebuffer: WebGlFramebuffer,
+    texture: WebGlTexture,
+    stencil_renderbuffer: WebGlRenderbuffer,
+
+    viewport_width: f32,
+    viewport_height: f32,
+    view_matrix: [[f32; 4]; 4],
+    num_masks: u32,
+    num_masks_active: u32,
+    write_stencil_mask: u32,
+    test_stencil_mask: u32,
+    next_stencil_mask: u32,
+    mask_stack: Vec<(u32, u32)>,
+}
+
 struct MsaaBuffers {
     color_renderbuffer: WebGlRenderbuffer,
     stencil_renderbuffer: WebGlRenderbuffer,
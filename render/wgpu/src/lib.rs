@@ -9,6 +9,7 @@ use ruffle_core::backend::render::{
     Transform,
 };
 use ruffle_core::shape_utils::{DistilledShape, DrawPath};
+use ruffle_core::stage::StageQuality;
 use std::convert::TryInto;
 use swf::{CharacterId, DefineBitsLossless, Glyph};
 
@@ -18,7 +19,9 @@ use raw_window_handle::HasRawWindowHandle;
 
 use crate::pipelines::Pipelines;
 use crate::shapes::{Draw, DrawType, GradientUniforms, IncompleteDrawType, Mesh};
-use crate::target::{RenderTarget, RenderTargetFrame, SwapChainTarget};
+use crate::target::{
+    RenderTarget, RenderTargetFrame, SwapChainTarget, TextureTarget, TextureTargetFrame,
+};
 use crate::utils::{
     build_view_matrix, create_buffer_with_data, ruffle_path_to_lyon_path, swf_bitmap_to_gl_matrix,
     swf_to_gl_matrix,
@@ -41,16 +44,19 @@ pub struct WgpuRenderBackend<T: RenderTarget> {
     queue: Rc<wgpu::Queue>,
     target: T,
     msaa_sample_count: u32,
+
+    /// Whether bitmaps drawn with `render_bitmap` are smoothed. Disabled at low quality.
+    smooth_bitmaps: bool,
     pipelines: Pipelines,
     frame_buffer_view: wgpu::TextureView,
     depth_texture_view: wgpu::TextureView,
-    current_frame: Option<(T::Frame, wgpu::CommandEncoder)>,
+    current_frame: Option<(Frame<T::Frame>, wgpu::CommandEncoder)>,
     register_encoder: wgpu::CommandEncoder,
     meshes: Vec<Mesh>,
     viewport_width: f32,
     viewport_height: f32,
     view_matrix: [[f32; 4]; 4],
-    textures: Vec<(swf::CharacterId, Texture)>,
+    textures: Vec<(swf::CharacterId, Option<Texture>)>,
     num_masks: u32,
     num_masks_active: u32,
     write_stencil_mask: u32,
@@ -60,6 +66,43 @@ pub struct WgpuRenderBackend<T: RenderTarget> {
     quad_vbo: wgpu::Buffer,
     quad_ibo: wgpu::Buffer,
     quad_tex_transforms: wgpu::Buffer,
+    bitmap_render_target: Option<BitmapRenderTarget<T::Frame>>,
+}
+
+/// The frame currently being rendered to: either a frame of the main render target,
+/// or an offscreen texture used by `begin_render_to_bitmap`.
+#[derive(Debug)]
+enum Frame<F: RenderTargetFrame> {
+    Target(F),
+    Bitmap(TextureTargetFrame),
+}
+
+impl<F: RenderTargetFrame> RenderTargetFrame for Frame<F> {
+    fn view(&self) -> &wgpu::TextureView {
+        match self {
+            Frame::Target(frame) => frame.view(),
+            Frame::Bitmap(frame) => frame.view(),
+        }
+    }
+}
+
+/// An offscreen texture used by `begin_render_to_bitmap`, along with the
+/// state of the main render target to restore afterwards.
+struct BitmapRenderTarget<F: RenderTargetFrame> {
+    target: Option<BitmapHandle>,
+    texture_target: TextureTarget,
+    frame: Option<(Frame<F>, wgpu::CommandEncoder)>,
+    frame_buffer_view: wgpu::TextureView,
+    depth_texture_view: wgpu::TextureView,
+    viewport_width: f32,
+    viewport_height: f32,
+    view_matrix: [[f32; 4]; 4],
+    num_masks: u32,
+    num_masks_active: u32,
+    write_stencil_mask: u32,
+    test_stencil_mask: u32,
+    next_stencil_mask: u32,
+    mask_stack: Vec<(u32, u32)>,
 }
 
 #[repr(C)]
@@ -199,6 +242,7 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             queue,
             target,
             msaa_sample_count,
+            smooth_bitmaps: true,
             pipelines,
             frame_buffer_view,
             depth_texture_view,
@@ -218,13 +262,14 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             quad_vbo,
             quad_ibo,
             quad_tex_transforms,
+            bitmap_render_target: None,
         })
     }
 
+    /// Tessellates a shape into a mesh. The bitmap fills of shapes from the drawing API
+    /// refer to `bitmaps` by index, while those of other shapes use character IDs.
     #[allow(clippy::cognitive_complexity)]
-    fn register_shape_internal(&mut self, shape: DistilledShape) -> ShapeHandle {
-        let handle = ShapeHandle(self.meshes.len());
-
+    fn build_mesh(&mut self, shape: DistilledShape, bitmaps: Option<&[BitmapHandle]>) -> Mesh {
         use lyon::tessellation::{FillOptions, StrokeOptions};
 
         let transforms_label = create_debug_label!("Shape {} transforms ubo", shape.id);
@@ -564,16 +609,21 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
                             continue;
                         }
 
-                        let texture = match self
-                            .textures
-                            .iter()
-                            .find(|(other_id, _tex)| *other_id == *id)
-                        {
+                        let texture = match bitmaps {
+                            Some(bitmaps) => bitmaps
+                                .get(usize::from(*id))
+                                .and_then(|handle| self.textures.get(handle.0)),
+                            None => self
+                                .textures
+                                .iter()
+                                .find(|(other_id, _tex)| *other_id == *id),
+                        };
+                        let texture = match texture.and_then(|(_id, tex)| tex.as_ref()) {
                             None => {
                                 log::error!("Couldn't fill shape with unknown bitmap {}", id);
                                 continue;
                             }
-                            Some(t) => &t.1,
+                            Some(t) => t,
                         };
                         let texture_view = texture.texture.create_default_view();
 
@@ -667,14 +717,19 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             &self.pipelines,
         );
 
-        self.meshes.push(Mesh {
+        Mesh {
             draws,
             transforms: transforms_ubo,
             colors_buffer: colors_ubo,
             colors_last: ColorTransform::default(),
             shape_id: shape.id,
-        });
+        }
+    }
 
+    fn register_shape_internal(&mut self, shape: DistilledShape) -> ShapeHandle {
+        let handle = ShapeHandle(self.meshes.len());
+        let mesh = self.build_mesh(shape, None);
+        self.meshes.push(mesh);
         handle
     }
 
@@ -684,6 +739,22 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
         bitmap: Bitmap,
         debug_str: &str,
     ) -> Result<BitmapInfo, Error> {
+        let (width, height) = (bitmap.width, bitmap.height);
+        let texture = self.create_texture(id, bitmap, debug_str);
+
+        let handle = BitmapHandle(self.textures.len());
+        self.textures.push((id, Some(texture)));
+
+        Ok(BitmapInfo {
+            handle,
+            width: width.try_into().unwrap(),
+            height: height.try_into().unwrap(),
+        })
+    }
+
+    /// Uploads the given bitmap to a new GPU texture.
+    /// A CPU-side copy of the RGBA data is retained so that it may be read back later.
+    fn create_texture(&mut self, id: swf::CharacterId, bitmap: Bitmap, debug_str: &str) -> Texture {
         let extent = wgpu::Extent3d {
             width: bitmap.width,
             height: bitmap.height,
@@ -741,149 +812,22 @@ impl<T: RenderTarget> WgpuRenderBackend<T> {
             extent,
         );
 
-        let handle = BitmapHandle(self.textures.len());
-        self.textures.push((
-            id,
-            Texture {
-                texture,
-                width: bitmap.width,
-                height: bitmap.height,
-            },
-        ));
-
-        Ok(BitmapInfo {
-            handle,
-            width: bitmap.width.try_into().unwrap(),
-            height: bitmap.height.try_into().unwrap(),
-        })
-    }
-
-    pub fn target(&self) -> &T {
-        &self.target
-    }
-
-    pub fn device(&self) -> &wgpu::Device {
-        &self.device
-    }
-
-    fn draw_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color) {
-        let (frame_output, encoder) = if let Some((frame_output, encoder)) = &mut self.current_frame
-        {
-            (frame_output, encoder)
-        } else {
-            return;
-        };
-
-        let world_matrix = [
-            [width, 0.0, 0.0, 0.0],
-            [0.0, height, 0.0, 0.0],
-            [0.0, 0.0, 1.0, 0.0],
-            [x, y, 0.0, 1.0],
-        ];
-
-        let mult_color = [
-            f32::from(color.r) / 255.0,
-            f32::from(color.g) / 255.0,
-            f32::from(color.b) / 255.0,
-            f32::from(color.a) / 255.0,
-        ];
-
-        let add_color = [0.0, 0.0, 0.0, 0.0];
-
-        let transforms_ubo = create_buffer_with_data(
-            &self.device,
-            bytemuck::cast_slice(&[Transforms {
-                view_matrix: self.view_matrix,
-                world_matrix,
-            }]),
-            wgpu::BufferUsage::UNIFORM,
-            create_debug_label!("Rectangle transfer buffer"),
-        );
-
-        let colors_ubo = create_buffer_with_data(
-            &self.device,
-            bytemuck::cast_slice(&[ColorAdjustments {
-                mult_color,
-                add_color,
-            }]),
-            wgpu::BufferUsage::UNIFORM,
-            create_debug_label!("Rectangle colors transfer buffer"),
-        );
-
-        let bind_group_label = create_debug_label!("Rectangle bind group");
-        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-            layout: &self.pipelines.color.bind_layout,
-            bindings: &[
-                wgpu::Binding {
-                    binding: 0,
-                    resource: wgpu::BindingResource::Buffer {
-                        buffer: &transforms_ubo,
-                        range: 0..std::mem::size_of::<Transforms>() as u64,
-                    },
-                },
-                wgpu::Binding {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Buffer {
-                        buffer: &colors_ubo,
-                        range: 0..std::mem::size_of::<ColorAdjustments>() as u64,
-                    },
-                },
-            ],
-            label: bind_group_label.as_deref(),
-        });
-
-        let (color_attachment, resolve_target) = if self.msaa_sample_count >= 2 {
-            (&self.frame_buffer_view, Some(frame_output.view()))
-        } else {
-            (frame_output.view(), None)
-        };
-        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-            color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
-                attachment: color_attachment,
-                load_op: wgpu::LoadOp::Load,
-                store_op: wgpu::StoreOp::Store,
-                clear_color: wgpu::Color::WHITE,
-                resolve_target,
-            }],
-            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachmentDescriptor {
-                attachment: &self.depth_texture_view,
-                depth_load_op: wgpu::LoadOp::Load,
-                depth_store_op: wgpu::StoreOp::Store,
-                stencil_load_op: wgpu::LoadOp::Load,
-                stencil_store_op: wgpu::StoreOp::Store,
-                clear_depth: 0.0,
-                clear_stencil: 0,
-            }),
-        });
-
-        render_pass.set_pipeline(&self.pipelines.color.pipeline_for(
-            self.num_masks,
-            self.num_masks_active,
-            self.test_stencil_mask,
-            self.write_stencil_mask,
-        ));
-        render_pass.set_bind_group(0, &bind_group, &[]);
-        render_pass.set_vertex_buffer(0, &self.quad_vbo, 0, 0);
-        render_pass.set_index_buffer(&self.quad_ibo, 0, 0);
-
-        if self.num_masks_active < self.num_masks {
-            render_pass.set_stencil_reference(self.write_stencil_mask);
-        } else {
-            render_pass.set_stencil_reference(self.test_stencil_mask);
+        Texture {
+            texture,
+            width: bitmap.width,
+            height: bitmap.height,
+            data,
         }
-
-        render_pass.draw_indexed(0..6, 0, 0..1);
     }
-}
-
-impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
-    fn set_viewport_dimensions(&mut self, width: u32, height: u32) {
-        // Avoid panics from creating 0-sized framebuffers.
-        let width = std::cmp::max(width, 1);
-        let height = std::cmp::max(height, 1);
-
-        self.target.resize(&self.device, width, height);
 
+    /// Creates the multisampled framebuffer and depth/stencil textures for a render target
+    /// of the given size.
+    fn create_frame_buffer_views(
+        &self,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+    ) -> (wgpu::TextureView, wgpu::TextureView) {
         let label = create_debug_label!("Framebuffer texture");
         let frame_buffer = self.device.create_texture(&wgpu::TextureDescriptor {
             label: label.as_deref(),
@@ -896,10 +840,9 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
             mip_level_count: 1,
             sample_count: self.msaa_sample_count,
             dimension: wgpu::TextureDimension::D2,
-            format: self.target.format(),
+            format,
             usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
         });
-        self.frame_buffer_view = frame_buffer.create_default_view();
 
         let label = create_debug_label!("Depth texture");
         let depth_texture = self.device.create_texture(&wgpu::TextureDescriptor {
@@ -916,17 +859,130 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
             format: wgpu::TextureFormat::Depth24PlusStencil8,
             usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
         });
-        self.depth_texture_view = depth_texture.create_default_view();
+
+        (
+            frame_buffer.create_default_view(),
+            depth_texture.create_default_view(),
+        )
+    }
+
+    /// Resets the mask state and clears the current frame.
+    fn clear_frame(&mut self, clear: Color) {
+        self.num_masks = 0;
+        self.num_masks_active = 0;
+        self.write_stencil_mask = 0;
+        self.test_stencil_mask = 0;
+        self.next_stencil_mask = 1;
+
+        if let Some((frame_output, encoder)) = &mut self.current_frame {
+            let (color_attachment, resolve_target) = if self.msaa_sample_count >= 2 {
+                (&self.frame_buffer_view, Some(frame_output.view()))
+            } else {
+                (frame_output.view(), None)
+            };
+            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                    attachment: color_attachment,
+                    load_op: wgpu::LoadOp::Clear,
+                    store_op: wgpu::StoreOp::Store,
+                    clear_color: wgpu::Color {
+                        r: f64::from(clear.r) / 255.0,
+                        g: f64::from(clear.g) / 255.0,
+                        b: f64::from(clear.b) / 255.0,
+                        a: f64::from(clear.a) / 255.0,
+                    },
+                    resolve_target,
+                }],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachmentDescriptor {
+                    attachment: &self.depth_texture_view,
+                    depth_load_op: wgpu::LoadOp::Clear,
+                    depth_store_op: wgpu::StoreOp::Store,
+                    stencil_load_op: wgpu::LoadOp::Clear,
+                    stencil_store_op: wgpu::StoreOp::Store,
+                    clear_depth: 0.0,
+                    clear_stencil: 0,
+                }),
+            });
+        }
+    }
+
+    pub fn target(&self) -> &T {
+        &self.target
+    }
+
+    pub fn device(&self) -> &wgpu::Device {
+        &self.device
+    }
+}
+
+impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
+    fn set_viewport_dimensions(&mut self, width: u32, height: u32) {
+        // Avoid panics from creating 0-sized framebuffers.
+        let width = std::cmp::max(width, 1);
+        let height = std::cmp::max(height, 1);
+
+        self.target.resize(&self.device, width, height);
+
+        let (frame_buffer_view, depth_texture_view) =
+            self.create_frame_buffer_views(width, height, self.target.format());
+        self.frame_buffer_view = frame_buffer_view;
+        self.depth_texture_view = depth_texture_view;
 
         self.viewport_width = width as f32;
         self.viewport_height = height as f32;
         self.view_matrix = build_view_matrix(width, height);
     }
 
+    fn set_quality(&mut self, quality: StageQuality) {
+        let msaa_sample_count = match quality {
+            StageQuality::Low => 1,
+            StageQuality::Medium => 2,
+            StageQuality::High | StageQuality::Best => 4,
+        };
+        self.smooth_bitmaps = quality != StageQuality::Low;
+        if msaa_sample_count == self.msaa_sample_count {
+            return;
+        }
+
+        if let Err(e) = self.pipelines.rebuild(&self.device, msaa_sample_count) {
+            log::error!(
+                "Couldn't rebuild pipelines for {}x MSAA: {}",
+                msaa_sample_count,
+                e
+            );
+            return;
+        }
+        self.msaa_sample_count = msaa_sample_count;
+        let (frame_buffer_view, depth_texture_view) = self.create_frame_buffer_views(
+            self.target.width(),
+            self.target.height(),
+            self.target.format(),
+        );
+        self.frame_buffer_view = frame_buffer_view;
+        self.depth_texture_view = depth_texture_view;
+    }
+
     fn register_shape(&mut self, shape: DistilledShape) -> ShapeHandle {
         self.register_shape_internal(shape)
     }
 
+    fn register_drawing(&mut self, shape: DistilledShape, bitmaps: &[BitmapHandle]) -> ShapeHandle {
+        let handle = ShapeHandle(self.meshes.len());
+        let mesh = self.build_mesh(shape, Some(bitmaps));
+        self.meshes.push(mesh);
+        handle
+    }
+
+    fn replace_drawing(
+        &mut self,
+        handle: ShapeHandle,
+        shape: DistilledShape,
+        bitmaps: &[BitmapHandle],
+    ) {
+        let mesh = self.build_mesh(shape, Some(bitmaps));
+        self.meshes[handle.0] = mesh;
+    }
+
     fn register_glyph_shape(&mut self, glyph: &Glyph) -> ShapeHandle {
         let shape = swf::Shape {
             version: 2,
@@ -987,13 +1043,64 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
             .expect("Unable to register bitmap")
     }
 
+    fn register_bitmap_raw(&mut self, width: u32, height: u32, rgba: Vec<u8>) -> BitmapHandle {
+        let bitmap = Bitmap {
+            width,
+            height,
+            data: BitmapFormat::Rgba(rgba),
+        };
+        self.register_bitmap(0, bitmap, "RAW")
+            .expect("Unable to register bitmap")
+            .handle
+    }
+
+    fn update_texture(
+        &mut self,
+        handle: BitmapHandle,
+        width: u32,
+        height: u32,
+        rgba: Vec<u8>,
+    ) -> BitmapHandle {
+        let id = if let Some((id, Some(_))) = self.textures.get(handle.0) {
+            *id
+        } else {
+            log::warn!("Tried to update nonexistent bitmap {:?}", handle);
+            return handle;
+        };
+
+        let bitmap = Bitmap {
+            width,
+            height,
+            data: BitmapFormat::Rgba(rgba),
+        };
+        let texture = self.create_texture(id, bitmap, "Updated");
+        self.textures[handle.0] = (id, Some(texture));
+        handle
+    }
+
+    fn get_bitmap_pixels(&mut self, handle: BitmapHandle) -> Option<Bitmap> {
+        let (_id, texture) = self.textures.get(handle.0)?;
+        texture.as_ref().map(|texture| Bitmap {
+            width: texture.width,
+            height: texture.height,
+            data: BitmapFormat::Rgba(texture.data.clone()),
+        })
+    }
+
+    fn unregister_bitmap(&mut self, handle: BitmapHandle) {
+        // Keep the slot so that the other handles stay valid.
+        if let Some((_id, texture)) = self.textures.get_mut(handle.0) {
+            *texture = None;
+        }
+    }
+
     fn begin_frame(&mut self, clear: Color) {
         assert!(self.current_frame.is_none());
         self.current_frame = match self.target.get_next_texture() {
             Ok(frame) => {
                 let label = create_debug_label!("Frame encoder");
                 Some((
-                    frame,
+                    Frame::Target(frame),
                     self.device
                         .create_command_encoder(&wgpu::CommandEncoderDescriptor {
                             label: label.as_deref(),
@@ -1005,46 +1112,124 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
                 None
             }
         };
-        self.num_masks = 0;
-        self.num_masks_active = 0;
-        self.write_stencil_mask = 0;
-        self.test_stencil_mask = 0;
-        self.next_stencil_mask = 1;
+        self.clear_frame(clear);
+    }
 
-        if let Some((frame_output, encoder)) = &mut self.current_frame {
-            let (color_attachment, resolve_target) = if self.msaa_sample_count >= 2 {
-                (&self.frame_buffer_view, Some(frame_output.view()))
-            } else {
-                (frame_output.view(), None)
-            };
-            encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
-                    attachment: color_attachment,
-                    load_op: wgpu::LoadOp::Clear,
-                    store_op: wgpu::StoreOp::Store,
-                    clear_color: wgpu::Color {
-                        r: f64::from(clear.r) / 255.0,
-                        g: f64::from(clear.g) / 255.0,
-                        b: f64::from(clear.b) / 255.0,
-                        a: f64::from(clear.a) / 255.0,
-                    },
-                    resolve_target,
-                }],
-                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachmentDescriptor {
-                    attachment: &self.depth_texture_view,
-                    depth_load_op: wgpu::LoadOp::Clear,
-                    depth_store_op: wgpu::StoreOp::Store,
-                    stencil_load_op: wgpu::LoadOp::Clear,
-                    stencil_store_op: wgpu::StoreOp::Store,
-                    clear_depth: 0.0,
-                    clear_stencil: 0,
-                }),
+    fn begin_render_to_bitmap(
+        &mut self,
+        target: Option<BitmapHandle>,
+        width: u32,
+        height: u32,
+        clear: Color,
+    ) {
+        if self.bitmap_render_target.is_some() {
+            log::error!("begin_render_to_bitmap called while already rendering to a bitmap");
+            return;
+        }
+
+        // Avoid panics from creating 0-sized textures.
+        let width = std::cmp::max(width, 1);
+        let height = std::cmp::max(height, 1);
+
+        let mut texture_target = TextureTarget::new(&self.device, (width, height));
+        let frame = match texture_target.get_next_texture() {
+            Ok(frame) => frame,
+            Err(wgpu::TimeOut) => {
+                log::warn!("Couldn't begin render to bitmap: timed out whilst aquiring texture");
+                return;
+            }
+        };
+        let (frame_buffer_view, depth_texture_view) =
+            self.create_frame_buffer_views(width, height, texture_target.format());
+
+        let label = create_debug_label!("Bitmap encoder");
+        let encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: label.as_deref(),
             });
+
+        self.bitmap_render_target = Some(BitmapRenderTarget {
+            target,
+            texture_target,
+            frame: replace(
+                &mut self.current_frame,
+                Some((Frame::Bitmap(frame), encoder)),
+            ),
+            frame_buffer_view: replace(&mut self.frame_buffer_view, frame_buffer_view),
+            depth_texture_view: replace(&mut self.depth_texture_view, depth_texture_view),
+            viewport_width: replace(&mut self.viewport_width, width as f32),
+            viewport_height: replace(&mut self.viewport_height, height as f32),
+            view_matrix: replace(&mut self.view_matrix, build_view_matrix(width, height)),
+            num_masks: self.num_masks,
+            num_masks_active: self.num_masks_active,
+            write_stencil_mask: self.write_stencil_mask,
+            test_stencil_mask: self.test_stencil_mask,
+            next_stencil_mask: self.next_stencil_mask,
+            mask_stack: std::mem::take(&mut self.mask_stack),
+        });
+
+        self.clear_frame(clear);
+
+        // Start with the existing contents of the target bitmap.
+        if let Some(target) = target {
+            self.render_bitmap(target, &Default::default());
+        }
+    }
+
+    fn end_render_to_bitmap(&mut self) -> Option<Bitmap> {
+        let render_target = self.bitmap_render_target.take()?;
+
+        // Submit the commands for the offscreen texture and read it back.
+        if let Some((_frame, encoder)) = replace(&mut self.current_frame, render_target.frame) {
+            let register_encoder_label = create_debug_label!("Register encoder");
+            let new_register_encoder =
+                self.device
+                    .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                        label: register_encoder_label.as_deref(),
+                    });
+            let register_buffer =
+                replace(&mut self.register_encoder, new_register_encoder).finish();
+            render_target.texture_target.submit(
+                &self.device,
+                &self.queue,
+                &[register_buffer, encoder.finish()],
+            );
+        }
+        let width = render_target.texture_target.width();
+        let height = render_target.texture_target.height();
+        let image = render_target.texture_target.capture(&self.device);
+
+        self.frame_buffer_view = render_target.frame_buffer_view;
+        self.depth_texture_view = render_target.depth_texture_view;
+        self.viewport_width = render_target.viewport_width;
+        self.viewport_height = render_target.viewport_height;
+        self.view_matrix = render_target.view_matrix;
+        self.num_masks = render_target.num_masks;
+        self.num_masks_active = render_target.num_masks_active;
+        self.write_stencil_mask = render_target.write_stencil_mask;
+        self.test_stencil_mask = render_target.test_stencil_mask;
+        self.next_stencil_mask = render_target.next_stencil_mask;
+        self.mask_stack = render_target.mask_stack;
+
+        // The texture is in BGRA order.
+        let mut rgba = image?.into_raw();
+        for pixel in rgba.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
         }
+
+        if let Some(target) = render_target.target {
+            self.update_texture(target, width, height, rgba.clone());
+        }
+        Some(Bitmap {
+            width,
+            height,
+            data: BitmapFormat::Rgba(rgba),
+        })
     }
 
     fn render_bitmap(&mut self, bitmap: BitmapHandle, transform: &Transform) {
-        if let Some((_id, texture)) = self.textures.get(bitmap.0) {
+        if let Some((_id, Some(texture))) = self.textures.get(bitmap.0) {
             let (frame_output, encoder) =
                 if let Some((frame_output, encoder)) = &mut self.current_frame {
                     (frame_output, encoder)
@@ -1093,13 +1278,18 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
             );
 
             let texture_view = texture.texture.create_default_view();
+            let filter = if self.smooth_bitmaps {
+                wgpu::FilterMode::Linear
+            } else {
+                wgpu::FilterMode::Nearest
+            };
             let sampler = self.device.create_sampler(&wgpu::SamplerDescriptor {
                 address_mode_u: wgpu::AddressMode::ClampToEdge,
                 address_mode_v: wgpu::AddressMode::ClampToEdge,
                 address_mode_w: wgpu::AddressMode::ClampToEdge,
-                mag_filter: wgpu::FilterMode::Linear,
-                min_filter: wgpu::FilterMode::Linear,
-                mipmap_filter: wgpu::FilterMode::Linear,
+                mag_filter: filter,
+                min_filter: filter,
+                mipmap_filter: filter,
                 lod_min_clamp: 0.0,
                 lod_max_clamp: 100.0,
                 compare: wgpu::CompareFunction::Undefined,
@@ -1387,6 +1577,115 @@ impl<T: RenderTarget + 'static> RenderBackend for WgpuRenderBackend<T> {
         }
     }
 
+    fn draw_rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: Color) {
+        let (frame_output, encoder) = if let Some((frame_output, encoder)) = &mut self.current_frame
+        {
+            (frame_output, encoder)
+        } else {
+            return;
+        };
+
+        let world_matrix = [
+            [width, 0.0, 0.0, 0.0],
+            [0.0, height, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [x, y, 0.0, 1.0],
+        ];
+
+        let mult_color = [
+            f32::from(color.r) / 255.0,
+            f32::from(color.g) / 255.0,
+            f32::from(color.b) / 255.0,
+            f32::from(color.a) / 255.0,
+        ];
+
+        let add_color = [0.0, 0.0, 0.0, 0.0];
+
+        let transforms_ubo = create_buffer_with_data(
+            &self.device,
+            bytemuck::cast_slice(&[Transforms {
+                view_matrix: self.view_matrix,
+                world_matrix,
+            }]),
+            wgpu::BufferUsage::UNIFORM,
+            create_debug_label!("Rectangle transfer buffer"),
+        );
+
+        let colors_ubo = create_buffer_with_data(
+            &self.device,
+            bytemuck::cast_slice(&[ColorAdjustments {
+                mult_color,
+                add_color,
+            }]),
+            wgpu::BufferUsage::UNIFORM,
+            create_debug_label!("Rectangle colors transfer buffer"),
+        );
+
+        let bind_group_label = create_debug_label!("Rectangle bind group");
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.pipelines.color.bind_layout,
+            bindings: &[
+                wgpu::Binding {
+                    binding: 0,
+                    resource: wgpu::BindingResource::Buffer {
+                        buffer: &transforms_ubo,
+                        range: 0..std::mem::size_of::<Transforms>() as u64,
+                    },
+                },
+                wgpu::Binding {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Buffer {
+                        buffer: &colors_ubo,
+                        range: 0..std::mem::size_of::<ColorAdjustments>() as u64,
+                    },
+                },
+            ],
+            label: bind_group_label.as_deref(),
+        });
+
+        let (color_attachment, resolve_target) = if self.msaa_sample_count >= 2 {
+            (&self.frame_buffer_view, Some(frame_output.view()))
+        } else {
+            (frame_output.view(), None)
+        };
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
+                attachment: color_attachment,
+                load_op: wgpu::LoadOp::Load,
+                store_op: wgpu::StoreOp::Store,
+                clear_color: wgpu::Color::WHITE,
+                resolve_target,
+            }],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachmentDescriptor {
+                attachment: &self.depth_texture_view,
+                depth_load_op: wgpu::LoadOp::Load,
+                depth_store_op: wgpu::StoreOp::Store,
+                stencil_load_op: wgpu::LoadOp::Load,
+                stencil_store_op: wgpu::StoreOp::Store,
+                clear_depth: 0.0,
+                clear_stencil: 0,
+            }),
+        });
+
+        render_pass.set_pipeline(&self.pipelines.color.pipeline_for(
+            self.num_masks,
+            self.num_masks_active,
+            self.test_stencil_mask,
+            self.write_stencil_mask,
+        ));
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.set_vertex_buffer(0, &self.quad_vbo, 0, 0);
+        render_pass.set_index_buffer(&self.quad_ibo, 0, 0);
+
+        if self.num_masks_active < self.num_masks {
+            render_pass.set_stencil_reference(self.write_stencil_mask);
+        } else {
+            render_pass.set_stencil_reference(self.test_stencil_mask);
+        }
+
+        render_pass.draw_indexed(0..6, 0, 0..1);
+    }
+
     fn push_mask(&mut self) {
         // Desktop draws the masker to the stencil buffer, one bit per mask.
         // Masks-within-masks are handled as a bitmask.
@@ -1508,6 +1807,7 @@ struct Texture {
     width: u32,
     height: u32,
     texture: wgpu::Texture,
+    data: Vec<u8>,
 }
 
 struct RuffleVertexCtor {
@@ -33,6 +33,106 @@ impl ShapePipeline {
 
 impl Pipelines {
     pub fn new(device: &wgpu::Device, msaa_sample_count: u32) -> Result<Self, Error> {
+        let shaders = Shaders::load(device)?;
+
+        let color_bind_layout = create_color_bind_layout(device);
+        let (write_mask_pipelines, read_mask_pipelines) = create_color_pipelines(
+            device,
+            &shaders.color_vs,
+            &shaders.color_fs,
+            &color_bind_layout,
+            msaa_sample_count,
+        );
+        let color = ShapePipeline {
+            write_mask_pipelines,
+            read_mask_pipelines,
+            bind_layout: color_bind_layout,
+        };
+
+        let bitmap_bind_layout = create_bitmap_bind_layout(device);
+        let (write_mask_pipelines, read_mask_pipelines) = create_bitmap_pipeline(
+            device,
+            &shaders.texture_vs,
+            &shaders.bitmap_fs,
+            &bitmap_bind_layout,
+            msaa_sample_count,
+        );
+        let bitmap = ShapePipeline {
+            write_mask_pipelines,
+            read_mask_pipelines,
+            bind_layout: bitmap_bind_layout,
+        };
+
+        let gradient_bind_layout = create_gradient_bind_layout(device);
+        let (write_mask_pipelines, read_mask_pipelines) = create_gradient_pipeline(
+            device,
+            &shaders.texture_vs,
+            &shaders.gradient_fs,
+            &gradient_bind_layout,
+            msaa_sample_count,
+        );
+        let gradient = ShapePipeline {
+            write_mask_pipelines,
+            read_mask_pipelines,
+            bind_layout: gradient_bind_layout,
+        };
+
+        Ok(Self {
+            color,
+            bitmap,
+            gradient,
+        })
+    }
+
+    /// Recreates the pipelines for a new MSAA sample count.
+    /// The bind group layouts are kept, so existing bind groups remain valid.
+    pub fn rebuild(&mut self, device: &wgpu::Device, msaa_sample_count: u32) -> Result<(), Error> {
+        let shaders = Shaders::load(device)?;
+
+        let (write_mask_pipelines, read_mask_pipelines) = create_color_pipelines(
+            device,
+            &shaders.color_vs,
+            &shaders.color_fs,
+            &self.color.bind_layout,
+            msaa_sample_count,
+        );
+        self.color.write_mask_pipelines = write_mask_pipelines;
+        self.color.read_mask_pipelines = read_mask_pipelines;
+
+        let (write_mask_pipelines, read_mask_pipelines) = create_bitmap_pipeline(
+            device,
+            &shaders.texture_vs,
+            &shaders.bitmap_fs,
+            &self.bitmap.bind_layout,
+            msaa_sample_count,
+        );
+        self.bitmap.write_mask_pipelines = write_mask_pipelines;
+        self.bitmap.read_mask_pipelines = read_mask_pipelines;
+
+        let (write_mask_pipelines, read_mask_pipelines) = create_gradient_pipeline(
+            device,
+            &shaders.texture_vs,
+            &shaders.gradient_fs,
+            &self.gradient.bind_layout,
+            msaa_sample_count,
+        );
+        self.gradient.write_mask_pipelines = write_mask_pipelines;
+        self.gradient.read_mask_pipelines = read_mask_pipelines;
+
+        Ok(())
+    }
+}
+
+struct Shaders {
+    color_vs: wgpu::ShaderModule,
+    color_fs: wgpu::ShaderModule,
+    texture_vs: wgpu::ShaderModule,
+    gradient_fs: wgpu::ShaderModule,
+    bitmap_fs: wgpu::ShaderModule,
+}
+
+impl Shaders {
+    fn load(device: &wgpu::Device) -> Result<Self, Error> {
         let color_vs_bytes = include_bytes!("../shaders/color.vert.spv");
         let color_vs = device.create_shader_module(&wgpu::read_spirv(std::io::Cursor::new(
             &color_vs_bytes[..],
@@ -55,18 +155,18 @@ impl Pipelines {
         ))?);
 
         Ok(Self {
-            color: create_color_pipelines(&device, &color_vs, &color_fs, msaa_sample_count),
-            bitmap: create_bitmap_pipeline(&device, &texture_vs, &bitmap_fs, msaa_sample_count),
-            gradient: create_gradient_pipeline(
-                &device,
-                &texture_vs,
-                &gradient_fs,
-                msaa_sample_count,
-            ),
+            color_vs,
+            color_fs,
+            texture_vs,
+            gradient_fs,
+            bitmap_fs,
         })
     }
 }
 
+/// The write-mask and read-mask pipelines of a `ShapePipeline`.
+type MaskPipelines = (Vec<wgpu::RenderPipeline>, Vec<wgpu::RenderPipeline>);
+
 fn create_pipeline_descriptor<'a>(
     vertex_shader: &'a wgpu::ShaderModule,
     fragment_shader: &'a wgpu::ShaderModule,
@@ -112,14 +212,9 @@ fn create_pipeline_descriptor<'a>(
     }
 }
 
-fn create_color_pipelines(
-    device: &wgpu::Device,
-    vertex_shader: &wgpu::ShaderModule,
-    fragment_shader: &wgpu::ShaderModule,
-    msaa_sample_count: u32,
-) -> ShapePipeline {
+fn create_color_bind_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
     let label = create_debug_label!("Color shape bind group");
-    let bind_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
         bindings: &[
             wgpu::BindGroupLayoutEntry {
                 binding: 0,
@@ -133,10 +228,18 @@ fn create_color_pipelines(
             },
         ],
         label: label.as_deref(),
-    });
+    })
+}
 
+fn create_color_pipelines(
+    device: &wgpu::Device,
+    vertex_shader: &wgpu::ShaderModule,
+    fragment_shader: &wgpu::ShaderModule,
+    bind_layout: &wgpu::BindGroupLayout,
+    msaa_sample_count: u32,
+) -> MaskPipelines {
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-        bind_group_layouts: &[&bind_layout],
+        bind_group_layouts: &[bind_layout],
     });
 
     let mut write_mask_pipelines = Vec::new();
@@ -226,21 +329,12 @@ fn create_color_pipelines(
         )));
     }
 
-    ShapePipeline {
-        write_mask_pipelines,
-        read_mask_pipelines,
-        bind_layout,
-    }
+    (write_mask_pipelines, read_mask_pipelines)
 }
 
-fn create_bitmap_pipeline(
-    device: &wgpu::Device,
-    vertex_shader: &wgpu::ShaderModule,
-    fragment_shader: &wgpu::ShaderModule,
-    msaa_sample_count: u32,
-) -> ShapePipeline {
+fn create_bitmap_bind_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
     let label = create_debug_label!("Bitmap shape bind group");
-    let bind_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
         bindings: &[
             wgpu::BindGroupLayoutEntry {
                 binding: 0,
@@ -273,10 +367,18 @@ fn create_bitmap_pipeline(
             },
         ],
         label: label.as_deref(),
-    });
+    })
+}
 
+fn create_bitmap_pipeline(
+    device: &wgpu::Device,
+    vertex_shader: &wgpu::ShaderModule,
+    fragment_shader: &wgpu::ShaderModule,
+    bind_layout: &wgpu::BindGroupLayout,
+    msaa_sample_count: u32,
+) -> MaskPipelines {
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-        bind_group_layouts: &[&bind_layout],
+        bind_group_layouts: &[bind_layout],
     });
 
     let mut write_mask_pipelines = Vec::new();
@@ -366,21 +468,12 @@ fn create_bitmap_pipeline(
         )));
     }
 
-    ShapePipeline {
-        write_mask_pipelines,
-        read_mask_pipelines,
-        bind_layout,
-    }
+    (write_mask_pipelines, read_mask_pipelines)
 }
 
-fn create_gradient_pipeline(
-    device: &wgpu::Device,
-    vertex_shader: &wgpu::ShaderModule,
-    fragment_shader: &wgpu::ShaderModule,
-    msaa_sample_count: u32,
-) -> ShapePipeline {
+fn create_gradient_bind_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
     let label = create_debug_label!("Gradient shape bind group");
-    let bind_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+    device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
         bindings: &[
             wgpu::BindGroupLayoutEntry {
                 binding: 0,
@@ -407,10 +500,18 @@ fn create_gradient_pipeline(
             },
         ],
         label: label.as_deref(),
-    });
+    })
+}
 
+fn create_gradient_pipeline(
+    device: &wgpu::Device,
+    vertex_shader: &wgpu::ShaderModule,
+    fragment_shader: &wgpu::ShaderModule,
+    bind_layout: &wgpu::BindGroupLayout,
+    msaa_sample_count: u32,
+) -> MaskPipelines {
     let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-        bind_group_layouts: &[&bind_layout],
+        bind_group_layouts: &[bind_layout],
     });
 
     let mut write_mask_pipelines = Vec::new();
@@ -500,9 +601,5 @@ fn create_gradient_pipeline(
         )));
     }
 
-    ShapePipeline {
-        write_mask_pipelines,
-        read_mask_pipelines,
-        bind_layout,
-    }
+    (write_mask_pipelines, read_mask_pipelines)
 }
@@ -0,0 +1,65 @@
+//! Prints a disassembly of the ABC bytecode in a SWF file.
+
+use ruffle_core::swf::avm2::read::Reader;
+use ruffle_core::swf::Tag;
+use std::error::Error;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::path::Path;
+
+/// Prints a disassembly of each `DoAbc` tag in the SWF at `path` to stdout.
+///
+/// A `DoAbc` tag that can't be read or disassembled is reported, both in the listing and on
+/// stderr, and the remaining tags are still printed.
+pub fn dump_abc(path: &Path) -> Result<(), Box<dyn Error>> {
+    let data = std::fs::read(path)?;
+    let swf_buf = ruffle_core::swf::decompress_swf(&data[..])?;
+    let swf = ruffle_core::swf::parse_swf(&swf_buf)?;
+
+    let mut num_abcs = 0;
+    let mut num_errors = 0;
+    for tag in &swf.tags {
+        let do_abc = match tag {
+            Tag::DoAbc(do_abc) => do_abc,
+            _ => continue,
+        };
+        if num_abcs > 0 {
+            println!();
+        }
+        num_abcs += 1;
+
+        println!("; DoAbc {:?}", do_abc.name);
+        match disassemble(do_abc.data) {
+            Ok(text) => print!("{}", text),
+            Err(e) => {
+                num_errors += 1;
+                println!("; Error: {}", e);
+                eprintln!("Unable to disassemble DoAbc {:?}: {}", do_abc.name, e);
+            }
+        }
+    }
+
+    if num_abcs == 0 {
+        eprintln!("No DoAbc tags found in {}", path.display());
+    } else if num_errors > 0 {
+        eprintln!(
+            "{} of {} DoAbc tags could not be disassembled",
+            num_errors, num_abcs
+        );
+    }
+
+    Ok(())
+}
+
+fn disassemble(data: &[u8]) -> Result<String, Box<dyn Error>> {
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let abc = Reader::new(data).read()?;
+        ruffle_core::swf::avm2::disasm::disassemble(&abc)
+    }));
+    match result {
+        Ok(text) => Ok(text?),
+        Err(e) => match e.downcast::<String>() {
+            Ok(e) => Err(format!("PANIC: {}", e).into()),
+            Err(_) => Err("PANIC".into()),
+        },
+    }
+}
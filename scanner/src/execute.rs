@@ -0,0 +1,157 @@
+//! Runs a single SWF in a headless player, in its own process.
+//!
+//! Problems are reported on stdout as they happen, one per line, as a category and a message
+//! separated by a tab. The scanner reads these back even if the process crashes or is killed.
+
+use ruffle_core::backend::audio::NullAudioBackend;
+use ruffle_core::backend::font::NullFontBackend;
+use ruffle_core::backend::input::NullInputBackend;
+use ruffle_core::backend::navigator::NullNavigatorBackend;
+use ruffle_core::backend::render::NullRenderer;
+use ruffle_core::swf::avm1::read::Reader;
+use ruffle_core::swf::avm1::types::Action;
+use ruffle_core::swf::Tag;
+use ruffle_core::tag_utils::SwfMovie;
+use ruffle_core::Player;
+use std::error::Error;
+use std::path::Path;
+
+pub const ERROR: &str = "error";
+pub const PANIC: &str = "panic";
+pub const AVM1_ERROR: &str = "avm1_error";
+pub const AVM1_WARNING: &str = "avm1_warning";
+pub const UNKNOWN_OPCODE: &str = "opcode";
+pub const UNSUPPORTED_TAG: &str = "tag";
+
+/// Reports a problem to the scanner.
+fn report(category: &str, message: &str) {
+    println!("{}\t{}", category, message.replace('\n', " "));
+}
+
+/// Reports the errors and warnings logged by AVM1, such as unimplemented properties.
+struct ScanLogger;
+
+impl log::Log for ScanLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Warn && metadata.target().starts_with("ruffle_core::avm1")
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let category = if record.level() == log::Level::Error {
+            AVM1_ERROR
+        } else {
+            AVM1_WARNING
+        };
+        report(category, &record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+/// Runs the SWF at `path` for the given number of frames, reporting any problems on stdout.
+pub fn execute_file(path: &Path, frames: u32) {
+    let data = match std::fs::read(path) {
+        Ok(data) => data,
+        Err(e) => {
+            report(ERROR, &e.to_string());
+            return;
+        }
+    };
+
+    // Panics abort the process, so the hook is the only chance to report them.
+    std::panic::set_hook(Box::new(|info| report(PANIC, &info.to_string())));
+
+    // Unsupported tags and unknown opcodes are found up front, whether or not they are reached
+    // while running. A movie that can't be parsed is still run, as the player reads it lazily.
+    let _ = scan_movie(&data);
+
+    let _ = log::set_boxed_logger(Box::new(ScanLogger));
+    log::set_max_level(log::LevelFilter::Warn);
+
+    if let Err(e) = run_movie(&data, frames) {
+        report(ERROR, &e.to_string());
+    }
+}
+
+fn run_movie(data: &[u8], frames: u32) -> Result<(), Box<dyn Error>> {
+    let movie = SwfMovie::from_data(data)?;
+    let player = Player::new(
+        Box::new(NullRenderer::new()),
+        Box::new(NullAudioBackend::new()),
+        Box::new(NullNavigatorBackend::new()),
+        Box::new(NullInputBackend::new()),
+        Box::new(NullFontBackend::new()),
+        movie,
+    )?;
+
+    for _ in 0..frames {
+        let mut player = player.lock().unwrap();
+        player.run_frame();
+        player.render();
+    }
+
+    Ok(())
+}
+
+fn scan_movie(data: &[u8]) -> Result<(), Box<dyn Error>> {
+    let swf_buf = ruffle_core::swf::decompress_swf(data)?;
+    let swf = ruffle_core::swf::parse_swf(&swf_buf)?;
+    scan_tags(&swf.tags, swf.header.version);
+    Ok(())
+}
+
+/// Reports the unsupported tags in a tag list, and the unknown opcodes in its actions.
+fn scan_tags(tags: &[Tag], version: u8) {
+    for tag in tags {
+        match tag {
+            Tag::DoAbc(_) => report(UNSUPPORTED_TAG, "DoAbc"),
+            Tag::DefineVideoStream(_) => report(UNSUPPORTED_TAG, "DefineVideoStream"),
+            Tag::Unknown { tag_code, .. } => {
+                report(UNSUPPORTED_TAG, &format!("Unknown tag {}", tag_code))
+            }
+            Tag::DefineSprite(sprite) => scan_tags(&sprite.tags, version),
+            Tag::DoAction(action_data) => scan_actions(action_data, version),
+            Tag::DoInitAction { action_data, .. } => scan_actions(action_data, version),
+            Tag::DefineButton(button) | Tag::DefineButton2(button) => {
+                for action in &button.actions {
+                    scan_actions(action.action_data, version);
+                }
+            }
+            Tag::PlaceObject(place_object) => {
+                for action in &place_object.clip_actions {
+                    scan_actions(action.action_data, version);
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Reports the unknown opcodes in a block of actions, including any nested blocks.
+fn scan_actions(data: &[u8], version: u8) {
+    let mut reader = Reader::new(data, version);
+    while let Ok(Some(action)) = reader.read_action() {
+        match action {
+            Action::Unknown { opcode, .. } => {
+                report(UNKNOWN_OPCODE, &format!("0x{:02X}", opcode));
+            }
+            Action::DefineFunction { actions, .. } | Action::With { actions } => {
+                scan_actions(actions, version);
+            }
+            Action::DefineFunction2(function) => scan_actions(function.actions, version),
+            Action::Try(try_block) => {
+                scan_actions(try_block.try_actions, version);
+                if let Some((_, catch_actions)) = try_block.catch {
+                    scan_actions(catch_actions, version);
+                }
+                if let Some(finally_actions) = try_block.finally {
+                    scan_actions(finally_actions, version);
+                }
+            }
+            _ => (),
+        }
+    }
+}
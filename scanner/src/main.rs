@@ -1,33 +1,77 @@
 use indicatif::{ProgressBar, ProgressStyle};
 use path_slash::PathExt;
-use ruffle_core::swf::read_swf;
+use ruffle_core::swf::read::{decompress_swf_with_options, parse_swf_with_options, ReadOptions};
 
 use serde::Serialize;
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
 
-use std::panic::catch_unwind;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use structopt::StructOpt;
 use walkdir::{DirEntry, WalkDir};
 
+mod abc;
+mod execute;
+
 #[derive(Serialize, Debug)]
 struct FileResults {
     name: String,
     error: Option<String>,
+    warnings: String,
+}
+
+#[derive(Serialize, Debug, Default)]
+struct RunResults {
+    name: String,
+    error: Option<String>,
+    avm1_errors: String,
+    avm1_warnings: String,
+    unknown_opcodes: String,
+    unsupported_tags: String,
 }
 
 #[derive(StructOpt, Debug)]
 struct Opt {
     /// The directory (containing SWF files) to scan
-    #[structopt(name = "directory", parse(from_os_str))]
-    input_path: PathBuf,
+    #[structopt(name = "directory", parse(from_os_str), required_unless_one = &["abc", "execute"])]
+    input_path: Option<PathBuf>,
 
     /// The file to store results in CSV format
-    #[structopt(name = "results", parse(from_os_str))]
-    output_path: PathBuf,
+    #[structopt(name = "results", parse(from_os_str), required_unless_one = &["abc", "execute"])]
+    output_path: Option<PathBuf>,
 
     /// Filenames to ignore
     #[structopt(short = "i", long = "ignore")]
     ignore: Vec<String>,
+
+    /// Treat recoverable problems, such as trailing data in tags, as errors
+    #[structopt(short = "s", long = "strict")]
+    strict: bool,
+
+    /// Print a disassembly of the ABC bytecode in a single SWF file, instead of scanning a
+    /// directory
+    #[structopt(long = "abc", parse(from_os_str))]
+    abc: Option<PathBuf>,
+
+    /// Run each file in a headless player and record the problems it hits, instead of only
+    /// parsing it
+    #[structopt(short = "r", long = "run")]
+    run: bool,
+
+    /// Number of frames to run each file for
+    #[structopt(short = "f", long = "frames", default_value = "60")]
+    frames: u32,
+
+    /// Number of seconds after which a running file is killed
+    #[structopt(short = "t", long = "timeout", default_value = "10")]
+    timeout: u64,
+
+    /// Run a single file and report its problems on stdout. Used internally by --run to isolate
+    /// each file in its own process
+    #[structopt(long = "execute", parse(from_os_str), hidden = true)]
+    execute: Option<PathBuf>,
 }
 
 fn find_files(root: &Path, ignore: &[String]) -> Vec<DirEntry> {
@@ -51,50 +95,180 @@ fn find_files(root: &Path, ignore: &[String]) -> Vec<DirEntry> {
     results
 }
 
-fn scan_file(file: DirEntry, name: String) -> FileResults {
+fn scan_file(file: DirEntry, name: String, options: &ReadOptions) -> FileResults {
     let data = match std::fs::read(file.path()) {
         Ok(data) => data,
         Err(e) => {
             return {
                 FileResults {
                     name,
-                    error: Some(format!("File error: {}", e.to_string())),
+                    error: Some(format!("File error: {}", e)),
+                    warnings: String::new(),
                 }
             }
         }
     };
 
-    match catch_unwind(|| read_swf(&data[..])) {
+    let mut diagnostics = Vec::new();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        let swf_buf = decompress_swf_with_options(&data[..], options, &mut diagnostics)?;
+        parse_swf_with_options(&swf_buf, options, &mut diagnostics).map(|_swf| ())
+    }));
+    let warnings = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    match result {
         Ok(swf) => match swf {
-            Ok(_swf) => FileResults { name, error: None },
+            Ok(()) => FileResults {
+                name,
+                error: None,
+                warnings,
+            },
             Err(e) => FileResults {
                 name,
-                error: Some(format!("Parse error: {}", e.to_string())),
+                error: Some(format!("Parse error: {}", e)),
+                warnings,
             },
         },
         Err(e) => match e.downcast::<String>() {
             Ok(e) => FileResults {
                 name,
-                error: Some(format!("PANIC: {}", e.to_string())),
+                error: Some(format!("PANIC: {}", e)),
+                warnings,
             },
             Err(_) => FileResults {
                 name,
                 error: Some("PANIC".to_string()),
+                warnings,
             },
         },
     }
 }
 
+/// Runs a file in a child process, collecting the problems it reports.
+fn run_file(path: &Path, name: String, frames: u32, timeout: Duration) -> RunResults {
+    let mut results = RunResults {
+        name,
+        ..Default::default()
+    };
+
+    let child = std::env::current_exe().and_then(|exe| {
+        Command::new(exe)
+            .arg("--execute")
+            .arg(path)
+            .arg("--frames")
+            .arg(frames.to_string())
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+    });
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => {
+            results.error = Some(format!("Process error: {}", e));
+            return results;
+        }
+    };
+
+    // Read the output on another thread, so that a chatty child can't fill up the pipe and
+    // block while we're waiting for it.
+    let mut stdout = child.stdout.take().unwrap();
+    let reader = std::thread::spawn(move || {
+        let mut output = String::new();
+        let _ = stdout.read_to_string(&mut output);
+        output
+    });
+
+    let start = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) if start.elapsed() < timeout => std::thread::sleep(Duration::from_millis(10)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+        }
+    };
+    let output = reader.join().unwrap_or_default();
+
+    for line in output.lines() {
+        let mut parts = line.splitn(2, '\t');
+        let category = parts.next().unwrap_or_default();
+        let message = parts.next().unwrap_or_default();
+        let field = match category {
+            execute::ERROR | execute::PANIC => {
+                if results.error.is_none() {
+                    let prefix = if category == execute::PANIC {
+                        "PANIC"
+                    } else {
+                        "Run error"
+                    };
+                    results.error = Some(format!("{}: {}", prefix, message));
+                }
+                continue;
+            }
+            execute::AVM1_ERROR => &mut results.avm1_errors,
+            execute::AVM1_WARNING => &mut results.avm1_warnings,
+            execute::UNKNOWN_OPCODE => &mut results.unknown_opcodes,
+            execute::UNSUPPORTED_TAG => &mut results.unsupported_tags,
+            _ => continue,
+        };
+        // Each distinct problem is only listed once.
+        if !field.lines().any(|existing| existing == message) {
+            if !field.is_empty() {
+                field.push('\n');
+            }
+            field.push_str(message);
+        }
+    }
+
+    if results.error.is_none() {
+        results.error = match status {
+            None => Some(format!("Timed out after {} seconds", timeout.as_secs())),
+            Some(status) if !status.success() => Some(format!("Crashed: {}", status)),
+            Some(_) => None,
+        };
+    }
+
+    results
+}
+
 fn main() -> Result<(), std::io::Error> {
+    let opt = Opt::from_args();
+    if let Some(path) = &opt.execute {
+        execute::execute_file(path, opt.frames);
+        return Ok(());
+    }
+
     env_logger::init();
 
-    let opt = Opt::from_args();
-    let to_scan = find_files(&opt.input_path, &opt.ignore);
+    if let Some(path) = &opt.abc {
+        if let Err(e) = abc::dump_abc(path) {
+            eprintln!("Unable to read {}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Both are required by the argument parser unless a single file is executed or dumped.
+    let input_path = opt.input_path.unwrap();
+    let output_path = opt.output_path.unwrap();
+    let to_scan = find_files(&input_path, &opt.ignore);
     let total = to_scan.len() as u64;
+    let options = ReadOptions { strict: opt.strict };
     let mut good = 0;
     let mut bad = 0;
     let progress = ProgressBar::new(total);
-    let mut writer = csv::Writer::from_path(opt.output_path)?;
+    let timeout = Duration::from_secs(opt.timeout);
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_path(output_path)?;
 
     progress.set_style(
         ProgressStyle::default_bar()
@@ -104,30 +278,52 @@ fn main() -> Result<(), std::io::Error> {
             .progress_chars("##-"),
     );
 
-    writer.write_record(&["Filename", "Error"])?;
+    if opt.run {
+        writer.write_record([
+            "Filename",
+            "Error",
+            "AVM1 Errors",
+            "AVM1 Warnings",
+            "Unknown Opcodes",
+            "Unsupported Tags",
+        ])?;
+    } else {
+        writer.write_record(["Filename", "Error", "Warnings"])?;
+    }
 
     for file in to_scan {
         let name = file
             .path()
-            .strip_prefix(&opt.input_path)
+            .strip_prefix(&input_path)
             .unwrap_or_else(|_| file.path())
             .to_slash_lossy();
         progress.inc(1);
         progress.set_message(&name);
-        let result = scan_file(file, name);
+        let is_ok = if opt.run {
+            let result = run_file(file.path(), name, opt.frames, timeout);
+            let is_ok = result.error.is_none();
+            writer.serialize(result)?;
+            is_ok
+        } else {
+            let result = scan_file(file, name, &options);
+            let is_ok = result.error.is_none();
+            writer.serialize(result)?;
+            is_ok
+        };
 
-        if result.error.is_none() {
+        if is_ok {
             good += 1;
         } else {
             bad += 1;
         }
-
-        writer.serialize(result)?;
     }
 
     progress.finish_with_message(&format!(
-        "Scanned {} swf files. {} successfully parsed, {} encountered errors",
-        total, good, bad
+        "Scanned {} swf files. {} successfully {}, {} encountered errors",
+        total,
+        good,
+        if opt.run { "ran" } else { "parsed" },
+        bad
     ));
 
     Ok(())
@@ -1,3 +1,5 @@
+pub mod decompile;
+pub mod disasm;
 pub(crate) mod opcode;
 pub mod read;
 pub mod types;
@@ -0,0 +1,1627 @@
+//! Decompiles AVM1 bytecode to ActionScript.
+//!
+//! `decompile` converts the actions of a `DoAction` or `DoInitAction` tag, and
+//! `decompile_clip_action` converts an `onClipEvent` or `on` handler of a placed movie clip.
+//!
+//! Control flow emitted by the Flash authoring tool is reconstructed from branches: `if`/`else`,
+//! `while`, `do`/`while`, `for`..`in`, `break` and `continue`, as well as the `&&`, `||` and
+//! `?:` operators. Expressions are rebuilt by tracking the stack, and registers are named after
+//! the parameters and preloaded values of a `DefineFunction2`, or `registerN` otherwise.
+//!
+//! A region of code that can't be structured, such as a `switch` statement or a branch into the
+//! middle of a loop, is written as p-code in a comment, in the format used by the `disasm`
+//! module. The output is meant to be read, and won't necessarily compile back to the same
+//! bytecode.
+
+use crate::avm1::disasm;
+use crate::avm1::read::Reader;
+use crate::avm1::types::*;
+use crate::error::Result;
+use crate::types::{ClipAction, ClipEventFlag};
+use std::collections::{HashMap, HashSet};
+
+/// Converts AVM1 bytecode to ActionScript.
+///
+/// # Example
+/// ```
+/// let data = swf::avm1::disasm::assemble("Push \"hello\"\nTrace\nEnd\n", 8).unwrap();
+/// let text = swf::avm1::decompile::decompile(&data, 8).unwrap();
+/// assert_eq!(text, "trace(\"hello\");\n");
+/// ```
+pub fn decompile(data: &[u8], version: u8) -> Result<String> {
+    let statements = decompile_block(data, version, &mut Scope::default())?;
+    let mut text = String::new();
+    for statement in statements {
+        text.push_str(&statement);
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+/// Converts the actions of a clip event handler to an `onClipEvent` or `on` block.
+pub fn decompile_clip_action(clip_action: &ClipAction, version: u8) -> Result<String> {
+    let statements = decompile_block(clip_action.action_data, version, &mut Scope::default())?;
+    let mut is_button_event = true;
+    let mut events = vec![];
+    for event in clip_action.events.iter() {
+        let name = match event {
+            ClipEventFlag::Construct => "construct",
+            ClipEventFlag::Data => "data",
+            ClipEventFlag::EnterFrame => "enterFrame",
+            ClipEventFlag::Initialize => "initialize",
+            ClipEventFlag::KeyDown => "keyDown",
+            ClipEventFlag::KeyUp => "keyUp",
+            ClipEventFlag::Load => "load",
+            ClipEventFlag::MouseDown => "mouseDown",
+            ClipEventFlag::MouseMove => "mouseMove",
+            ClipEventFlag::MouseUp => "mouseUp",
+            ClipEventFlag::Unload => "unload",
+            ClipEventFlag::DragOut => "dragOut",
+            ClipEventFlag::DragOver => "dragOver",
+            ClipEventFlag::Press => "press",
+            ClipEventFlag::Release => "release",
+            ClipEventFlag::ReleaseOutside => "releaseOutside",
+            ClipEventFlag::RollOut => "rollOut",
+            ClipEventFlag::RollOver => "rollOver",
+            ClipEventFlag::KeyPress => {
+                let key = clip_action.key_code.unwrap_or(0);
+                events.push(format!("keyPress {}", key_name(key)));
+                continue;
+            }
+        };
+        // These events can only be handled by `onClipEvent`.
+        if matches!(
+            event,
+            ClipEventFlag::Construct
+                | ClipEventFlag::Data
+                | ClipEventFlag::EnterFrame
+                | ClipEventFlag::Initialize
+                | ClipEventFlag::KeyDown
+                | ClipEventFlag::KeyUp
+                | ClipEventFlag::Load
+                | ClipEventFlag::MouseDown
+                | ClipEventFlag::MouseMove
+                | ClipEventFlag::MouseUp
+                | ClipEventFlag::Unload
+        ) {
+            is_button_event = false;
+        }
+        events.push(name.to_string());
+    }
+    let keyword = if is_button_event { "on" } else { "onClipEvent" };
+    let mut text = braces(&format!("{}({})", keyword, events.join(", ")), &statements);
+    text.push('\n');
+    Ok(text)
+}
+
+/// The name of a key in an `on(keyPress ...)` handler.
+fn key_name(key_code: u8) -> String {
+    let name = match key_code {
+        1 => "<Left>",
+        2 => "<Right>",
+        3 => "<Home>",
+        4 => "<End>",
+        5 => "<Insert>",
+        6 => "<Delete>",
+        8 => "<Backspace>",
+        13 => "<Enter>",
+        14 => "<Up>",
+        15 => "<Down>",
+        16 => "<PageUp>",
+        17 => "<PageDown>",
+        18 => "<Tab>",
+        19 => "<Escape>",
+        32 => "<Space>",
+        _ => return quote(&char::from(key_code).to_string()),
+    };
+    format!("\"{}\"", name)
+}
+
+fn decompile_block<'a>(data: &'a [u8], version: u8, scope: &mut Scope<'a>) -> Result<Vec<String>> {
+    let mut reader = Reader::new(data, version);
+    let mut actions = Vec::new();
+    let mut indices = HashMap::new();
+    while reader.pos() < data.len() {
+        let start = reader.pos();
+        let action = reader.read_action()?;
+        indices.insert(start, actions.len());
+        actions.push((start, reader.pos(), action));
+    }
+    indices.insert(data.len(), actions.len());
+
+    let mut block = Block {
+        data,
+        version,
+        actions,
+        indices,
+        back_jumps: HashMap::new(),
+        back_ifs: HashMap::new(),
+        scope,
+    };
+    for i in 0..block.actions.len() {
+        let is_jump = match block.actions[i].2 {
+            Some(Action::Jump { .. }) => true,
+            Some(Action::If { .. }) => false,
+            _ => continue,
+        };
+        if let Ok(target) = block.target(i) {
+            if target <= i {
+                let sources = if is_jump {
+                    &mut block.back_jumps
+                } else {
+                    &mut block.back_ifs
+                };
+                sources.entry(target).or_insert_with(Vec::new).push(i);
+            }
+        }
+    }
+    Ok(block.statements(0, block.actions.len(), Loop::default()))
+}
+
+/// The constant pool and register names visible to a block of actions.
+#[derive(Clone, Default)]
+struct Scope<'a> {
+    constant_pool: Vec<&'a str>,
+    registers: HashMap<u8, String>,
+
+    /// Whether the block is the body of a `DefineFunction2`, where registers are local variables.
+    is_function2: bool,
+
+    /// The registers that have already been declared with `var`.
+    declared: HashSet<u8>,
+}
+
+/// The targets of `break` and `continue` in the innermost loop.
+#[derive(Clone, Copy, Default)]
+struct Loop {
+    break_index: Option<usize>,
+    continue_index: Option<usize>,
+}
+
+/// The reason that a region couldn't be structured.
+type Structured<T> = std::result::Result<T, String>;
+
+const ASSIGNMENT: u8 = 1;
+const CONDITIONAL: u8 = 2;
+const LOGICAL_OR: u8 = 3;
+const LOGICAL_AND: u8 = 4;
+const BIT_OR: u8 = 5;
+const BIT_XOR: u8 = 6;
+const BIT_AND: u8 = 7;
+const EQUALITY: u8 = 8;
+const RELATIONAL: u8 = 9;
+const SHIFT: u8 = 10;
+const ADDITIVE: u8 = 11;
+const MULTIPLICATIVE: u8 = 12;
+const UNARY: u8 = 13;
+const PRIMARY: u8 = 14;
+
+/// An expression on the stack, rendered as ActionScript.
+#[derive(Clone, Debug)]
+struct Expr {
+    text: String,
+    precedence: u8,
+    kind: ExprKind,
+}
+
+/// The expressions that are treated specially when they are consumed.
+#[derive(Clone, Debug)]
+enum ExprKind {
+    Other,
+    Undefined,
+    Number(f64),
+    Str(String),
+    Not(Box<Expr>),
+    Increment(String),
+    Decrement(String),
+    StoreRegister(u8, Box<Expr>),
+}
+
+impl Expr {
+    fn new(text: String, precedence: u8) -> Self {
+        Expr {
+            text,
+            precedence,
+            kind: ExprKind::Other,
+        }
+    }
+
+    fn with_kind(mut self, kind: ExprKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Returns the text of the expression, in parentheses if it binds looser than `precedence`.
+    fn wrap(&self, precedence: u8) -> String {
+        if self.precedence < precedence {
+            format!("({})", self.text)
+        } else {
+            self.text.clone()
+        }
+    }
+
+    fn binary(op: &str, precedence: u8, left: &Expr, right: &Expr) -> Self {
+        Expr::new(
+            format!(
+                "{} {} {}",
+                left.wrap(precedence),
+                op,
+                right.wrap(precedence + 1)
+            ),
+            precedence,
+        )
+    }
+
+    fn unary(op: &str, operand: &Expr) -> Self {
+        Expr::new(format!("{}{}", op, operand.wrap(UNARY)), UNARY)
+    }
+
+    fn call(callee: &str, args: &[Expr]) -> Self {
+        let args: Vec<_> = args.iter().map(|arg| arg.wrap(ASSIGNMENT)).collect();
+        Expr::new(format!("{}({})", callee, args.join(", ")), PRIMARY)
+    }
+
+    fn negate(&self) -> Self {
+        match &self.kind {
+            ExprKind::Not(operand) => (**operand).clone(),
+            _ => Expr::unary("!", self).with_kind(ExprKind::Not(Box::new(self.clone()))),
+        }
+    }
+
+    /// Returns the name of the variable or property that this expression refers to, if it is a
+    /// string.
+    fn as_str(&self) -> Option<&str> {
+        match &self.kind {
+            ExprKind::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+struct Block<'a, 'b> {
+    data: &'a [u8],
+    version: u8,
+
+    /// The start and end offsets of each action. `None` is an `End` action.
+    actions: Vec<(usize, usize, Option<Action<'a>>)>,
+
+    /// The index of the action at each offset, including the end of the block.
+    indices: HashMap<usize, usize>,
+
+    /// The indices of the backward `Jump` actions to each loop head.
+    back_jumps: HashMap<usize, Vec<usize>>,
+
+    /// The indices of the backward `If` actions to each `do`..`while` loop head.
+    back_ifs: HashMap<usize, Vec<usize>>,
+
+    scope: &'b mut Scope<'a>,
+}
+
+impl<'a, 'b> Block<'a, 'b> {
+    /// Returns the index of the action that the `If` or `Jump` at `i` branches to.
+    fn target(&self, i: usize) -> Structured<usize> {
+        let (_, end, action) = &self.actions[i];
+        let offset = match action {
+            Some(Action::If { offset }) | Some(Action::Jump { offset }) => *offset,
+            _ => return Err("not a branch".to_string()),
+        };
+        let target = *end as isize + isize::from(offset);
+        if target < 0 {
+            return Err("branch out of the block".to_string());
+        }
+        match self.indices.get(&(target as usize)) {
+            Some(index) => Ok(*index),
+            None => Err("branch into the middle of an action".to_string()),
+        }
+    }
+
+    /// Returns the last of `sources` that is in the region ending at `hi`.
+    fn last_source(sources: Option<&Vec<usize>>, hi: usize) -> Option<usize> {
+        sources?.iter().copied().filter(|&j| j < hi).max()
+    }
+
+    fn register_name(&self, register: u8) -> String {
+        match self.scope.registers.get(&register) {
+            Some(name) => name.clone(),
+            None => format!("register{}", register),
+        }
+    }
+
+    /// Decompiles the statements in the actions from `lo` to `hi`, falling back to p-code if they
+    /// can't be structured.
+    fn statements(&mut self, lo: usize, hi: usize, ctx: Loop) -> Vec<String> {
+        let mut stack = vec![];
+        match self.structure(lo, hi, &mut stack, ctx) {
+            Ok(mut statements) => {
+                // Values left on the stack are evaluated for their side effects.
+                for expr in stack {
+                    let statement = self.expression_statement(expr);
+                    statements.push(statement);
+                }
+                statements
+            }
+            Err(reason) => vec![self.pcode(lo, hi, &reason)],
+        }
+    }
+
+    fn pcode(&self, lo: usize, hi: usize, reason: &str) -> String {
+        let start = self.actions.get(lo).map_or(self.data.len(), |a| a.0);
+        let end = self.actions.get(hi).map_or(self.data.len(), |a| a.0);
+        let text = match disasm::disassemble(&self.data[start..end], self.version) {
+            Ok(text) => text,
+            Err(e) => format!("{}\n", e),
+        };
+        let mut pcode = format!("/* p-code ({}):\n", reason);
+        for line in text.lines() {
+            pcode.push_str("    ");
+            pcode.push_str(line);
+            pcode.push('\n');
+        }
+        pcode.push_str("*/");
+        pcode
+    }
+
+    /// Decompiles the statements in the actions from `lo` to `hi`, leaving any values that they
+    /// push on `stack`.
+    fn structure(
+        &mut self,
+        lo: usize,
+        hi: usize,
+        stack: &mut Vec<Expr>,
+        ctx: Loop,
+    ) -> Structured<Vec<String>> {
+        let mut statements = vec![];
+        let mut i = lo;
+        while i < hi {
+            if let Some(j) = Self::last_source(self.back_jumps.get(&i), hi) {
+                self.while_loop(i, j, &mut statements);
+                i = j + 1;
+                continue;
+            }
+            if let Some(k) = Self::last_source(self.back_ifs.get(&i), hi) {
+                let ctx = Loop {
+                    break_index: Some(k + 1),
+                    continue_index: None,
+                };
+                let mut cond_stack = vec![];
+                let body = self.structure(i, k, &mut cond_stack, ctx)?;
+                let cond = pop(&mut cond_stack)?;
+                if !cond_stack.is_empty() {
+                    return Err("unbalanced loop condition".to_string());
+                }
+                statements.push(format!("do {{\n{}}} while ({});", indent(&body), cond.text));
+                i = k + 1;
+                continue;
+            }
+
+            let action = match self.actions[i].2.clone() {
+                Some(action) => action,
+                None => {
+                    i += 1;
+                    continue;
+                }
+            };
+            match action {
+                Action::If { .. } => {
+                    i = self.branch(i, hi, stack, &mut statements, ctx)?;
+                    continue;
+                }
+                Action::Jump { .. } => {
+                    let target = self.target(i)?;
+                    if Some(target) == ctx.break_index {
+                        statements.push("break;".to_string());
+                    } else if Some(target) == ctx.continue_index {
+                        statements.push("continue;".to_string());
+                    } else if target != i + 1 {
+                        return Err("unstructured jump".to_string());
+                    }
+                }
+                Action::Enumerate | Action::Enumerate2 => {
+                    let object = pop(stack)?;
+                    let object = match action {
+                        Action::Enumerate => variable(&object),
+                        _ => object,
+                    };
+                    i = self.for_in(i, hi, &object, &mut statements)?;
+                    continue;
+                }
+                Action::WaitForFrame {
+                    frame,
+                    num_actions_to_skip,
+                } => {
+                    let end = (i + 1 + usize::from(num_actions_to_skip)).min(hi);
+                    let body = self.statements(i + 1, end, ctx);
+                    let header = format!("ifFrameLoaded({})", u32::from(frame) + 1);
+                    statements.push(braces(&header, &body));
+                    i = end;
+                    continue;
+                }
+                Action::WaitForFrame2 {
+                    num_actions_to_skip,
+                } => {
+                    let frame = pop(stack)?;
+                    let end = (i + 1 + usize::from(num_actions_to_skip)).min(hi);
+                    let body = self.statements(i + 1, end, ctx);
+                    let header = format!("ifFrameLoaded({})", frame.text);
+                    statements.push(braces(&header, &body));
+                    i = end;
+                    continue;
+                }
+                Action::SetTarget(_) | Action::SetTarget2 => {
+                    let target = match action {
+                        Action::SetTarget(target) => string(target),
+                        _ => pop(stack)?,
+                    };
+                    let end = (i + 1..hi)
+                        .find(|&j| matches!(self.actions[j].2, Some(Action::SetTarget(""))));
+                    match end {
+                        Some(end) if target.as_str() != Some("") => {
+                            let body = self.statements(i + 1, end, ctx);
+                            let header = format!("tellTarget({})", target.text);
+                            statements.push(braces(&header, &body));
+                            i = end + 1;
+                            continue;
+                        }
+                        _ => statements.push(format!("setTarget({});", target.text)),
+                    }
+                }
+                action => self.action(action, stack, &mut statements)?,
+            }
+            i += 1;
+        }
+        Ok(statements)
+    }
+
+    /// Structures a loop whose head is at `head`, ending with a backward `Jump` at `j`.
+    fn while_loop(&mut self, head: usize, j: usize, statements: &mut Vec<String>) {
+        let exit = j + 1;
+        let ctx = Loop {
+            break_index: Some(exit),
+            continue_index: Some(head),
+        };
+
+        // The condition is evaluated at the head, and exits the loop if it is false.
+        let mut header = None;
+        let cond_if = (head..j).find(|&k| {
+            matches!(self.actions[k].2, Some(Action::If { .. })) && self.target(k) == Ok(exit)
+        });
+        if let Some(k) = cond_if {
+            // A failed attempt must not leave registers marked as declared.
+            let declared = self.scope.declared.clone();
+            let mut cond_stack = vec![];
+            if let Ok(cond_statements) = self.structure(head, k, &mut cond_stack, Loop::default()) {
+                if cond_statements.is_empty() && cond_stack.len() == 1 {
+                    header = Some((cond_stack[0].negate(), k + 1));
+                }
+            }
+            if header.is_none() {
+                self.scope.declared = declared;
+            }
+        }
+        let (cond, body_start) = header.unwrap_or_else(|| (literal("true"), head));
+        let body = self.statements(body_start, j, ctx);
+        statements.push(braces(&format!("while ({})", cond.text), &body));
+    }
+
+    /// Structures a `for`..`in` loop that enumerates `object` at `i`.
+    ///
+    /// Each iteration stores the next name in register 0, and the loop ends when it is `null`:
+    ///
+    /// ```text
+    /// Enumerate2
+    /// L0:
+    /// StoreRegister r0
+    /// Push null
+    /// Equals2
+    /// If L1
+    /// ...
+    /// Jump L0
+    /// L1:
+    /// ```
+    fn for_in(
+        &mut self,
+        i: usize,
+        hi: usize,
+        object: &Expr,
+        statements: &mut Vec<String>,
+    ) -> Structured<usize> {
+        let head = i + 1;
+        let j = Self::last_source(self.back_jumps.get(&head), hi)
+            .ok_or_else(|| "unstructured enumeration".to_string())?;
+        let exit = j + 1;
+        let is_header = head + 4 <= j
+            && matches!(self.actions[head].2, Some(Action::StoreRegister(0)))
+            && matches!(&self.actions[head + 1].2, Some(Action::Push(values)) if values[..] == [Value::Null])
+            && matches!(
+                self.actions[head + 2].2,
+                Some(Action::Equals2) | Some(Action::Equals) | Some(Action::StrictEquals)
+            )
+            && matches!(self.actions[head + 3].2, Some(Action::If { .. }))
+            && self.target(head + 3) == Ok(exit);
+        if !is_header {
+            return Err("unstructured enumeration".to_string());
+        }
+
+        let ctx = Loop {
+            break_index: Some(exit),
+            continue_index: Some(head),
+        };
+        let mut body = self.statements(head + 4, j, ctx);
+
+        // The loop variable is assigned from register 0 at the start of each iteration.
+        let suffix = format!(" = {};", self.register_name(0));
+        let name = match body.first().and_then(|s| s.strip_suffix(&suffix)) {
+            Some(name) => {
+                let name = name.to_string();
+                body.remove(0);
+                name
+            }
+            None => self.register_name(0),
+        };
+        let header = format!("for ({} in {})", name, object.text);
+        statements.push(braces(&header, &body));
+        Ok(exit)
+    }
+
+    /// Structures the forward `If` at `k`, returning the index following the structure.
+    fn branch(
+        &mut self,
+        k: usize,
+        hi: usize,
+        stack: &mut Vec<Expr>,
+        statements: &mut Vec<String>,
+        ctx: Loop,
+    ) -> Structured<usize> {
+        let t = self.target(k)?;
+        if Some(t) == ctx.break_index || Some(t) == ctx.continue_index {
+            let cond = pop(stack)?;
+            let statement = if Some(t) == ctx.break_index {
+                "break;"
+            } else {
+                "continue;"
+            };
+            statements.push(format!("if ({}) {{\n    {}\n}}", cond.text, statement));
+            return Ok(k + 1);
+        }
+        if t <= k || t > hi {
+            return Err("unstructured branch".to_string());
+        }
+
+        // `a || b` duplicates `a` and skips `b` if it is true, and `a && b` if it is false.
+        let op = match (
+            k.checked_sub(2).and_then(|j| self.actions[j].2.as_ref()),
+            k.checked_sub(1).and_then(|j| self.actions[j].2.as_ref()),
+            self.actions.get(k + 1).and_then(|a| a.2.as_ref()),
+        ) {
+            (_, Some(Action::PushDuplicate), Some(Action::Pop)) => Some(("||", LOGICAL_OR)),
+            (Some(Action::PushDuplicate), Some(Action::Not), Some(Action::Pop)) => {
+                Some(("&&", LOGICAL_AND))
+            }
+            _ => None,
+        };
+        if let Some((op, precedence)) = op {
+            pop(stack)?;
+            let left = pop(stack)?;
+            let mut right_stack = vec![];
+            let right_statements = self.structure(k + 2, t, &mut right_stack, ctx)?;
+            if !right_statements.is_empty() || right_stack.len() != 1 {
+                return Err("unstructured logical expression".to_string());
+            }
+            stack.push(Expr::binary(op, precedence, &left, &right_stack[0]));
+            return Ok(t);
+        }
+
+        let cond = pop(stack)?;
+
+        // An `else` branch is skipped by a jump at the end of the `if` branch.
+        let else_end = match self.actions[t - 1].2 {
+            Some(Action::Jump { .. }) if t - 1 > k => match self.target(t - 1) {
+                Ok(e) if e >= t && e <= hi => Some(e),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        if let Some(e) = else_end {
+            // `c ? a : b` pushes a value in each branch.
+            let declared = self.scope.declared.clone();
+            let mut then_stack = vec![];
+            let mut else_stack = vec![];
+            let then_statements = self.structure(k + 1, t - 1, &mut then_stack, ctx);
+            let else_statements = self.structure(t, e, &mut else_stack, ctx);
+            if let (Ok(then_statements), Ok(else_statements)) = (then_statements, else_statements) {
+                if then_statements.is_empty()
+                    && else_statements.is_empty()
+                    && then_stack.len() == 1
+                    && else_stack.len() == 1
+                {
+                    let (cond, a, b) = match &cond.kind {
+                        ExprKind::Not(cond) => ((**cond).clone(), &then_stack[0], &else_stack[0]),
+                        _ => (cond.clone(), &else_stack[0], &then_stack[0]),
+                    };
+                    stack.push(Expr::new(
+                        format!(
+                            "{} ? {} : {}",
+                            cond.wrap(LOGICAL_OR),
+                            a.wrap(ASSIGNMENT),
+                            b.wrap(ASSIGNMENT)
+                        ),
+                        CONDITIONAL,
+                    ));
+                    return Ok(e);
+                }
+            }
+
+            self.scope.declared = declared;
+            let then_statements = self.statements(k + 1, t - 1, ctx);
+            let else_statements = self.statements(t, e, ctx);
+            if then_statements.is_empty() {
+                statements.push(braces(&format!("if ({})", cond.text), &else_statements));
+            } else {
+                let mut text = braces(&format!("if ({})", cond.negate().text), &then_statements);
+                match &else_statements[..] {
+                    [statement] if statement.starts_with("if (") => {
+                        text.push_str(" else ");
+                        text.push_str(statement);
+                    }
+                    _ => text.push_str(&braces(" else", &else_statements)),
+                }
+                statements.push(text);
+            }
+            Ok(e)
+        } else {
+            let then_statements = self.statements(k + 1, t, ctx);
+            statements.push(braces(
+                &format!("if ({})", cond.negate().text),
+                &then_statements,
+            ));
+            Ok(t)
+        }
+    }
+
+    /// Decompiles an action that doesn't branch.
+    fn action(
+        &mut self,
+        action: Action<'a>,
+        stack: &mut Vec<Expr>,
+        statements: &mut Vec<String>,
+    ) -> Structured<()> {
+        let mut statement = |s: String| statements.push(s);
+        match action {
+            Action::Add | Action::Add2 => binary(stack, "+", ADDITIVE)?,
+            Action::And => binary(stack, "and", LOGICAL_AND)?,
+            Action::AsciiToChar => call(stack, "chr", 1)?,
+            Action::BitAnd => binary(stack, "&", BIT_AND)?,
+            Action::BitLShift => binary(stack, "<<", SHIFT)?,
+            Action::BitOr => binary(stack, "|", BIT_OR)?,
+            Action::BitRShift => binary(stack, ">>", SHIFT)?,
+            Action::BitURShift => binary(stack, ">>>", SHIFT)?,
+            Action::BitXor => binary(stack, "^", BIT_XOR)?,
+            Action::Call => {
+                let frame = pop(stack)?;
+                statement(format!("call({});", frame.text));
+            }
+            Action::CallFunction => {
+                let name = pop(stack)?;
+                let args = pop_args(stack)?;
+                let callee = match name.as_str() {
+                    Some(name) if is_path(name) => name.to_string(),
+                    _ => format!("eval({})", name.text),
+                };
+                stack.push(Expr::call(&callee, &args));
+            }
+            Action::CallMethod => {
+                let name = pop(stack)?;
+                let object = pop(stack)?;
+                let args = pop_args(stack)?;
+                let callee = match &name.kind {
+                    ExprKind::Undefined => object.wrap(PRIMARY),
+                    ExprKind::Str(name) if name.is_empty() => object.wrap(PRIMARY),
+                    _ => member(&object, &name).text,
+                };
+                stack.push(Expr::call(&callee, &args));
+            }
+            Action::CastOp => {
+                let object = pop(stack)?;
+                let constructor = pop(stack)?;
+                stack.push(Expr::call(&constructor.wrap(PRIMARY), &[object]));
+            }
+            Action::CharToAscii => call(stack, "ord", 1)?,
+            Action::CloneSprite => {
+                let depth = pop(stack)?;
+                let target = pop(stack)?;
+                let source = pop(stack)?;
+                let args = [source, target, depth];
+                statement(format!("{};", Expr::call("duplicateMovieClip", &args).text));
+            }
+            Action::ConstantPool(constant_pool) => self.scope.constant_pool = constant_pool,
+            Action::Decrement => {
+                let operand = pop(stack)?;
+                let expr = Expr::binary("-", ADDITIVE, &operand, &literal("1"));
+                stack.push(expr.with_kind(ExprKind::Decrement(operand.text)));
+            }
+            Action::DefineFunction {
+                name,
+                params,
+                actions,
+            } => {
+                // `DefineFunction` uses the global registers, so their names are unknown.
+                let mut scope = Scope {
+                    constant_pool: self.scope.constant_pool.clone(),
+                    ..Default::default()
+                };
+                let body = decompile_block(actions, self.version, &mut scope)
+                    .map_err(|e| e.to_string())?;
+                let header = format!("function {}({})", name, params.join(", "));
+                self.function(name, &header, &body, stack, &mut statement);
+            }
+            Action::DefineFunction2(function) => {
+                let mut scope = Scope {
+                    constant_pool: self.scope.constant_pool.clone(),
+                    is_function2: true,
+                    ..Default::default()
+                };
+                // Preloaded values are stored in consecutive registers, starting at 1.
+                let mut register = 1;
+                for &(is_preloaded, name) in &[
+                    (function.preload_this, "this"),
+                    (function.preload_arguments, "arguments"),
+                    (function.preload_super, "super"),
+                    (function.preload_root, "_root"),
+                    (function.preload_parent, "_parent"),
+                    (function.preload_global, "_global"),
+                ] {
+                    if is_preloaded {
+                        scope.registers.insert(register, name.to_string());
+                        scope.declared.insert(register);
+                        register += 1;
+                    }
+                }
+                for param in &function.params {
+                    if let Some(register) = param.register_index {
+                        scope.registers.insert(register, param.name.to_string());
+                        scope.declared.insert(register);
+                    }
+                }
+                let body = decompile_block(function.actions, self.version, &mut scope)
+                    .map_err(|e| e.to_string())?;
+                let params: Vec<_> = function.params.iter().map(|p| p.name).collect();
+                let header = format!("function {}({})", function.name, params.join(", "));
+                self.function(function.name, &header, &body, stack, &mut statement);
+            }
+            Action::DefineLocal => {
+                let value = pop(stack)?;
+                let name = pop(stack)?;
+                statement(format!("var {} = {};", variable(&name).text, value.text));
+            }
+            Action::DefineLocal2 => {
+                let name = pop(stack)?;
+                statement(format!("var {};", variable(&name).text));
+            }
+            Action::Delete => {
+                let name = pop(stack)?;
+                let object = pop(stack)?;
+                stack.push(Expr::unary("delete ", &member(&object, &name)));
+            }
+            Action::Delete2 => {
+                let name = pop(stack)?;
+                stack.push(Expr::unary("delete ", &variable(&name)));
+            }
+            Action::Divide => binary(stack, "/", MULTIPLICATIVE)?,
+            Action::EndDrag => statement("stopDrag();".to_string()),
+            Action::Equals | Action::Equals2 => binary(stack, "==", EQUALITY)?,
+            Action::Extends => {
+                let superclass = pop(stack)?;
+                let subclass = pop(stack)?;
+                statement(format!("{} extends {};", subclass.text, superclass.text));
+            }
+            Action::GetMember => {
+                let name = pop(stack)?;
+                let object = pop(stack)?;
+                stack.push(member(&object, &name));
+            }
+            Action::GetProperty => {
+                let index = pop(stack)?;
+                let target = pop(stack)?;
+                stack.push(property(&target, &index, None));
+            }
+            Action::GetTime => stack.push(Expr::call("getTimer", &[])),
+            Action::GetUrl { url, target } => {
+                let call = match url.strip_prefix("FSCommand:") {
+                    Some(command) => Expr::call("fscommand", &[string(command), string(target)]),
+                    None => Expr::call("getURL", &[string(url), string(target)]),
+                };
+                statement(format!("{};", call.text));
+            }
+            Action::GetUrl2 {
+                send_vars_method,
+                is_target_sprite,
+                is_load_vars,
+            } => {
+                let target = pop(stack)?;
+                let url = pop(stack)?;
+                let function = match (is_target_sprite, is_load_vars) {
+                    (_, true) => "loadVariables",
+                    (true, false) => "loadMovie",
+                    (false, false) => "getURL",
+                };
+                let mut args = vec![url, target];
+                match send_vars_method {
+                    SendVarsMethod::None => (),
+                    SendVarsMethod::Get => args.push(string("GET")),
+                    SendVarsMethod::Post => args.push(string("POST")),
+                }
+                statement(format!("{};", Expr::call(function, &args).text));
+            }
+            Action::GetVariable => {
+                let name = pop(stack)?;
+                stack.push(variable(&name));
+            }
+            Action::GotoFrame(frame) => {
+                statement(format!("gotoAndStop({});", u32::from(frame) + 1));
+            }
+            Action::GotoFrame2 {
+                set_playing,
+                scene_offset,
+            } => {
+                let frame = pop(stack)?;
+                let function = if set_playing {
+                    "gotoAndPlay"
+                } else {
+                    "gotoAndStop"
+                };
+                let mut text = format!("{};", Expr::call(function, &[frame]).text);
+                if scene_offset != 0 {
+                    text.push_str(&format!(" // Scene offset {}", scene_offset));
+                }
+                statement(text);
+            }
+            Action::GotoLabel(label) => {
+                statement(format!("gotoAndStop({});", quote(label)));
+            }
+            Action::Greater => binary(stack, ">", RELATIONAL)?,
+            Action::ImplementsOp => {
+                let constructor = pop(stack)?;
+                // Unlike arguments, the interfaces are pushed in order.
+                let interfaces = pop_args(stack)?;
+                let interfaces: Vec<_> = interfaces.into_iter().rev().map(|i| i.text).collect();
+                statement(format!(
+                    "{} implements {};",
+                    constructor.text,
+                    interfaces.join(", ")
+                ));
+            }
+            Action::Increment => {
+                let operand = pop(stack)?;
+                let expr = Expr::binary("+", ADDITIVE, &operand, &literal("1"));
+                stack.push(expr.with_kind(ExprKind::Increment(operand.text)));
+            }
+            Action::InitArray => {
+                let elements = pop_args(stack)?;
+                let elements: Vec<_> = elements.iter().map(|e| e.wrap(ASSIGNMENT)).collect();
+                stack.push(Expr::new(format!("[{}]", elements.join(", ")), PRIMARY));
+            }
+            Action::InitObject => {
+                let count = pop_count(stack)?;
+                let mut properties = Vec::with_capacity(count);
+                for _ in 0..count {
+                    let value = pop(stack)?;
+                    let name = pop(stack)?;
+                    let name = match name.as_str() {
+                        Some(s) if is_identifier(s) => s.to_string(),
+                        _ => name.text,
+                    };
+                    properties.push(format!("{}: {}", name, value.wrap(ASSIGNMENT)));
+                }
+                stack.push(Expr::new(format!("{{{}}}", properties.join(", ")), PRIMARY));
+            }
+            Action::InstanceOf => binary(stack, "instanceof", RELATIONAL)?,
+            Action::Less | Action::Less2 => binary(stack, "<", RELATIONAL)?,
+            Action::MBAsciiToChar => call(stack, "mbchr", 1)?,
+            Action::MBCharToAscii => call(stack, "mbord", 1)?,
+            Action::MBStringExtract => call(stack, "mbsubstring", 3)?,
+            Action::MBStringLength => call(stack, "mblength", 1)?,
+            Action::Modulo => binary(stack, "%", MULTIPLICATIVE)?,
+            Action::Multiply => binary(stack, "*", MULTIPLICATIVE)?,
+            Action::NewMethod => {
+                let name = pop(stack)?;
+                let object = pop(stack)?;
+                let args = pop_args(stack)?;
+                let constructor = match &name.kind {
+                    ExprKind::Undefined => object.wrap(PRIMARY),
+                    ExprKind::Str(name) if name.is_empty() => object.wrap(PRIMARY),
+                    _ => member(&object, &name).text,
+                };
+                let call = Expr::call(&constructor, &args);
+                stack.push(Expr::new(format!("new {}", call.text), PRIMARY));
+            }
+            Action::NewObject => {
+                let name = pop(stack)?;
+                let args = pop_args(stack)?;
+                let constructor = match name.as_str() {
+                    Some(name) if is_path(name) => name.to_string(),
+                    _ => format!("(eval({}))", name.text),
+                };
+                let call = Expr::call(&constructor, &args);
+                stack.push(Expr::new(format!("new {}", call.text), PRIMARY));
+            }
+            Action::NextFrame => statement("nextFrame();".to_string()),
+            Action::Not => {
+                let operand = pop(stack)?;
+                stack.push(operand.negate());
+            }
+            Action::Or => binary(stack, "or", LOGICAL_OR)?,
+            Action::Play => statement("play();".to_string()),
+            Action::Pop => {
+                // The AS2 compiler emits a stray `Pop` after class definitions, which is harmless
+                // as popping an empty stack gives `undefined`.
+                if let Some(expr) = stack.pop() {
+                    let text = self.expression_statement(expr);
+                    statement(text);
+                }
+            }
+            Action::PreviousFrame => statement("prevFrame();".to_string()),
+            Action::Push(values) => {
+                for value in &values {
+                    let expr = self.value(value)?;
+                    stack.push(expr);
+                }
+            }
+            Action::PushDuplicate => {
+                let expr = stack.last().cloned().ok_or("stack underflow")?;
+                stack.push(expr);
+            }
+            Action::RandomNumber => call(stack, "random", 1)?,
+            Action::RemoveSprite => {
+                let target = pop(stack)?;
+                statement(format!("removeMovieClip({});", target.text));
+            }
+            Action::Return => {
+                let value = pop(stack)?;
+                match value.kind {
+                    ExprKind::Undefined => statement("return;".to_string()),
+                    _ => statement(format!("return {};", value.text)),
+                }
+            }
+            Action::SetMember => {
+                let value = pop(stack)?;
+                let name = pop(stack)?;
+                let object = pop(stack)?;
+                let target = member(&object, &name);
+                statement(format!("{};", assignment(&target.text, &value)));
+            }
+            Action::SetProperty => {
+                let value = pop(stack)?;
+                let index = pop(stack)?;
+                let target = pop(stack)?;
+                let property = property(&target, &index, Some(&value));
+                statement(format!("{};", property.text));
+            }
+            Action::SetVariable => {
+                let value = pop(stack)?;
+                let name = pop(stack)?;
+                match name.as_str() {
+                    Some(s) if is_path(s) => {
+                        statement(format!("{};", assignment(s, &value)));
+                    }
+                    _ => statement(format!("{};", Expr::call("set", &[name, value]).text)),
+                }
+            }
+            Action::StackSwap => {
+                let a = pop(stack)?;
+                let b = pop(stack)?;
+                stack.push(a);
+                stack.push(b);
+            }
+            Action::StartDrag => {
+                let target = pop(stack)?;
+                let lock_center = pop(stack)?;
+                let constrain = pop(stack)?;
+                let mut args = vec![target, lock_center];
+                match constrain.kind {
+                    ExprKind::Number(n) => {
+                        if n != 0.0 {
+                            let bottom = pop(stack)?;
+                            let right = pop(stack)?;
+                            let top = pop(stack)?;
+                            let left = pop(stack)?;
+                            args.extend(vec![left, top, right, bottom]);
+                        }
+                    }
+                    _ => return Err("computed drag constraint".to_string()),
+                }
+                statement(format!("{};", Expr::call("startDrag", &args).text));
+            }
+            Action::Stop => statement("stop();".to_string()),
+            Action::StopSounds => statement("stopAllSounds();".to_string()),
+            Action::StoreRegister(register) => {
+                let value = pop(stack)?;
+                let text = format!("{} = {}", self.register_name(register), value.text);
+                stack.push(
+                    Expr::new(text, ASSIGNMENT)
+                        .with_kind(ExprKind::StoreRegister(register, Box::new(value))),
+                );
+            }
+            Action::StrictEquals => binary(stack, "===", EQUALITY)?,
+            Action::StringAdd => binary(stack, "add", ADDITIVE)?,
+            Action::StringEquals => binary(stack, "eq", EQUALITY)?,
+            Action::StringExtract => call(stack, "substring", 3)?,
+            Action::StringGreater => binary(stack, "gt", RELATIONAL)?,
+            Action::StringLength => call(stack, "length", 1)?,
+            Action::StringLess => binary(stack, "lt", RELATIONAL)?,
+            Action::Subtract => binary(stack, "-", ADDITIVE)?,
+            Action::TargetPath => call(stack, "targetPath", 1)?,
+            Action::Throw => {
+                let value = pop(stack)?;
+                statement(format!("throw {};", value.text));
+            }
+            Action::ToInteger => call(stack, "int", 1)?,
+            Action::ToNumber => call(stack, "Number", 1)?,
+            Action::ToString => call(stack, "String", 1)?,
+            Action::ToggleQuality => statement("toggleHighQuality();".to_string()),
+            Action::Trace => {
+                let value = pop(stack)?;
+                statement(format!("{};", Expr::call("trace", &[value]).text));
+            }
+            Action::Try(try_block) => {
+                let try_statements =
+                    decompile_block(try_block.try_actions, self.version, &mut self.scope.clone())
+                        .map_err(|e| e.to_string())?;
+                let mut text = braces("try", &try_statements);
+                if let Some((var, actions)) = try_block.catch {
+                    let mut scope = self.scope.clone();
+                    let name = match var {
+                        CatchVar::Var(name) => name.to_string(),
+                        CatchVar::Register(register) => {
+                            scope.declared.insert(register);
+                            self.register_name(register)
+                        }
+                    };
+                    let catch_statements = decompile_block(actions, self.version, &mut scope)
+                        .map_err(|e| e.to_string())?;
+                    text.push_str(&braces(&format!(" catch ({})", name), &catch_statements));
+                }
+                if let Some(actions) = try_block.finally {
+                    let finally_statements =
+                        decompile_block(actions, self.version, &mut self.scope.clone())
+                            .map_err(|e| e.to_string())?;
+                    text.push_str(&braces(" finally", &finally_statements));
+                }
+                statement(text);
+            }
+            Action::TypeOf => {
+                let operand = pop(stack)?;
+                stack.push(Expr::unary("typeof ", &operand));
+            }
+            Action::With { actions } => {
+                let object = pop(stack)?;
+                let body = decompile_block(actions, self.version, &mut self.scope.clone())
+                    .map_err(|e| e.to_string())?;
+                statement(braces(&format!("with ({})", object.text), &body));
+            }
+            Action::Unknown { opcode, .. } => {
+                return Err(format!("unknown action {:#x}", opcode));
+            }
+            Action::If { .. }
+            | Action::Jump { .. }
+            | Action::Enumerate
+            | Action::Enumerate2
+            | Action::SetTarget(_)
+            | Action::SetTarget2
+            | Action::WaitForFrame { .. }
+            | Action::WaitForFrame2 { .. } => unreachable!("Structured actions are handled above"),
+        }
+        Ok(())
+    }
+
+    /// Adds a function definition as a statement if it is named, or an expression otherwise.
+    fn function(
+        &self,
+        name: &str,
+        header: &str,
+        body: &[String],
+        stack: &mut Vec<Expr>,
+        statement: &mut impl FnMut(String),
+    ) {
+        let text = braces(header, body);
+        if name.is_empty() {
+            stack.push(Expr::new(text, ASSIGNMENT));
+        } else {
+            statement(text);
+        }
+    }
+
+    /// Returns a statement that evaluates `expr` and discards its value.
+    fn expression_statement(&mut self, expr: Expr) -> String {
+        match expr.kind {
+            ExprKind::StoreRegister(register, value) => {
+                // Registers of a `DefineFunction2` are declared by their first assignment.
+                let var = if self.scope.is_function2 && self.scope.declared.insert(register) {
+                    "var "
+                } else {
+                    ""
+                };
+                let name = self.register_name(register);
+                format!("{}{};", var, assignment(&name, &value))
+            }
+            _ => format!("{};", expr.text),
+        }
+    }
+
+    fn value(&self, value: &Value) -> Structured<Expr> {
+        Ok(match *value {
+            Value::Undefined => literal("undefined").with_kind(ExprKind::Undefined),
+            Value::Null => literal("null"),
+            Value::Bool(value) => literal(&value.to_string())
+                .with_kind(ExprKind::Number(if value { 1.0 } else { 0.0 })),
+            Value::Int(value) => number(f64::from(value)),
+            Value::Float(value) => number(f64::from(value)),
+            Value::Double(value) => number(value),
+            Value::Str(value) => string(value),
+            Value::Register(register) => literal(&self.register_name(register)),
+            Value::ConstantPool(index) => match self.scope.constant_pool.get(usize::from(index)) {
+                Some(value) => string(value),
+                None => return Err(format!("missing constant pool entry {}", index)),
+            },
+        })
+    }
+}
+
+fn pop(stack: &mut Vec<Expr>) -> Structured<Expr> {
+    stack.pop().ok_or_else(|| "stack underflow".to_string())
+}
+
+/// Pops a count pushed as a literal, such as the number of arguments to a function.
+fn pop_count(stack: &mut Vec<Expr>) -> Structured<usize> {
+    match pop(stack)?.kind {
+        ExprKind::Number(n) if n >= 0.0 && n.fract() == 0.0 && n as usize <= stack.len() => {
+            Ok(n as usize)
+        }
+        _ => Err("computed argument count".to_string()),
+    }
+}
+
+/// Pops a count followed by that many values, the first of which was pushed last.
+fn pop_args(stack: &mut Vec<Expr>) -> Structured<Vec<Expr>> {
+    let count = pop_count(stack)?;
+    let mut args = Vec::with_capacity(count);
+    for _ in 0..count {
+        args.push(pop(stack)?);
+    }
+    Ok(args)
+}
+
+fn binary(stack: &mut Vec<Expr>, op: &str, precedence: u8) -> Structured<()> {
+    let right = pop(stack)?;
+    let left = pop(stack)?;
+    stack.push(Expr::binary(op, precedence, &left, &right));
+    Ok(())
+}
+
+/// Replaces `num_args` values with a call to a global function, the first argument having been
+/// pushed first.
+fn call(stack: &mut Vec<Expr>, function: &str, num_args: usize) -> Structured<()> {
+    if stack.len() < num_args {
+        return Err("stack underflow".to_string());
+    }
+    let args = stack.split_off(stack.len() - num_args);
+    stack.push(Expr::call(function, &args));
+    Ok(())
+}
+
+fn literal(text: &str) -> Expr {
+    Expr::new(text.to_string(), PRIMARY)
+}
+
+fn number(n: f64) -> Expr {
+    let text = if n.is_nan() {
+        "NaN".to_string()
+    } else if n.is_infinite() {
+        if n > 0.0 { "Infinity" } else { "-Infinity" }.to_string()
+    } else {
+        n.to_string()
+    };
+    let precedence = if text.starts_with('-') {
+        UNARY
+    } else {
+        PRIMARY
+    };
+    Expr::new(text, precedence).with_kind(ExprKind::Number(n))
+}
+
+fn string(s: &str) -> Expr {
+    literal(&quote(s)).with_kind(ExprKind::Str(s.to_string()))
+}
+
+/// Returns the value of the variable named by `name`.
+fn variable(name: &Expr) -> Expr {
+    match name.as_str() {
+        Some(s) if is_path(s) => literal(s),
+        _ => Expr::call("eval", std::slice::from_ref(name)),
+    }
+}
+
+fn member(object: &Expr, name: &Expr) -> Expr {
+    match name.as_str() {
+        Some(s) if is_identifier(s) => literal(&format!("{}.{}", object.wrap(PRIMARY), s)),
+        _ => literal(&format!("{}[{}]", object.wrap(PRIMARY), name.text)),
+    }
+}
+
+/// Returns a `getProperty` call, or a `setProperty` call if `value` is given.
+fn property(target: &Expr, index: &Expr, value: Option<&Expr>) -> Expr {
+    const PROPERTIES: [&str; 22] = [
+        "_x",
+        "_y",
+        "_xscale",
+        "_yscale",
+        "_currentframe",
+        "_totalframes",
+        "_alpha",
+        "_visible",
+        "_width",
+        "_height",
+        "_rotation",
+        "_target",
+        "_framesloaded",
+        "_name",
+        "_droptarget",
+        "_url",
+        "_highquality",
+        "_focusrect",
+        "_soundbuftime",
+        "_quality",
+        "_xmouse",
+        "_ymouse",
+    ];
+    let name = match index.kind {
+        ExprKind::Number(n) if n >= 0.0 && (n as usize) < PROPERTIES.len() => {
+            Some(PROPERTIES[n as usize])
+        }
+        _ => None,
+    };
+    match (target.as_str(), name, value) {
+        (Some(""), Some(name), None) => literal(name),
+        (Some(""), Some(name), Some(value)) => {
+            Expr::new(format!("{} = {}", name, value.text), ASSIGNMENT)
+        }
+        (_, _, None) => Expr::call(
+            "getProperty",
+            &[target.clone(), name.map_or_else(|| index.clone(), literal)],
+        ),
+        (_, _, Some(value)) => Expr::call(
+            "setProperty",
+            &[
+                target.clone(),
+                name.map_or_else(|| index.clone(), literal),
+                value.clone(),
+            ],
+        ),
+    }
+}
+
+/// Returns an assignment of `value` to `target`, using `++` or `--` if possible.
+fn assignment(target: &str, value: &Expr) -> String {
+    match &value.kind {
+        ExprKind::Increment(operand) if operand == target => format!("{}++", target),
+        ExprKind::Decrement(operand) if operand == target => format!("{}--", target),
+        _ => format!("{} = {}", target, value.text),
+    }
+}
+
+/// Returns a block of statements in braces, following `header`.
+fn braces(header: &str, statements: &[String]) -> String {
+    format!("{} {{\n{}}}", header, indent(statements))
+}
+
+/// Indents each line of `statements`, ending each with a newline.
+fn indent(statements: &[String]) -> String {
+    let mut text = String::new();
+    for statement in statements {
+        for line in statement.lines() {
+            text.push_str("    ");
+            text.push_str(line);
+            text.push('\n');
+        }
+    }
+    text
+}
+
+/// Returns a string literal.
+fn quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            '\u{8}' => quoted.push_str("\\b"),
+            '\u{c}' => quoted.push_str("\\f"),
+            c if c.is_control() => quoted.push_str(&format!("\\x{:02x}", u32::from(c))),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Returns whether `s` is a dot-separated path of identifiers, such as `_root.clip.x`.
+fn is_path(s: &str) -> bool {
+    s.split('.').all(is_identifier)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm1::disasm::assemble;
+
+    fn decompile_pcode(pcode: &str) -> String {
+        let data = assemble(pcode, 8).unwrap();
+        decompile(&data, 8).unwrap()
+    }
+
+    #[test]
+    fn decompile_expressions() {
+        let pcode = r#"
+ConstantPool "x", "_root", "y", "foo"
+Push c0, 1, 2
+Add2
+Push 3
+Multiply
+SetVariable
+Push c1
+GetVariable
+Push c2, 3, 1, 2, c3
+CallFunction
+SetMember
+Push c0, c0
+GetVariable
+Increment
+SetVariable
+Push "v", "a", 1, "b", true, 2
+InitObject
+DefineLocal
+Push "t", 9
+GetProperty
+Push 2
+Subtract
+Trace
+Push "mc", 0
+GetProperty
+Not
+Not
+Not
+Trace
+End
+"#;
+        assert_eq!(
+            decompile_pcode(pcode),
+            r#"x = (1 + 2) * 3;
+_root.y = foo(1, 3);
+x++;
+var v = {b: true, a: 1};
+trace(getProperty("t", _height) - 2);
+trace(!getProperty("mc", _x));
+"#
+        );
+    }
+
+    #[test]
+    fn decompile_control_flow() {
+        let pcode = r#"
+Push "a"
+GetVariable
+Not
+If L0
+Push "b"
+Trace
+Jump L1
+L0:
+Push "c"
+Trace
+L1:
+L2:
+Push "i"
+GetVariable
+Push 10
+Less2
+Not
+If L4
+Push "i"
+GetVariable
+Push 5
+Equals2
+If L4
+Push "i", "i"
+GetVariable
+Increment
+SetVariable
+Jump L2
+L4:
+L5:
+Push "i", "i"
+GetVariable
+Decrement
+SetVariable
+Push "i"
+GetVariable
+Push 0
+Greater
+If L5
+Push "o"
+GetVariable
+Enumerate2
+L6:
+StoreRegister r0
+Push null
+Equals2
+If L7
+Push "k", r0
+SetVariable
+Push "k"
+GetVariable
+Trace
+Jump L6
+L7:
+End
+"#;
+        assert_eq!(
+            decompile_pcode(pcode),
+            r#"if (a) {
+    trace("b");
+} else {
+    trace("c");
+}
+while (i < 10) {
+    if (i == 5) {
+        break;
+    }
+    i++;
+}
+do {
+    i--;
+} while (i > 0);
+for (k in o) {
+    trace(k);
+}
+"#
+        );
+    }
+
+    #[test]
+    fn decompile_logical_and_conditional() {
+        let pcode = r#"
+Push "x", "a"
+GetVariable
+PushDuplicate
+Not
+If L0
+Pop
+Push "b"
+GetVariable
+L0:
+PushDuplicate
+If L1
+Pop
+Push "c"
+GetVariable
+L1:
+SetVariable
+Push "y", "a"
+GetVariable
+If L2
+Push 1
+Jump L3
+L2:
+Push 2
+L3:
+SetVariable
+End
+"#;
+        assert_eq!(decompile_pcode(pcode), "x = a && b || c;\ny = a ? 2 : 1;\n");
+    }
+
+    #[test]
+    fn decompile_functions_and_blocks() {
+        let pcode = r#"
+DefineFunction2 "f", 4, [r2:"a"], [preload_this, suppress_arguments, suppress_super] {
+    Push r1, "n"
+    GetMember
+    Push r2
+    Add2
+    StoreRegister r3
+    Pop
+    Push r3
+    Return
+}
+Push "o"
+GetVariable
+With {
+    Try "e" {
+        Push "e"
+        Throw
+    } catch {
+        Push "e"
+        GetVariable
+        Trace
+    }
+}
+Push 0, "m"
+GetVariable
+Push "go"
+CallMethod
+Pop
+End
+"#;
+        assert_eq!(
+            decompile_pcode(pcode),
+            r#"function f(a) {
+    var register3 = this.n + a;
+    return register3;
+}
+with (o) {
+    try {
+        throw "e";
+    } catch (e) {
+        trace(e);
+    }
+}
+m.go();
+"#
+        );
+    }
+
+    #[test]
+    fn decompile_unstructured_to_pcode() {
+        let pcode = r#"
+Push "a"
+GetVariable
+If L0
+Push 1
+Trace
+Jump L1
+L0:
+Push 2
+L1:
+Trace
+End
+"#;
+        let text = decompile_pcode(pcode);
+        assert!(text.starts_with("/* p-code ("), "{}", text);
+        assert!(text.contains("    Push \"a\"\n"), "{}", text);
+        assert!(text.ends_with("*/\n"), "{}", text);
+    }
+
+    #[test]
+    fn decompile_clip_events() {
+        let data = assemble("Stop\nEnd\n", 8).unwrap();
+        let clip_action = ClipAction {
+            events: ClipEventFlag::Load | ClipEventFlag::EnterFrame,
+            key_code: None,
+            action_data: &data,
+        };
+        assert_eq!(
+            decompile_clip_action(&clip_action, 8).unwrap(),
+            "onClipEvent(enterFrame, load) {\n    stop();\n}\n"
+        );
+
+        let clip_action = ClipAction {
+            events: ClipEventFlag::Press | ClipEventFlag::KeyPress,
+            key_code: Some(13),
+            action_data: &data,
+        };
+        assert_eq!(
+            decompile_clip_action(&clip_action, 8).unwrap(),
+            "on(keyPress \"<Enter>\", press) {\n    stop();\n}\n"
+        );
+    }
+}
@@ -0,0 +1,1129 @@
+//! A textual format for AVM1 bytecode.
+//!
+//! `disassemble` converts AVM1 bytecode to p-code text, and `assemble` converts the text back
+//! to bytecode, so that AVM1 test cases can be written by hand and bytecode can be diffed.
+//!
+//! Each line contains an action, named after its `Action` variant, followed by its
+//! comma-separated arguments:
+//!
+//! ```text
+//! ConstantPool "_root", "count"
+//! Push c0:"_root"
+//! GetVariable
+//! Push c1:"count"
+//! GetMember
+//! If L0
+//! DefineFunction2 "inc", 3, [r2:"x"], [preload_this, suppress_super] {
+//!     Push r2:x, 1
+//!     Add2
+//!     Return
+//! }
+//! L0:
+//! End
+//! ```
+//!
+//! * Strings are quoted, with Rust escape sequences.
+//! * Integers are written as `1`, doubles as `1.0`, and floats as `1.0f`.
+//! * `rN` is register `N`, and `cN` is constant pool index `N`. Either may be followed by
+//!   `:name`, which is shown for readability and ignored when assembling.
+//! * `If` and `Jump` target a label defined as `name:` in the same block, or a relative byte
+//!   offset if the target is not at the start of an action.
+//! * Actions containing code, such as `DefineFunction`, `With` and `Try`, are followed by
+//!   their indented body in braces. A `Try` body may be followed by `catch { ... }` and
+//!   `finally { ... }` blocks.
+//! * `;` starts a comment.
+
+use crate::avm1::opcode::OpCode;
+use crate::avm1::read::Reader;
+use crate::avm1::types::*;
+use crate::avm1::write::Writer;
+use crate::error::{Error, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+/// Converts AVM1 bytecode to p-code text.
+///
+/// # Example
+/// ```
+/// let text = swf::avm1::disasm::disassemble(&[0x96, 2, 0, 5, 1, 0x26, 0], 8).unwrap();
+/// assert_eq!(text, "Push true\nTrace\nEnd\n");
+/// ```
+pub fn disassemble(data: &[u8], version: u8) -> Result<String> {
+    let mut disassembler = Disassembler {
+        version,
+        output: String::new(),
+        num_labels: 0,
+    };
+    disassembler.disassemble_block(data, &mut Scope::default(), 0)?;
+    Ok(disassembler.output)
+}
+
+/// Converts p-code text, as produced by `disassemble`, to AVM1 bytecode.
+///
+/// # Example
+/// ```
+/// let data = swf::avm1::disasm::assemble("Push true\nTrace\nEnd\n", 8).unwrap();
+/// assert_eq!(data, [0x96, 2, 0, 5, 1, 0x26, 0]);
+/// ```
+pub fn assemble(text: &str, version: u8) -> Result<Vec<u8>> {
+    let tokens = tokenize(text)?;
+    let mut assembler = Assembler {
+        tokens: &tokens,
+        pos: 0,
+        version,
+    };
+    let data = assembler.assemble_block()?;
+    if let Some((line, _)) = assembler.tokens.get(assembler.pos) {
+        return Err(syntax_error(*line, "Unexpected }"));
+    }
+    Ok(data)
+}
+
+/// The constant pool and register names visible to a block of actions.
+#[derive(Clone, Default)]
+struct Scope<'a> {
+    constant_pool: Vec<&'a str>,
+    registers: HashMap<u8, &'a str>,
+}
+
+struct Disassembler {
+    version: u8,
+    output: String,
+    num_labels: usize,
+}
+
+impl Disassembler {
+    fn disassemble_block<'a>(
+        &mut self,
+        data: &'a [u8],
+        scope: &mut Scope<'a>,
+        depth: usize,
+    ) -> Result<()> {
+        // Read the whole block first so that jump targets can be labelled.
+        let mut reader = Reader::new(data, self.version);
+        let mut actions = Vec::new();
+        while reader.pos() < data.len() {
+            let start = reader.pos();
+            let action = reader.read_action()?;
+            actions.push((start, reader.pos(), action));
+        }
+
+        let mut labels = BTreeMap::new();
+        for (_, end, action) in &actions {
+            if let Some(Action::If { offset }) | Some(Action::Jump { offset }) = action {
+                let target = *end as isize + isize::from(*offset);
+                let is_action_start = actions
+                    .iter()
+                    .any(|(start, _, _)| *start as isize == target);
+                if is_action_start || target == data.len() as isize {
+                    labels.insert(target as usize, String::new());
+                }
+            }
+        }
+        for name in labels.values_mut() {
+            *name = format!("L{}", self.num_labels);
+            self.num_labels += 1;
+        }
+
+        for (start, end, action) in &actions {
+            if let Some(label) = labels.get(start) {
+                self.write_line(depth, format_args!("{}:", label));
+            }
+            match action {
+                Some(action) => self.disassemble_action(action, *end, &labels, scope, depth)?,
+                None => self.write_line(depth, format_args!("End")),
+            }
+        }
+        if let Some(label) = labels.get(&data.len()) {
+            self.write_line(depth, format_args!("{}:", label));
+        }
+        Ok(())
+    }
+
+    fn disassemble_action<'a>(
+        &mut self,
+        action: &Action<'a>,
+        end: usize,
+        labels: &BTreeMap<usize, String>,
+        scope: &mut Scope<'a>,
+        depth: usize,
+    ) -> Result<()> {
+        match action {
+            Action::ConstantPool(constants) => {
+                let strings: Vec<_> = constants.iter().map(|c| format!("{:?}", c)).collect();
+                self.write_line(depth, format_args!("ConstantPool {}", strings.join(", ")));
+                scope.constant_pool = constants.clone();
+            }
+            Action::DefineFunction {
+                name,
+                params,
+                actions,
+            } => {
+                let params: Vec<_> = params.iter().map(|p| format!("{:?}", p)).collect();
+                self.write_line(
+                    depth,
+                    format_args!("DefineFunction {:?}, [{}] {{", name, params.join(", ")),
+                );
+                // DefineFunction uses the global registers, so their names are unknown.
+                let mut function_scope = Scope {
+                    constant_pool: scope.constant_pool.clone(),
+                    registers: HashMap::new(),
+                };
+                self.disassemble_block(actions, &mut function_scope, depth + 1)?;
+                self.write_line(depth, format_args!("}}"));
+            }
+            Action::DefineFunction2(function) => {
+                let mut function_scope = Scope {
+                    constant_pool: scope.constant_pool.clone(),
+                    registers: HashMap::new(),
+                };
+                let mut flags = vec![];
+                let mut register = 1;
+                for &(is_set, flag, preloaded_name) in &[
+                    (function.preload_this, "preload_this", Some("this")),
+                    (function.suppress_this, "suppress_this", None),
+                    (
+                        function.preload_arguments,
+                        "preload_arguments",
+                        Some("arguments"),
+                    ),
+                    (function.suppress_arguments, "suppress_arguments", None),
+                    (function.preload_super, "preload_super", Some("super")),
+                    (function.suppress_super, "suppress_super", None),
+                    (function.preload_root, "preload_root", Some("_root")),
+                    (function.preload_parent, "preload_parent", Some("_parent")),
+                    (function.preload_global, "preload_global", Some("_global")),
+                ] {
+                    if is_set {
+                        flags.push(flag);
+                        // Preloaded values are stored in consecutive registers, starting at 1.
+                        if let Some(preloaded_name) = preloaded_name {
+                            function_scope.registers.insert(register, preloaded_name);
+                            register += 1;
+                        }
+                    }
+                }
+                let mut params = vec![];
+                for param in &function.params {
+                    if let Some(register) = param.register_index {
+                        function_scope.registers.insert(register, param.name);
+                        params.push(format!("r{}:{:?}", register, param.name));
+                    } else {
+                        params.push(format!("{:?}", param.name));
+                    }
+                }
+                self.write_line(
+                    depth,
+                    format_args!(
+                        "DefineFunction2 {:?}, {}, [{}], [{}] {{",
+                        function.name,
+                        function.register_count,
+                        params.join(", "),
+                        flags.join(", ")
+                    ),
+                );
+                self.disassemble_block(function.actions, &mut function_scope, depth + 1)?;
+                self.write_line(depth, format_args!("}}"));
+            }
+            Action::GetUrl { url, target } => {
+                self.write_line(depth, format_args!("GetUrl {:?}, {:?}", url, target))
+            }
+            Action::GetUrl2 {
+                send_vars_method,
+                is_target_sprite,
+                is_load_vars,
+            } => {
+                let mut args = vec![match send_vars_method {
+                    SendVarsMethod::None => "none",
+                    SendVarsMethod::Get => "get",
+                    SendVarsMethod::Post => "post",
+                }];
+                if *is_target_sprite {
+                    args.push("target_sprite");
+                }
+                if *is_load_vars {
+                    args.push("load_vars");
+                }
+                self.write_line(depth, format_args!("GetUrl2 {}", args.join(", ")));
+            }
+            Action::GotoFrame(frame) => self.write_line(depth, format_args!("GotoFrame {}", frame)),
+            Action::GotoFrame2 {
+                set_playing,
+                scene_offset,
+            } => {
+                let play = if *set_playing { "play" } else { "stop" };
+                if *scene_offset != 0 {
+                    self.write_line(depth, format_args!("GotoFrame2 {}, {}", play, scene_offset));
+                } else {
+                    self.write_line(depth, format_args!("GotoFrame2 {}", play));
+                }
+            }
+            Action::GotoLabel(label) => {
+                self.write_line(depth, format_args!("GotoLabel {:?}", label))
+            }
+            Action::If { offset } | Action::Jump { offset } => {
+                let name = if let Action::If { .. } = action {
+                    "If"
+                } else {
+                    "Jump"
+                };
+                let target = end as isize + isize::from(*offset);
+                let label = if target >= 0 {
+                    labels.get(&(target as usize))
+                } else {
+                    None
+                };
+                match label {
+                    Some(label) => self.write_line(depth, format_args!("{} {}", name, label)),
+                    None => self.write_line(depth, format_args!("{} {}", name, offset)),
+                }
+            }
+            Action::Push(values) => {
+                let values: Vec<_> = values
+                    .iter()
+                    .map(|value| disassemble_value(value, scope))
+                    .collect();
+                self.write_line(depth, format_args!("Push {}", values.join(", ")));
+            }
+            Action::SetTarget(target) => {
+                self.write_line(depth, format_args!("SetTarget {:?}", target))
+            }
+            Action::StoreRegister(register) => {
+                let register = disassemble_value(&Value::Register(*register), scope);
+                self.write_line(depth, format_args!("StoreRegister {}", register));
+            }
+            Action::Try(try_block) => {
+                match try_block.catch {
+                    Some((CatchVar::Var(name), _)) => {
+                        self.write_line(depth, format_args!("Try {:?} {{", name))
+                    }
+                    Some((CatchVar::Register(register), _)) => {
+                        self.write_line(depth, format_args!("Try r{} {{", register))
+                    }
+                    None => self.write_line(depth, format_args!("Try {{")),
+                }
+                self.disassemble_block(try_block.try_actions, &mut scope.clone(), depth + 1)?;
+                if let Some((_, catch_actions)) = try_block.catch {
+                    self.write_line(depth, format_args!("}} catch {{"));
+                    self.disassemble_block(catch_actions, &mut scope.clone(), depth + 1)?;
+                }
+                if let Some(finally_actions) = try_block.finally {
+                    self.write_line(depth, format_args!("}} finally {{"));
+                    self.disassemble_block(finally_actions, &mut scope.clone(), depth + 1)?;
+                }
+                self.write_line(depth, format_args!("}}"));
+            }
+            Action::WaitForFrame {
+                frame,
+                num_actions_to_skip,
+            } => self.write_line(
+                depth,
+                format_args!("WaitForFrame {}, {}", frame, num_actions_to_skip),
+            ),
+            Action::WaitForFrame2 {
+                num_actions_to_skip,
+            } => self.write_line(depth, format_args!("WaitForFrame2 {}", num_actions_to_skip)),
+            Action::With { actions } => {
+                self.write_line(depth, format_args!("With {{"));
+                self.disassemble_block(actions, &mut scope.clone(), depth + 1)?;
+                self.write_line(depth, format_args!("}}"));
+            }
+            Action::Unknown { opcode, data } => {
+                let data: Vec<_> = data.iter().map(|b| b.to_string()).collect();
+                self.write_line(
+                    depth,
+                    format_args!("Unknown {:#04x}, [{}]", opcode, data.join(", ")),
+                );
+            }
+            // All remaining actions have no arguments.
+            _ => self.write_line(depth, format_args!("{:?}", action)),
+        }
+        Ok(())
+    }
+
+    fn write_line(&mut self, depth: usize, line: std::fmt::Arguments) {
+        for _ in 0..depth {
+            self.output.push_str("    ");
+        }
+        // Writing to a `String` can't fail.
+        let _ = self.output.write_fmt(line);
+        self.output.push('\n');
+    }
+}
+
+fn disassemble_value(value: &Value, scope: &Scope) -> String {
+    match value {
+        Value::Undefined => "undefined".to_string(),
+        Value::Null => "null".to_string(),
+        Value::Bool(value) => value.to_string(),
+        Value::Int(value) => value.to_string(),
+        Value::Float(value) => format!("{:?}f", value),
+        Value::Double(value) => format!("{:?}", value),
+        Value::Str(value) => format!("{:?}", value),
+        Value::Register(register) => match scope.registers.get(register) {
+            Some(name) if is_identifier(name) => format!("r{}:{}", register, name),
+            Some(name) => format!("r{}:{:?}", register, name),
+            None => format!("r{}", register),
+        },
+        Value::ConstantPool(index) => match scope.constant_pool.get(usize::from(*index)) {
+            Some(constant) => format!("c{}:{:?}", index, constant),
+            None => format!("c{}", index),
+        },
+    }
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    /// An action name, label, number, register or constant.
+    Word(String),
+    Str(String),
+    Comma,
+    Colon,
+    OpenBrace,
+    CloseBrace,
+    OpenBracket,
+    CloseBracket,
+    Newline,
+}
+
+fn syntax_error(line: usize, message: impl std::fmt::Display) -> Error {
+    Error::invalid_data(format!("Line {}: {}", line, message))
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "_$.+-".contains(c)
+}
+
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>> {
+    let mut tokens = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line_num = i + 1;
+        let mut chars = line.char_indices().peekable();
+        while let Some((start, c)) = chars.next() {
+            let token = match c {
+                ';' => break,
+                ',' => Token::Comma,
+                ':' => Token::Colon,
+                '{' => Token::OpenBrace,
+                '}' => Token::CloseBrace,
+                '[' => Token::OpenBracket,
+                ']' => Token::CloseBracket,
+                '"' => {
+                    let mut s = String::new();
+                    loop {
+                        let c = match chars.next() {
+                            Some((_, '"')) => break,
+                            Some((_, '\\')) => match chars.next() {
+                                Some((_, 'n')) => '\n',
+                                Some((_, 'r')) => '\r',
+                                Some((_, 't')) => '\t',
+                                Some((_, '0')) => '\0',
+                                Some((_, 'u')) => {
+                                    let mut code = String::new();
+                                    if chars.next().map(|(_, c)| c) != Some('{') {
+                                        return Err(syntax_error(line_num, "Invalid escape"));
+                                    }
+                                    loop {
+                                        match chars.next() {
+                                            Some((_, '}')) => break,
+                                            Some((_, c)) => code.push(c),
+                                            None => {
+                                                return Err(syntax_error(
+                                                    line_num,
+                                                    "Invalid escape",
+                                                ))
+                                            }
+                                        }
+                                    }
+                                    u32::from_str_radix(&code, 16)
+                                        .ok()
+                                        .and_then(std::char::from_u32)
+                                        .ok_or_else(|| syntax_error(line_num, "Invalid escape"))?
+                                }
+                                Some((_, c)) => c,
+                                None => return Err(syntax_error(line_num, "Unterminated string")),
+                            },
+                            Some((_, c)) => c,
+                            None => return Err(syntax_error(line_num, "Unterminated string")),
+                        };
+                        s.push(c);
+                    }
+                    Token::Str(s)
+                }
+                c if c.is_whitespace() => continue,
+                c if is_word_char(c) => {
+                    let mut end = start + c.len_utf8();
+                    while let Some(&(i, c)) = chars.peek() {
+                        if !is_word_char(c) {
+                            break;
+                        }
+                        end = i + c.len_utf8();
+                        chars.next();
+                    }
+                    Token::Word(line[start..end].to_string())
+                }
+                c => {
+                    return Err(syntax_error(
+                        line_num,
+                        format!("Unexpected character {:?}", c),
+                    ))
+                }
+            };
+            tokens.push((line_num, token));
+        }
+        tokens.push((line_num, Token::Newline));
+    }
+    Ok(tokens)
+}
+
+/// The target of an `If` or `Jump`.
+enum JumpTarget<'a> {
+    Label(&'a str),
+    Offset(i16),
+}
+
+/// An assembled action, or a jump whose offset is resolved once the whole block is assembled.
+enum Item<'a> {
+    Bytes(Vec<u8>),
+    Jump {
+        opcode: OpCode,
+        target: JumpTarget<'a>,
+        line: usize,
+    },
+}
+
+/// The length of an assembled `If` or `Jump` action.
+const JUMP_LENGTH: usize = 5;
+
+struct Assembler<'a> {
+    tokens: &'a [(usize, Token)],
+    pos: usize,
+    version: u8,
+}
+
+impl<'a> Assembler<'a> {
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or_else(|| self.tokens.last())
+            .map_or(0, |(line, _)| *line)
+    }
+
+    fn peek(&self) -> Option<&'a Token> {
+        self.tokens.get(self.pos).map(|(_, token)| token)
+    }
+
+    fn next(&mut self) -> Option<&'a Token> {
+        let token = self.peek();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        match self.next() {
+            Some(token) if *token == expected => Ok(()),
+            token => Err(self.unexpected(token)),
+        }
+    }
+
+    fn unexpected(&self, token: Option<&Token>) -> Error {
+        // Report the line of the token that was just consumed.
+        let line = self
+            .tokens
+            .get(self.pos.saturating_sub(1))
+            .map_or(0, |t| t.0);
+        match token {
+            Some(Token::Newline) | None => syntax_error(line, "Unexpected end of line"),
+            Some(token) => syntax_error(line, format!("Unexpected {:?}", token)),
+        }
+    }
+
+    fn error(&self, message: impl std::fmt::Display) -> Error {
+        syntax_error(self.line(), message)
+    }
+
+    /// Assembles actions until a closing brace or the end of the text.
+    fn assemble_block(&mut self) -> Result<Vec<u8>> {
+        let mut items = vec![];
+        let mut labels = HashMap::new();
+        let mut len = 0;
+        loop {
+            match self.peek() {
+                None | Some(Token::CloseBrace) => break,
+                Some(Token::Newline) => {
+                    self.pos += 1;
+                }
+                Some(Token::Word(name)) => {
+                    if let Some((_, Token::Colon)) = self.tokens.get(self.pos + 1) {
+                        if labels.insert(name.as_str(), len).is_some() {
+                            return Err(self.error(format!("Duplicate label {}", name)));
+                        }
+                        self.pos += 2;
+                        continue;
+                    }
+                    self.pos += 1;
+                    let item = self.assemble_action(name)?;
+                    if !matches!(self.peek(), None | Some(Token::Newline)) {
+                        return Err(self.unexpected(self.peek()));
+                    }
+                    len += match &item {
+                        Item::Bytes(bytes) => bytes.len(),
+                        Item::Jump { .. } => JUMP_LENGTH,
+                    };
+                    items.push(item);
+                }
+                token => return Err(self.unexpected(token)),
+            }
+        }
+
+        let mut data = Vec::with_capacity(len);
+        for item in items {
+            match item {
+                Item::Bytes(bytes) => data.extend_from_slice(&bytes),
+                Item::Jump {
+                    opcode,
+                    target,
+                    line,
+                } => {
+                    let offset = match target {
+                        JumpTarget::Label(label) => {
+                            let target = *labels.get(label).ok_or_else(|| {
+                                syntax_error(line, format!("Unknown label {}", label))
+                            })?;
+                            let offset = target as isize - (data.len() + JUMP_LENGTH) as isize;
+                            if offset < i16::MIN.into() || offset > i16::MAX.into() {
+                                return Err(syntax_error(line, "Jump is too far"));
+                            }
+                            offset as i16
+                        }
+                        JumpTarget::Offset(offset) => offset,
+                    };
+                    data.push(opcode as u8);
+                    data.extend_from_slice(&2u16.to_le_bytes());
+                    data.extend_from_slice(&offset.to_le_bytes());
+                }
+            }
+        }
+        Ok(data)
+    }
+
+    /// Assembles the body of an action, including its braces.
+    fn assemble_body(&mut self) -> Result<Vec<u8>> {
+        self.expect(Token::OpenBrace)?;
+        let data = self.assemble_block()?;
+        if self.next() != Some(&Token::CloseBrace) {
+            return Err(self.error("Missing }"));
+        }
+        if data.len() > usize::from(u16::MAX) {
+            return Err(self.error("Action body is too long"));
+        }
+        Ok(data)
+    }
+
+    fn write_action(&self, action: &Action) -> Result<Item<'a>> {
+        let mut data = vec![];
+        Writer::new(&mut data, self.version).write_action(action)?;
+        Ok(Item::Bytes(data))
+    }
+
+    fn assemble_action(&mut self, name: &'a str) -> Result<Item<'a>> {
+        let action = match name {
+            "ConstantPool" => {
+                let mut constants = vec![];
+                if self.peek() != Some(&Token::Newline) {
+                    constants.push(self.string()?);
+                    while self.comma() {
+                        constants.push(self.string()?);
+                    }
+                }
+                Action::ConstantPool(constants)
+            }
+            "DefineFunction" => {
+                let name = self.string()?;
+                self.expect(Token::Comma)?;
+                let mut params = vec![];
+                self.list(|assembler| {
+                    params.push(assembler.string()?);
+                    Ok(())
+                })?;
+                let actions = self.assemble_body()?;
+                return self.write_action(&Action::DefineFunction {
+                    name,
+                    params,
+                    actions: &actions,
+                });
+            }
+            "DefineFunction2" => {
+                let name = self.string()?;
+                self.expect(Token::Comma)?;
+                let register_count = self.number()?;
+                self.expect(Token::Comma)?;
+                let mut params = vec![];
+                self.list(|assembler| {
+                    let register_index = match assembler.peek() {
+                        Some(Token::Word(_)) => {
+                            let register = assembler.register()?;
+                            assembler.expect(Token::Colon)?;
+                            Some(register)
+                        }
+                        _ => None,
+                    };
+                    params.push(FunctionParam {
+                        name: assembler.string()?,
+                        register_index,
+                    });
+                    Ok(())
+                })?;
+                self.expect(Token::Comma)?;
+                let mut function = Function {
+                    name,
+                    register_count,
+                    params,
+                    preload_parent: false,
+                    preload_root: false,
+                    suppress_super: false,
+                    preload_super: false,
+                    suppress_arguments: false,
+                    preload_arguments: false,
+                    suppress_this: false,
+                    preload_this: false,
+                    preload_global: false,
+                    actions: &[],
+                };
+                self.list(|assembler| {
+                    let flag = match assembler.word()? {
+                        "preload_parent" => &mut function.preload_parent,
+                        "preload_root" => &mut function.preload_root,
+                        "suppress_super" => &mut function.suppress_super,
+                        "preload_super" => &mut function.preload_super,
+                        "suppress_arguments" => &mut function.suppress_arguments,
+                        "preload_arguments" => &mut function.preload_arguments,
+                        "suppress_this" => &mut function.suppress_this,
+                        "preload_this" => &mut function.preload_this,
+                        "preload_global" => &mut function.preload_global,
+                        flag => return Err(assembler.error(format!("Unknown flag {}", flag))),
+                    };
+                    *flag = true;
+                    Ok(())
+                })?;
+                let actions = self.assemble_body()?;
+                function.actions = &actions;
+                return self.write_action(&Action::DefineFunction2(function));
+            }
+            "End" => return Ok(Item::Bytes(vec![OpCode::End as u8])),
+            "GetUrl" => {
+                let url = self.string()?;
+                self.expect(Token::Comma)?;
+                let target = self.string()?;
+                Action::GetUrl { url, target }
+            }
+            "GetUrl2" => {
+                let send_vars_method = match self.word()? {
+                    "none" => SendVarsMethod::None,
+                    "get" => SendVarsMethod::Get,
+                    "post" => SendVarsMethod::Post,
+                    method => return Err(self.error(format!("Unknown method {}", method))),
+                };
+                let mut is_target_sprite = false;
+                let mut is_load_vars = false;
+                while self.comma() {
+                    match self.word()? {
+                        "target_sprite" => is_target_sprite = true,
+                        "load_vars" => is_load_vars = true,
+                        flag => return Err(self.error(format!("Unknown flag {}", flag))),
+                    }
+                }
+                Action::GetUrl2 {
+                    send_vars_method,
+                    is_target_sprite,
+                    is_load_vars,
+                }
+            }
+            "GotoFrame" => Action::GotoFrame(self.number()?),
+            "GotoFrame2" => {
+                let set_playing = match self.word()? {
+                    "play" => true,
+                    "stop" => false,
+                    arg => return Err(self.error(format!("Expected play or stop, got {}", arg))),
+                };
+                let scene_offset = if self.comma() { self.number()? } else { 0 };
+                Action::GotoFrame2 {
+                    set_playing,
+                    scene_offset,
+                }
+            }
+            "GotoLabel" => Action::GotoLabel(self.string()?),
+            "If" | "Jump" => {
+                let line = self.line();
+                let word = self.word()?;
+                let target = match word.parse() {
+                    Ok(offset) => JumpTarget::Offset(offset),
+                    Err(_) => JumpTarget::Label(word),
+                };
+                let opcode = if name == "If" {
+                    OpCode::If
+                } else {
+                    OpCode::Jump
+                };
+                return Ok(Item::Jump {
+                    opcode,
+                    target,
+                    line,
+                });
+            }
+            "Push" => {
+                let mut values = vec![];
+                if self.peek() != Some(&Token::Newline) {
+                    values.push(self.value()?);
+                    while self.comma() {
+                        values.push(self.value()?);
+                    }
+                }
+                Action::Push(values)
+            }
+            "SetTarget" => Action::SetTarget(self.string()?),
+            "StoreRegister" => {
+                let register = self.register()?;
+                self.skip_annotation()?;
+                Action::StoreRegister(register)
+            }
+            "Try" => {
+                let catch_var = match self.peek() {
+                    Some(Token::Str(_)) => Some(CatchVar::Var(self.string()?)),
+                    Some(Token::Word(_)) => Some(CatchVar::Register(self.register()?)),
+                    _ => None,
+                };
+                let try_actions = self.assemble_body()?;
+                let catch_actions = if self.keyword("catch") {
+                    Some(self.assemble_body()?)
+                } else {
+                    None
+                };
+                let finally_actions = if self.keyword("finally") {
+                    Some(self.assemble_body()?)
+                } else {
+                    None
+                };
+                let catch = match (catch_var, &catch_actions) {
+                    (Some(catch_var), Some(catch_actions)) => Some((catch_var, &catch_actions[..])),
+                    (None, None) => None,
+                    (None, Some(_)) => return Err(self.error("catch requires a catch variable")),
+                    (Some(_), None) => return Err(self.error("Missing catch block")),
+                };
+                return self.write_action(&Action::Try(TryBlock {
+                    try_actions: &try_actions,
+                    catch,
+                    finally: finally_actions.as_deref(),
+                }));
+            }
+            "WaitForFrame" => {
+                let frame = self.number()?;
+                self.expect(Token::Comma)?;
+                Action::WaitForFrame {
+                    frame,
+                    num_actions_to_skip: self.number()?,
+                }
+            }
+            "WaitForFrame2" => Action::WaitForFrame2 {
+                num_actions_to_skip: self.number()?,
+            },
+            "With" => {
+                let actions = self.assemble_body()?;
+                return self.write_action(&Action::With { actions: &actions });
+            }
+            "Unknown" => {
+                let opcode = self.number()?;
+                self.expect(Token::Comma)?;
+                let mut data = vec![];
+                self.list(|assembler| {
+                    data.push(assembler.number()?);
+                    Ok(())
+                })?;
+                if opcode < 0x80 && !data.is_empty() {
+                    return Err(self.error("Opcodes less than 0x80 can't have data"));
+                }
+                return self.write_action(&Action::Unknown {
+                    opcode,
+                    data: &data,
+                });
+            }
+            name => match simple_action(name) {
+                Some(action) => action,
+                None => return Err(self.error(format!("Unknown action {}", name))),
+            },
+        };
+        self.write_action(&action)
+    }
+
+    /// Consumes the given word if it is the next token.
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(word)) if word == keyword => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Consumes a comma if it is the next token.
+    fn comma(&mut self) -> bool {
+        if self.peek() == Some(&Token::Comma) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Reads a comma-separated list in brackets, calling `f` to read each element.
+    fn list(&mut self, mut f: impl FnMut(&mut Self) -> Result<()>) -> Result<()> {
+        self.expect(Token::OpenBracket)?;
+        if self.peek() == Some(&Token::CloseBracket) {
+            self.pos += 1;
+            return Ok(());
+        }
+        loop {
+            f(self)?;
+            if !self.comma() {
+                return self.expect(Token::CloseBracket);
+            }
+        }
+    }
+
+    fn word(&mut self) -> Result<&'a str> {
+        match self.next() {
+            Some(Token::Word(word)) => Ok(word),
+            token => Err(self.unexpected(token)),
+        }
+    }
+
+    fn string(&mut self) -> Result<&'a str> {
+        match self.next() {
+            Some(Token::Str(s)) => Ok(s),
+            token => Err(self.unexpected(token)),
+        }
+    }
+
+    fn number<T: std::str::FromStr>(&mut self) -> Result<T> {
+        let word = self.word()?;
+        let number = if let Some(hex) = word.strip_prefix("0x") {
+            u64::from_str_radix(hex, 16)
+                .ok()
+                .and_then(|n| n.to_string().parse().ok())
+        } else {
+            word.parse().ok()
+        };
+        number.ok_or_else(|| self.error(format!("Invalid number {}", word)))
+    }
+
+    /// Skips the `:name` annotation after a register or constant.
+    fn skip_annotation(&mut self) -> Result<()> {
+        if self.peek() == Some(&Token::Colon) {
+            self.pos += 1;
+            match self.next() {
+                Some(Token::Word(_)) | Some(Token::Str(_)) => (),
+                token => return Err(self.unexpected(token)),
+            }
+        }
+        Ok(())
+    }
+
+    fn register(&mut self) -> Result<u8> {
+        let word = self.word()?;
+        let register = word
+            .strip_prefix('r')
+            .and_then(|n| n.parse().ok())
+            .ok_or_else(|| self.error(format!("Invalid register {}", word)))?;
+        Ok(register)
+    }
+
+    fn value(&mut self) -> Result<Value<'a>> {
+        let word = match self.next() {
+            Some(Token::Str(s)) => return Ok(Value::Str(s)),
+            Some(Token::Word(word)) => word.as_str(),
+            token => return Err(self.unexpected(token)),
+        };
+        let line = self.line();
+        let invalid = || syntax_error(line, format!("Invalid value {}", word));
+        let value = match word {
+            "undefined" => Value::Undefined,
+            "null" => Value::Null,
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            "NaN" | "inf" | "+inf" | "-inf" => Value::Double(word.parse().map_err(|_| invalid())?),
+            _ if word.starts_with('r') => {
+                let register = word[1..].parse().map_err(|_| invalid())?;
+                self.skip_annotation()?;
+                Value::Register(register)
+            }
+            _ if word.starts_with('c') => {
+                let index = word[1..].parse().map_err(|_| invalid())?;
+                self.skip_annotation()?;
+                Value::ConstantPool(index)
+            }
+            _ if word.ends_with('f') => {
+                Value::Float(word[..word.len() - 1].parse().map_err(|_| invalid())?)
+            }
+            _ if word.contains(&['.', 'e', 'E'][..]) => {
+                Value::Double(word.parse().map_err(|_| invalid())?)
+            }
+            _ => Value::Int(word.parse().map_err(|_| invalid())?),
+        };
+        Ok(value)
+    }
+}
+
+/// Returns the action with the given name if it has no arguments.
+fn simple_action(name: &str) -> Option<Action<'static>> {
+    let action = match name {
+        "Add" => Action::Add,
+        "Add2" => Action::Add2,
+        "And" => Action::And,
+        "AsciiToChar" => Action::AsciiToChar,
+        "BitAnd" => Action::BitAnd,
+        "BitLShift" => Action::BitLShift,
+        "BitOr" => Action::BitOr,
+        "BitRShift" => Action::BitRShift,
+        "BitURShift" => Action::BitURShift,
+        "BitXor" => Action::BitXor,
+        "Call" => Action::Call,
+        "CallFunction" => Action::CallFunction,
+        "CallMethod" => Action::CallMethod,
+        "CastOp" => Action::CastOp,
+        "CharToAscii" => Action::CharToAscii,
+        "CloneSprite" => Action::CloneSprite,
+        "Decrement" => Action::Decrement,
+        "DefineLocal" => Action::DefineLocal,
+        "DefineLocal2" => Action::DefineLocal2,
+        "Delete" => Action::Delete,
+        "Delete2" => Action::Delete2,
+        "Divide" => Action::Divide,
+        "EndDrag" => Action::EndDrag,
+        "Enumerate" => Action::Enumerate,
+        "Enumerate2" => Action::Enumerate2,
+        "Equals" => Action::Equals,
+        "Equals2" => Action::Equals2,
+        "Extends" => Action::Extends,
+        "GetMember" => Action::GetMember,
+        "GetProperty" => Action::GetProperty,
+        "GetTime" => Action::GetTime,
+        "GetVariable" => Action::GetVariable,
+        "Greater" => Action::Greater,
+        "ImplementsOp" => Action::ImplementsOp,
+        "Increment" => Action::Increment,
+        "InitArray" => Action::InitArray,
+        "InitObject" => Action::InitObject,
+        "InstanceOf" => Action::InstanceOf,
+        "Less" => Action::Less,
+        "Less2" => Action::Less2,
+        "MBAsciiToChar" => Action::MBAsciiToChar,
+        "MBCharToAscii" => Action::MBCharToAscii,
+        "MBStringExtract" => Action::MBStringExtract,
+        "MBStringLength" => Action::MBStringLength,
+        "Modulo" => Action::Modulo,
+        "Multiply" => Action::Multiply,
+        "NewMethod" => Action::NewMethod,
+        "NewObject" => Action::NewObject,
+        "NextFrame" => Action::NextFrame,
+        "Not" => Action::Not,
+        "Or" => Action::Or,
+        "Play" => Action::Play,
+        "Pop" => Action::Pop,
+        "PreviousFrame" => Action::PreviousFrame,
+        "PushDuplicate" => Action::PushDuplicate,
+        "RandomNumber" => Action::RandomNumber,
+        "RemoveSprite" => Action::RemoveSprite,
+        "Return" => Action::Return,
+        "SetMember" => Action::SetMember,
+        "SetProperty" => Action::SetProperty,
+        "SetTarget2" => Action::SetTarget2,
+        "SetVariable" => Action::SetVariable,
+        "StackSwap" => Action::StackSwap,
+        "StartDrag" => Action::StartDrag,
+        "Stop" => Action::Stop,
+        "StopSounds" => Action::StopSounds,
+        "StrictEquals" => Action::StrictEquals,
+        "StringAdd" => Action::StringAdd,
+        "StringEquals" => Action::StringEquals,
+        "StringExtract" => Action::StringExtract,
+        "StringGreater" => Action::StringGreater,
+        "StringLength" => Action::StringLength,
+        "StringLess" => Action::StringLess,
+        "Subtract" => Action::Subtract,
+        "TargetPath" => Action::TargetPath,
+        "Throw" => Action::Throw,
+        "ToInteger" => Action::ToInteger,
+        "ToNumber" => Action::ToNumber,
+        "ToString" => Action::ToString,
+        "ToggleQuality" => Action::ToggleQuality,
+        "Trace" => Action::Trace,
+        "TypeOf" => Action::TypeOf,
+        _ => return None,
+    };
+    Some(action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data;
+
+    #[test]
+    fn round_trip_actions() {
+        for (swf_version, action, bytes) in test_data::avm1_tests() {
+            let text = disassemble(&bytes, swf_version).unwrap();
+            let assembled = assemble(&text, swf_version)
+                .unwrap_or_else(|e| panic!("Error assembling {:?} from {:?}: {}", action, text, e));
+            assert_eq!(assembled, bytes, "Incorrectly assembled {:?}", text);
+        }
+    }
+
+    #[test]
+    fn disassemble_labels_and_functions() {
+        let text = r#"ConstantPool "x", "y"
+DefineFunction2 "f", 3, [r2:"a", "b"], [preload_this, suppress_super] {
+    Push r2:a, c1:"y", c2
+    If L1
+    Push r1:this, 1.5, 2.0f, -3, undefined, null, true
+    Jump L2
+    L1:
+    Try "e" {
+        Throw
+    } catch {
+        StoreRegister r2:a
+    } finally {
+        Pop
+    }
+    L2:
+}
+With {
+    GetUrl2 post, load_vars
+    GotoFrame2 play, 3
+}
+Jump L0
+Push "tab\tquote\"", NaN
+L0:
+End
+"#;
+        let data = assemble(text, 8).unwrap();
+        assert_eq!(disassemble(&data, 8).unwrap(), text);
+    }
+
+    #[test]
+    fn assemble_errors() {
+        assert!(assemble("Jump Nowhere", 8).is_err());
+        assert!(assemble("NotAnAction", 8).is_err());
+        assert!(assemble("Push ,", 8).is_err());
+        assert!(assemble("With {\n    Pop\n", 8).is_err());
+        assert!(assemble("Pop\n}", 8).is_err());
+        assert!(assemble("L0:\nL0:", 8).is_err());
+        assert!(assemble("Push \"unterminated", 8).is_err());
+    }
+}
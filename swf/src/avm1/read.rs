@@ -363,9 +363,9 @@ impl<'a> Reader<'a> {
         let finally_length = usize::from(self.read_u16()?);
         *length += try_length + catch_length + finally_length;
         let catch_var = if flags & 0b100 != 0 {
-            CatchVar::Var(self.read_c_string()?)
-        } else {
             CatchVar::Register(self.read_u8()?)
+        } else {
+            CatchVar::Var(self.read_c_string()?)
         };
         let try_actions = self.read_slice(try_length)?;
         let catch_actions = self.read_slice(catch_length)?;
@@ -453,4 +453,32 @@ pub mod tests {
         let action = reader.read_action().unwrap().unwrap();
         assert_eq!(action, Action::Push(vec![Value::Null, Value::Undefined]));
     }
+
+    /// Flag 0b100 means the caught value is stored in a register; this was inverted.
+    #[test]
+    fn read_try_catch_in_register_flag() {
+        let action_bytes = [0x8F, 8, 0, 0b101, 1, 0, 1, 0, 0, 0, 3, 0x2A, 0x17];
+        let mut reader = Reader::new(&action_bytes[..], 7);
+        let action = reader.read_action().unwrap().unwrap();
+        assert_eq!(
+            action,
+            Action::Try(TryBlock {
+                try_actions: &[0x2A],
+                catch: Some((CatchVar::Register(3), &[0x17])),
+                finally: None,
+            })
+        );
+
+        let action_bytes = [0x8F, 9, 0, 0b001, 1, 0, 1, 0, 0, 0, b'e', 0, 0x2A, 0x17];
+        let mut reader = Reader::new(&action_bytes[..], 7);
+        let action = reader.read_action().unwrap().unwrap();
+        assert_eq!(
+            action,
+            Action::Try(TryBlock {
+                try_actions: &[0x2A],
+                catch: Some((CatchVar::Var("e"), &[0x17])),
+                finally: None,
+            })
+        );
+    }
 }
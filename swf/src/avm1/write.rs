@@ -160,11 +160,11 @@ impl<W: Write> Writer<W> {
             } => {
                 if scene_offset != 0 {
                     self.write_action_header(OpCode::GotoFrame2, 3)?;
-                    self.write_u8(if set_playing { 0b11 } else { 0b01 })?;
+                    self.write_u8(if set_playing { 0b11 } else { 0b10 })?;
                     self.write_u16(scene_offset)?;
                 } else {
                     self.write_action_header(OpCode::GotoFrame2, 1)?;
-                    self.write_u8(if set_playing { 0b10 } else { 0b00 })?;
+                    self.write_u8(if set_playing { 0b01 } else { 0b00 })?;
                 }
             }
             Action::GotoLabel(ref label) => {
@@ -280,13 +280,12 @@ impl<W: Write> Writer<W> {
                         0
                     };
                 }
-                let len = 7
-                    + action_buf.len()
-                    + if let Some((CatchVar::Var(ref name), _)) = try_block.catch {
-                        name.len() + 1
-                    } else {
-                        1
-                    };
+                // The lengths of the blocks aren't included in the action length.
+                let len = 7 + if let Some((CatchVar::Var(name), _)) = try_block.catch {
+                    name.len() + 1
+                } else {
+                    1
+                };
                 self.write_action_header(OpCode::Try, len)?;
                 self.write_u8(
                     if let Some((CatchVar::Register(_), _)) = try_block.catch {
@@ -302,7 +301,7 @@ impl<W: Write> Writer<W> {
                 match try_block.catch {
                     Some((CatchVar::Var(ref name), _)) => self.write_c_string(name)?,
                     Some((CatchVar::Register(i), _)) => self.write_u8(i)?,
-                    _ => (),
+                    None => self.write_u8(0)?,
                 }
                 self.inner.write_all(&action_buf)?;
             }
@@ -322,7 +321,9 @@ impl<W: Write> Writer<W> {
                 self.write_u8(num_actions_to_skip)?;
             }
             Action::With { ref actions } => {
-                self.write_action_header(OpCode::With, actions.len())?;
+                // The code length isn't included in the action length.
+                self.write_action_header(OpCode::With, 2)?;
+                self.write_u16(actions.len() as u16)?;
                 self.inner.write_all(&actions)?;
             }
             Action::Unknown { opcode, ref data } => {
@@ -416,4 +417,52 @@ mod tests {
             }
         }
     }
+
+    fn write(action: Action, swf_version: u8) -> Vec<u8> {
+        let mut written_bytes = Vec::new();
+        Writer::new(&mut written_bytes, swf_version)
+            .write_action(&action)
+            .unwrap();
+        written_bytes
+    }
+
+    /// The play flag is bit 0 and the scene bias flag is bit 1; these were swapped.
+    #[test]
+    fn write_goto_frame_2_flags() {
+        let action = Action::GotoFrame2 {
+            set_playing: true,
+            scene_offset: 0,
+        };
+        assert_eq!(write(action, 5), [0x9F, 1, 0, 0b01]);
+
+        let action = Action::GotoFrame2 {
+            set_playing: false,
+            scene_offset: 2,
+        };
+        assert_eq!(write(action, 5), [0x9F, 3, 0, 0b10, 2, 0]);
+    }
+
+    /// The action length of Try must not include the blocks that follow it,
+    /// and the catch variable byte is always written.
+    #[test]
+    fn write_try_length() {
+        let action = Action::Try(TryBlock {
+            try_actions: &[0x2A, 0x2A],
+            catch: None,
+            finally: Some(&[0x17]),
+        });
+        assert_eq!(
+            write(action, 7),
+            [0x8F, 8, 0, 0b010, 2, 0, 0, 0, 1, 0, 0, 0x2A, 0x2A, 0x17]
+        );
+    }
+
+    /// The action length of With covers only the code size field, not the code block.
+    #[test]
+    fn write_with_length() {
+        let action = Action::With {
+            actions: &[0x17, 0x17, 0x17],
+        };
+        assert_eq!(write(action, 5), [0x94, 2, 0, 3, 0, 0x17, 0x17, 0x17]);
+    }
 }
@@ -0,0 +1,949 @@
+//! A textual listing of AVM2 bytecode.
+//!
+//! `decode` reads the instructions of a method body, and `disassemble` prints the scripts,
+//! classes and method bodies of an `AbcFile`:
+//!
+//! ```text
+//! ; ABC version 46.16
+//!
+//! script 0, init method 1
+//!     method Avm2Test, disp_id 1, method 0
+//!
+//! method 0 ():void
+//!     max_stack 2, num_locals 1, init_scope_depth 1, max_scope_depth 2
+//!     getlocal0
+//!     pushscope
+//!     findpropstrict trace
+//!     pushstring "Test"
+//!     callpropvoid trace, 1
+//!     returnvoid
+//! ```
+//!
+//! * Instructions are named after their mnemonic in the AVM2 overview.
+//! * Strings are quoted, with Rust escape sequences.
+//! * A qualified name is written as `namespace::name`, or just `name` in the public package.
+//!   Other namespaces are written as `Kind("name")`, named after their `Namespace` variant.
+//!   Attribute names are prefixed with `@`, and `*` is the any name or namespace.
+//! * Branch and exception targets are labels defined as `name:` in the method body, or
+//!   `@offset` if the target is not at the start of an instruction.
+
+use crate::avm2::read::Reader;
+use crate::avm2::types::*;
+use crate::error::Result;
+use crate::read::SwfRead;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// An instruction decoded from the code of a method body.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instruction {
+    /// The offset of the instruction in the code.
+    pub offset: usize,
+
+    /// The offset following the instruction.
+    pub end: usize,
+
+    pub op: Op,
+}
+
+impl Instruction {
+    /// Returns the offsets in the code that this instruction may branch to.
+    ///
+    /// Conditional branches and jumps are relative to the end of the instruction, while
+    /// `lookupswitch` targets are relative to the start of the instruction.
+    pub fn branch_targets(&self) -> Vec<isize> {
+        let end = self.end as isize;
+        match self.op {
+            Op::IfEq { offset }
+            | Op::IfFalse { offset }
+            | Op::IfGe { offset }
+            | Op::IfGt { offset }
+            | Op::IfLe { offset }
+            | Op::IfLt { offset }
+            | Op::IfNge { offset }
+            | Op::IfNgt { offset }
+            | Op::IfNle { offset }
+            | Op::IfNlt { offset }
+            | Op::IfNe { offset }
+            | Op::IfStrictEq { offset }
+            | Op::IfStrictNe { offset }
+            | Op::IfTrue { offset }
+            | Op::Jump { offset } => vec![end + offset as isize],
+            Op::LookupSwitch {
+                default_offset,
+                ref case_offsets,
+            } => {
+                let start = self.offset as isize;
+                std::iter::once(default_offset)
+                    .chain(case_offsets.iter().copied())
+                    .map(|offset| start + offset as isize)
+                    .collect()
+            }
+            _ => vec![],
+        }
+    }
+}
+
+/// Decodes the instructions in the code of a method body.
+///
+/// # Example
+/// ```
+/// use swf::avm2::types::Op;
+///
+/// let instructions = swf::avm2::disasm::decode(&[0xd0, 0x30, 0x47]).unwrap();
+/// assert_eq!(instructions[2].offset, 2);
+/// assert_eq!(instructions[2].op, Op::ReturnVoid);
+/// ```
+pub fn decode(code: &[u8]) -> Result<Vec<Instruction>> {
+    let mut reader = Reader::new(code);
+    let mut instructions = Vec::new();
+    let mut offset = 0;
+    while offset < code.len() {
+        let op = reader.read_op()?;
+        let end = code.len() - reader.get_inner().len();
+        instructions.push(Instruction { offset, end, op });
+        offset = end;
+    }
+    Ok(instructions)
+}
+
+/// Prints the scripts, classes and method bodies of an ABC file.
+pub fn disassemble(abc: &AbcFile) -> Result<String> {
+    let mut disassembler = Disassembler {
+        abc,
+        output: String::new(),
+    };
+    disassembler.disassemble_abc()?;
+    Ok(disassembler.output)
+}
+
+/// Prints the instructions and exception table of a single method body.
+pub fn disassemble_method_body(abc: &AbcFile, body: &MethodBody) -> Result<String> {
+    let mut disassembler = Disassembler {
+        abc,
+        output: String::new(),
+    };
+    disassembler.disassemble_method_body(body, 0)?;
+    Ok(disassembler.output)
+}
+
+struct Disassembler<'a> {
+    abc: &'a AbcFile,
+    output: String,
+}
+
+impl<'a> Disassembler<'a> {
+    fn disassemble_abc(&mut self) -> Result<()> {
+        let abc = self.abc;
+        self.write_line(
+            0,
+            format_args!("; ABC version {}.{}", abc.major_version, abc.minor_version),
+        );
+
+        for (i, script) in abc.scripts.iter().enumerate() {
+            self.output.push('\n');
+            self.write_line(
+                0,
+                format_args!("script {}, init method {}", i, script.init_method.0),
+            );
+            self.disassemble_traits(&script.traits, 1);
+        }
+
+        for (i, (instance, class)) in abc.instances.iter().zip(&abc.classes).enumerate() {
+            self.output.push('\n');
+            self.disassemble_class(i, instance, class);
+        }
+
+        for body in &abc.method_bodies {
+            self.output.push('\n');
+            self.disassemble_method_body(body, 0)?;
+        }
+        Ok(())
+    }
+
+    fn disassemble_class(&mut self, i: usize, instance: &Instance, class: &Class) {
+        let mut line = format!("class {} {}", i, self.multiname(&instance.name));
+        if instance.super_name.0 != 0 {
+            let _ = write!(line, " extends {}", self.multiname(&instance.super_name));
+        }
+        if !instance.interfaces.is_empty() {
+            let interfaces: Vec<_> = instance
+                .interfaces
+                .iter()
+                .map(|interface| self.multiname(interface))
+                .collect();
+            let _ = write!(line, " implements {}", interfaces.join(", "));
+        }
+        let mut flags = vec![];
+        if instance.is_sealed {
+            flags.push("sealed".to_string());
+        }
+        if instance.is_final {
+            flags.push("final".to_string());
+        }
+        if instance.is_interface {
+            flags.push("interface".to_string());
+        }
+        if let Some(namespace) = &instance.protected_namespace {
+            flags.push(format!("protected_namespace {}", self.namespace(namespace)));
+        }
+        if !flags.is_empty() {
+            let _ = write!(line, " [{}]", flags.join(", "));
+        }
+        self.write_line(0, format_args!("{}", line));
+
+        self.write_line(
+            1,
+            format_args!("instance init method {}", instance.init_method.0),
+        );
+        self.write_line(
+            1,
+            format_args!("static init method {}", class.init_method.0),
+        );
+        if !instance.traits.is_empty() {
+            self.write_line(1, format_args!("instance traits"));
+            self.disassemble_traits(&instance.traits, 2);
+        }
+        if !class.traits.is_empty() {
+            self.write_line(1, format_args!("static traits"));
+            self.disassemble_traits(&class.traits, 2);
+        }
+    }
+
+    fn disassemble_traits(&mut self, traits: &[Trait], depth: usize) {
+        for t in traits {
+            for metadata in &t.metadata {
+                let line = self.metadata(metadata);
+                self.write_line(depth, format_args!("{}", line));
+            }
+
+            let name = self.multiname(&t.name);
+            let mut line = match &t.kind {
+                TraitKind::Slot {
+                    slot_id,
+                    type_name,
+                    value,
+                } => self.slot("slot", &name, *slot_id, type_name, value),
+                TraitKind::Const {
+                    slot_id,
+                    type_name,
+                    value,
+                } => self.slot("const", &name, *slot_id, type_name, value),
+                TraitKind::Method { disp_id, method } => {
+                    format!("method {}, disp_id {}, method {}", name, disp_id, method.0)
+                }
+                TraitKind::Getter { disp_id, method } => {
+                    format!("getter {}, disp_id {}, method {}", name, disp_id, method.0)
+                }
+                TraitKind::Setter { disp_id, method } => {
+                    format!("setter {}, disp_id {}, method {}", name, disp_id, method.0)
+                }
+                TraitKind::Class { slot_id, class } => {
+                    format!("class {}, slot_id {}, class {}", name, slot_id, class.0)
+                }
+                TraitKind::Function { slot_id, function } => format!(
+                    "function {}, slot_id {}, method {}",
+                    name, slot_id, function.0
+                ),
+            };
+            match (t.is_final, t.is_override) {
+                (true, true) => line.push_str(" [final, override]"),
+                (true, false) => line.push_str(" [final]"),
+                (false, true) => line.push_str(" [override]"),
+                (false, false) => (),
+            }
+            self.write_line(depth, format_args!("{}", line));
+        }
+    }
+
+    fn slot(
+        &self,
+        kind: &str,
+        name: &str,
+        slot_id: u32,
+        type_name: &Index<Multiname>,
+        value: &Option<DefaultValue>,
+    ) -> String {
+        let mut line = format!("{} {}:{}", kind, name, self.multiname(type_name));
+        if let Some(value) = value {
+            let _ = write!(line, " = {}", self.default_value(value));
+        }
+        let _ = write!(line, ", slot_id {}", slot_id);
+        line
+    }
+
+    fn metadata(&self, index: &Index<Metadata>) -> String {
+        let metadata = match self.abc.metadata.get(index.0 as usize) {
+            Some(metadata) => metadata,
+            None => return format!("[#{}]", index.0),
+        };
+        let items: Vec<_> = metadata
+            .items
+            .iter()
+            .map(|item| {
+                if item.key.0 == 0 {
+                    self.string(&item.value)
+                } else {
+                    format!(
+                        "{}={}",
+                        self.pool_string(&item.key),
+                        self.string(&item.value)
+                    )
+                }
+            })
+            .collect();
+        format!(
+            "[{}({})]",
+            self.pool_string(&metadata.name),
+            items.join(", ")
+        )
+    }
+
+    fn method_signature(&self, index: &Index<Method>) -> String {
+        let method = match self.abc.methods.get(index.0 as usize) {
+            Some(method) => method,
+            None => return format!("method {}", index.0),
+        };
+        let params: Vec<_> = method
+            .params
+            .iter()
+            .map(|param| {
+                let mut s = match &param.name {
+                    Some(name) => {
+                        format!("{}:{}", self.pool_string(name), self.multiname(&param.kind))
+                    }
+                    None => self.multiname(&param.kind),
+                };
+                if let Some(value) = &param.default_value {
+                    let _ = write!(s, " = {}", self.default_value(value));
+                }
+                s
+            })
+            .collect();
+        let mut signature = format!(
+            "method {} {}({}):{}",
+            index.0,
+            self.pool_string(&method.name),
+            params.join(", "),
+            self.multiname(&method.return_type)
+        );
+        let mut flags = vec![];
+        if method.needs_arguments_object {
+            flags.push("need_arguments");
+        }
+        if method.needs_activation {
+            flags.push("need_activation");
+        }
+        if method.needs_rest {
+            flags.push("need_rest");
+        }
+        if method.needs_dxns {
+            flags.push("set_dxns");
+        }
+        if !flags.is_empty() {
+            let _ = write!(signature, " [{}]", flags.join(", "));
+        }
+        signature
+    }
+
+    fn disassemble_method_body(&mut self, body: &MethodBody, depth: usize) -> Result<()> {
+        let instructions = decode(&body.code)?;
+
+        // Label every branch and exception target that starts an instruction.
+        let is_target = |target: isize| {
+            target == body.code.len() as isize
+                || instructions
+                    .iter()
+                    .any(|instruction| instruction.offset as isize == target)
+        };
+        let mut labels = BTreeMap::new();
+        let exception_targets = body.exceptions.iter().flat_map(|exception| {
+            vec![
+                exception.from_offset as isize,
+                exception.to_offset as isize,
+                exception.target_offset as isize,
+            ]
+        });
+        for target in instructions
+            .iter()
+            .flat_map(Instruction::branch_targets)
+            .chain(exception_targets)
+        {
+            if is_target(target) {
+                labels.insert(target as usize, String::new());
+            }
+        }
+        for (i, name) in labels.values_mut().enumerate() {
+            *name = format!("L{}", i);
+        }
+
+        let signature = self.method_signature(&body.method);
+        self.write_line(depth, format_args!("{}", signature));
+        self.write_line(
+            depth + 1,
+            format_args!(
+                "max_stack {}, num_locals {}, init_scope_depth {}, max_scope_depth {}",
+                body.max_stack, body.num_locals, body.init_scope_depth, body.max_scope_depth
+            ),
+        );
+        if !body.traits.is_empty() {
+            self.write_line(depth + 1, format_args!("traits"));
+            self.disassemble_traits(&body.traits, depth + 2);
+        }
+        for instruction in &instructions {
+            if let Some(label) = labels.get(&instruction.offset) {
+                self.write_line(depth + 1, format_args!("{}:", label));
+            }
+            let line = self.instruction(instruction, &labels);
+            self.write_line(depth + 1, format_args!("{}", line));
+        }
+        if let Some(label) = labels.get(&body.code.len()) {
+            self.write_line(depth + 1, format_args!("{}:", label));
+        }
+        for exception in &body.exceptions {
+            let label = |offset: u32| match labels.get(&(offset as usize)) {
+                Some(label) => label.clone(),
+                None => format!("@{}", offset),
+            };
+            self.write_line(
+                depth + 1,
+                format_args!(
+                    "exception from {} to {}, target {}, type {}, variable {}",
+                    label(exception.from_offset),
+                    label(exception.to_offset),
+                    label(exception.target_offset),
+                    self.multiname(&exception.type_name),
+                    self.multiname_name(&exception.variable_name),
+                ),
+            );
+        }
+        Ok(())
+    }
+
+    fn instruction(&self, instruction: &Instruction, labels: &BTreeMap<usize, String>) -> String {
+        let target = |target: isize| match labels.get(&(target as usize)) {
+            Some(label) if target >= 0 => label.clone(),
+            _ => format!("@{}", target),
+        };
+        let name = op_name(&instruction.op);
+        let operands = match &instruction.op {
+            Op::AsType { type_name: index }
+            | Op::Coerce { index }
+            | Op::DeleteProperty { index }
+            | Op::FindProperty { index }
+            | Op::FindPropStrict { index }
+            | Op::GetDescendants { index }
+            | Op::GetLex { index }
+            | Op::GetProperty { index }
+            | Op::GetSuper { index }
+            | Op::InitProperty { index }
+            | Op::IsType { index }
+            | Op::SetProperty { index }
+            | Op::SetSuper { index } => self.multiname(index),
+            Op::CallProperty { index, num_args }
+            | Op::CallPropLex { index, num_args }
+            | Op::CallPropVoid { index, num_args }
+            | Op::CallSuper { index, num_args }
+            | Op::CallSuperVoid { index, num_args }
+            | Op::ConstructProp { index, num_args } => {
+                format!("{}, {}", self.multiname(index), num_args)
+            }
+            Op::CallMethod { index, num_args } | Op::CallStatic { index, num_args } => {
+                format!("method {}, {}", index.0, num_args)
+            }
+            Op::Call { num_args }
+            | Op::Construct { num_args }
+            | Op::ConstructSuper { num_args }
+            | Op::NewArray { num_args }
+            | Op::NewObject { num_args } => num_args.to_string(),
+            Op::Debug {
+                is_local_register,
+                register_name,
+                register,
+            } => format!(
+                "{}, {}, {}",
+                u8::from(*is_local_register),
+                self.string(register_name),
+                register
+            ),
+            Op::DebugFile { file_name } => self.string(file_name),
+            Op::DebugLine { line_num } => line_num.to_string(),
+            Op::GetLocal { index: 0..=3 } | Op::SetLocal { index: 0..=3 } => String::new(),
+            Op::DecLocal { index }
+            | Op::DecLocalI { index }
+            | Op::GetGlobalSlot { index }
+            | Op::GetLocal { index }
+            | Op::GetSlot { index }
+            | Op::IncLocal { index }
+            | Op::IncLocalI { index }
+            | Op::Kill { index }
+            | Op::SetGlobalSlot { index }
+            | Op::SetLocal { index }
+            | Op::SetSlot { index } => index.to_string(),
+            Op::Dxns { index } => self.string(index),
+            Op::GetScopeObject { index } => index.to_string(),
+            Op::HasNext2 {
+                object_register,
+                index_register,
+            } => format!("{}, {}", object_register, index_register),
+            Op::LookupSwitch { .. } => {
+                let targets: Vec<_> = instruction
+                    .branch_targets()
+                    .into_iter()
+                    .map(target)
+                    .collect();
+                format!("{}, [{}]", targets[0], targets[1..].join(", "))
+            }
+            Op::NewCatch { index } => format!("exception {}", index.0),
+            Op::NewClass { index } => format!("class {}", index.0),
+            Op::NewFunction { index } => format!("method {}", index.0),
+            // `pushbyte` sign-extends its operand.
+            Op::PushByte { value } => (*value as i8).to_string(),
+            Op::PushDouble { value } => match self.abc.constant_pool.doubles.get(pool_index(value))
+            {
+                Some(value) => format!("{:?}", value),
+                None => format!("#{}", value.0),
+            },
+            Op::PushInt { value } => match self.abc.constant_pool.ints.get(pool_index(value)) {
+                Some(value) => value.to_string(),
+                None => format!("#{}", value.0),
+            },
+            Op::PushUint { value } => match self.abc.constant_pool.uints.get(pool_index(value)) {
+                Some(value) => value.to_string(),
+                None => format!("#{}", value.0),
+            },
+            Op::PushNamespace { value } => self.namespace(value),
+            Op::PushShort { value } => value.to_string(),
+            Op::PushString { value } => self.string(value),
+            _ => match instruction.branch_targets().first() {
+                Some(offset) => target(*offset),
+                None => String::new(),
+            },
+        };
+        if operands.is_empty() {
+            name.to_string()
+        } else {
+            format!("{} {}", name, operands)
+        }
+    }
+
+    /// Returns a string from the constant pool, quoted.
+    fn string(&self, index: &Index<String>) -> String {
+        match self.abc.constant_pool.strings.get(pool_index(index)) {
+            Some(s) => format!("{:?}", s),
+            None if index.0 == 0 => "null".to_string(),
+            None => format!("#{}", index.0),
+        }
+    }
+
+    /// Returns a string from the constant pool, unquoted.
+    fn pool_string(&self, index: &Index<String>) -> String {
+        match self.abc.constant_pool.strings.get(pool_index(index)) {
+            Some(s) => s.clone(),
+            None if index.0 == 0 => String::new(),
+            None => format!("#{}", index.0),
+        }
+    }
+
+    /// Returns the local name of a multiname, where index 0 is the any name.
+    fn multiname_name(&self, index: &Index<String>) -> String {
+        if index.0 == 0 {
+            "*".to_string()
+        } else {
+            self.pool_string(index)
+        }
+    }
+
+    fn namespace(&self, index: &Index<Namespace>) -> String {
+        let (kind, name) = match self.abc.constant_pool.namespaces.get(pool_index(index)) {
+            Some(Namespace::Namespace(name)) => ("Namespace", name),
+            Some(Namespace::Package(name)) => ("Package", name),
+            Some(Namespace::PackageInternal(name)) => ("PackageInternal", name),
+            Some(Namespace::Protected(name)) => ("Protected", name),
+            Some(Namespace::Explicit(name)) => ("Explicit", name),
+            Some(Namespace::StaticProtected(name)) => ("StaticProtected", name),
+            Some(Namespace::Private(name)) => ("Private", name),
+            None if index.0 == 0 => return "*".to_string(),
+            None => return format!("#{}", index.0),
+        };
+        format!("{}({:?})", kind, self.pool_string(name))
+    }
+
+    fn namespace_set(&self, index: &Index<NamespaceSet>) -> String {
+        match self.abc.constant_pool.namespace_sets.get(pool_index(index)) {
+            Some(namespace_set) => {
+                let namespaces: Vec<_> = namespace_set
+                    .iter()
+                    .map(|namespace| self.namespace(namespace))
+                    .collect();
+                format!("[{}]", namespaces.join(", "))
+            }
+            None => format!("#{}", index.0),
+        }
+    }
+
+    fn multiname(&self, index: &Index<Multiname>) -> String {
+        let multiname = match self.abc.constant_pool.multinames.get(pool_index(index)) {
+            Some(multiname) => multiname,
+            None if index.0 == 0 => return "*".to_string(),
+            None => return format!("#{}", index.0),
+        };
+        match multiname {
+            Multiname::QName { namespace, name } => self.qname(namespace, name),
+            Multiname::QNameA { namespace, name } => format!("@{}", self.qname(namespace, name)),
+            Multiname::RTQName { name } => format!("RTQName({})", self.multiname_name(name)),
+            Multiname::RTQNameA { name } => format!("@RTQName({})", self.multiname_name(name)),
+            Multiname::RTQNameL => "RTQNameL".to_string(),
+            Multiname::RTQNameLA => "@RTQNameL".to_string(),
+            Multiname::Multiname {
+                namespace_set,
+                name,
+            } => format!(
+                "Multiname({}, {})",
+                self.multiname_name(name),
+                self.namespace_set(namespace_set)
+            ),
+            Multiname::MultinameA {
+                namespace_set,
+                name,
+            } => format!(
+                "@Multiname({}, {})",
+                self.multiname_name(name),
+                self.namespace_set(namespace_set)
+            ),
+            Multiname::MultinameL { namespace_set } => {
+                format!("MultinameL({})", self.namespace_set(namespace_set))
+            }
+            Multiname::MultinameLA { namespace_set } => {
+                format!("@MultinameL({})", self.namespace_set(namespace_set))
+            }
+        }
+    }
+
+    fn qname(&self, namespace: &Index<Namespace>, name: &Index<String>) -> String {
+        let name = self.multiname_name(name);
+        match self.abc.constant_pool.namespaces.get(pool_index(namespace)) {
+            Some(Namespace::Package(package)) => {
+                let package = self.pool_string(package);
+                if package.is_empty() {
+                    name
+                } else {
+                    format!("{}::{}", package, name)
+                }
+            }
+            _ => format!("{}::{}", self.namespace(namespace), name),
+        }
+    }
+
+    fn default_value(&self, value: &DefaultValue) -> String {
+        let pool = &self.abc.constant_pool;
+        match value {
+            DefaultValue::Int(index) => match pool.ints.get(pool_index(index)) {
+                Some(value) => value.to_string(),
+                None => format!("#{}", index.0),
+            },
+            DefaultValue::Uint(index) => match pool.uints.get(pool_index(index)) {
+                Some(value) => value.to_string(),
+                None => format!("#{}", index.0),
+            },
+            DefaultValue::Double(index) => match pool.doubles.get(pool_index(index)) {
+                Some(value) => format!("{:?}", value),
+                None => format!("#{}", index.0),
+            },
+            DefaultValue::String(index) => self.string(index),
+            DefaultValue::True => "true".to_string(),
+            DefaultValue::False => "false".to_string(),
+            DefaultValue::Null => "null".to_string(),
+            DefaultValue::Undefined => "undefined".to_string(),
+            DefaultValue::Namespace(index)
+            | DefaultValue::Package(index)
+            | DefaultValue::PackageInternal(index)
+            | DefaultValue::Protected(index)
+            | DefaultValue::Explicit(index)
+            | DefaultValue::StaticProtected(index)
+            | DefaultValue::Private(index) => self.namespace(index),
+        }
+    }
+
+    fn write_line(&mut self, depth: usize, line: std::fmt::Arguments) {
+        for _ in 0..depth {
+            self.output.push_str("    ");
+        }
+        // Writing to a `String` can't fail.
+        let _ = self.output.write_fmt(line);
+        self.output.push('\n');
+    }
+}
+
+/// Converts a constant pool index to an index into the `ConstantPool` vectors, which omit the
+/// implicit entry 0.
+fn pool_index<T>(index: &Index<T>) -> usize {
+    (index.0 as usize).wrapping_sub(1)
+}
+
+fn op_name(op: &Op) -> &'static str {
+    match op {
+        Op::Add => "add",
+        Op::AddI => "add_i",
+        Op::AsType { .. } => "astype",
+        Op::AsTypeLate => "astypelate",
+        Op::BitAnd => "bitand",
+        Op::BitNot => "bitnot",
+        Op::BitOr => "bitor",
+        Op::BitXor => "bitxor",
+        Op::Call { .. } => "call",
+        Op::CallMethod { .. } => "callmethod",
+        Op::CallProperty { .. } => "callproperty",
+        Op::CallPropLex { .. } => "callproplex",
+        Op::CallPropVoid { .. } => "callpropvoid",
+        Op::CallStatic { .. } => "callstatic",
+        Op::CallSuper { .. } => "callsuper",
+        Op::CallSuperVoid { .. } => "callsupervoid",
+        Op::CheckFilter => "checkfilter",
+        Op::Coerce { .. } => "coerce",
+        Op::CoerceA => "coerce_a",
+        Op::CoerceS => "coerce_s",
+        Op::Construct { .. } => "construct",
+        Op::ConstructProp { .. } => "constructprop",
+        Op::ConstructSuper { .. } => "constructsuper",
+        Op::ConvertB => "convert_b",
+        Op::ConvertD => "convert_d",
+        Op::ConvertI => "convert_i",
+        Op::ConvertO => "convert_o",
+        Op::ConvertS => "convert_s",
+        Op::ConvertU => "convert_u",
+        Op::Debug { .. } => "debug",
+        Op::DebugFile { .. } => "debugfile",
+        Op::DebugLine { .. } => "debugline",
+        Op::DecLocal { .. } => "declocal",
+        Op::DecLocalI { .. } => "declocal_i",
+        Op::Decrement => "decrement",
+        Op::DecrementI => "decrement_i",
+        Op::DeleteProperty { .. } => "deleteproperty",
+        Op::Divide => "divide",
+        Op::Dup => "dup",
+        Op::Dxns { .. } => "dxns",
+        Op::DxnsLate => "dxnslate",
+        Op::Equals => "equals",
+        Op::EscXAttr => "esc_xattr",
+        Op::EscXElem => "esc_xelem",
+        Op::FindProperty { .. } => "findproperty",
+        Op::FindPropStrict { .. } => "findpropstrict",
+        Op::GetDescendants { .. } => "getdescendants",
+        Op::GetGlobalScope => "getglobalscope",
+        Op::GetGlobalSlot { .. } => "getglobalslot",
+        Op::GetLex { .. } => "getlex",
+        Op::GetLocal { index: 0 } => "getlocal0",
+        Op::GetLocal { index: 1 } => "getlocal1",
+        Op::GetLocal { index: 2 } => "getlocal2",
+        Op::GetLocal { index: 3 } => "getlocal3",
+        Op::GetLocal { .. } => "getlocal",
+        Op::GetProperty { .. } => "getproperty",
+        Op::GetScopeObject { .. } => "getscopeobject",
+        Op::GetSlot { .. } => "getslot",
+        Op::GetSuper { .. } => "getsuper",
+        Op::GreaterEquals => "greaterequals",
+        Op::GreaterThan => "greaterthan",
+        Op::HasNext => "hasnext",
+        Op::HasNext2 { .. } => "hasnext2",
+        Op::IfEq { .. } => "ifeq",
+        Op::IfFalse { .. } => "iffalse",
+        Op::IfGe { .. } => "ifge",
+        Op::IfGt { .. } => "ifgt",
+        Op::IfLe { .. } => "ifle",
+        Op::IfLt { .. } => "iflt",
+        Op::IfNge { .. } => "ifnge",
+        Op::IfNgt { .. } => "ifngt",
+        Op::IfNle { .. } => "ifnle",
+        Op::IfNlt { .. } => "ifnlt",
+        Op::IfNe { .. } => "ifne",
+        Op::IfStrictEq { .. } => "ifstricteq",
+        Op::IfStrictNe { .. } => "ifstrictne",
+        Op::IfTrue { .. } => "iftrue",
+        Op::In => "in",
+        Op::IncLocal { .. } => "inclocal",
+        Op::IncLocalI { .. } => "inclocal_i",
+        Op::Increment => "increment",
+        Op::IncrementI => "increment_i",
+        Op::InitProperty { .. } => "initproperty",
+        Op::InstanceOf => "instanceof",
+        Op::IsType { .. } => "istype",
+        Op::IsTypeLate => "istypelate",
+        Op::Jump { .. } => "jump",
+        Op::Kill { .. } => "kill",
+        Op::Label => "label",
+        Op::LessEquals => "lessequals",
+        Op::LessThan => "lessthan",
+        Op::LookupSwitch { .. } => "lookupswitch",
+        Op::LShift => "lshift",
+        Op::Modulo => "modulo",
+        Op::Multiply => "multiply",
+        Op::MultiplyI => "multiply_i",
+        Op::Negate => "negate",
+        Op::NegateI => "negate_i",
+        Op::NewActivation => "newactivation",
+        Op::NewArray { .. } => "newarray",
+        Op::NewCatch { .. } => "newcatch",
+        Op::NewClass { .. } => "newclass",
+        Op::NewFunction { .. } => "newfunction",
+        Op::NewObject { .. } => "newobject",
+        Op::NextName => "nextname",
+        Op::NextValue => "nextvalue",
+        Op::Nop => "nop",
+        Op::Not => "not",
+        Op::Pop => "pop",
+        Op::PopScope => "popscope",
+        Op::PushByte { .. } => "pushbyte",
+        Op::PushDouble { .. } => "pushdouble",
+        Op::PushFalse => "pushfalse",
+        Op::PushInt { .. } => "pushint",
+        Op::PushNamespace { .. } => "pushnamespace",
+        Op::PushNaN => "pushnan",
+        Op::PushNull => "pushnull",
+        Op::PushScope => "pushscope",
+        Op::PushShort { .. } => "pushshort",
+        Op::PushString { .. } => "pushstring",
+        Op::PushTrue => "pushtrue",
+        Op::PushUint { .. } => "pushuint",
+        Op::PushUndefined => "pushundefined",
+        Op::PushWith => "pushwith",
+        Op::ReturnValue => "returnvalue",
+        Op::ReturnVoid => "returnvoid",
+        Op::RShift => "rshift",
+        Op::SetLocal { index: 0 } => "setlocal0",
+        Op::SetLocal { index: 1 } => "setlocal1",
+        Op::SetLocal { index: 2 } => "setlocal2",
+        Op::SetLocal { index: 3 } => "setlocal3",
+        Op::SetLocal { .. } => "setlocal",
+        Op::SetGlobalSlot { .. } => "setglobalslot",
+        Op::SetProperty { .. } => "setproperty",
+        Op::SetSlot { .. } => "setslot",
+        Op::SetSuper { .. } => "setsuper",
+        Op::StrictEquals => "strictequals",
+        Op::Subtract => "subtract",
+        Op::SubtractI => "subtract_i",
+        Op::Swap => "swap",
+        Op::Throw => "throw",
+        Op::TypeOf => "typeof",
+        Op::URShift => "urshift",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::avm2::write::Writer;
+    use crate::test_data;
+
+    #[test]
+    fn decode_method_bodies() {
+        for (_, abc_file, _) in test_data::avm2_tests() {
+            for body in &abc_file.method_bodies {
+                let instructions = decode(&body.code).unwrap();
+                let mut code = vec![];
+                let mut writer = Writer::new(&mut code);
+                for instruction in &instructions {
+                    writer.write_op(&instruction.op).unwrap();
+                }
+                assert_eq!(code, body.code, "Incorrectly decoded {:?}", instructions);
+            }
+        }
+    }
+
+    #[test]
+    fn decode_branches() {
+        let code = [
+            0x10, 0x04, 0x00, 0x00, // jump +4
+            0x1b, 0xfc, 0xff, 0xff, 0x01, 0x00, 0x00, 0x00, 0x0c, 0x00, 0x00, // lookupswitch
+            0x47, // returnvoid
+        ];
+        let instructions = decode(&code).unwrap();
+        assert_eq!(instructions.len(), 3);
+        assert_eq!(instructions[0].branch_targets(), vec![8]);
+        assert_eq!(
+            instructions[1].op,
+            Op::LookupSwitch {
+                default_offset: -4,
+                case_offsets: vec![0, 12],
+            }
+        );
+        assert_eq!(instructions[1].branch_targets(), vec![0, 4, 16]);
+        assert_eq!(instructions[2].offset, 15);
+    }
+
+    #[test]
+    fn disassemble_abc() {
+        let (_, abc_file, _) = test_data::avm2_tests().remove(0);
+        let text = disassemble(&abc_file).unwrap();
+        assert_eq!(
+            text,
+            r#"; ABC version 46.16
+
+script 0, init method 1
+    method Avm2Test, disp_id 1, method 0
+
+method 0 ():void
+    max_stack 2, num_locals 1, init_scope_depth 1, max_scope_depth 2
+    getlocal0
+    pushscope
+    findpropstrict trace
+    pushstring "Test"
+    callpropvoid trace, 1
+    returnvoid
+
+method 1 ():*
+    max_stack 1, num_locals 2, init_scope_depth 1, max_scope_depth 2
+    getlocal0
+    pushscope
+    findpropstrict Avm2Test
+    callproperty Avm2Test, 0
+    coerce_a
+    setlocal1
+    getlocal1
+    returnvalue
+"#
+        );
+    }
+
+    #[test]
+    fn disassemble_labels_and_exceptions() {
+        let (_, mut abc_file, _) = test_data::avm2_tests().remove(0);
+        let body = MethodBody {
+            method: Index::new(0),
+            max_stack: 1,
+            num_locals: 1,
+            init_scope_depth: 0,
+            max_scope_depth: 1,
+            code: vec![
+                0x26, // pushtrue
+                0x11, 0x01, 0x00, 0x00, // iftrue +1
+                0x24, 0xff, // pushbyte -1
+                0x03, // throw
+                0x47, // returnvoid
+            ],
+            exceptions: vec![Exception {
+                from_offset: 0,
+                to_offset: 8,
+                target_offset: 8,
+                variable_name: Index::new(4),
+                type_name: Index::new(0),
+            }],
+            traits: vec![],
+        };
+        abc_file.method_bodies = vec![body.clone()];
+        assert_eq!(
+            disassemble_method_body(&abc_file, &body).unwrap(),
+            r#"method 0 ():void
+    max_stack 1, num_locals 1, init_scope_depth 0, max_scope_depth 1
+    L0:
+    pushtrue
+    iftrue @6
+    pushbyte -1
+    throw
+    L1:
+    returnvoid
+    exception from L0 to L1, target L1, type *, variable trace
+"#
+        );
+    }
+}
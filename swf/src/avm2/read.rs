@@ -90,11 +90,12 @@ impl<R: Read> Reader<R> {
         self.read_u30()
     }
 
-    #[allow(dead_code)]
     fn read_i24(&mut self) -> Result<i32> {
-        Ok(i32::from(self.read_u8()?)
+        let n = i32::from(self.read_u8()?)
             | (i32::from(self.read_u8()?) << 8)
-            | (i32::from(self.read_u8()?) << 16))
+            | (i32::from(self.read_u8()?) << 16);
+        // Sign-extend the 24-bit value.
+        Ok((n << 8) >> 8)
     }
     fn read_i32(&mut self) -> Result<i32> {
         let mut n: i32 = 0;
@@ -517,8 +518,8 @@ impl<R: Read> Reader<R> {
         })
     }
 
-    #[allow(dead_code)]
-    fn read_op(&mut self) -> Result<Option<Op>> {
+    /// Reads a single instruction from a method body's code.
+    pub fn read_op(&mut self) -> Result<Op> {
         use crate::avm2::opcode::OpCode;
         use num_traits::FromPrimitive;
 
@@ -840,7 +841,7 @@ impl<R: Read> Reader<R> {
             OpCode::URShift => Op::URShift,
         };
 
-        Ok(Some(op))
+        Ok(op)
     }
 
     fn read_exception(&mut self) -> Result<Exception> {
@@ -866,10 +867,11 @@ pub mod tests {
         let mut file = File::open(path).unwrap();
         let mut data = Vec::new();
         file.read_to_end(&mut data).unwrap();
-        let swf = crate::read_swf(&data[..]).unwrap();
+        let swf_buf = crate::decompress_swf(&data[..]).unwrap();
+        let swf = crate::parse_swf(&swf_buf).unwrap();
         for tag in swf.tags {
             if let Tag::DoAbc(do_abc) = tag {
-                return do_abc.data;
+                return do_abc.data.to_vec();
             }
         }
         panic!("ABC tag not found in {}", path);
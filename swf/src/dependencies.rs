@@ -0,0 +1,424 @@
+//! Finds the characters used by each character of an SWF.
+//!
+//! Characters refer to each other by ID: sprites and buttons place other characters, shapes
+//! are filled with bitmaps and text uses fonts. A `DependencyGraph` collects these references
+//! so that a character can be extracted or removed along with everything it needs.
+
+use crate::types::*;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The characters used by each character of a movie.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct DependencyGraph {
+    dependencies: BTreeMap<CharacterId, BTreeSet<CharacterId>>,
+    timeline: BTreeSet<CharacterId>,
+    exports: BTreeMap<String, CharacterId>,
+    imports: BTreeMap<CharacterId, Import>,
+}
+
+/// A character imported from another movie by an `ImportAssets` tag.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Import {
+    pub url: String,
+    pub name: String,
+}
+
+impl DependencyGraph {
+    /// Builds the graph from the tags of the main timeline.
+    pub fn new(tags: &[Tag]) -> Self {
+        let mut graph = Self::default();
+        for tag in tags {
+            match tag {
+                Tag::ExportAssets(exports) => {
+                    for asset in exports {
+                        graph.exports.insert(asset.name.clone(), asset.id);
+                    }
+                }
+                Tag::ImportAssets { url, imports } => {
+                    for asset in imports {
+                        let import = Import {
+                            url: url.clone(),
+                            name: asset.name.clone(),
+                        };
+                        graph.imports.insert(asset.id, import);
+                        graph.dependencies.entry(asset.id).or_default();
+                    }
+                }
+                _ => match owner_id(tag) {
+                    Some(id) => references(tag, graph.dependencies.entry(id).or_default()),
+                    None => references(tag, &mut graph.timeline),
+                },
+            }
+        }
+        graph
+    }
+
+    /// The IDs of all characters defined or imported by the movie.
+    pub fn characters(&self) -> impl Iterator<Item = CharacterId> + '_ {
+        self.dependencies.keys().copied()
+    }
+
+    /// The characters directly used by a character.
+    pub fn dependencies(&self, id: CharacterId) -> Option<&BTreeSet<CharacterId>> {
+        self.dependencies.get(&id)
+    }
+
+    /// The characters used by a character, including the characters that they use in turn.
+    pub fn all_dependencies(&self, id: CharacterId) -> BTreeSet<CharacterId> {
+        let mut ids = BTreeSet::new();
+        let mut pending = vec![id];
+        while let Some(id) = pending.pop() {
+            for &dependency in self.dependencies(id).into_iter().flatten() {
+                if ids.insert(dependency) {
+                    pending.push(dependency);
+                }
+            }
+        }
+        ids
+    }
+
+    /// The characters that directly use a character.
+    pub fn dependents(&self, id: CharacterId) -> BTreeSet<CharacterId> {
+        self.dependencies
+            .iter()
+            .filter(|(_, dependencies)| dependencies.contains(&id))
+            .map(|(&dependent, _)| dependent)
+            .collect()
+    }
+
+    /// The characters placed or played by the main timeline.
+    pub fn timeline(&self) -> &BTreeSet<CharacterId> {
+        &self.timeline
+    }
+
+    /// The characters exported by the movie, by name.
+    pub fn exports(&self) -> &BTreeMap<String, CharacterId> {
+        &self.exports
+    }
+
+    /// The characters imported from other movies.
+    pub fn imports(&self) -> &BTreeMap<CharacterId, Import> {
+        &self.imports
+    }
+}
+
+/// The ID of the character defined by a tag.
+pub(crate) fn character_id(tag: &Tag) -> Option<CharacterId> {
+    let id = match tag {
+        Tag::DefineBinaryData { id, .. } => *id,
+        Tag::DefineBits { id, .. } => *id,
+        Tag::DefineBitsJpeg2 { id, .. } => *id,
+        Tag::DefineBitsJpeg3(bitmap) => bitmap.id,
+        Tag::DefineBitsLossless(bitmap) => bitmap.id,
+        Tag::DefineButton(button) | Tag::DefineButton2(button) => button.id,
+        Tag::DefineEditText(edit_text) => edit_text.id,
+        Tag::DefineFont(font) => font.id,
+        Tag::DefineFont2(font) => font.id,
+        Tag::DefineFont4(font) => font.id,
+        Tag::DefineMorphShape(morph_shape) => morph_shape.id,
+        Tag::DefineShape(shape) => shape.id,
+        Tag::DefineSound(sound) => sound.id,
+        Tag::DefineSprite(sprite) => sprite.id,
+        Tag::DefineText(text) => text.id,
+        Tag::DefineVideoStream(video) => video.id,
+        _ => return None,
+    };
+    Some(id)
+}
+
+/// The character a tag belongs to, either because it defines the character or because it
+/// adds extra information to it.
+pub(crate) fn owner_id(tag: &Tag) -> Option<CharacterId> {
+    match tag {
+        Tag::CsmTextSettings(settings) => Some(settings.id),
+        Tag::DefineButtonColorTransform(color_transform) => Some(color_transform.id),
+        Tag::DefineButtonSound(sounds) => Some(sounds.id),
+        Tag::DefineFontAlignZones { id, .. } => Some(*id),
+        Tag::DefineFontInfo(font_info) => Some(font_info.id),
+        Tag::DefineFontName { id, .. } => Some(*id),
+        Tag::DefineScalingGrid { id, .. } => Some(*id),
+        Tag::DoInitAction { id, .. } => Some(*id),
+        _ => character_id(tag),
+    }
+}
+
+/// Collects the IDs of the characters used by a tag.
+pub(crate) fn references(tag: &Tag, ids: &mut BTreeSet<CharacterId>) {
+    visit_ids(tag, &mut |&id, role| {
+        if role == IdRole::Uses {
+            ids.insert(id);
+        }
+    });
+}
+
+/// How a tag refers to a character.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum IdRole {
+    /// The tag defines the character.
+    Defines,
+    /// The tag adds information to a character defined by another tag.
+    Describes,
+    /// The tag uses the character, such as by placing it or filling a shape with it.
+    Uses,
+    /// The tag imports or exports the character, or links a class to it.
+    Links,
+}
+
+/// Generates a function calling `f` with every character ID in a tag, including the tags of
+/// sprites. IDs that mean "no character", such as bitmap fills with an ID of 0xFFFF or button
+/// sounds with an ID of 0, are skipped.
+///
+/// This is instantiated once for shared and once for mutable references, so that finding the
+/// characters used by a tag and renumbering them can't disagree.
+macro_rules! id_visitor {
+    ($visit:ident, $visit_styles:ident, $visit_records:ident $(, $mut:tt)?) => {
+        pub(crate) fn $visit(tag: &$($mut)? Tag, f: &mut dyn FnMut(&$($mut)? CharacterId, IdRole)) {
+            use IdRole::*;
+            match tag {
+                Tag::CsmTextSettings(settings) => f(&$($mut)? settings.id, Describes),
+                Tag::DefineBinaryData { id, .. } => f(id, Defines),
+                Tag::DefineBits { id, .. } => f(id, Defines),
+                Tag::DefineBitsJpeg2 { id, .. } => f(id, Defines),
+                Tag::DefineBitsJpeg3(bitmap) => f(&$($mut)? bitmap.id, Defines),
+                Tag::DefineBitsLossless(bitmap) => f(&$($mut)? bitmap.id, Defines),
+                Tag::DefineButton(button) | Tag::DefineButton2(button) => {
+                    f(&$($mut)? button.id, Defines);
+                    for record in &$($mut)? button.records {
+                        f(&$($mut)? record.id, Uses);
+                    }
+                }
+                Tag::DefineButtonColorTransform(color_transform) => {
+                    f(&$($mut)? color_transform.id, Describes)
+                }
+                Tag::DefineButtonSound(sounds) => {
+                    f(&$($mut)? sounds.id, Describes);
+                    let sounds = [
+                        &$($mut)? sounds.over_to_up_sound,
+                        &$($mut)? sounds.up_to_over_sound,
+                        &$($mut)? sounds.over_to_down_sound,
+                        &$($mut)? sounds.down_to_over_sound,
+                    ];
+                    for sound in sounds {
+                        match sound {
+                            Some((id, _)) if *id != 0 => f(id, Uses),
+                            _ => (),
+                        }
+                    }
+                }
+                Tag::DefineEditText(edit_text) => {
+                    f(&$($mut)? edit_text.id, Defines);
+                    if let Some(font_id) = &$($mut)? edit_text.font_id {
+                        f(font_id, Uses);
+                    }
+                }
+                Tag::DefineFont(font) => f(&$($mut)? font.id, Defines),
+                Tag::DefineFont2(font) => f(&$($mut)? font.id, Defines),
+                Tag::DefineFont4(font) => f(&$($mut)? font.id, Defines),
+                Tag::DefineFontAlignZones { id, .. } => f(id, Describes),
+                Tag::DefineFontInfo(font_info) => f(&$($mut)? font_info.id, Describes),
+                Tag::DefineFontName { id, .. } => f(id, Describes),
+                Tag::DefineMorphShape(morph_shape) => {
+                    f(&$($mut)? morph_shape.id, Defines);
+                    for shape in [&$($mut)? morph_shape.start, &$($mut)? morph_shape.end] {
+                        $visit_styles(&$($mut)? shape.fill_styles, &$($mut)? shape.line_styles, f);
+                        $visit_records(&$($mut)? shape.shape, f);
+                    }
+                }
+                Tag::DefineScalingGrid { id, .. } => f(id, Describes),
+                Tag::DefineShape(shape) => {
+                    f(&$($mut)? shape.id, Defines);
+                    let styles = &$($mut)? shape.styles;
+                    $visit_styles(&$($mut)? styles.fill_styles, &$($mut)? styles.line_styles, f);
+                    $visit_records(&$($mut)? shape.shape, f);
+                }
+                Tag::DefineSound(sound) => f(&$($mut)? sound.id, Defines),
+                Tag::DefineSprite(sprite) => {
+                    f(&$($mut)? sprite.id, Defines);
+                    for tag in &$($mut)? sprite.tags {
+                        $visit(tag, f);
+                    }
+                }
+                Tag::DefineText(text) => {
+                    f(&$($mut)? text.id, Defines);
+                    for record in &$($mut)? text.records {
+                        if let Some(font_id) = &$($mut)? record.font_id {
+                            f(font_id, Uses);
+                        }
+                    }
+                }
+                Tag::DefineVideoStream(video) => f(&$($mut)? video.id, Defines),
+                Tag::DoInitAction { id, .. } => f(id, Describes),
+                Tag::ExportAssets(exports) => {
+                    for asset in exports {
+                        f(&$($mut)? asset.id, Links);
+                    }
+                }
+                Tag::ImportAssets { imports, .. } => {
+                    for asset in imports {
+                        f(&$($mut)? asset.id, Links);
+                    }
+                }
+                Tag::PlaceObject(place_object) => match &$($mut)? place_object.action {
+                    PlaceObjectAction::Place(id) | PlaceObjectAction::Replace(id) => f(id, Uses),
+                    PlaceObjectAction::Modify => (),
+                },
+                Tag::RemoveObject(remove_object) => {
+                    if let Some(id) = &$($mut)? remove_object.character_id {
+                        f(id, Uses);
+                    }
+                }
+                Tag::StartSound(start_sound) => f(&$($mut)? start_sound.id, Uses),
+                Tag::SymbolClass(links) => {
+                    // ID 0 is the main timeline, not a character.
+                    for link in links {
+                        if link.id != 0 {
+                            f(&$($mut)? link.id, Links);
+                        }
+                    }
+                }
+                Tag::VideoFrame(video_frame) => f(&$($mut)? video_frame.stream_id, Uses),
+                _ => (),
+            }
+        }
+
+        fn $visit_styles(
+            fill_styles: &$($mut)? [FillStyle],
+            line_styles: &$($mut)? [LineStyle],
+            f: &mut dyn FnMut(&$($mut)? CharacterId, IdRole),
+        ) {
+            let mut visit = |fill_style: &$($mut)? FillStyle| {
+                if let FillStyle::Bitmap { id, .. } = fill_style {
+                    if *id != 0xffff {
+                        f(id, IdRole::Uses);
+                    }
+                }
+            };
+            for fill_style in fill_styles {
+                visit(fill_style);
+            }
+            for style in line_styles {
+                if let Some(fill_style) = &$($mut)? style.fill_style {
+                    visit(fill_style);
+                }
+            }
+        }
+
+        fn $visit_records(records: &$($mut)? [ShapeRecord], f: &mut dyn FnMut(&$($mut)? CharacterId, IdRole)) {
+            for record in records {
+                if let ShapeRecord::StyleChange(StyleChangeData {
+                    new_styles: Some(styles),
+                    ..
+                }) = record
+                {
+                    $visit_styles(&$($mut)? styles.fill_styles, &$($mut)? styles.line_styles, f);
+                }
+            }
+        }
+    };
+}
+
+id_visitor!(visit_ids, visit_style_ids, visit_record_ids);
+id_visitor!(
+    visit_ids_mut,
+    visit_style_ids_mut,
+    visit_record_ids_mut,
+    mut
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_data::{bitmap_filled_shape, place};
+
+    fn set(ids: &[CharacterId]) -> BTreeSet<CharacterId> {
+        ids.iter().copied().collect()
+    }
+
+    #[test]
+    fn dependency_graph() {
+        let sound_info = SoundInfo {
+            event: SoundEvent::Event,
+            in_sample: None,
+            out_sample: None,
+            num_loops: 1,
+            envelope: None,
+        };
+        let tags = vec![
+            Tag::ImportAssets {
+                url: "library.swf".to_string(),
+                imports: vec![ExportedAsset {
+                    id: 1,
+                    name: "texture".to_string(),
+                }],
+            },
+            bitmap_filled_shape(2, 1),
+            Tag::DefineSprite(Sprite {
+                id: 3,
+                num_frames: 1,
+                tags: vec![place(2, 1), Tag::ShowFrame],
+            }),
+            Tag::DefineText(Box::new(Text {
+                id: 4,
+                bounds: Default::default(),
+                matrix: Matrix::new(),
+                records: vec![TextRecord {
+                    font_id: Some(10),
+                    color: None,
+                    x_offset: None,
+                    y_offset: None,
+                    height: None,
+                    glyphs: vec![],
+                }],
+            })),
+            Tag::DefineButton2(Box::new(Button {
+                id: 5,
+                is_track_as_menu: false,
+                records: vec![ButtonRecord {
+                    states: vec![ButtonState::Up].into_iter().collect(),
+                    id: 3,
+                    depth: 1,
+                    matrix: Matrix::new(),
+                    color_transform: ColorTransform::new(),
+                    filters: vec![],
+                    blend_mode: BlendMode::Normal,
+                }],
+                actions: vec![],
+            })),
+            Tag::DefineButtonSound(Box::new(ButtonSounds {
+                id: 5,
+                over_to_up_sound: None,
+                up_to_over_sound: Some((11, sound_info.clone())),
+                over_to_down_sound: Some((0, sound_info)),
+                down_to_over_sound: None,
+            })),
+            Tag::ExportAssets(vec![ExportedAsset {
+                id: 5,
+                name: "button".to_string(),
+            }]),
+            place(4, 1),
+            place(5, 2),
+            Tag::ShowFrame,
+        ];
+        let graph = DependencyGraph::new(&tags);
+
+        assert_eq!(graph.characters().collect::<Vec<_>>(), vec![1, 2, 3, 4, 5]);
+        assert_eq!(graph.dependencies(1), Some(&set(&[])));
+        assert_eq!(graph.dependencies(2), Some(&set(&[1])));
+        assert_eq!(graph.dependencies(3), Some(&set(&[2])));
+        assert_eq!(graph.dependencies(4), Some(&set(&[10])));
+        assert_eq!(graph.dependencies(5), Some(&set(&[3, 11])));
+        assert_eq!(graph.dependencies(6), None);
+        assert_eq!(graph.all_dependencies(5), set(&[1, 2, 3, 11]));
+        assert_eq!(graph.dependents(1), set(&[2]));
+        assert_eq!(graph.dependents(3), set(&[5]));
+        assert_eq!(graph.timeline(), &set(&[4, 5]));
+        assert_eq!(graph.exports().get("button"), Some(&5));
+        assert_eq!(
+            graph.imports().get(&1),
+            Some(&Import {
+                url: "library.swf".to_string(),
+                name: "texture".to_string(),
+            })
+        );
+    }
+}
@@ -0,0 +1,633 @@
+//! Convenience operations for modifying an SWF before writing it back out.
+//!
+//! `Editor` wraps a parsed `Swf` and keeps character IDs consistent while characters are
+//! replaced, added, renumbered or merged in from another movie.
+//!
+//! # Example
+//! ```
+//! use swf::editor::Editor;
+//! use swf::*;
+//!
+//! # let swf = Swf {
+//! #     header: Header {
+//! #         version: 6,
+//! #         compression: Compression::Zlib,
+//! #         stage_size: Rectangle::default(),
+//! #         frame_rate: 30.0,
+//! #         num_frames: 1,
+//! #     },
+//! #     tags: vec![Tag::DoAction(&[0]), Tag::ShowFrame],
+//! # };
+//! let mut editor = Editor::new(swf);
+//! editor.strip_actions();
+//! editor.set_compression(Compression::None);
+//! let mut output = Vec::new();
+//! editor.write(&mut output).unwrap();
+//! ```
+
+use crate::dependencies::{character_id, visit_ids_mut, DependencyGraph};
+use crate::error::{Error, Result};
+use crate::types::*;
+use crate::write::write_swf;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::Write;
+
+/// Maps old character IDs to the new IDs they were renumbered to.
+pub type IdMap = HashMap<CharacterId, CharacterId>;
+
+/// Edits the tags of an `Swf`.
+#[derive(Debug)]
+pub struct Editor<'a> {
+    swf: Swf<'a>,
+}
+
+impl<'a> Editor<'a> {
+    pub fn new(swf: Swf<'a>) -> Self {
+        Self { swf }
+    }
+
+    pub fn swf(&self) -> &Swf<'a> {
+        &self.swf
+    }
+
+    /// The tags of the main timeline, for arbitrary rewriting.
+    pub fn tags_mut(&mut self) -> &mut Vec<Tag<'a>> {
+        &mut self.swf.tags
+    }
+
+    /// Returns the edited `Swf`, with its frame counts updated.
+    pub fn into_swf(mut self) -> Swf<'a> {
+        self.update_frame_counts();
+        self.swf
+    }
+
+    /// The IDs of all characters defined or imported by this movie.
+    pub fn character_ids(&self) -> BTreeSet<CharacterId> {
+        let mut ids = BTreeSet::new();
+        for tag in &self.swf.tags {
+            if let Tag::ImportAssets { imports, .. } = tag {
+                ids.extend(imports.iter().map(|asset| asset.id));
+            } else if let Some(id) = character_id(tag) {
+                ids.insert(id);
+            }
+        }
+        ids
+    }
+
+    /// The tag that defines the character with the given ID.
+    pub fn character(&self, id: CharacterId) -> Option<&Tag<'a>> {
+        self.swf
+            .tags
+            .iter()
+            .find(|tag| character_id(tag) == Some(id))
+    }
+
+    /// The characters directly used by the given character, such as the bitmaps filling a
+    /// shape or the children placed by a sprite.
+    pub fn dependencies(&self, id: CharacterId) -> Result<BTreeSet<CharacterId>> {
+        if self.character(id).is_none() {
+            return Err(undefined_character(id));
+        }
+        let graph = DependencyGraph::new(&self.swf.tags);
+        Ok(graph.dependencies(id).cloned().unwrap_or_default())
+    }
+
+    /// The characters that directly use the given character.
+    pub fn dependents(&self, id: CharacterId) -> BTreeSet<CharacterId> {
+        DependencyGraph::new(&self.swf.tags).dependents(id)
+    }
+
+    /// Adds a new character definition to the first frame.
+    ///
+    /// If the ID of the character is 0 or already in use, it is given the lowest unused ID.
+    /// Returns the ID the character was added with.
+    pub fn add_character(&mut self, mut tag: Tag<'a>) -> Result<CharacterId> {
+        let id = character_id(&tag).ok_or_else(not_a_character)?;
+        let ids = self.character_ids();
+        let id = if id == 0 || ids.contains(&id) {
+            let new_id = free_id(&ids)?;
+            remap_tag(&mut tag, &mut |i| if i == id { new_id } else { i });
+            new_id
+        } else {
+            id
+        };
+        // Insert after any characters added previously so that they can be referenced.
+        let index = self
+            .swf
+            .tags
+            .iter()
+            .position(|tag| *tag == Tag::ShowFrame)
+            .unwrap_or(self.swf.tags.len());
+        self.swf.tags.insert(index, tag);
+        Ok(id)
+    }
+
+    /// Replaces the definition of a character with another definition that has the same ID.
+    /// Returns the old definition.
+    pub fn replace_character(&mut self, tag: Tag<'a>) -> Result<Tag<'a>> {
+        let id = character_id(&tag).ok_or_else(not_a_character)?;
+        let old_tag = self
+            .swf
+            .tags
+            .iter_mut()
+            .find(|tag| character_id(tag) == Some(id))
+            .ok_or_else(|| undefined_character(id))?;
+        Ok(std::mem::replace(old_tag, tag))
+    }
+
+    /// Replaces the bitmap with the given ID.
+    ///
+    /// The new bitmap can be of any format, and is given the ID of the old bitmap.
+    pub fn replace_bitmap(&mut self, id: CharacterId, mut bitmap: Tag<'a>) -> Result<Tag<'a>> {
+        if !is_bitmap(&bitmap) {
+            return Err(Error::invalid_data("Tag is not a bitmap"));
+        }
+        match self.character(id) {
+            Some(tag) if is_bitmap(tag) => (),
+            Some(_) => {
+                return Err(Error::invalid_data(format!(
+                    "Character {} is not a bitmap",
+                    id
+                )))
+            }
+            None => return Err(undefined_character(id)),
+        }
+        if let Some(old_id) = character_id(&bitmap) {
+            remap_tag(&mut bitmap, &mut |i| if i == old_id { id } else { i });
+        }
+        self.replace_character(bitmap)
+    }
+
+    /// Replaces the event sound with the given ID.
+    pub fn replace_sound(&mut self, id: CharacterId, mut sound: Sound<'a>) -> Result<Tag<'a>> {
+        match self.character(id) {
+            Some(Tag::DefineSound(_)) => (),
+            Some(_) => {
+                return Err(Error::invalid_data(format!(
+                    "Character {} is not a sound",
+                    id
+                )))
+            }
+            None => return Err(undefined_character(id)),
+        }
+        sound.id = id;
+        self.replace_character(Tag::DefineSound(Box::new(sound)))
+    }
+
+    /// Keeps only the tags matching the predicate, including tags nested in sprites.
+    /// Returns the number of tags removed.
+    pub fn retain_tags(&mut self, mut f: impl FnMut(&Tag<'a>) -> bool) -> usize {
+        fn retain<'a>(tags: &mut Vec<Tag<'a>>, f: &mut dyn FnMut(&Tag<'a>) -> bool) -> usize {
+            let len = tags.len();
+            tags.retain(|tag| f(tag));
+            let mut removed = len - tags.len();
+            for tag in tags {
+                if let Tag::DefineSprite(sprite) = tag {
+                    removed += retain(&mut sprite.tags, f);
+                }
+            }
+            removed
+        }
+        retain(&mut self.swf.tags, &mut f)
+    }
+
+    /// Removes all `DoAction` and `DoInitAction` tags. Returns the number of tags removed.
+    pub fn strip_actions(&mut self) -> usize {
+        self.retain_tags(|tag| !matches!(tag, Tag::DoAction(_) | Tag::DoInitAction { .. }))
+    }
+
+    /// Renumbers characters, updating every tag that refers to them.
+    ///
+    /// IDs missing from the map are left alone. Fails without changing anything if two
+    /// characters would end up with the same ID.
+    pub fn remap_ids(&mut self, map: &IdMap) -> Result<()> {
+        let ids = self.character_ids();
+        let mut new_ids = BTreeSet::new();
+        for id in &ids {
+            let new_id = map.get(id).copied().unwrap_or(*id);
+            if new_id == 0 || !new_ids.insert(new_id) {
+                return Err(Error::invalid_data(format!(
+                    "Renumbering character {} to {} would collide",
+                    id, new_id
+                )));
+            }
+        }
+        for tag in &mut self.swf.tags {
+            remap_tag(tag, &mut |id| map.get(&id).copied().unwrap_or(id));
+        }
+        Ok(())
+    }
+
+    /// Appends the frames of another movie after the frames of this movie.
+    ///
+    /// Characters of the other movie whose IDs are already in use are renumbered to unused IDs.
+    /// If this movie leaves objects on the stage, the objects of the other movie are moved to
+    /// depths above them. Exports of names that are already exported are dropped, and tags
+    /// that describe the other movie as a whole, such as its `FileAttributes` or its document
+    /// class, are dropped. Returns the renumbered IDs.
+    ///
+    /// Fails without changing anything if the movies have different `JpegTables`, or if there
+    /// are no free IDs or depths left.
+    pub fn merge(&mut self, other: Swf<'a>) -> Result<IdMap> {
+        let mut other = Editor::new(other);
+        let ids = self.character_ids();
+        let other_ids = other.character_ids();
+        let mut used: BTreeSet<_> = ids.union(&other_ids).copied().collect();
+        let mut map = IdMap::new();
+        for &id in ids.intersection(&other_ids) {
+            let new_id = free_id(&used)?;
+            used.insert(new_id);
+            map.insert(id, new_id);
+        }
+        other.remap_ids(&map)?;
+
+        let jpeg_tables = self.jpeg_tables();
+        if let (Some(tables), Some(other_tables)) = (jpeg_tables, other.jpeg_tables()) {
+            if tables != other_tables {
+                return Err(Error::invalid_data("The movies have different JpegTables"));
+            }
+        }
+
+        // Depths in scripts, such as for `getInstanceAtDepth`, are not updated.
+        let depth_offset = stage_depths(&self.swf.tags).into_iter().max().unwrap_or(0);
+        if depth_offset > 0 {
+            let max_depth = max_depth(&other.swf.tags);
+            if max_depth.checked_add(depth_offset).is_none() {
+                return Err(Error::invalid_data(format!(
+                    "Depth {} is too deep to be placed above depth {}",
+                    max_depth, depth_offset
+                )));
+            }
+            shift_depths(&mut other.swf.tags, depth_offset);
+        }
+
+        let mut export_names: HashSet<String> = self
+            .swf
+            .tags
+            .iter()
+            .filter_map(|tag| match tag {
+                Tag::ExportAssets(exports) => Some(exports),
+                _ => None,
+            })
+            .flatten()
+            .map(|asset| asset.name.clone())
+            .collect();
+        for mut tag in other.swf.tags {
+            match tag {
+                Tag::DebugId(_)
+                | Tag::DefineSceneAndFrameLabelData(_)
+                | Tag::EnableDebugger(_)
+                | Tag::EnableTelemetry { .. }
+                | Tag::End
+                | Tag::FileAttributes(_)
+                | Tag::Metadata(_)
+                | Tag::ProductInfo(_)
+                | Tag::Protect(_)
+                | Tag::ScriptLimits { .. }
+                | Tag::SetBackgroundColor(_) => continue,
+                Tag::JpegTables(_) if jpeg_tables.is_some() => continue,
+                // The first export of a name is the one that is used.
+                Tag::ExportAssets(ref mut exports) => {
+                    exports.retain(|asset| export_names.insert(asset.name.clone()));
+                    if exports.is_empty() {
+                        continue;
+                    }
+                }
+                // ID 0 links the document class to the main timeline.
+                Tag::SymbolClass(ref mut links) => links.retain(|link| link.id != 0),
+                _ => (),
+            }
+            self.swf.tags.push(tag);
+        }
+        self.update_frame_counts();
+        Ok(map)
+    }
+
+    /// The JPEG encoding tables shared by the `DefineBits` bitmaps of this movie.
+    fn jpeg_tables(&self) -> Option<JpegTables<'a>> {
+        self.swf.tags.iter().find_map(|tag| match tag {
+            Tag::JpegTables(tables) => Some(*tables),
+            _ => None,
+        })
+    }
+
+    /// Sets the frame counts of the movie and its sprites to the number of `ShowFrame` tags.
+    pub fn update_frame_counts(&mut self) {
+        fn count_frames(tags: &mut [Tag]) -> u16 {
+            let mut num_frames = 0;
+            for tag in tags {
+                match tag {
+                    Tag::ShowFrame => num_frames += 1,
+                    Tag::DefineSprite(sprite) => sprite.num_frames = count_frames(&mut sprite.tags),
+                    _ => (),
+                }
+            }
+            num_frames
+        }
+        self.swf.header.num_frames = count_frames(&mut self.swf.tags);
+    }
+
+    /// Sets the compression of the movie, raising its version if it is too old to support it.
+    pub fn set_compression(&mut self, compression: Compression) {
+        let min_version = match compression {
+            Compression::None => 1,
+            Compression::Zlib => 6,
+            Compression::Lzma => 13,
+        };
+        self.swf.header.version = self.swf.header.version.max(min_version);
+        self.swf.header.compression = compression;
+    }
+
+    /// Updates the frame counts and writes the movie.
+    pub fn write<W: Write>(&mut self, output: W) -> Result<()> {
+        self.update_frame_counts();
+        write_swf(&self.swf, output)
+    }
+}
+
+fn not_a_character() -> Error {
+    Error::invalid_data("Tag does not define a character")
+}
+
+fn undefined_character(id: CharacterId) -> Error {
+    Error::invalid_data(format!("Character {} is not defined", id))
+}
+
+fn free_id(used: &BTreeSet<CharacterId>) -> Result<CharacterId> {
+    (1..=CharacterId::MAX)
+        .find(|id| !used.contains(id))
+        .ok_or_else(|| Error::invalid_data("No unused character IDs"))
+}
+
+/// The depths of the objects left on the stage after the last frame of a timeline.
+fn stage_depths(tags: &[Tag]) -> BTreeSet<Depth> {
+    let mut depths = BTreeSet::new();
+    for tag in tags {
+        match tag {
+            Tag::PlaceObject(place_object) if place_object.action != PlaceObjectAction::Modify => {
+                depths.insert(place_object.depth);
+            }
+            Tag::RemoveObject(remove_object) => {
+                depths.remove(&remove_object.depth);
+            }
+            _ => (),
+        }
+    }
+    depths
+}
+
+/// The deepest depth used by a timeline, including the depths masked by clipping layers.
+fn max_depth(tags: &[Tag]) -> Depth {
+    let mut max_depth = 0;
+    for tag in tags {
+        let depth = match tag {
+            Tag::PlaceObject(place_object) => {
+                place_object.depth.max(place_object.clip_depth.unwrap_or(0))
+            }
+            Tag::RemoveObject(remove_object) => remove_object.depth,
+            Tag::SetTabIndex { depth, .. } => *depth,
+            _ => continue,
+        };
+        max_depth = max_depth.max(depth);
+    }
+    max_depth
+}
+
+/// Moves the objects of a timeline deeper by `offset`.
+fn shift_depths(tags: &mut [Tag], offset: Depth) {
+    for tag in tags {
+        match tag {
+            Tag::PlaceObject(place_object) => {
+                place_object.depth += offset;
+                if let Some(clip_depth) = &mut place_object.clip_depth {
+                    *clip_depth += offset;
+                }
+            }
+            Tag::RemoveObject(remove_object) => remove_object.depth += offset,
+            Tag::SetTabIndex { depth, .. } => *depth += offset,
+            _ => (),
+        }
+    }
+}
+
+fn is_bitmap(tag: &Tag) -> bool {
+    matches!(
+        tag,
+        Tag::DefineBits { .. }
+            | Tag::DefineBitsJpeg2 { .. }
+            | Tag::DefineBitsJpeg3(_)
+            | Tag::DefineBitsLossless(_)
+    )
+}
+
+/// Renumbers every character ID in a tag, whether it is defined or used by the tag.
+fn remap_tag(tag: &mut Tag, f: &mut dyn FnMut(CharacterId) -> CharacterId) {
+    visit_ids_mut(tag, &mut |id, _| *id = f(*id));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read::{decompress_swf, parse_swf};
+    use crate::test_data::{bitmap, bitmap_filled_shape, place};
+
+    /// A bitmap, a shape filled with it and a sprite containing the shape.
+    fn new_swf() -> Swf<'static> {
+        Swf {
+            header: Header {
+                version: 8,
+                compression: Compression::Zlib,
+                stage_size: Rectangle::default(),
+                frame_rate: 30.0,
+                num_frames: 1,
+            },
+            tags: vec![
+                bitmap(1),
+                bitmap_filled_shape(2, 1),
+                Tag::DefineSprite(Sprite {
+                    id: 3,
+                    num_frames: 1,
+                    tags: vec![place(2, 1), Tag::DoAction(&[0]), Tag::ShowFrame],
+                }),
+                Tag::ExportAssets(vec![ExportedAsset {
+                    id: 3,
+                    name: "clip".to_string(),
+                }]),
+                place(3, 1),
+                Tag::DoAction(&[0]),
+                Tag::ShowFrame,
+            ],
+        }
+    }
+
+    #[test]
+    fn dependencies() {
+        let editor = Editor::new(new_swf());
+        assert_eq!(editor.character_ids(), [1, 2, 3].iter().copied().collect());
+        assert_eq!(editor.dependencies(1).unwrap(), BTreeSet::new());
+        assert_eq!(
+            editor.dependencies(2).unwrap(),
+            [1].iter().copied().collect()
+        );
+        assert_eq!(
+            editor.dependencies(3).unwrap(),
+            [2].iter().copied().collect()
+        );
+        assert_eq!(editor.dependents(1), [2].iter().copied().collect());
+        assert!(editor.dependencies(4).is_err());
+    }
+
+    #[test]
+    fn replace_assets() {
+        let mut editor = Editor::new(new_swf());
+        let jpeg = Tag::DefineBitsJpeg2 {
+            id: 0,
+            jpeg_data: &[0xff, 0xd8, 0xff, 0xd9],
+        };
+        let old_bitmap = editor.replace_bitmap(1, jpeg).unwrap();
+        assert_eq!(old_bitmap, bitmap(1));
+        assert_eq!(
+            editor.character(1),
+            Some(&Tag::DefineBitsJpeg2 {
+                id: 1,
+                jpeg_data: &[0xff, 0xd8, 0xff, 0xd9],
+            })
+        );
+        assert!(editor.replace_bitmap(2, bitmap(0)).is_err());
+
+        let sound = Sound {
+            id: 0,
+            format: SoundFormat {
+                compression: AudioCompression::Uncompressed,
+                sample_rate: 5512,
+                is_stereo: false,
+                is_16_bit: false,
+            },
+            num_samples: 1,
+            data: &[128],
+        };
+        assert!(editor.replace_sound(1, sound).is_err());
+    }
+
+    #[test]
+    fn add_and_strip() {
+        let mut editor = Editor::new(new_swf());
+        assert_eq!(editor.add_character(bitmap(1)).unwrap(), 4);
+        assert_eq!(editor.add_character(bitmap_filled_shape(0, 4)).unwrap(), 5);
+        assert_eq!(editor.dependents(4), [5].iter().copied().collect());
+        assert!(editor.add_character(Tag::ShowFrame).is_err());
+
+        assert_eq!(editor.strip_actions(), 2);
+        let mut has_actions = false;
+        editor.retain_tags(|tag| {
+            has_actions |= matches!(tag, Tag::DoAction(_));
+            true
+        });
+        assert!(!has_actions);
+    }
+
+    #[test]
+    fn remap_ids() {
+        let mut editor = Editor::new(new_swf());
+        let map: IdMap = [(1, 2)].iter().copied().collect();
+        assert!(editor.remap_ids(&map).is_err());
+
+        let map: IdMap = [(1, 10), (2, 20), (3, 30)].iter().copied().collect();
+        editor.remap_ids(&map).unwrap();
+        assert_eq!(
+            editor.character_ids(),
+            [10, 20, 30].iter().copied().collect()
+        );
+        assert_eq!(
+            editor.dependencies(20).unwrap(),
+            [10].iter().copied().collect()
+        );
+        assert_eq!(
+            editor.dependencies(30).unwrap(),
+            [20].iter().copied().collect()
+        );
+        assert_eq!(
+            editor.swf().tags[3],
+            Tag::ExportAssets(vec![ExportedAsset {
+                id: 30,
+                name: "clip".to_string(),
+            }])
+        );
+    }
+
+    #[test]
+    fn merge_and_write() {
+        let mut editor = Editor::new(new_swf());
+        let map = editor.merge(new_swf()).unwrap();
+        let expected: IdMap = [(1, 4), (2, 5), (3, 6)].iter().copied().collect();
+        assert_eq!(map, expected);
+        assert_eq!(
+            editor.dependencies(5).unwrap(),
+            [4].iter().copied().collect()
+        );
+        assert_eq!(
+            editor.dependencies(6).unwrap(),
+            [5].iter().copied().collect()
+        );
+
+        editor.set_compression(Compression::None);
+        let mut buf = Vec::new();
+        editor.write(&mut buf).unwrap();
+        let swf_buf = decompress_swf(&buf[..]).unwrap();
+        let swf = parse_swf(&swf_buf).unwrap();
+        assert_eq!(swf.header.num_frames, 2);
+        assert_eq!(swf.header.compression, Compression::None);
+        assert_eq!(swf, editor.into_swf());
+    }
+
+    #[test]
+    fn merge_conflicts() {
+        let mut editor = Editor::new(new_swf());
+        editor.merge(new_swf()).unwrap();
+        let depths: Vec<_> = editor
+            .swf()
+            .tags
+            .iter()
+            .filter_map(|tag| match tag {
+                Tag::PlaceObject(place_object) => Some(place_object.depth),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(depths, vec![1, 2]);
+        let exports: Vec<_> = editor
+            .swf()
+            .tags
+            .iter()
+            .filter(|tag| matches!(tag, Tag::ExportAssets(_)))
+            .collect();
+        assert_eq!(
+            exports,
+            vec![&Tag::ExportAssets(vec![ExportedAsset {
+                id: 3,
+                name: "clip".to_string(),
+            }])]
+        );
+
+        let mut deep_swf = new_swf();
+        deep_swf.tags.push(place(1, Depth::MAX));
+        assert!(editor.merge(deep_swf).is_err());
+
+        let with_tables = |tables: JpegTables<'static>| {
+            let mut swf = new_swf();
+            swf.tags.insert(0, Tag::JpegTables(tables));
+            swf
+        };
+        let mut editor = Editor::new(with_tables(&[1]));
+        let num_tags = editor.swf().tags.len();
+        assert!(editor.merge(with_tables(&[2])).is_err());
+        assert_eq!(editor.swf().tags.len(), num_tags);
+        editor.merge(with_tables(&[1])).unwrap();
+        let num_tables = editor
+            .swf()
+            .tags
+            .iter()
+            .filter(|tag| matches!(tag, Tag::JpegTables(_)))
+            .count();
+        assert_eq!(num_tables, 1);
+    }
+}
@@ -25,6 +25,8 @@ pub enum Error {
     IoError(io::Error),
     /// This SWF requires unsupported features.
     Unsupported(borrow::Cow<'static, str>),
+    /// A problem that is normally only a warning was found while reading in strict mode.
+    Strict(Diagnostic),
 }
 
 impl Error {
@@ -105,6 +107,7 @@ impl fmt::Display for Error {
             Error::IoError(e) => e.fmt(f),
             Error::InvalidData(message) => write!(f, "Invalid data: {}", message),
             Error::Unsupported(message) => write!(f, "Unsupported data: {}", message),
+            Error::Strict(diagnostic) => diagnostic.fmt(f),
         }
     }
 }
@@ -118,6 +121,7 @@ impl error::Error for Error {
             Error::InvalidData(_) => None,
             Error::SwfParseError { source, .. } => source.as_ref().map(|s| s.deref()),
             Error::Unsupported(_) => None,
+            Error::Strict(_) => None,
         }
     }
 }
@@ -127,3 +131,57 @@ impl From<io::Error> for Error {
         Error::IoError(error)
     }
 }
+
+/// A recoverable problem found while reading an SWF.
+///
+/// When reading leniently, these are collected and the SWF is read as well as possible.
+/// In strict mode, they are returned as `Error::Strict` instead.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// The code of the tag the problem was found in, if any.
+    pub tag_code: Option<u16>,
+
+    /// The byte offset of the problem in the uncompressed SWF data, not including
+    /// the SWF header. For tags, this is the offset of the tag header.
+    pub offset: usize,
+
+    pub kind: DiagnosticKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiagnosticKind {
+    /// A tag was longer than its contents, and the remaining bytes were ignored.
+    TrailingBytes(usize),
+
+    /// The uncompressed length of the SWF didn't match the length in its header.
+    LengthMismatch { expected: usize, actual: usize },
+
+    /// A tag code was not recognized, and the tag was read as `Tag::Unknown`.
+    UnknownTag,
+
+    /// The compressed SWF data could not be fully decompressed.
+    DecompressionError(String),
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            DiagnosticKind::TrailingBytes(len) => {
+                write!(f, "{} bytes of data remaining in tag", len)?
+            }
+            DiagnosticKind::LengthMismatch { expected, actual } => write!(
+                f,
+                "SWF length {} doesn't match header length {}, may be corrupt",
+                actual, expected
+            )?,
+            DiagnosticKind::UnknownTag => "Unknown tag".fmt(f)?,
+            DiagnosticKind::DecompressionError(message) => {
+                write!(f, "Error decompressing SWF, may be corrupt: {}", message)?
+            }
+        }
+        if let Some(tag_code) = self.tag_code {
+            write!(f, " in {}", crate::tag_code::TagCode::name(tag_code))?;
+        }
+        write!(f, " at offset {}", self.offset)
+    }
+}
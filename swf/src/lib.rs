@@ -5,7 +5,7 @@
 //! # Organization
 //!
 //! This library consits of a `read` module for decoding SWF data, and a `write` library for
-//! writing SWF data.
+//! writing SWF data. The `editor` module modifies parsed SWFs before they are written back out.
 
 extern crate byteorder;
 #[cfg(feature = "flate2")]
@@ -20,6 +20,8 @@ extern crate xz2;
 
 pub mod avm1;
 pub mod avm2;
+pub mod dependencies;
+pub mod editor;
 pub mod error;
 pub mod read;
 mod tag_code;
@@ -30,7 +32,7 @@ pub mod write;
 mod test_data;
 
 /// Reexports
-pub use read::{read_swf, read_swf_header};
+pub use read::{decompress_swf, parse_swf, read_swf_header, TagItem, TagIterator};
 pub use tag_code::TagCode;
 pub use types::*;
 pub use write::write_swf;
@@ -4,68 +4,328 @@
     clippy::unreadable_literal
 )]
 
-use crate::error::{Error, Result};
+use crate::error::{Diagnostic, DiagnosticKind, Error, Result};
+use crate::tag_code::TagCode;
 use crate::types::*;
 use byteorder::{LittleEndian, ReadBytesExt};
 use enumset::EnumSet;
 use std::collections::HashSet;
 use std::convert::TryInto;
 use std::io::{self, Read};
+use std::ops::Range;
+
+/// Options that control how strictly an SWF is read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReadOptions {
+    /// Whether problems that are normally recoverable, such as trailing data in a tag,
+    /// should be returned as `Error::Strict` instead of `Diagnostic`s.
+    pub strict: bool,
+}
+
+/// Records `diagnostic` in `diagnostics`, or logs it if there is no collector.
+/// In strict mode, the diagnostic is returned as an error instead.
+fn report(
+    options: &ReadOptions,
+    diagnostics: Option<&mut Vec<Diagnostic>>,
+    diagnostic: Diagnostic,
+) -> Result<()> {
+    if options.strict {
+        return Err(Error::Strict(diagnostic));
+    }
+    match diagnostics {
+        Some(diagnostics) => diagnostics.push(diagnostic),
+        None => log::warn!("{}", diagnostic),
+    }
+    Ok(())
+}
 
-/// Convenience method to parse an SWF.
+/// Convenience method to decompress an SWF.
 ///
-/// Decompresses the SWF in memory and returns a `Vec` of tags.
-/// If you would like to stream the SWF instead, use `read_swf_header` and
-/// `read_tag`.
+/// Decompresses the SWF in memory and returns an `SwfBuf` containing the header
+/// and the uncompressed tag data. Use `parse_swf` to read the tags from the
+/// `SwfBuf`.
 ///
 /// # Example
 /// ```
 /// let data = std::fs::read("tests/swfs/DefineSprite.swf").unwrap();
-/// let swf = swf::read_swf(&data[..]).unwrap();
-/// println!("Number of frames: {}", swf.header.num_frames);
+/// let swf_buf = swf::decompress_swf(&data[..]).unwrap();
+/// println!("Number of frames: {}", swf_buf.header.num_frames);
 /// ```
-pub fn read_swf<R: Read>(input: R) -> Result<Swf> {
+pub fn decompress_swf<R: Read>(input: R) -> Result<SwfBuf> {
+    decompress_swf_impl(input, &ReadOptions::default(), None)
+}
+
+/// Decompresses an SWF like `decompress_swf`, collecting any problems in `diagnostics`.
+pub fn decompress_swf_with_options<R: Read>(
+    input: R,
+    options: &ReadOptions,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<SwfBuf> {
+    decompress_swf_impl(input, options, Some(diagnostics))
+}
+
+fn decompress_swf_impl<R: Read>(
+    input: R,
+    options: &ReadOptions,
+    mut diagnostics: Option<&mut Vec<Diagnostic>>,
+) -> Result<SwfBuf> {
     let swf_stream = read_swf_header(input)?;
     let header = swf_stream.header;
     let mut reader = swf_stream.reader;
 
     // Decompress all of SWF into memory at once.
-    let mut data = if header.compression == Compression::Lzma {
-        // TODO: The LZMA decoder is still funky.
-        // It always errors, and doesn't return all the data if you use read_to_end,
-        // but read_exact at least returns the data... why?
-        // Does the decoder need to be flushed somehow?
-        let mut data = vec![0u8; swf_stream.uncompressed_length];
-        let _ = reader.get_mut().read_exact(&mut data);
-        data
-    } else {
-        let mut data = Vec::with_capacity(swf_stream.uncompressed_length);
-        if let Err(e) = reader.get_mut().read_to_end(&mut data) {
-            log::error!("Error decompressing SWF, may be corrupt: {}", e);
-        }
-        data
-    };
-    let version = header.version;
+    // The LZMA decoder errors if it is asked for more data than the uncompressed
+    // length in the header, so never read past it in the first pass.
+    // Whatever was decompressed before an error is kept, so a truncated SWF yields
+    // the tags that were fully received.
+    let mut data = Vec::with_capacity(swf_stream.uncompressed_length);
+    let mut result = reader
+        .get_mut()
+        .take(swf_stream.uncompressed_length as u64)
+        .read_to_end(&mut data);
 
     // Some SWF streams may not be compressed correctly,
-    // (e.g. incorrect data length in the stream), so decompressing
-    // may throw an error even though the data otherwise comes
-    // through the stream.
+    // (e.g. incorrect data length in the stream), so there may still
+    // be data in the stream after the uncompressed length.
     // We'll still try to parse what we get if the full decompression fails.
-    if let Err(e) = reader.get_mut().read_to_end(&mut data) {
-        log::warn!("Error decompressing SWF stream, may be corrupt: {}", e);
+    if result.is_ok() && header.compression != Compression::Lzma {
+        result = reader.get_mut().read_to_end(&mut data);
+    }
+    if let Err(e) = result {
+        let diagnostic = Diagnostic {
+            tag_code: None,
+            offset: data.len(),
+            kind: DiagnosticKind::DecompressionError(e.to_string()),
+        };
+        report(options, diagnostics.as_deref_mut(), diagnostic)?;
     }
     if data.len() != swf_stream.uncompressed_length {
-        log::warn!("SWF length doesn't match header, may be corrupt");
+        let diagnostic = Diagnostic {
+            tag_code: None,
+            offset: data.len().min(swf_stream.uncompressed_length),
+            kind: DiagnosticKind::LengthMismatch {
+                expected: swf_stream.uncompressed_length,
+                actual: data.len(),
+            },
+        };
+        report(options, diagnostics, diagnostic)?;
     }
-    let mut reader = Reader::new(&data[..], version);
+
+    Ok(SwfBuf { header, data })
+}
+
+/// Convenience method to parse the tags of a decompressed SWF.
+///
+/// The returned `Swf` borrows tag payloads such as bitmap, sound, and action data
+/// from the `SwfBuf` instead of copying them.
+///
+/// # Example
+/// ```
+/// let data = std::fs::read("tests/swfs/DefineSprite.swf").unwrap();
+/// let swf_buf = swf::decompress_swf(&data[..]).unwrap();
+/// let swf = swf::parse_swf(&swf_buf).unwrap();
+/// println!("Number of tags: {}", swf.tags.len());
+/// ```
+pub fn parse_swf(swf_buf: &SwfBuf) -> Result<Swf<'_>> {
+    let mut reader = Reader::new(&swf_buf.data[..], swf_buf.header.version);
 
     Ok(Swf {
-        header,
+        header: swf_buf.header.clone(),
         tags: reader.read_tag_list()?,
     })
 }
 
+/// Parses the tags of a decompressed SWF like `parse_swf`, collecting any problems
+/// in `diagnostics`.
+///
+/// # Example
+/// ```
+/// let data = std::fs::read("tests/swfs/DefineSprite.swf").unwrap();
+/// let options = swf::read::ReadOptions { strict: false };
+/// let mut diagnostics = Vec::new();
+/// let swf_buf = swf::read::decompress_swf_with_options(&data[..], &options, &mut diagnostics).unwrap();
+/// let swf = swf::read::parse_swf_with_options(&swf_buf, &options, &mut diagnostics).unwrap();
+/// for diagnostic in &diagnostics {
+///     println!("Warning: {}", diagnostic);
+/// }
+/// ```
+pub fn parse_swf_with_options<'a>(
+    swf_buf: &'a SwfBuf,
+    options: &ReadOptions,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Result<Swf<'a>> {
+    let mut reader = Reader::with_options(&swf_buf.data[..], swf_buf.header.version, *options);
+    let tags = reader.read_tag_list();
+    diagnostics.append(&mut reader.take_diagnostics());
+
+    Ok(Swf {
+        header: swf_buf.header.clone(),
+        tags: tags?,
+    })
+}
+
+/// A lazy iterator over the tags of a decompressed SWF.
+///
+/// Each tag is framed by its header but not parsed until `TagItem::parse` is
+/// called, so a malformed tag does not stop the iteration. Byte ranges of tags,
+/// including those inside a `DefineSprite`, are offsets into the buffer the
+/// iterator was created with.
+///
+/// The iterator stops at the `End` tag. If the buffer ends before that, such as
+/// when reading an SWF that is still loading, it stops and `needs_more_data`
+/// returns `true`. Iteration can continue from `position` once more data arrives.
+///
+/// # Example
+/// ```
+/// let data = std::fs::read("tests/swfs/DefineSprite.swf").unwrap();
+/// let swf_buf = swf::decompress_swf(&data[..]).unwrap();
+/// for item in swf::TagIterator::new(&swf_buf.data, swf_buf.header.version) {
+///     println!("{:?} at {:?}: {:?}", item.tag_code(), item.range, item.parse());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct TagIterator<'a> {
+    data: &'a [u8],
+    version: u8,
+    pos: usize,
+    end: usize,
+    state: TagIteratorState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TagIteratorState {
+    Reading,
+    Finished,
+    NeedMoreData,
+}
+
+impl<'a> TagIterator<'a> {
+    /// Creates an iterator over the tags in `data`, the decompressed body of an SWF.
+    pub fn new(data: &'a [u8], version: u8) -> Self {
+        Self::from_position(data, version, 0)
+    }
+
+    /// Creates an iterator over the tags in `data`, starting at the tag at `position`.
+    pub fn from_position(data: &'a [u8], version: u8, position: usize) -> Self {
+        Self {
+            data,
+            version,
+            pos: position,
+            end: data.len(),
+            state: TagIteratorState::Reading,
+        }
+    }
+
+    /// The offset of the next tag to be read.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Returns whether the iterator stopped because the data ended in the middle of a tag
+    /// or before the `End` tag.
+    pub fn needs_more_data(&self) -> bool {
+        self.state == TagIteratorState::NeedMoreData
+    }
+
+    fn read_tag_header(&self) -> Option<(u16, Range<usize>)> {
+        let mut reader = Reader::new(self.data.get(self.pos..self.end)?, self.version);
+        let (tag_code, length) = reader.read_tag_code_and_length().ok()?;
+        let body_start = self.end - reader.get_ref().len();
+        let body_end = body_start.checked_add(length)?;
+        if body_end <= self.end {
+            Some((tag_code, body_start..body_end))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a> Iterator for TagIterator<'a> {
+    type Item = TagItem<'a>;
+
+    fn next(&mut self) -> Option<TagItem<'a>> {
+        if self.state != TagIteratorState::Reading {
+            return None;
+        }
+
+        let (code, body) = match self.read_tag_header() {
+            Some(header) => header,
+            None => {
+                self.state = TagIteratorState::NeedMoreData;
+                return None;
+            }
+        };
+        if code == TagCode::End as u16 {
+            self.pos = body.end;
+            self.state = TagIteratorState::Finished;
+            return None;
+        }
+
+        let item = TagItem {
+            code,
+            range: self.pos..body.end,
+            body_start: body.start,
+            data: self.data,
+            version: self.version,
+        };
+        self.pos = body.end;
+        Some(item)
+    }
+}
+
+/// A tag framed by a `TagIterator`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagItem<'a> {
+    /// The raw tag code. See `tag_code` for the known tag codes.
+    pub code: u16,
+
+    /// The byte range of the tag, including its header.
+    pub range: Range<usize>,
+
+    body_start: usize,
+    data: &'a [u8],
+    version: u8,
+}
+
+impl<'a> TagItem<'a> {
+    /// The code of this tag, or `None` if it is not a known tag.
+    pub fn tag_code(&self) -> Option<TagCode> {
+        TagCode::from_u16(self.code)
+    }
+
+    /// The contents of this tag, excluding its header.
+    pub fn body(&self) -> &'a [u8] {
+        &self.data[self.body_start..self.range.end]
+    }
+
+    /// Parses this tag.
+    ///
+    /// A `DefineSprite` is parsed along with all of its tags; use `sprite_tags` to
+    /// iterate over them lazily instead.
+    pub fn parse(&self) -> Result<Tag<'a>> {
+        let mut reader = Reader::new(&self.data[self.range.clone()], self.version);
+        reader.offset = self.range.start;
+        reader.read_tag()
+    }
+
+    /// Returns an iterator over the tags of this `DefineSprite`, or `None` if this is
+    /// not a `DefineSprite`.
+    pub fn sprite_tags(&self) -> Option<TagIterator<'a>> {
+        if self.tag_code() != Some(TagCode::DefineSprite) || self.body().len() < 4 {
+            return None;
+        }
+
+        // Skip the sprite ID and frame count.
+        Some(TagIterator {
+            data: self.data,
+            version: self.version,
+            pos: self.body_start + 4,
+            end: self.range.end,
+            state: TagIteratorState::Reading,
+        })
+    }
+}
+
 /// Parses an SWF header and returns a `Reader` that can be used
 /// to read the SWF tags inside the SWF file.
 ///
@@ -87,7 +347,7 @@ pub fn read_swf_header<'a, R: Read + 'a>(mut input: R) -> Result<SwfStream<'a>>
     let uncompressed_length = input.read_u32::<LittleEndian>()? - 8;
 
     // Now the SWF switches to a compressed stream.
-    let decompressed_input: Box<dyn Read> = match compression {
+    let mut decompressed_input: Box<dyn Read> = match compression {
         Compression::None => Box::new(input),
         Compression::Zlib => {
             if version < 6 {
@@ -109,10 +369,16 @@ pub fn read_swf_header<'a, R: Read + 'a>(mut input: R) -> Result<SwfStream<'a>>
         }
     };
 
-    let mut reader = Reader::new(decompressed_input, version);
-    let stage_size = reader.read_rectangle()?;
-    let frame_rate = reader.read_fixed8()?;
-    let num_frames = reader.read_u16()?;
+    // The rest of the header is part of the compressed stream. Its length depends on the
+    // bit width of the stage rectangle, given by the first 5 bits.
+    let first_byte = decompressed_input.read_u8()?;
+    let header_length = (5 + 4 * usize::from(first_byte >> 3)).div_ceil(8) + 4;
+    let mut header_data = vec![first_byte; header_length];
+    decompressed_input.read_exact(&mut header_data[1..])?;
+    let mut header_reader = Reader::new(&header_data[..], version);
+    let stage_size = header_reader.read_rectangle()?;
+    let frame_rate = header_reader.read_fixed8()?;
+    let num_frames = header_reader.read_u16()?;
     let header = Header {
         version,
         compression,
@@ -120,10 +386,11 @@ pub fn read_swf_header<'a, R: Read + 'a>(mut input: R) -> Result<SwfStream<'a>>
         frame_rate,
         num_frames,
     };
+    let uncompressed_length: usize = uncompressed_length.try_into().unwrap();
     Ok(SwfStream {
         header,
-        uncompressed_length: uncompressed_length.try_into().unwrap(),
-        reader,
+        uncompressed_length: uncompressed_length.saturating_sub(header_length),
+        reader: Reader::new(decompressed_input, version),
     })
 }
 
@@ -273,6 +540,56 @@ pub trait SwfRead<R: Read> {
     }
 }
 
+/// An input that can lend out slices of its underlying buffer.
+///
+/// Readers over an `SliceRead` input return tag payloads that borrow from the
+/// input instead of copying them into owned buffers.
+pub trait SliceRead<'a>: Read {
+    /// Reads the next `len` bytes as a slice of the underlying buffer.
+    fn read_slice(&mut self, len: usize) -> io::Result<&'a [u8]>;
+
+    /// Reads all remaining bytes as a slice of the underlying buffer.
+    fn read_slice_to_end(&mut self) -> &'a [u8];
+}
+
+impl<'a> SliceRead<'a> for &'a [u8] {
+    fn read_slice(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if len > self.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "Buffer underrun",
+            ));
+        }
+        let (slice, rest) = self.split_at(len);
+        *self = rest;
+        Ok(slice)
+    }
+
+    fn read_slice_to_end(&mut self) -> &'a [u8] {
+        std::mem::take(self)
+    }
+}
+
+impl<'a> SliceRead<'a> for io::Cursor<&'a [u8]> {
+    fn read_slice(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        let data: &'a [u8] = self.get_ref();
+        let pos = self.position() as usize;
+        let slice = data
+            .get(pos..)
+            .and_then(|data| data.get(..len))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "Buffer underrun"))?;
+        self.set_position((pos + len) as u64);
+        Ok(slice)
+    }
+
+    fn read_slice_to_end(&mut self) -> &'a [u8] {
+        let data: &'a [u8] = self.get_ref();
+        let pos = std::cmp::min(self.position() as usize, data.len());
+        self.set_position(data.len() as u64);
+        &data[pos..]
+    }
+}
+
 pub struct Reader<R: Read> {
     input: R,
     version: u8,
@@ -282,6 +599,12 @@ pub struct Reader<R: Read> {
 
     num_fill_bits: u8,
     num_line_bits: u8,
+
+    options: ReadOptions,
+    /// Diagnostics are collected here when reading with `with_options`, and logged otherwise.
+    diagnostics: Option<Vec<Diagnostic>>,
+    /// The offset of the next tag in the SWF data, used to locate diagnostics.
+    offset: usize,
 }
 
 impl<R: Read> SwfRead<R> for Reader<R> {
@@ -339,6 +662,47 @@ impl<R: Read> Reader<R> {
             bit_index: 0,
             num_fill_bits: 0,
             num_line_bits: 0,
+            options: Default::default(),
+            diagnostics: None,
+            offset: 0,
+        }
+    }
+
+    /// Creates a reader that collects diagnostics instead of logging them.
+    /// Use `take_diagnostics` to retrieve them.
+    pub fn with_options(input: R, version: u8, options: ReadOptions) -> Reader<R> {
+        Reader {
+            options,
+            diagnostics: Some(Vec::new()),
+            ..Reader::new(input, version)
+        }
+    }
+
+    /// Returns the diagnostics collected so far, leaving the collector empty.
+    pub fn take_diagnostics(&mut self) -> Vec<Diagnostic> {
+        self.diagnostics
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    fn report(&mut self, tag_code: u16, offset: usize, kind: DiagnosticKind) -> Result<()> {
+        let diagnostic = Diagnostic {
+            tag_code: Some(tag_code),
+            offset,
+            kind,
+        };
+        report(&self.options, self.diagnostics.as_mut(), diagnostic)
+    }
+
+    /// Creates a reader for the contents of a tag starting at `offset`,
+    /// sharing this reader's options.
+    fn tag_reader<'a>(&self, input: &'a [u8], offset: usize) -> Reader<&'a [u8]> {
+        Reader {
+            options: self.options,
+            diagnostics: self.diagnostics.as_ref().map(|_| Vec::new()),
+            offset,
+            ..Reader::new(input, self.version)
         }
     }
 
@@ -355,28 +719,49 @@ impl<R: Read> Reader<R> {
     }
 
     /// Reads the next SWF tag from the stream.
+    ///
+    /// The payload of the tag borrows from the underlying buffer.
     /// # Example
     /// ```
     /// let data = std::fs::read("tests/swfs/DefineSprite.swf").unwrap();
-    /// let mut swf_stream = swf::read_swf_header(&data[..]).unwrap();
-    /// while let Ok(tag) = swf_stream.reader.read_tag() {
+    /// let swf_buf = swf::decompress_swf(&data[..]).unwrap();
+    /// let mut reader = swf::read::Reader::new(&swf_buf.data[..], swf_buf.header.version);
+    /// while let Ok(tag) = reader.read_tag() {
     ///     println!("Tag: {:?}", tag);
     /// }
     /// ```
-    pub fn read_tag(&mut self) -> Result<Tag> {
+    pub fn read_tag<'a>(&mut self) -> Result<Tag<'a>>
+    where
+        R: SliceRead<'a>,
+    {
+        let tag_offset = self.offset;
         let (tag_code, length) = self.read_tag_code_and_length()?;
-        let tag = self.read_tag_with_code(tag_code, length);
+        let body_offset = self.offset;
+        let tag = self.read_tag_with_code(tag_code, length, tag_offset, body_offset);
 
         if let Err(e) = tag {
             return Err(Error::swf_parse_error_with_source(tag_code, e));
         }
 
+        self.offset = body_offset + length;
         tag
     }
 
-    fn read_tag_with_code(&mut self, tag_code: u16, length: usize) -> Result<Tag> {
-        let mut tag_reader = Reader::new(self.input.by_ref().take(length as u64), self.version);
-        use crate::tag_code::TagCode;
+    fn read_tag_with_code<'a>(
+        &mut self,
+        tag_code: u16,
+        length: usize,
+        tag_offset: usize,
+        body_offset: usize,
+    ) -> Result<Tag<'a>>
+    where
+        R: SliceRead<'a>,
+    {
+        let tag_data = self.input.read_slice(length)?;
+        let mut tag_reader = self.tag_reader(tag_data, body_offset);
+        if TagCode::from_u16(tag_code).is_none() {
+            self.report(tag_code, tag_offset, DiagnosticKind::UnknownTag)?;
+        }
         let tag = match TagCode::from_u16(tag_code) {
             Some(TagCode::End) => Tag::End,
             Some(TagCode::ShowFrame) => Tag::ShowFrame,
@@ -384,20 +769,17 @@ impl<R: Read> Reader<R> {
             Some(TagCode::DefineBinaryData) => {
                 let id = tag_reader.read_u16()?;
                 tag_reader.read_u32()?; // Reserved
-                let mut data = Vec::with_capacity(length - 6);
-                tag_reader.input.read_to_end(&mut data)?;
+                let data = tag_reader.input.read_slice_to_end();
                 Tag::DefineBinaryData { id, data }
             }
             Some(TagCode::DefineBits) => {
                 let id = tag_reader.read_u16()?;
-                let mut jpeg_data = Vec::with_capacity(length - 2);
-                tag_reader.input.read_to_end(&mut jpeg_data)?;
+                let jpeg_data = tag_reader.input.read_slice_to_end();
                 Tag::DefineBits { id, jpeg_data }
             }
             Some(TagCode::DefineBitsJpeg2) => {
                 let id = tag_reader.read_u16()?;
-                let mut jpeg_data = Vec::with_capacity(length - 2);
-                tag_reader.input.read_to_end(&mut jpeg_data)?;
+                let jpeg_data = tag_reader.input.read_slice_to_end();
                 Tag::DefineBitsJpeg2 { id, jpeg_data }
             }
             Some(TagCode::DefineBitsJpeg3) => tag_reader.read_define_bits_jpeg_3(3)?,
@@ -452,46 +834,22 @@ impl<R: Read> Reader<R> {
             Some(TagCode::EnableTelemetry) => {
                 tag_reader.read_u16()?; // Reserved
                 let password_hash = if length > 2 {
-                    let mut data = vec![0; 32];
-                    tag_reader.input.read_exact(&mut data)?;
-                    data
+                    tag_reader.input.read_slice(32)?
                 } else {
-                    vec![]
+                    &[]
                 };
                 Tag::EnableTelemetry { password_hash }
             }
             Some(TagCode::ImportAssets) => {
-                let url = tag_reader.read_c_string()?;
-                let num_imports = tag_reader.read_u16()?;
-                let mut imports = Vec::with_capacity(num_imports as usize);
-                for _ in 0..num_imports {
-                    imports.push(ExportedAsset {
-                        id: tag_reader.read_u16()?,
-                        name: tag_reader.read_c_string()?,
-                    });
-                }
+                let (url, imports) = tag_reader.read_import_assets()?;
                 Tag::ImportAssets { url, imports }
             }
             Some(TagCode::ImportAssets2) => {
-                let url = tag_reader.read_c_string()?;
-                tag_reader.read_u8()?; // Reserved; must be 1
-                tag_reader.read_u8()?; // Reserved; must be 0
-                let num_imports = tag_reader.read_u16()?;
-                let mut imports = Vec::with_capacity(num_imports as usize);
-                for _ in 0..num_imports {
-                    imports.push(ExportedAsset {
-                        id: tag_reader.read_u16()?,
-                        name: tag_reader.read_c_string()?,
-                    });
-                }
+                let (url, imports) = tag_reader.read_import_assets_2()?;
                 Tag::ImportAssets { url, imports }
             }
 
-            Some(TagCode::JpegTables) => {
-                let mut data = Vec::with_capacity(length);
-                tag_reader.input.read_to_end(&mut data)?;
-                Tag::JpegTables(data)
-            }
+            Some(TagCode::JpegTables) => Tag::JpegTables(tag_reader.input.read_slice_to_end()),
 
             Some(TagCode::Metadata) => {
                 let mut s = String::with_capacity(length);
@@ -504,9 +862,7 @@ impl<R: Read> Reader<R> {
             Some(TagCode::SetBackgroundColor) => Tag::SetBackgroundColor(tag_reader.read_rgb()?),
 
             Some(TagCode::SoundStreamBlock) => {
-                let mut data = Vec::with_capacity(length);
-                tag_reader.input.read_to_end(&mut data)?;
-                Tag::SoundStreamBlock(data)
+                Tag::SoundStreamBlock(tag_reader.input.read_slice_to_end())
             }
 
             Some(TagCode::SoundStreamHead) => Tag::SoundStreamHead(
@@ -542,25 +898,18 @@ impl<R: Read> Reader<R> {
             Some(TagCode::DoAbc) => {
                 let flags = tag_reader.read_u32()?;
                 let name = tag_reader.read_c_string()?;
-                let mut abc_data = Vec::with_capacity(length - 4 - name.len());
-                tag_reader.input.read_to_end(&mut abc_data)?;
                 Tag::DoAbc(DoAbc {
                     name,
                     is_lazy_initialize: flags & 1 != 0,
-                    data: abc_data,
+                    data: tag_reader.input.read_slice_to_end(),
                 })
             }
 
-            Some(TagCode::DoAction) => {
-                let mut action_data = Vec::with_capacity(length);
-                tag_reader.input.read_to_end(&mut action_data)?;
-                Tag::DoAction(action_data)
-            }
+            Some(TagCode::DoAction) => Tag::DoAction(tag_reader.input.read_slice_to_end()),
 
             Some(TagCode::DoInitAction) => {
                 let id = tag_reader.read_u16()?;
-                let mut action_data = Vec::with_capacity(length);
-                tag_reader.input.read_to_end(&mut action_data)?;
+                let action_data = tag_reader.input.read_slice_to_end();
                 Tag::DoInitAction { id, action_data }
             }
 
@@ -620,14 +969,7 @@ impl<R: Read> Reader<R> {
 
             Some(TagCode::FrameLabel) => Tag::FrameLabel(tag_reader.read_frame_label(length)?),
 
-            Some(TagCode::DefineSprite) => {
-                // TODO: There's probably a better way to prevent the infinite type recursion.
-                // Tags can only be nested one level deep, so perhaps I can implement
-                // read_tag_list for Reader<Take<R>> to enforce this.
-                let mut sprite_reader =
-                    Reader::new(&mut tag_reader.input as &mut dyn Read, self.version);
-                sprite_reader.read_define_sprite()?
-            }
+            Some(TagCode::DefineSprite) => tag_reader.read_define_sprite()?,
 
             Some(TagCode::PlaceObject) => {
                 Tag::PlaceObject(Box::new(tag_reader.read_place_object(length)?))
@@ -648,28 +990,28 @@ impl<R: Read> Reader<R> {
 
             Some(TagCode::VideoFrame) => tag_reader.read_video_frame()?,
             Some(TagCode::ProductInfo) => Tag::ProductInfo(tag_reader.read_product_info()?),
-            _ => {
-                let size = length as usize;
-                let mut data = vec![0; size];
-                tag_reader.input.read_exact(&mut data[..])?;
-                Tag::Unknown { tag_code, data }
-            }
+            _ => Tag::Unknown {
+                tag_code,
+                data: tag_reader.input.read_slice_to_end(),
+            },
         };
 
-        if tag_reader.read_u8().is_ok() {
+        if let (Some(diagnostics), Some(tag_diagnostics)) =
+            (&mut self.diagnostics, &mut tag_reader.diagnostics)
+        {
+            diagnostics.append(tag_diagnostics);
+        }
+
+        if !tag_reader.input.is_empty() {
             // There should be no data remaining in the tag if we read it correctly.
             // If there is data remaining, the most likely scenario is we screwed up parsing.
             // But sometimes tools will export SWF tags that are larger than they should be.
-            // TODO: It might be worthwhile to have a "strict mode" to determine
-            // whether this should error or not.
-            log::warn!(
-                "Data remaining in buffer when parsing {} ({})",
-                TagCode::name(tag_code),
-                tag_code
-            );
-
-            // Discard the rest of the data.
-            let _ = std::io::copy(&mut tag_reader.get_mut(), &mut io::sink());
+            let remaining = tag_reader.input.len();
+            self.report(
+                tag_code,
+                tag_offset,
+                DiagnosticKind::TrailingBytes(remaining),
+            )?;
         }
 
         Ok(tag)
@@ -862,7 +1204,10 @@ impl<R: Read> Reader<R> {
         })
     }
 
-    fn read_tag_list(&mut self) -> Result<Vec<Tag>> {
+    fn read_tag_list<'a>(&mut self) -> Result<Vec<Tag<'a>>>
+    where
+        R: SliceRead<'a>,
+    {
         let mut tags = Vec::new();
         loop {
             match self.read_tag() {
@@ -881,21 +1226,25 @@ impl<R: Read> Reader<R> {
         let tag_code_and_length = self.read_u16()?;
         let tag_code = tag_code_and_length >> 6;
         let mut length = (tag_code_and_length & 0b111111) as usize;
+        self.offset += 2;
         if length == 0b111111 {
             // Extended tag.
             length = self.read_u32()? as usize;
+            self.offset += 4;
         }
         Ok((tag_code, length))
     }
 
-    pub fn read_define_button_1(&mut self) -> Result<Button> {
+    pub fn read_define_button_1<'a>(&mut self) -> Result<Button<'a>>
+    where
+        R: SliceRead<'a>,
+    {
         let id = self.read_u16()?;
         let mut records = Vec::new();
         while let Some(record) = self.read_button_record(1)? {
             records.push(record);
         }
-        let mut action_data = Vec::new();
-        self.input.read_to_end(&mut action_data)?;
+        let action_data = self.input.read_slice_to_end();
         Ok(Button {
             id,
             is_track_as_menu: false,
@@ -905,12 +1254,15 @@ impl<R: Read> Reader<R> {
                     .into_iter()
                     .collect(),
                 key_code: None,
-                action_data: action_data,
+                action_data,
             }],
         })
     }
 
-    pub fn read_define_button_2(&mut self) -> Result<Button> {
+    pub fn read_define_button_2<'a>(&mut self) -> Result<Button<'a>>
+    where
+        R: SliceRead<'a>,
+    {
         let id = self.read_u16()?;
         let flags = self.read_u8()?;
         let is_track_as_menu = (flags & 0b1) != 0;
@@ -1045,7 +1397,10 @@ impl<R: Read> Reader<R> {
         }))
     }
 
-    fn read_button_action(&mut self) -> Result<(ButtonAction, bool)> {
+    fn read_button_action<'a>(&mut self) -> Result<(ButtonAction<'a>, bool)>
+    where
+        R: SliceRead<'a>,
+    {
         let length = self.read_u16()?;
         let flags = self.read_u16()?;
         let mut conditions = HashSet::with_capacity(8);
@@ -1081,19 +1436,17 @@ impl<R: Read> Reader<R> {
         if key_code != 0 {
             conditions.insert(ButtonActionCondition::KeyPress);
         }
-        let mut action_data = Vec::with_capacity(length as usize);
-        if length >= 4 {
-            action_data.resize(length as usize - 4, 0);
-            self.input.read_exact(&mut action_data)?;
+        let action_data = if length >= 4 {
+            self.input.read_slice(length as usize - 4)?
         } else if length == 0 {
             // Last action, read to end.
-            self.input.read_to_end(&mut action_data)?;
+            self.input.read_slice_to_end()
         } else {
             // Some SWFs have phantom action records with an invalid length.
             // See 401799_pre_Scene_1.swf
             // TODO: How does Flash handle this?
             return Err(Error::invalid_data("Button action length is too short"));
-        }
+        };
         Ok((
             ButtonAction {
                 conditions,
@@ -1104,7 +1457,7 @@ impl<R: Read> Reader<R> {
         ))
     }
 
-    fn read_csm_text_settings(&mut self) -> Result<Tag> {
+    fn read_csm_text_settings<'a>(&mut self) -> Result<Tag<'a>> {
         let id = self.read_character_id()?;
         let flags = self.read_u8()?;
         let thickness = self.read_f32()?;
@@ -1314,15 +1667,16 @@ impl<R: Read> Reader<R> {
         })
     }
 
-    pub fn read_define_font_4(&mut self) -> Result<Font4> {
+    pub fn read_define_font_4<'a>(&mut self) -> Result<Font4<'a>>
+    where
+        R: SliceRead<'a>,
+    {
         let id = self.read_character_id()?;
         let flags = self.read_u8()?;
         let name = self.read_c_string()?;
         let has_font_data = flags & 0b100 != 0;
         let data = if has_font_data {
-            let mut data = vec![];
-            self.input.read_to_end(&mut data)?;
-            Some(data)
+            Some(self.input.read_slice_to_end())
         } else {
             None
         };
@@ -1351,7 +1705,7 @@ impl<R: Read> Reader<R> {
         })
     }
 
-    fn read_define_font_align_zones(&mut self) -> Result<Tag> {
+    fn read_define_font_align_zones<'a>(&mut self) -> Result<Tag<'a>> {
         let id = self.read_character_id()?;
         let thickness = match self.read_u8()? {
             0b00_000000 => FontThickness::Thin,
@@ -1382,7 +1736,7 @@ impl<R: Read> Reader<R> {
         Ok(zone)
     }
 
-    fn read_define_font_info(&mut self, version: u8) -> Result<Tag> {
+    fn read_define_font_info<'a>(&mut self, version: u8) -> Result<Tag<'a>> {
         let id = self.read_u16()?;
 
         let font_name_len = self.read_u8()?;
@@ -1427,7 +1781,7 @@ impl<R: Read> Reader<R> {
         })))
     }
 
-    fn read_define_font_name(&mut self) -> Result<Tag> {
+    fn read_define_font_name<'a>(&mut self) -> Result<Tag<'a>> {
         Ok(Tag::DefineFontName {
             id: self.read_character_id()?,
             name: self.read_c_string()?,
@@ -1751,12 +2105,14 @@ impl<R: Read> Reader<R> {
         })
     }
 
-    pub fn read_define_sound(&mut self) -> Result<Sound> {
+    pub fn read_define_sound<'a>(&mut self) -> Result<Sound<'a>>
+    where
+        R: SliceRead<'a>,
+    {
         let id = self.read_u16()?;
         let format = self.read_sound_format()?;
         let num_samples = self.read_u32()?;
-        let mut data = Vec::new();
-        self.input.read_to_end(&mut data)?;
+        let data = self.input.read_slice_to_end();
         Ok(Sound {
             id,
             format,
@@ -2038,10 +2394,16 @@ impl<R: Read> Reader<R> {
         Ok(shape_record)
     }
 
-    pub fn read_define_sprite(&mut self) -> Result<Tag> {
+    pub fn read_define_sprite<'a>(&mut self) -> Result<Tag<'a>>
+    where
+        R: SliceRead<'a>,
+    {
+        let id = self.read_u16()?;
+        let num_frames = self.read_u16()?;
+        self.offset += 4;
         Ok(Tag::DefineSprite(Sprite {
-            id: self.read_u16()?,
-            num_frames: self.read_u16()?,
+            id,
+            num_frames,
             tags: self.read_tag_list()?,
         }))
     }
@@ -2058,7 +2420,24 @@ impl<R: Read> Reader<R> {
         Ok(exports)
     }
 
-    pub fn read_place_object(&mut self, tag_length: usize) -> Result<PlaceObject> {
+    /// Reads an `ImportAssets` tag, returning the URL of the shared library
+    /// and the character IDs bound to each imported export name.
+    pub fn read_import_assets(&mut self) -> Result<(String, ExportAssets)> {
+        let url = self.read_c_string()?;
+        let imports = self.read_export_assets()?;
+        Ok((url, imports))
+    }
+
+    /// Reads an `ImportAssets2` tag. See `read_import_assets`.
+    pub fn read_import_assets_2(&mut self) -> Result<(String, ExportAssets)> {
+        let url = self.read_c_string()?;
+        self.read_u8()?; // Reserved; must be 1
+        self.read_u8()?; // Reserved; must be 0
+        let imports = self.read_export_assets()?;
+        Ok((url, imports))
+    }
+
+    pub fn read_place_object<'a>(&mut self, tag_length: usize) -> Result<PlaceObject<'a>> {
         // TODO: What's a best way to know if the tag has a color transform?
         // You only know if there is still data remaining after the matrix.
         // This sucks.
@@ -2090,7 +2469,13 @@ impl<R: Read> Reader<R> {
         })
     }
 
-    pub fn read_place_object_2_or_3(&mut self, place_object_version: u8) -> Result<PlaceObject> {
+    pub fn read_place_object_2_or_3<'a>(
+        &mut self,
+        place_object_version: u8,
+    ) -> Result<PlaceObject<'a>>
+    where
+        R: SliceRead<'a>,
+    {
         let flags = if place_object_version >= 3 {
             self.read_u16()?
         } else {
@@ -2172,9 +2557,7 @@ impl<R: Read> Reader<R> {
             vec![]
         };
         let amf_data = if place_object_version >= 4 {
-            let mut amf = vec![];
-            self.input.read_to_end(&mut amf)?;
-            Some(amf)
+            Some(self.input.read_slice_to_end())
         } else {
             None
         };
@@ -2233,7 +2616,10 @@ impl<R: Read> Reader<R> {
         })
     }
 
-    fn read_clip_actions(&mut self) -> Result<Vec<ClipAction>> {
+    fn read_clip_actions<'a>(&mut self) -> Result<Vec<ClipAction<'a>>>
+    where
+        R: SliceRead<'a>,
+    {
         self.read_u16()?; // Must be 0
         self.read_clip_event_flags()?; // All event flags
         let mut clip_actions = vec![];
@@ -2243,7 +2629,10 @@ impl<R: Read> Reader<R> {
         Ok(clip_actions)
     }
 
-    fn read_clip_action(&mut self) -> Result<Option<ClipAction>> {
+    fn read_clip_action<'a>(&mut self) -> Result<Option<ClipAction<'a>>>
+    where
+        R: SliceRead<'a>,
+    {
         let events = self.read_clip_event_flags()?;
         if events.is_empty() {
             Ok(None)
@@ -2257,8 +2646,7 @@ impl<R: Read> Reader<R> {
                 None
             };
 
-            let mut action_data = vec![0; length as usize];
-            self.input.read_exact(&mut action_data)?;
+            let action_data = self.input.read_slice(length as usize)?;
 
             Ok(Some(ClipAction {
                 events,
@@ -2715,7 +3103,7 @@ impl<R: Read> Reader<R> {
         })
     }
 
-    fn read_define_video_stream(&mut self) -> Result<Tag> {
+    fn read_define_video_stream<'a>(&mut self) -> Result<Tag<'a>> {
         let id = self.read_character_id()?;
         let num_frames = self.read_u16()?;
         let width = self.read_u16()?;
@@ -2748,11 +3136,13 @@ impl<R: Read> Reader<R> {
         }))
     }
 
-    fn read_video_frame(&mut self) -> Result<Tag> {
+    fn read_video_frame<'a>(&mut self) -> Result<Tag<'a>>
+    where
+        R: SliceRead<'a>,
+    {
         let stream_id = self.read_character_id()?;
         let frame_num = self.read_u16()?;
-        let mut data = vec![];
-        self.input.read_to_end(&mut data)?;
+        let data = self.input.read_slice_to_end();
         Ok(Tag::VideoFrame(VideoFrame {
             stream_id,
             frame_num,
@@ -2760,7 +3150,10 @@ impl<R: Read> Reader<R> {
         }))
     }
 
-    fn read_define_bits_jpeg_3(&mut self, version: u8) -> Result<Tag> {
+    fn read_define_bits_jpeg_3<'a>(&mut self, version: u8) -> Result<Tag<'a>>
+    where
+        R: SliceRead<'a>,
+    {
         let id = self.read_character_id()?;
         let data_size = self.read_u32()? as usize;
         let deblocking = if version >= 4 {
@@ -2768,11 +3161,8 @@ impl<R: Read> Reader<R> {
         } else {
             0.0
         };
-        let mut data = vec![];
-        data.resize(data_size, 0);
-        self.input.read_exact(&mut data)?;
-        let mut alpha_data = vec![];
-        self.input.read_to_end(&mut alpha_data)?;
+        let data = self.input.read_slice(data_size)?;
+        let alpha_data = self.input.read_slice_to_end();
         Ok(Tag::DefineBitsJpeg3(DefineBitsJpeg3 {
             version,
             id,
@@ -2782,7 +3172,10 @@ impl<R: Read> Reader<R> {
         }))
     }
 
-    pub fn read_define_bits_lossless(&mut self, version: u8) -> Result<DefineBitsLossless> {
+    pub fn read_define_bits_lossless<'a>(&mut self, version: u8) -> Result<DefineBitsLossless<'a>>
+    where
+        R: SliceRead<'a>,
+    {
         let id = self.read_character_id()?;
         let format = match self.read_u8()? {
             3 => BitmapFormat::ColorMap8,
@@ -2797,8 +3190,7 @@ impl<R: Read> Reader<R> {
         } else {
             0
         };
-        let mut data = Vec::new();
-        self.input.read_to_end(&mut data)?;
+        let data = self.input.read_slice_to_end();
         Ok(DefineBitsLossless {
             version,
             id,
@@ -2846,11 +3238,11 @@ pub mod tests {
         Reader::new(data, default_version)
     }
 
-    fn read_from_file(path: &str) -> Swf {
+    fn read_from_file(path: &str) -> SwfBuf {
         let mut file = File::open(path).unwrap();
         let mut data = Vec::new();
         file.read_to_end(&mut data).unwrap();
-        read_swf(&data[..]).unwrap()
+        decompress_swf(&data[..]).unwrap()
     }
 
     pub fn read_tag_bytes_from_file_with_index(
@@ -2936,7 +3328,7 @@ pub mod tests {
     #[test]
     fn read_invalid_swf() {
         let junk = [0u8; 128];
-        let result = read_swf(&junk[..]);
+        let result = decompress_swf(&junk[..]);
         // TODO: Verify correct error.
         assert!(result.is_err());
     }
@@ -3284,6 +3676,221 @@ pub mod tests {
         }
     }
 
+    /// Ensure that tag payloads borrow from the input instead of copying it.
+    #[test]
+    fn read_tag_borrows_payload() {
+        let buf = [0b00_000011, 0b00000011, 1, 2, 3, 0, 0];
+        let mut reader = Reader::new(&buf[..], 5);
+        match reader.read_tag().unwrap() {
+            Tag::DoAction(action_data) => {
+                assert_eq!(action_data, [1, 2, 3]);
+                assert_eq!(action_data.as_ptr(), buf[2..].as_ptr());
+            }
+            tag => panic!("Expected DoAction, got {:?}", tag),
+        }
+        assert_eq!(reader.read_tag().unwrap(), Tag::End);
+
+        let mut reader = Reader::new(Cursor::new(&buf[..]), 5);
+        match reader.read_tag().unwrap() {
+            Tag::DoAction(action_data) => assert_eq!(action_data.as_ptr(), buf[2..].as_ptr()),
+            tag => panic!("Expected DoAction, got {:?}", tag),
+        }
+        assert_eq!(reader.get_ref().position(), 5);
+    }
+
+    #[test]
+    fn tag_iterator() {
+        // ShowFrame, DoAction, End
+        let buf = [
+            0b01_000000,
+            0b00000000,
+            0b00_000011,
+            0b00000011,
+            1,
+            2,
+            3,
+            0,
+            0,
+        ];
+        let mut tags = TagIterator::new(&buf[..], 5);
+
+        let item = tags.next().unwrap();
+        assert_eq!(item.tag_code(), Some(TagCode::ShowFrame));
+        assert_eq!(item.range, 0..2);
+        assert_eq!(item.parse().unwrap(), Tag::ShowFrame);
+
+        let item = tags.next().unwrap();
+        assert_eq!(item.tag_code(), Some(TagCode::DoAction));
+        assert_eq!(item.range, 2..7);
+        assert_eq!(item.body(), [1, 2, 3]);
+        assert_eq!(item.parse().unwrap(), Tag::DoAction(&[1, 2, 3]));
+        assert!(item.sprite_tags().is_none());
+
+        assert!(tags.next().is_none());
+        assert!(!tags.needs_more_data());
+        assert_eq!(tags.position(), 9);
+    }
+
+    #[test]
+    fn tag_iterator_needs_more_data() {
+        let buf = [
+            0b01_000000,
+            0b00000000,
+            0b00_000011,
+            0b00000011,
+            1,
+            2,
+            3,
+            0,
+            0,
+        ];
+        for len in &[0, 1, 3, 6] {
+            let tags: Vec<_> = TagIterator::new(&buf[..*len], 5).collect();
+            assert!(tags.iter().all(|item| item.range.end <= *len));
+        }
+
+        let mut tags = TagIterator::new(&buf[..6], 5);
+        assert_eq!(tags.next().unwrap().range, 0..2);
+        assert!(tags.next().is_none());
+        assert!(tags.needs_more_data());
+        assert_eq!(tags.position(), 2);
+
+        // Resume once the rest of the data has arrived.
+        let mut tags = TagIterator::from_position(&buf[..], 5, tags.position());
+        assert_eq!(tags.next().unwrap().range, 2..7);
+        assert!(tags.next().is_none());
+        assert!(!tags.needs_more_data());
+    }
+
+    #[test]
+    fn tag_iterator_sprite_tags() {
+        let swf_buf = read_from_file("tests/swfs/DefineSprite.swf");
+        let sprite = TagIterator::new(&swf_buf.data, swf_buf.header.version)
+            .find(|item| item.tag_code() == Some(TagCode::DefineSprite))
+            .unwrap();
+        let sprite_tags: Vec<_> = sprite.sprite_tags().unwrap().collect();
+        assert!(!sprite_tags.is_empty());
+
+        let parsed_tags = match sprite.parse().unwrap() {
+            Tag::DefineSprite(sprite) => sprite.tags,
+            tag => panic!("Expected DefineSprite, got {:?}", tag),
+        };
+        let lazy_tags: Vec<_> = sprite_tags
+            .iter()
+            .map(|item| {
+                assert!(item.range.start >= sprite.range.start);
+                assert!(item.range.end <= sprite.range.end);
+                item.parse().unwrap()
+            })
+            .collect();
+        assert_eq!(lazy_tags, parsed_tags);
+    }
+
+    #[test]
+    fn read_tag_diagnostics() {
+        // ShowFrame with a trailing byte, unknown tag 1000, End
+        let buf = [
+            0b01_000001,
+            0b00000000,
+            0xff,
+            0b00_000001,
+            0b11111010,
+            0xff,
+            0,
+            0,
+        ];
+
+        let mut reader = Reader::with_options(&buf[..], 5, ReadOptions { strict: false });
+        assert_eq!(
+            reader.read_tag_list().unwrap(),
+            [
+                Tag::ShowFrame,
+                Tag::Unknown {
+                    tag_code: 1000,
+                    data: &[0xff],
+                }
+            ]
+        );
+        assert_eq!(
+            reader.take_diagnostics(),
+            [
+                Diagnostic {
+                    tag_code: Some(TagCode::ShowFrame as u16),
+                    offset: 0,
+                    kind: DiagnosticKind::TrailingBytes(1),
+                },
+                Diagnostic {
+                    tag_code: Some(1000),
+                    offset: 3,
+                    kind: DiagnosticKind::UnknownTag,
+                },
+            ]
+        );
+
+        let mut reader = Reader::with_options(&buf[..], 5, ReadOptions { strict: true });
+        match reader.read_tag() {
+            Err(Error::SwfParseError {
+                tag_code: 1,
+                source: Some(source),
+            }) => match source.downcast_ref::<Error>() {
+                Some(Error::Strict(diagnostic)) => {
+                    assert_eq!(diagnostic.kind, DiagnosticKind::TrailingBytes(1))
+                }
+                source => panic!("Expected strict error, got {:?}", source),
+            },
+            result => panic!("Expected SwfParseError, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn read_sprite_diagnostics() {
+        // DefineSprite containing ShowFrame with a trailing byte, End
+        let buf = [
+            0b11_001001,
+            0b00001001,
+            1,
+            0,
+            1,
+            0,
+            0b01_000001,
+            0b00000000,
+            0xff,
+            0,
+            0,
+            0,
+            0,
+        ];
+        let mut reader = Reader::with_options(&buf[..], 5, Default::default());
+        reader.read_tag_list().unwrap();
+        assert_eq!(
+            reader.take_diagnostics(),
+            [Diagnostic {
+                tag_code: Some(TagCode::ShowFrame as u16),
+                offset: 6,
+                kind: DiagnosticKind::TrailingBytes(1),
+            }]
+        );
+    }
+
+    #[test]
+    fn decompress_swf_length_mismatch() {
+        let mut data = std::fs::read("tests/swfs/DefineSprite.swf").unwrap();
+        let mut diagnostics = Vec::new();
+        decompress_swf_with_options(&data[..], &Default::default(), &mut diagnostics).unwrap();
+        assert_eq!(diagnostics, []);
+
+        data.truncate(data.len() - 1);
+        let swf_buf = decompress_swf_with_options(&data[..], &Default::default(), &mut diagnostics);
+        assert!(swf_buf.is_ok());
+        assert!(diagnostics
+            .iter()
+            .any(|d| matches!(d.kind, DiagnosticKind::LengthMismatch { .. })));
+
+        let swf_buf =
+            decompress_swf_with_options(&data[..], &ReadOptions { strict: true }, &mut Vec::new());
+        assert!(matches!(swf_buf, Err(Error::Strict(_))));
+    }
+
     /// Ensure that we return an error on invalid data.
     #[test]
     fn read_invalid_tag() {
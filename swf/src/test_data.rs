@@ -3,8 +3,8 @@
 use crate::avm1::types::*;
 use crate::avm2::read::tests::read_abc_from_file;
 use crate::avm2::types::*;
-use crate::read::read_swf;
 use crate::read::tests::{read_tag_bytes_from_file, read_tag_bytes_from_file_with_index};
+use crate::read::{decompress_swf, parse_swf};
 use crate::tag_code::TagCode;
 use crate::types::*;
 use crate::write::write_swf;
@@ -14,13 +14,14 @@ use std::vec::Vec;
 #[allow(dead_code)]
 pub fn echo_swf(filename: &str) {
     let in_file = File::open(filename).unwrap();
-    let swf = read_swf(in_file).unwrap();
+    let swf_buf = decompress_swf(in_file).unwrap();
+    let swf = parse_swf(&swf_buf).unwrap();
     let out_file = File::create(filename).unwrap();
     write_swf(&swf, out_file).unwrap();
 }
 
 pub type TestData<T> = (u8, T, Vec<u8>);
-pub type TagTestData = TestData<Tag>;
+pub type TagTestData = TestData<Tag<'static>>;
 pub type Avm1TestData = TestData<Action<'static>>;
 pub type Avm2TestData = TestData<AbcFile>;
 
@@ -41,7 +42,7 @@ pub fn tag_tests() -> Vec<TagTestData> {
             9, // Minimum version not listed in SWF19.
             Tag::DefineBinaryData {
                 id: 1,
-                data: vec![84, 101, 115, 116, 105, 110, 103, 33],
+                data: &[84, 101, 115, 116, 105, 110, 103, 33],
             },
             read_tag_bytes_from_file("tests/swfs/DefineBinaryData.swf", TagCode::DefineBinaryData),
         ),
@@ -49,7 +50,7 @@ pub fn tag_tests() -> Vec<TagTestData> {
             1,
             Tag::DefineBits {
                 id: 1,
-                jpeg_data: vec![
+                jpeg_data: &[
                     255, 216, 255, 224, 0, 16, 74, 70, 73, 70, 0, 1, 1, 0, 0, 1, 0, 1, 0, 0, 255,
                     192, 0, 17, 8, 0, 5, 0, 6, 3, 1, 34, 0, 2, 17, 1, 3, 17, 1, 255, 218, 0, 12, 3,
                     1, 0, 2, 17, 3, 17, 0, 63, 0, 252, 215, 162, 138, 43, 248, 28, 255, 0, 180, 3,
@@ -65,7 +66,7 @@ pub fn tag_tests() -> Vec<TagTestData> {
             1,
             Tag::DefineBitsJpeg2 {
                 id: 1,
-                jpeg_data: vec![
+                jpeg_data: &[
                     255, 216, 255, 219, 0, 67, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
                     1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
                     1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 255, 219, 0,
@@ -111,7 +112,7 @@ pub fn tag_tests() -> Vec<TagTestData> {
                 id: 1,
                 version: 3,
                 deblocking: 0.0,
-                data: vec![
+                data: &[
                     255, 216, 255, 224, 0, 16, 74, 70, 73, 70, 0, 1, 1, 0, 0, 1, 0, 1, 0, 0, 255,
                     219, 0, 67, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
                     1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
@@ -125,7 +126,7 @@ pub fn tag_tests() -> Vec<TagTestData> {
                     255, 196, 0, 20, 17, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 255,
                     218, 0, 12, 3, 1, 0, 2, 17, 3, 17, 0, 63, 0, 134, 240, 23, 224, 94, 255, 217,
                 ],
-                alpha_data: vec![120, 218, 107, 104, 160, 12, 0, 0, 16, 124, 32, 1],
+                alpha_data: &[120, 218, 107, 104, 160, 12, 0, 0, 16, 124, 32, 1],
             }),
             read_tag_bytes_from_file("tests/swfs/DefineBitsJpeg3.swf", TagCode::DefineBitsJpeg3),
         ),
@@ -164,7 +165,7 @@ pub fn tag_tests() -> Vec<TagTestData> {
                 width: 8,
                 height: 8,
                 num_colors: 0,
-                data: vec![
+                data: &[
                     120, 218, 251, 207, 192, 240, 255, 255, 8, 198, 0, 4, 128, 127, 129,
                 ],
             }),
@@ -182,7 +183,7 @@ pub fn tag_tests() -> Vec<TagTestData> {
                 width: 8,
                 height: 8,
                 num_colors: 0,
-                data: vec![
+                data: &[
                     120, 218, 107, 96, 96, 168, 107, 24, 193, 24, 0, 227, 81, 63, 129,
                 ],
             }),
@@ -225,7 +226,7 @@ pub fn tag_tests() -> Vec<TagTestData> {
                         .into_iter()
                         .collect(),
                     key_code: None,
-                    action_data: vec![0],
+                    action_data: &[0],
                 }],
             })),
             read_tag_bytes_from_file("tests/swfs/DefineButton-MX.swf", TagCode::DefineButton),
@@ -287,12 +288,12 @@ pub fn tag_tests() -> Vec<TagTestData> {
                             .into_iter()
                             .collect(),
                         key_code: None,
-                        action_data: vec![150, 3, 0, 0, 65, 0, 38, 0], // trace("A");
+                        action_data: &[150, 3, 0, 0, 65, 0, 38, 0], // trace("A");
                     },
                     ButtonAction {
                         conditions: vec![ButtonActionCondition::KeyPress].into_iter().collect(),
-                        key_code: Some(3),                             // Home
-                        action_data: vec![150, 3, 0, 0, 66, 0, 38, 0], // trace("B");
+                        key_code: Some(3),                          // Home
+                        action_data: &[150, 3, 0, 0, 66, 0, 38, 0], // trace("B");
                     },
                 ],
             })),
@@ -1856,7 +1857,7 @@ pub fn tag_tests() -> Vec<TagTestData> {
                     is_stereo: false,
                 },
                 num_samples: 10,
-                data: vec![
+                data: &[
                     255, 127, 0, 128, 255, 127, 0, 128, 255, 127, 0, 128, 255, 127, 0, 128, 255,
                     127, 0, 128,
                 ],
@@ -1936,7 +1937,7 @@ pub fn tag_tests() -> Vec<TagTestData> {
         ),
         (
             5,
-            Tag::DoAction(vec![
+            Tag::DoAction(&[
                 150, 10, 0, 0, 84, 101, 115, 116, 105, 110, 103, 33, 0, 38, 0,
             ]),
             read_tag_bytes_from_file("tests/swfs/DoAction-CS6.swf", TagCode::DoAction),
@@ -1945,7 +1946,7 @@ pub fn tag_tests() -> Vec<TagTestData> {
             6,
             Tag::DoInitAction {
                 id: 2,
-                action_data: vec![150, 6, 0, 0, 116, 101, 115, 116, 0, 38, 0],
+                action_data: &[150, 6, 0, 0, 116, 101, 115, 116, 0, 38, 0],
             },
             read_tag_bytes_from_file("tests/swfs/DoInitAction-CS6.swf", TagCode::DoInitAction),
         ),
@@ -1959,15 +1960,13 @@ pub fn tag_tests() -> Vec<TagTestData> {
         ),
         (
             10,
-            Tag::EnableTelemetry {
-                password_hash: vec![],
-            },
+            Tag::EnableTelemetry { password_hash: &[] },
             read_tag_bytes_from_file("tests/swfs/EnableTelemetry.swf", TagCode::EnableTelemetry),
         ),
         (
             10,
             Tag::EnableTelemetry {
-                password_hash: vec![
+                password_hash: &[
                     207, 128, 205, 138, 237, 72, 45, 93, 21, 39, 215, 220, 114, 252, 239, 248, 78,
                     99, 38, 89, 40, 72, 68, 125, 45, 192, 176, 232, 125, 252, 154, 144,
                 ],
@@ -2044,7 +2043,7 @@ pub fn tag_tests() -> Vec<TagTestData> {
         ),
         (
             1,
-            Tag::JpegTables(vec![
+            Tag::JpegTables(&[
                 255, 216, 255, 219, 0, 67, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
                 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1,
                 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1, 255, 219, 0, 67, 1, 1, 1, 1,
@@ -2125,7 +2124,7 @@ pub fn tag_tests() -> Vec<TagTestData> {
                 clip_actions: vec![ClipAction {
                     events: ClipEventFlag::EnterFrame.into(),
                     key_code: None,
-                    action_data: vec![150, 6, 0, 0, 99, 108, 105, 112, 0, 38, 0],
+                    action_data: &[150, 6, 0, 0, 99, 108, 105, 112, 0, 38, 0],
                 }],
                 is_image: false,
                 is_bitmap_cached: false,
@@ -2156,17 +2155,17 @@ pub fn tag_tests() -> Vec<TagTestData> {
                     ClipAction {
                         events: ClipEventFlag::Press | ClipEventFlag::Release,
                         key_code: None,
-                        action_data: vec![150, 3, 0, 0, 65, 0, 38, 0],
+                        action_data: &[150, 3, 0, 0, 65, 0, 38, 0],
                     },
                     ClipAction {
                         events: ClipEventFlag::KeyPress.into(),
                         key_code: Some(99),
-                        action_data: vec![150, 3, 0, 0, 66, 0, 38, 0],
+                        action_data: &[150, 3, 0, 0, 66, 0, 38, 0],
                     },
                     ClipAction {
                         events: ClipEventFlag::EnterFrame.into(),
                         key_code: None,
-                        action_data: vec![150, 3, 0, 0, 67, 0, 38, 0],
+                        action_data: &[150, 3, 0, 0, 67, 0, 38, 0],
                     },
                 ],
                 is_image: false,
@@ -2326,12 +2325,12 @@ pub fn tag_tests() -> Vec<TagTestData> {
                     ClipAction {
                         events: ClipEventFlag::ReleaseOutside | ClipEventFlag::RollOver,
                         key_code: None,
-                        action_data: vec![0],
+                        action_data: &[0],
                     },
                     ClipAction {
                         events: ClipEventFlag::Data.into(),
                         key_code: None,
-                        action_data: vec![150, 3, 0, 0, 66, 0, 38, 0],
+                        action_data: &[150, 3, 0, 0, 66, 0, 38, 0],
                     },
                 ],
                 is_image: false,
@@ -2371,7 +2370,7 @@ pub fn tag_tests() -> Vec<TagTestData> {
                 is_image: false,
                 is_bitmap_cached: false,
                 is_visible: true,
-                amf_data: Some(vec![
+                amf_data: Some(&[
                     10, 11, 1, 9, 116, 101, 115, 116, 6, 17, 84, 101, 115, 116, 105, 110, 103, 33,
                     1,
                 ]),
@@ -2486,7 +2485,7 @@ pub fn tag_tests() -> Vec<TagTestData> {
             Tag::VideoFrame(VideoFrame {
                 stream_id: 1,
                 frame_num: 0,
-                data: vec![0, 0, 132, 0, 4, 4, 17, 38, 190, 190, 190, 190, 201, 182],
+                data: &[0, 0, 132, 0, 4, 4, 17, 38, 190, 190, 190, 190, 201, 182],
             }),
             read_tag_bytes_from_file("tests/swfs/DefineVideoStream.swf", TagCode::VideoFrame),
         ),
@@ -2494,7 +2493,7 @@ pub fn tag_tests() -> Vec<TagTestData> {
             1,
             Tag::Unknown {
                 tag_code: 512,
-                data: vec![],
+                data: &[],
             },
             vec![0b00_000000, 0b10000000],
         ),
@@ -2502,7 +2501,7 @@ pub fn tag_tests() -> Vec<TagTestData> {
             1,
             Tag::Unknown {
                 tag_code: 513,
-                data: vec![1, 2],
+                data: &[1, 2],
             },
             vec![0b01_000010, 0b10000000, 1, 2],
         ),
@@ -2510,7 +2509,7 @@ pub fn tag_tests() -> Vec<TagTestData> {
             1,
             Tag::Unknown {
                 tag_code: 513,
-                data: vec![0; 64],
+                data: &[0; 64],
             },
             vec![
                 0b01_111111,
@@ -2791,6 +2790,39 @@ pub fn avm1_tests() -> Vec<Avm1TestData> {
                 0x65, 0x00, 0x1c, 0x47, 0x3e,
             ],
         ),
+        (
+            5,
+            Action::GotoFrame2 {
+                set_playing: true,
+                scene_offset: 0,
+            },
+            vec![0x9F, 1, 0, 0b01],
+        ),
+        (
+            5,
+            Action::With { actions: &[0x17] },
+            vec![0x94, 2, 0, 1, 0, 0x17],
+        ),
+        (
+            7,
+            Action::Try(TryBlock {
+                try_actions: &[0x2A],
+                catch: Some((CatchVar::Register(1), &[0x17])),
+                finally: None,
+            }),
+            vec![0x8F, 8, 0, 0b101, 1, 0, 1, 0, 0, 0, 1, 0x2A, 0x17],
+        ),
+        (
+            7,
+            Action::Try(TryBlock {
+                try_actions: &[0x2A],
+                catch: Some((CatchVar::Var("e"), &[0x17])),
+                finally: Some(&[0x17]),
+            }),
+            vec![
+                0x8F, 9, 0, 0b011, 1, 0, 1, 0, 1, 0, b'e', 0, 0x2A, 0x17, 0x17,
+            ],
+        ),
     ]
 }
 
@@ -2890,3 +2922,88 @@ pub fn avm2_tests() -> Vec<Avm2TestData> {
         read_abc_from_file("tests/swfs/Avm2Dummy.swf"),
     )]
 }
+
+/// A 1x1 RGB bitmap.
+pub fn bitmap(id: CharacterId) -> Tag<'static> {
+    Tag::DefineBitsLossless(DefineBitsLossless {
+        version: 1,
+        id,
+        format: BitmapFormat::Rgb32,
+        width: 1,
+        height: 1,
+        num_colors: 0,
+        data: &[120, 218, 99, 96, 0, 0, 0, 4, 0, 1],
+    })
+}
+
+/// A 20x20 twip triangle filled with the bitmap `bitmap_id`.
+pub fn bitmap_filled_shape(id: CharacterId, bitmap_id: CharacterId) -> Tag<'static> {
+    let bounds = Rectangle {
+        x_min: Twips::new(0),
+        x_max: Twips::new(20),
+        y_min: Twips::new(0),
+        y_max: Twips::new(20),
+    };
+    Tag::DefineShape(Shape {
+        version: 1,
+        id,
+        shape_bounds: bounds.clone(),
+        edge_bounds: bounds,
+        has_fill_winding_rule: false,
+        has_non_scaling_strokes: true,
+        has_scaling_strokes: false,
+        styles: ShapeStyles {
+            fill_styles: vec![FillStyle::Bitmap {
+                id: bitmap_id,
+                matrix: Matrix::new(),
+                is_smoothed: true,
+                is_repeating: false,
+            }],
+            line_styles: vec![],
+        },
+        shape: vec![
+            ShapeRecord::StyleChange(StyleChangeData {
+                move_to: None,
+                fill_style_0: None,
+                fill_style_1: Some(1),
+                line_style: None,
+                new_styles: None,
+            }),
+            ShapeRecord::StraightEdge {
+                delta_x: Twips::new(20),
+                delta_y: Twips::new(0),
+            },
+            ShapeRecord::StraightEdge {
+                delta_x: Twips::new(0),
+                delta_y: Twips::new(20),
+            },
+            ShapeRecord::StraightEdge {
+                delta_x: Twips::new(-20),
+                delta_y: Twips::new(-20),
+            },
+        ],
+    })
+}
+
+/// Places the character `id` at `depth`.
+pub fn place(id: CharacterId, depth: Depth) -> Tag<'static> {
+    Tag::PlaceObject(Box::new(PlaceObject {
+        version: 2,
+        action: PlaceObjectAction::Place(id),
+        depth,
+        matrix: Some(Matrix::new()),
+        color_transform: None,
+        ratio: None,
+        name: None,
+        clip_depth: None,
+        class_name: None,
+        filters: vec![],
+        background_color: None,
+        blend_mode: BlendMode::Normal,
+        clip_actions: vec![],
+        is_image: false,
+        is_bitmap_cached: false,
+        is_visible: true,
+        amf_data: None,
+    }))
+}
@@ -7,17 +7,32 @@ use enumset::{EnumSet, EnumSetType};
 use std::collections::HashSet;
 
 /// A complete header and tags in the SWF file.
-/// This is returned by the `swf::read_swf` convenience method.
+/// This is returned by the `swf::parse_swf` convenience method.
+///
+/// Tag payloads borrow from the `SwfBuf` that was parsed.
 #[derive(Debug, PartialEq)]
-pub struct Swf {
+pub struct Swf<'a> {
     pub header: Header,
-    pub tags: Vec<Tag>,
+    pub tags: Vec<Tag<'a>>,
+}
+
+/// The decompressed contents of an SWF file.
+/// This is returned by the `swf::decompress_swf` convenience method,
+/// and can be parsed into an `Swf` using `swf::parse_swf`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SwfBuf {
+    pub header: Header,
+
+    /// The uncompressed SWF data following the header.
+    pub data: Vec<u8>,
 }
 
 /// Returned by `read::read_swf_header`. Includes the decompress
 /// stream as well as the uncompressed data length.
 pub struct SwfStream<'a> {
     pub header: Header,
+
+    /// The length of the uncompressed SWF data following the header.
     pub uncompressed_length: usize,
     pub reader: crate::read::Reader<Box<dyn std::io::Read + 'a>>,
 }
@@ -276,7 +291,7 @@ pub type Depth = u16;
 pub type CharacterId = u16;
 
 #[derive(Debug, PartialEq)]
-pub struct PlaceObject {
+pub struct PlaceObject<'a> {
     pub version: u8,
     pub action: PlaceObjectAction,
     pub depth: Depth,
@@ -289,11 +304,11 @@ pub struct PlaceObject {
     pub filters: Vec<Filter>,
     pub background_color: Option<Color>,
     pub blend_mode: BlendMode,
-    pub clip_actions: Vec<ClipAction>,
+    pub clip_actions: Vec<ClipAction<'a>>,
     pub is_image: bool,
     pub is_bitmap_cached: bool,
     pub is_visible: bool,
-    pub amf_data: Option<Vec<u8>>,
+    pub amf_data: Option<&'a [u8]>,
 }
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -429,10 +444,10 @@ pub enum BlendMode {
 ///
 /// [SWF19 pp.37-38 ClipActionRecord](https://www.adobe.com/content/dam/acom/en/devnet/pdf/swf-file-format-spec.pdf#page=37)
 #[derive(Debug, Clone, PartialEq)]
-pub struct ClipAction {
+pub struct ClipAction<'a> {
     pub events: EnumSet<ClipEventFlag>,
     pub key_code: Option<KeyCode>,
-    pub action_data: Vec<u8>,
+    pub action_data: &'a [u8],
 }
 
 /// An event that can be attached to a movieclip instance using
@@ -473,7 +488,7 @@ pub type KeyCode = u8;
 ///
 // [SWF19 p.29](https://www.adobe.com/content/dam/acom/en/devnet/pdf/swf-file-format-spec.pdf#page=29)
 #[derive(Debug, PartialEq)]
-pub enum Tag {
+pub enum Tag<'a> {
     ExportAssets(ExportAssets),
     ScriptLimits {
         max_recursion_depth: u16,
@@ -486,26 +501,26 @@ pub enum Tag {
     DebugId(DebugId),
     DefineBinaryData {
         id: CharacterId,
-        data: Vec<u8>,
+        data: &'a [u8],
     },
     DefineBits {
         id: CharacterId,
-        jpeg_data: Vec<u8>,
+        jpeg_data: &'a [u8],
     },
     DefineBitsJpeg2 {
         id: CharacterId,
-        jpeg_data: Vec<u8>,
+        jpeg_data: &'a [u8],
     },
-    DefineBitsJpeg3(DefineBitsJpeg3),
-    DefineBitsLossless(DefineBitsLossless),
-    DefineButton(Box<Button>),
-    DefineButton2(Box<Button>),
+    DefineBitsJpeg3(DefineBitsJpeg3<'a>),
+    DefineBitsLossless(DefineBitsLossless<'a>),
+    DefineButton(Box<Button<'a>>),
+    DefineButton2(Box<Button<'a>>),
     DefineButtonColorTransform(ButtonColorTransform),
     DefineButtonSound(Box<ButtonSounds>),
     DefineEditText(Box<EditText>),
     DefineFont(Box<FontV1>),
     DefineFont2(Box<Font>),
-    DefineFont4(Font4),
+    DefineFont4(Font4<'a>),
     DefineFontAlignZones {
         id: CharacterId,
         thickness: FontThickness,
@@ -523,19 +538,19 @@ pub enum Tag {
         splitter_rect: Rectangle,
     },
     DefineShape(Shape),
-    DefineSound(Box<Sound>),
-    DefineSprite(Sprite),
+    DefineSound(Box<Sound<'a>>),
+    DefineSprite(Sprite<'a>),
     DefineText(Box<Text>),
     DefineVideoStream(DefineVideoStream),
-    DoAbc(DoAbc),
-    DoAction(DoAction),
+    DoAbc(DoAbc<'a>),
+    DoAction(DoAction<'a>),
     DoInitAction {
         id: CharacterId,
-        action_data: Vec<u8>,
+        action_data: &'a [u8],
     },
     EnableDebugger(String),
     EnableTelemetry {
-        password_hash: Vec<u8>,
+        password_hash: &'a [u8],
     },
     End,
     Metadata(String),
@@ -543,13 +558,13 @@ pub enum Tag {
         url: String,
         imports: Vec<ExportedAsset>,
     },
-    JpegTables(JpegTables),
+    JpegTables(JpegTables<'a>),
     SetBackgroundColor(SetBackgroundColor),
     SetTabIndex {
         depth: Depth,
         tab_index: u16,
     },
-    SoundStreamBlock(SoundStreamBlock),
+    SoundStreamBlock(SoundStreamBlock<'a>),
     SoundStreamHead(Box<SoundStreamHead>),
     SoundStreamHead2(Box<SoundStreamHead>),
     StartSound(StartSound),
@@ -558,9 +573,9 @@ pub enum Tag {
         sound_info: Box<SoundInfo>,
     },
     SymbolClass(Vec<SymbolClassLink>),
-    PlaceObject(Box<PlaceObject>),
+    PlaceObject(Box<PlaceObject<'a>>),
     RemoveObject(RemoveObject),
-    VideoFrame(VideoFrame),
+    VideoFrame(VideoFrame<'a>),
     FileAttributes(FileAttributes),
 
     FrameLabel(FrameLabel),
@@ -570,7 +585,7 @@ pub enum Tag {
 
     Unknown {
         tag_code: u16,
-        data: Vec<u8>,
+        data: &'a [u8],
     },
 }
 
@@ -610,11 +625,11 @@ pub struct Shape {
 }
 
 #[derive(Debug, PartialEq, Clone)]
-pub struct Sound {
+pub struct Sound<'a> {
     pub id: CharacterId,
     pub format: SoundFormat,
     pub num_samples: u32,
-    pub data: Vec<u8>,
+    pub data: &'a [u8],
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -649,10 +664,10 @@ pub struct StartSound {
 }
 
 #[derive(Debug, PartialEq)]
-pub struct Sprite {
+pub struct Sprite<'a> {
     pub id: CharacterId,
     pub num_frames: u16,
-    pub tags: Vec<Tag>,
+    pub tags: Vec<Tag<'a>>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -802,14 +817,14 @@ pub struct SoundStreamHead {
     pub latency_seek: i16,
 }
 
-pub type SoundStreamBlock = Vec<u8>;
+pub type SoundStreamBlock<'a> = &'a [u8];
 
 #[derive(Debug, PartialEq, Clone)]
-pub struct Button {
+pub struct Button<'a> {
     pub id: CharacterId,
     pub is_track_as_menu: bool,
     pub records: Vec<ButtonRecord>,
-    pub actions: Vec<ButtonAction>,
+    pub actions: Vec<ButtonAction<'a>>,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -849,10 +864,10 @@ pub struct ButtonSounds {
 pub type ButtonSound = (CharacterId, SoundInfo);
 
 #[derive(Debug, PartialEq, Clone)]
-pub struct ButtonAction {
+pub struct ButtonAction<'a> {
     pub conditions: HashSet<ButtonActionCondition>,
     pub key_code: Option<u8>,
-    pub action_data: Vec<u8>,
+    pub action_data: &'a [u8],
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
@@ -910,12 +925,12 @@ pub struct Font {
 }
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct Font4 {
+pub struct Font4<'a> {
     pub id: CharacterId,
     pub is_italic: bool,
     pub is_bold: bool,
     pub name: String,
-    pub data: Option<Vec<u8>>,
+    pub data: Option<&'a [u8]>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -1053,14 +1068,14 @@ pub enum TextGridFit {
 }
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct DefineBitsLossless {
+pub struct DefineBitsLossless<'a> {
     pub version: u8,
     pub id: CharacterId,
     pub format: BitmapFormat,
     pub width: u16,
     pub height: u16,
     pub num_colors: u8,
-    pub data: Vec<u8>,
+    pub data: &'a [u8],
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -1100,31 +1115,31 @@ pub enum VideoCodec {
 }
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct VideoFrame {
+pub struct VideoFrame<'a> {
     pub stream_id: CharacterId,
     pub frame_num: u16,
-    pub data: Vec<u8>,
+    pub data: &'a [u8],
 }
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct DefineBitsJpeg3 {
+pub struct DefineBitsJpeg3<'a> {
     pub id: CharacterId,
     pub version: u8,
     pub deblocking: f32,
-    pub data: Vec<u8>,
-    pub alpha_data: Vec<u8>,
+    pub data: &'a [u8],
+    pub alpha_data: &'a [u8],
 }
 
 #[derive(Clone, Debug, PartialEq)]
-pub struct DoAbc {
+pub struct DoAbc<'a> {
     pub name: String,
     pub is_lazy_initialize: bool,
-    pub data: Vec<u8>,
+    pub data: &'a [u8],
 }
 
-pub type DoAction = Vec<u8>;
+pub type DoAction<'a> = &'a [u8];
 
-pub type JpegTables = Vec<u8>;
+pub type JpegTables<'a> = &'a [u8];
 
 /// `ProductInfo` contains information about the software used to generate the SWF.
 /// Not documented in the SWF19 reference. Emitted by mxmlc.
@@ -550,20 +550,20 @@ impl<W: Write> Writer<W> {
                 self.write_u8(0)?; // Reserved (0).
             }
 
-            Tag::DefineBinaryData { id, ref data } => {
+            Tag::DefineBinaryData { id, data } => {
                 self.write_tag_header(TagCode::DefineBinaryData, data.len() as u32 + 6)?;
                 self.write_u16(id)?;
                 self.write_u32(0)?; // Reserved
                 self.output.write_all(data)?;
             }
 
-            Tag::DefineBits { id, ref jpeg_data } => {
+            Tag::DefineBits { id, jpeg_data } => {
                 self.write_tag_header(TagCode::DefineBits, jpeg_data.len() as u32 + 2)?;
                 self.write_u16(id)?;
                 self.output.write_all(jpeg_data)?;
             }
 
-            Tag::DefineBitsJpeg2 { id, ref jpeg_data } => {
+            Tag::DefineBitsJpeg2 { id, jpeg_data } => {
                 self.write_tag_header(TagCode::DefineBitsJpeg2, jpeg_data.len() as u32 + 2)?;
                 self.write_u16(id)?;
                 self.output.write_all(jpeg_data)?;
@@ -580,8 +580,8 @@ impl<W: Write> Writer<W> {
                 }
                 // TODO(Herschel): Verify deblocking parameter is zero in version 3.
                 self.write_u32(jpeg.data.len() as u32)?;
-                self.output.write_all(&jpeg.data)?;
-                self.output.write_all(&jpeg.alpha_data)?;
+                self.output.write_all(jpeg.data)?;
+                self.output.write_all(jpeg.alpha_data)?;
             }
 
             Tag::DefineBitsLossless(ref tag) => {
@@ -608,7 +608,7 @@ impl<W: Write> Writer<W> {
                 if tag.format == BitmapFormat::ColorMap8 {
                     self.write_u8(tag.num_colors)?;
                 }
-                self.output.write_all(&tag.data)?;
+                self.output.write_all(tag.data)?;
             }
 
             Tag::DefineButton(ref button) => self.write_define_button(button)?,
@@ -806,16 +806,13 @@ impl<W: Write> Writer<W> {
                 self.write_tag_header(TagCode::DoAbc, len as u32)?;
                 self.write_u32(if do_abc.is_lazy_initialize { 1 } else { 0 })?;
                 self.write_c_string(&do_abc.name)?;
-                self.output.write_all(&do_abc.data)?;
+                self.output.write_all(do_abc.data)?;
             }
-            Tag::DoAction(ref action_data) => {
+            Tag::DoAction(action_data) => {
                 self.write_tag_header(TagCode::DoAction, action_data.len() as u32)?;
                 self.output.write_all(action_data)?;
             }
-            Tag::DoInitAction {
-                id,
-                ref action_data,
-            } => {
+            Tag::DoInitAction { id, action_data } => {
                 self.write_tag_header(TagCode::DoInitAction, action_data.len() as u32 + 2)?;
                 self.write_u16(id)?;
                 self.output.write_all(action_data)?;
@@ -834,7 +831,7 @@ impl<W: Write> Writer<W> {
                 self.write_c_string(password_md5)?;
             }
 
-            Tag::EnableTelemetry { ref password_hash } => {
+            Tag::EnableTelemetry { password_hash } => {
                 if !password_hash.is_empty() {
                     self.write_tag_header(TagCode::EnableTelemetry, 34)?;
                     self.write_u16(0)?;
@@ -872,7 +869,7 @@ impl<W: Write> Writer<W> {
                 }
             }
 
-            Tag::JpegTables(ref data) => {
+            Tag::JpegTables(data) => {
                 self.write_tag_header(TagCode::JpegTables, data.len() as u32)?;
                 self.output.write_all(data)?;
             }
@@ -921,7 +918,7 @@ impl<W: Write> Writer<W> {
                 self.write_u16(remove_object.depth)?;
             }
 
-            Tag::SoundStreamBlock(ref data) => {
+            Tag::SoundStreamBlock(data) => {
                 self.write_tag_header(TagCode::SoundStreamBlock, data.len() as u32)?;
                 self.output.write_all(data)?;
             }
@@ -995,7 +992,7 @@ impl<W: Write> Writer<W> {
                 self.write_tag_header(TagCode::VideoFrame, 4 + frame.data.len() as u32)?;
                 self.write_character_id(frame.stream_id)?;
                 self.write_u16(frame.frame_num)?;
-                self.output.write_all(&frame.data)?;
+                self.output.write_all(frame.data)?;
             }
 
             Tag::FileAttributes(ref attributes) => {
@@ -1039,7 +1036,7 @@ impl<W: Write> Writer<W> {
             Tag::ProductInfo(ref product_info) => self.write_product_info(product_info)?,
             Tag::DebugId(ref debug_id) => self.write_debug_id(debug_id)?,
 
-            Tag::Unknown { tag_code, ref data } => {
+            Tag::Unknown { tag_code, data } => {
                 self.write_tag_code_and_length(tag_code, data.len() as u32)?;
                 self.output.write_all(data)?;
             }
@@ -1057,7 +1054,7 @@ impl<W: Write> Writer<W> {
             }
             writer.write_u8(0)?; // End button records
                                  // TODO: Assert we have some action.
-            writer.output.write_all(&button.actions[0].action_data)?;
+            writer.output.write_all(button.actions[0].action_data)?;
         }
         self.write_tag_header(TagCode::DefineButton, buf.len() as u32)?;
         self.output.write_all(&buf)?;
@@ -1164,7 +1161,7 @@ impl<W: Write> Writer<W> {
                     }
                 }
                 writer.write_u8(flags)?;
-                writer.output.write_all(&action.action_data)?;
+                writer.output.write_all(action.action_data)?;
             }
         }
         self.write_tag_header(TagCode::DefineButton2, buf.len() as u32)?;
@@ -1544,7 +1541,7 @@ impl<W: Write> Writer<W> {
         self.write_u16(sound.id)?;
         self.write_sound_format(&sound.format)?;
         self.write_u32(sound.num_samples)?;
-        self.output.write_all(&sound.data)?;
+        self.output.write_all(sound.data)?;
         Ok(())
     }
 
@@ -2056,7 +2053,7 @@ impl<W: Write> Writer<W> {
 
             // PlaceObject4 adds some embedded AMF data per instance.
             if place_object_version >= 4 {
-                if let Some(ref data) = place_object.amf_data {
+                if let Some(data) = place_object.amf_data {
                     writer.output.write_all(data)?;
                 }
             }
@@ -2209,7 +2206,7 @@ impl<W: Write> Writer<W> {
             if let Some(k) = action.key_code {
                 self.write_u8(k)?;
             }
-            self.output.write_all(&action.action_data)?;
+            self.output.write_all(action.action_data)?;
         }
         if self.version <= 5 {
             self.write_u16(0)?;
@@ -2469,7 +2466,7 @@ impl<W: Write> Writer<W> {
 
     fn write_define_font_4(&mut self, font: &Font4) -> Result<()> {
         let mut tag_len = 4 + font.name.len();
-        if let Some(ref data) = font.data {
+        if let Some(data) = font.data {
             tag_len += data.len()
         };
         self.write_tag_header(TagCode::DefineFont4, tag_len as u32)?;
@@ -2480,7 +2477,7 @@ impl<W: Write> Writer<W> {
                 | if font.is_bold { 0b1 } else { 0 },
         )?;
         self.write_c_string(&font.name)?;
-        if let Some(ref data) = font.data {
+        if let Some(data) = font.data {
             self.output.write_all(data)?;
         }
         Ok(())
@@ -2759,7 +2756,7 @@ mod tests {
     use super::*;
     use crate::test_data;
 
-    fn new_swf() -> Swf {
+    fn new_swf() -> Swf<'static> {
         Swf {
             header: Header {
                 version: 13,
@@ -3083,7 +3080,7 @@ mod tests {
                     .write_tag_list(&[
                         Tag::Unknown {
                             tag_code: 512,
-                            data: vec![0; 100],
+                            data: &[0; 100],
                         },
                         Tag::ShowFrame,
                     ])
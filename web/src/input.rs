@@ -2,7 +2,7 @@ use ruffle_core::backend::input::{InputBackend, MouseCursor};
 use ruffle_core::events::KeyCode;
 use ruffle_web_common::JsResult;
 use std::collections::HashSet;
-use web_sys::HtmlCanvasElement;
+use web_sys::{HtmlCanvasElement, KeyboardEvent};
 
 /// An implementation of `InputBackend` utilizing `web_sys` bindings to input
 /// APIs
@@ -12,6 +12,9 @@ pub struct WebInputBackend {
     cursor_visible: bool,
     cursor: MouseCursor,
     last_key: KeyCode,
+    last_char: Option<char>,
+    caps_lock: bool,
+    num_lock: bool,
 }
 
 impl WebInputBackend {
@@ -22,18 +25,30 @@ impl WebInputBackend {
             cursor_visible: true,
             cursor: MouseCursor::Arrow,
             last_key: KeyCode::Unknown,
+            last_char: None,
+            caps_lock: false,
+            num_lock: false,
         }
     }
 
-    /// Register a key press for a given code string.
-    pub fn keydown(&mut self, code: String) {
+    /// Register a key press from a `keydown` event.
+    pub fn keydown(&mut self, event: &KeyboardEvent) {
+        let code = event.code();
         self.last_key = web_to_ruffle_key_code(&code).unwrap_or_else(|| KeyCode::Unknown);
+        self.last_char = web_key_to_codepoint(&event.key());
+        self.update_lock_state(event);
         self.keys_down.insert(code);
     }
 
-    /// Register a key release for a given code string.
-    pub fn keyup(&mut self, code: String) {
-        self.keys_down.remove(&code);
+    /// Register a key release from a `keyup` event.
+    pub fn keyup(&mut self, event: &KeyboardEvent) {
+        self.update_lock_state(event);
+        self.keys_down.remove(&event.code());
+    }
+
+    fn update_lock_state(&mut self, event: &KeyboardEvent) {
+        self.caps_lock = event.get_modifier_state("CapsLock");
+        self.num_lock = event.get_modifier_state("NumLock");
     }
 
     fn update_mouse_cursor(&self) {
@@ -59,6 +74,7 @@ impl InputBackend for WebInputBackend {
         match key {
             KeyCode::Unknown => false,
             KeyCode::Backspace => self.keys_down.contains("Backspace"),
+            KeyCode::Tab => self.keys_down.contains("Tab"),
             KeyCode::Return => self.keys_down.contains("Return"),
             KeyCode::Shift => {
                 self.keys_down.contains("ShiftLeft") || self.keys_down.contains("ShiftRight")
@@ -145,6 +161,7 @@ impl InputBackend for WebInputBackend {
             KeyCode::Insert => self.keys_down.contains("Insert"),
             KeyCode::Delete => self.keys_down.contains("Delete"),
             KeyCode::Pause => self.keys_down.contains("Pause"),
+            KeyCode::NumLock => self.keys_down.contains("NumLock"),
             KeyCode::ScrollLock => self.keys_down.contains("ScrollLock"),
             KeyCode::F1 => self.keys_down.contains("F1"),
             KeyCode::F2 => self.keys_down.contains("F2"),
@@ -165,6 +182,18 @@ impl InputBackend for WebInputBackend {
         self.last_key
     }
 
+    fn get_last_key_char(&self) -> Option<char> {
+        self.last_char
+    }
+
+    fn is_key_toggled(&self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::CapsLock => self.caps_lock,
+            KeyCode::NumLock => self.num_lock,
+            _ => false,
+        }
+    }
+
     fn mouse_visible(&self) -> bool {
         self.cursor_visible
     }
@@ -190,6 +219,7 @@ impl InputBackend for WebInputBackend {
 pub fn web_to_ruffle_key_code(key_code: &str) -> Option<KeyCode> {
     let out = match key_code {
         "Backspace" => KeyCode::Backspace,
+        "Tab" => KeyCode::Tab,
         "Enter" => KeyCode::Return,
         "ShiftLeft" | "ShiftRight" => KeyCode::Shift,
         "ControlLeft" | "ControlRight" => KeyCode::Control,
@@ -270,6 +300,7 @@ pub fn web_to_ruffle_key_code(key_code: &str) -> Option<KeyCode> {
         "Insert" => KeyCode::Insert,
         "Delete" => KeyCode::Delete,
         "Pause" => KeyCode::Pause,
+        "NumLock" => KeyCode::NumLock,
         "ScrollLock" => KeyCode::ScrollLock,
         "F1" => KeyCode::F1,
         "F2" => KeyCode::F2,
@@ -6,14 +6,16 @@ mod navigator;
 use crate::{audio::WebAudioBackend, input::WebInputBackend, navigator::WebNavigatorBackend};
 use generational_arena::{Arena, Index};
 use js_sys::Uint8Array;
+use ruffle_core::backend::font::NullFontBackend;
 use ruffle_core::backend::render::RenderBackend;
+use ruffle_core::stage::StageQuality;
 use ruffle_core::tag_utils::SwfMovie;
 use ruffle_core::PlayerEvent;
 use std::mem::drop;
 use std::sync::{Arc, Mutex};
 use std::{cell::RefCell, error::Error, num::NonZeroI32};
 use wasm_bindgen::{prelude::*, JsCast, JsValue};
-use web_sys::{Element, EventTarget, HtmlCanvasElement, KeyboardEvent, PointerEvent};
+use web_sys::{Element, EventTarget, HtmlCanvasElement, KeyboardEvent, PointerEvent, WheelEvent};
 
 thread_local! {
     /// We store the actual instances of the ruffle core in a static pool.
@@ -24,6 +26,12 @@ thread_local! {
 
 type AnimationHandler = Closure<dyn FnMut(f64)>;
 
+/// The distance in pixels of a line of mouse wheel scrolling.
+const PIXELS_PER_LINE: f64 = 32.0;
+
+/// The number of lines scrolled by a page of mouse wheel scrolling.
+const LINES_PER_PAGE: f64 = 20.0;
+
 struct RuffleInstance {
     core: Arc<Mutex<ruffle_core::Player>>,
     canvas: HtmlCanvasElement,
@@ -37,6 +45,9 @@ struct RuffleInstance {
     mouse_move_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
     mouse_down_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
     mouse_up_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
+    mouse_wheel_callback: Option<Closure<dyn FnMut(WheelEvent)>>,
+    /// The fraction of a line scrolled by the mouse wheel that hasn't been sent yet.
+    mouse_wheel_remainder: f64,
     window_mouse_down_callback: Option<Closure<dyn FnMut(PointerEvent)>>,
     key_down_callback: Option<Closure<dyn FnMut(KeyboardEvent)>>,
     key_up_callback: Option<Closure<dyn FnMut(KeyboardEvent)>>,
@@ -66,6 +77,20 @@ impl Ruffle {
         });
     }
 
+    /// Sets the rendering quality: one of "low", "medium", "high" or "best".
+    /// Unknown values are logged and ignored.
+    pub fn set_quality(&mut self, quality: &str) {
+        if let Some(quality) = StageQuality::from_name(quality) {
+            INSTANCES.with(|instances| {
+                let mut instances = instances.borrow_mut();
+                let instance = instances.get_mut(self.0).unwrap();
+                instance.core.lock().unwrap().set_quality(quality);
+            });
+        } else {
+            log::warn!("Unknown quality level {}", quality);
+        }
+    }
+
     pub fn destroy(&mut self) -> Result<(), JsValue> {
         // Remove instance from the active list.
         if let Some(instance) = INSTANCES.with(|instances| {
@@ -109,8 +134,10 @@ impl Ruffle {
         let audio = Box::new(WebAudioBackend::new()?);
         let navigator = Box::new(WebNavigatorBackend::new());
         let input = Box::new(WebInputBackend::new(&canvas));
+        // Browsers don't expose font files, so device text uses the built-in font.
+        let fonts = Box::new(NullFontBackend::new());
 
-        let core = ruffle_core::Player::new(renderer, audio, navigator, input, movie)?;
+        let core = ruffle_core::Player::new(renderer, audio, navigator, input, fonts, movie)?;
         let mut core_lock = core.lock().unwrap();
         let frame_rate = core_lock.frame_rate();
         core_lock.audio_mut().set_frame_rate(frame_rate);
@@ -129,6 +156,8 @@ impl Ruffle {
             mouse_down_callback: None,
             window_mouse_down_callback: None,
             mouse_up_callback: None,
+            mouse_wheel_callback: None,
+            mouse_wheel_remainder: 0.0,
             key_down_callback: None,
             key_up_callback: None,
             timestamp: None,
@@ -275,6 +304,46 @@ impl Ruffle {
                 instance.mouse_up_callback = Some(mouse_up_callback);
             }
 
+            // Create mouse wheel handler.
+            {
+                let mouse_wheel_callback = Closure::wrap(Box::new(move |js_event: WheelEvent| {
+                    INSTANCES.with(move |instances| {
+                        let mut instances = instances.borrow_mut();
+                        if let Some(instance) = instances.get_mut(index) {
+                            // Browsers scroll down for positive deltas, Flash scrolls up.
+                            let lines = match js_event.delta_mode() {
+                                WheelEvent::DOM_DELTA_LINE => -js_event.delta_y(),
+                                WheelEvent::DOM_DELTA_PAGE => -js_event.delta_y() * LINES_PER_PAGE,
+                                _ => -js_event.delta_y() / PIXELS_PER_LINE,
+                            };
+                            // Smooth scrolling sends fractions of a line, which add up
+                            // until a whole line can be sent.
+                            let lines = instance.mouse_wheel_remainder + lines;
+                            let delta = lines.trunc();
+                            instance.mouse_wheel_remainder = lines - delta;
+                            let delta = delta as i32;
+                            if delta != 0 {
+                                let event = PlayerEvent::MouseWheel { delta };
+                                instance.core.lock().unwrap().handle_event(event);
+                            }
+                            if instance.has_focus {
+                                js_event.prevent_default();
+                            }
+                        }
+                    });
+                })
+                    as Box<dyn FnMut(WheelEvent)>);
+                let canvas_events: &EventTarget = canvas.as_ref();
+                canvas_events
+                    .add_event_listener_with_callback(
+                        "wheel",
+                        mouse_wheel_callback.as_ref().unchecked_ref(),
+                    )
+                    .unwrap();
+                let instance = instances.get_mut(index).unwrap();
+                instance.mouse_wheel_callback = Some(mouse_wheel_callback);
+            }
+
             // Create click event handler.
             // {
             //     let click_callback = Closure::wrap(Box::new(move |_| {
@@ -313,7 +382,7 @@ impl Ruffle {
                                     .input_mut()
                                     .downcast_mut::<WebInputBackend>()
                                     .unwrap()
-                                    .keydown(code.clone());
+                                    .keydown(&js_event);
 
                                 if let Some(codepoint) =
                                     input::web_key_to_codepoint(&js_event.key())
@@ -364,7 +433,7 @@ impl Ruffle {
                                     .input_mut()
                                     .downcast_mut::<WebInputBackend>()
                                     .unwrap()
-                                    .keyup(code.clone());
+                                    .keyup(&js_event);
 
                                 if let Some(key_code) = input::web_to_ruffle_key_code(&code) {
                                     instance